@@ -0,0 +1,168 @@
+use bevy::ecs::{
+    component::Component,
+    entity::Entity,
+    event::{Event, EventReader},
+    query::{Added, Or},
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    data::{ArtistId, ReleaseId, UserId},
+    KnownEntities,
+};
+
+/// Other urls a node was found at, e.g. before an artist renamed their subdomain, or where a label
+/// lists one of its artists' releases
+#[derive(Debug, Default, Clone, Component)]
+pub struct Aliases(pub BTreeSet<String>);
+
+/// Sent when an already known node is found at a new url, `from` is the url to keep as an alias
+#[derive(Debug, Event)]
+pub struct Moved {
+    pub entity: Entity,
+    pub from: String,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Stored {
+    artists: BTreeMap<u64, BTreeSet<String>>,
+    releases: BTreeMap<u64, BTreeSet<String>>,
+    users: BTreeMap<u64, BTreeSet<String>>,
+}
+
+type Ids<'a> = (
+    Option<&'a ArtistId>,
+    Option<&'a ReleaseId>,
+    Option<&'a UserId>,
+);
+
+impl Stored {
+    fn get(&self, (artist, release, user): Ids<'_>) -> Option<&BTreeSet<String>> {
+        if let Some(artist) = artist {
+            self.artists.get(&artist.0)
+        } else if let Some(release) = release {
+            self.releases.get(&release.0)
+        } else {
+            user.and_then(|user| self.users.get(&user.0))
+        }
+    }
+
+    fn entry(&mut self, (artist, release, user): Ids<'_>) -> Option<&mut BTreeSet<String>> {
+        if let Some(artist) = artist {
+            Some(self.artists.entry(artist.0).or_default())
+        } else if let Some(release) = release {
+            Some(self.releases.entry(release.0).or_default())
+        } else {
+            user.map(|user| self.users.entry(user.0).or_default())
+        }
+    }
+}
+
+/// Alias urls of every node seen, persisted across runs
+#[derive(Debug, Resource)]
+pub struct AliasTable {
+    path: PathBuf,
+    stored: Stored,
+}
+
+impl AliasTable {
+    #[culpa::try_fn]
+    pub fn load(data_dir: &Path) -> eyre::Result<Self> {
+        let path = data_dir.join("aliases.json");
+        let stored = match std::fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Stored::default(),
+            Err(err) => Err(err)?,
+        };
+        Self { path, stored }
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.try_save() {
+            tracing::error!(?error, "failed saving aliases");
+        }
+    }
+
+    #[culpa::try_fn]
+    fn try_save(&self) -> eyre::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.stored)?)?;
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Moved>();
+        app.add_systems(bevy::app::Update, (restore, record));
+    }
+}
+
+/// Attach previously stored aliases to newly spawned nodes
+fn restore(
+    table: Res<AliasTable>,
+    mut known: ResMut<KnownEntities>,
+    nodes: Query<
+        (
+            Entity,
+            Option<&ArtistId>,
+            Option<&ReleaseId>,
+            Option<&UserId>,
+        ),
+        Or<(Added<ArtistId>, Added<ReleaseId>, Added<UserId>)>,
+    >,
+    mut commands: Commands,
+) {
+    for (entity, artist, release, user) in &nodes {
+        let Some(aliases) = table.stored.get((artist, release, user)).cloned() else {
+            continue;
+        };
+        for alias in &aliases {
+            known.aliases.insert(alias.clone(), entity);
+        }
+        commands.entity(entity).insert(Aliases(aliases));
+    }
+}
+
+fn record(
+    mut events: EventReader<Moved>,
+    mut table: ResMut<AliasTable>,
+    mut nodes: Query<(
+        Option<&mut Aliases>,
+        Option<&ArtistId>,
+        Option<&ReleaseId>,
+        Option<&UserId>,
+    )>,
+    mut commands: Commands,
+) {
+    let mut changed = false;
+
+    for Moved { entity, from } in events.read() {
+        let Ok((aliases, artist, release, user)) = nodes.get_mut(*entity) else {
+            continue;
+        };
+
+        tracing::info!(from, "node found at another url");
+
+        if let Some(stored) = table.stored.entry((artist, release, user)) {
+            changed |= stored.insert(from.clone());
+        }
+
+        if let Some(mut aliases) = aliases {
+            aliases.0.insert(from.clone());
+        } else {
+            commands
+                .entity(*entity)
+                .insert(Aliases(BTreeSet::from([from.clone()])));
+        }
+    }
+
+    if changed {
+        table.save();
+    }
+}
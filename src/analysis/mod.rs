@@ -0,0 +1,79 @@
+use bevy::ecs::{
+    entity::Entity,
+    event::{Event, EventReader, EventWriter},
+    query::Has,
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+use bevy::input::keyboard::{Key, KeyboardInput};
+
+use crate::{
+    interact::Selected,
+    ui::{
+        editor::{Editor, Target},
+        notify::Notify,
+    },
+};
+
+pub mod query;
+
+/// Select the nodes matching a [`query::Query`], replacing the current selection
+#[derive(Debug, Event)]
+pub struct RunQuery(pub String);
+
+/// The last query run, to edit when the query box is opened again
+#[derive(Debug, Default, Resource)]
+struct LastQuery(String);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<RunQuery>();
+        app.init_resource::<LastQuery>();
+        app.add_systems(bevy::app::Update, (keys, run));
+    }
+}
+
+fn keys(mut events: EventReader<KeyboardInput>, mut editor: ResMut<Editor>, last: Res<LastQuery>) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("q".into()) {
+            editor.open(Target::Query, last.0.clone());
+        }
+    }
+}
+
+fn run(
+    mut events: EventReader<RunQuery>,
+    mut last: ResMut<LastQuery>,
+    nodes: Query<(Entity, query::Subject, Has<Selected>)>,
+    mut notify: EventWriter<Notify>,
+    mut commands: Commands,
+) {
+    for RunQuery(source) in events.read() {
+        last.0.clone_from(source);
+        let query = match source.parse::<query::Query>() {
+            Ok(query) => query,
+            Err(error) => {
+                notify.send(Notify(format!("invalid query: {error}")));
+                continue;
+            }
+        };
+
+        let mut count = 0;
+        for (entity, subject, selected) in &nodes {
+            let matches = query.matches(&subject);
+            if matches && !selected {
+                commands.entity(entity).insert(Selected);
+            } else if !matches && selected {
+                commands.entity(entity).remove::<Selected>();
+            }
+            count += usize::from(matches);
+        }
+        notify.send(Notify(format!("selected {count} nodes")));
+    }
+}
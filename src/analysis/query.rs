@@ -0,0 +1,236 @@
+use bevy::ecs::query::QueryData;
+
+use crate::{
+    annotation::Annotation,
+    data::{ArtistDetails, EntityType, ReleaseDetails, TagDetails, UserDetails},
+    sim::RelationCount,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Artist, release, user, tag or label
+    Type,
+    /// Year a release was released
+    Year,
+    /// How many unique neighbors a node has
+    Degree,
+    /// How many fans a release has
+    Fans,
+    /// The tags an artist gave a release, or the user gave a node
+    Tag,
+    /// Part of a node's name
+    Name,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Less,
+    Greater,
+    Equal,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    field: Field,
+    op: Op,
+    value: String,
+    /// Written with a leading `-`, matching nodes the term doesn't
+    negated: bool,
+}
+
+impl std::str::FromStr for Term {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, String> {
+        let (negated, term) = match source.strip_prefix('-') {
+            Some(term) => (true, term),
+            None => (false, source),
+        };
+        let (field, value) = term
+            .split_once(':')
+            .ok_or_else(|| format!("{source:?} is missing `field:value`"))?;
+
+        let field = match field {
+            "type" | "is" => Field::Type,
+            "year" => Field::Year,
+            "degree" => Field::Degree,
+            "fans" => Field::Fans,
+            "tag" => Field::Tag,
+            "name" => Field::Name,
+            other => Err(format!("{source:?} has unknown field {other:?}"))?,
+        };
+
+        let (op, value) = if let Some(value) = value.strip_prefix('<') {
+            (Op::Less, value)
+        } else if let Some(value) = value.strip_prefix('>') {
+            (Op::Greater, value)
+        } else {
+            (Op::Equal, value.strip_prefix('=').unwrap_or(value))
+        };
+        let value = value.trim_matches(['\'', '"']).to_lowercase();
+
+        let numeric = matches!(field, Field::Year | Field::Degree | Field::Fans);
+        if numeric && value.parse::<i64>().is_err() {
+            Err(format!("{source:?} needs a number"))?;
+        }
+        if !numeric && op != Op::Equal {
+            Err(format!("{source:?} can't be compared with < or >"))?;
+        }
+
+        Ok(Self {
+            field,
+            op,
+            value,
+            negated,
+        })
+    }
+}
+
+/// What a node is matched against, shared by queries and color rules
+#[derive(QueryData)]
+pub struct Subject {
+    pub ty: &'static EntityType,
+    relations: &'static RelationCount,
+    release: Option<&'static ReleaseDetails>,
+    artist: Option<&'static ArtistDetails>,
+    user: Option<&'static UserDetails>,
+    tag: Option<&'static TagDetails>,
+    annotation: Option<&'static Annotation>,
+}
+
+impl SubjectItem<'_> {
+    fn name(&self) -> Option<&str> {
+        self.release
+            .map(|release| release.title.as_str())
+            .or(self.artist.map(|artist| artist.name.as_str()))
+            .or(self.user.map(|user| user.name.as_str()))
+            .or(self.tag.map(|tag| tag.name.as_str()))
+    }
+}
+
+impl Term {
+    fn matches(&self, subject: &SubjectItem<'_>) -> bool {
+        let number = match self.field {
+            Field::Type => return format!("{:?}", subject.ty).to_lowercase() == self.value,
+            Field::Tag => {
+                let release = subject.release.into_iter().flat_map(|r| &r.tags);
+                let annotation = subject.annotation.into_iter().flat_map(|a| &a.tags);
+                return release
+                    .chain(annotation)
+                    .any(|tag| tag.to_lowercase() == self.value);
+            }
+            Field::Name => {
+                return subject
+                    .name()
+                    .is_some_and(|name| name.to_lowercase().contains(&self.value))
+            }
+            Field::Year => subject
+                .release
+                .map(|release| i64::from(release.released.year())),
+            Field::Degree => Some(i64::from(subject.relations.count)),
+            Field::Fans => subject.release.map(|release| release.fan_count as i64),
+        };
+
+        // Checked when parsed
+        let value = self.value.parse::<i64>().unwrap();
+        number.is_some_and(|number| match self.op {
+            Op::Less => number < value,
+            Op::Greater => number > value,
+            Op::Equal => number == value,
+        })
+    }
+}
+
+/// Space separated terms that must all match a node, written like
+/// `type:release year:<2015 degree:>50 tag:ambient`, quote values with spaces like
+/// `name:"some artist"`
+#[derive(Debug, Clone)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl std::str::FromStr for Query {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, String> {
+        let terms = split(source)
+            .into_iter()
+            .map(str::parse)
+            .collect::<Result<Vec<Term>, _>>()?;
+        if terms.is_empty() {
+            Err("query is empty".to_owned())?;
+        }
+        Ok(Self { terms })
+    }
+}
+
+impl Query {
+    pub fn matches(&self, subject: &SubjectItem<'_>) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.matches(subject) != term.negated)
+    }
+}
+
+/// Split on whitespace, except inside double quotes
+fn split(source: &str) -> Vec<&str> {
+    let mut terms = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (i, c) in source.char_indices() {
+        match c {
+            '"' => quoted ^= true,
+            c if c.is_whitespace() && !quoted => {
+                if let Some(start) = start.take() {
+                    terms.push(&source[start..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(start) = start {
+        terms.push(&source[start..]);
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split, Field, Op, Query, Term};
+
+    #[test]
+    fn split_keeps_quoted_spaces() {
+        assert_eq!(
+            split(r#"  type:artist name:"some artist"   tag:ambient "#),
+            ["type:artist", r#"name:"some artist""#, "tag:ambient"],
+        );
+        assert!(split("   ").is_empty());
+    }
+
+    #[test]
+    fn term_from_str() {
+        let term = "-year:<2015".parse::<Term>().unwrap();
+        assert_eq!(
+            (term.field, term.op, term.value.as_str()),
+            (Field::Year, Op::Less, "2015")
+        );
+        assert!(term.negated);
+
+        let term = r#"name:"Some Artist""#.parse::<Term>().unwrap();
+        assert_eq!(
+            (term.field, term.op, term.value.as_str()),
+            (Field::Name, Op::Equal, "some artist"),
+        );
+        assert!(!term.negated);
+
+        let term = "is:=release".parse::<Term>().unwrap();
+        assert_eq!((term.field, term.op), (Field::Type, Op::Equal));
+
+        for invalid in ["year", "colour:red", "fans:>many", "tag:<ambient"] {
+            assert!(invalid.parse::<Term>().is_err(), "{invalid:?} parsed");
+        }
+        assert!("  ".parse::<Query>().is_err());
+    }
+}
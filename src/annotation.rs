@@ -0,0 +1,100 @@
+use bevy::ecs::{
+    component::Component,
+    entity::Entity,
+    query::{Added, Changed},
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use crate::data::Url;
+
+/// Free-text notes and tags the user has attached to a node
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component, serde::Serialize, serde::Deserialize)]
+pub struct Annotation {
+    pub note: String,
+    pub tags: BTreeSet<String>,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        self.note.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// Annotations of every node, keyed by url and persisted across runs
+#[derive(Debug, Resource)]
+pub struct Annotations {
+    path: PathBuf,
+    stored: BTreeMap<String, Annotation>,
+}
+
+impl Annotations {
+    #[culpa::try_fn]
+    pub fn load(data_dir: &Path) -> eyre::Result<Self> {
+        let path = data_dir.join("annotations.json");
+        let stored = match std::fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => Err(err)?,
+        };
+        Self { path, stored }
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.try_save() {
+            tracing::error!(?error, "failed saving annotations");
+        }
+    }
+
+    #[culpa::try_fn]
+    fn try_save(&self) -> eyre::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string(&self.stored)?)?;
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Update, (attach, persist));
+    }
+}
+
+/// Restore the annotations of nodes annotated in an earlier run
+fn attach(
+    annotations: Res<Annotations>,
+    nodes: Query<(Entity, &Url), Added<Url>>,
+    mut commands: Commands,
+) {
+    for (entity, url) in &nodes {
+        if let Some(annotation) = annotations.stored.get(&url.0) {
+            commands.entity(entity).insert(annotation.clone());
+        }
+    }
+}
+
+fn persist(
+    mut annotations: ResMut<Annotations>,
+    nodes: Query<(&Url, &Annotation), Changed<Annotation>>,
+) {
+    let mut changed = false;
+    for (url, annotation) in &nodes {
+        if annotations.stored.get(&url.0) == Some(annotation) {
+            continue;
+        }
+        if annotation.is_empty() {
+            annotations.stored.remove(&url.0);
+        } else {
+            annotations.stored.insert(url.0.clone(), annotation.clone());
+        }
+        changed = true;
+    }
+
+    if changed {
+        annotations.save();
+    }
+}
@@ -0,0 +1,366 @@
+use bevy::{
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        query::{Added, With, Without},
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource, Single},
+    },
+    hierarchy::DespawnRecursiveExt,
+    time::common_conditions::on_timer,
+};
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    background::{Request, Scraper},
+    data::{
+        Artist, ArtistId, DiscoveredVia, EntityType, RelationKind, Release, ReleaseId, Scrape, Url,
+        User, UserId,
+    },
+    interact::{Nearest, Selected},
+    sim::{Multiplicity, Partitions, PredictedPosition, RelationCount, Relationship, Ring},
+    ui::notify::Notify,
+    watch::Watched,
+    Args, KnownEntities, RelationshipParent,
+};
+
+/// How far under the cap to archive down to, so archiving doesn't run again as soon as the next
+/// few nodes are spawned
+const HEADROOM: f32 = 0.9;
+
+/// When the user last had a node nearest the cursor, the least recently viewed nodes are archived
+/// first
+#[derive(Debug, Component)]
+pub struct LastViewed(Instant);
+
+/// Placeholder for the nodes archived from around this one, which can be restored on demand
+#[derive(Debug, Default, Component)]
+pub struct Archived {
+    pub artists: usize,
+    pub releases: usize,
+    pub users: usize,
+}
+
+impl Archived {
+    fn add(&mut self, ty: EntityType) {
+        match ty {
+            EntityType::Artist | EntityType::Label => self.artists += 1,
+            EntityType::Release => self.releases += 1,
+            EntityType::User => self.users += 1,
+            // Tags are never archived, see `archive`
+            EntityType::Tag => {}
+        }
+    }
+}
+
+/// Send to restore the nodes archived from around a node
+#[derive(Debug, Event)]
+pub struct Restore(pub Entity);
+
+/// A node that was despawned to keep the live world small, along with how it related to the
+/// single neighbor it's stored under
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct ArchivedNode {
+    ty: EntityType,
+    id: u64,
+    url: String,
+    kinds: Vec<RelationKind>,
+    /// Whether the relationship was from this node to the neighbor
+    from: bool,
+}
+
+/// Archived nodes keyed by the url of the neighbor they were related to
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Stored {
+    nodes: BTreeMap<String, Vec<ArchivedNode>>,
+}
+
+/// Nodes archived out of the world, persisted across runs so they can still be restored
+#[derive(Debug, Resource)]
+pub struct Archive {
+    path: PathBuf,
+    stored: Stored,
+}
+
+impl Archive {
+    #[culpa::try_fn]
+    pub fn load(data_dir: &Path) -> eyre::Result<Self> {
+        let path = data_dir.join("archive.json");
+        let stored = match std::fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Stored::default(),
+            Err(err) => Err(err)?,
+        };
+        Self { path, stored }
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.try_save() {
+            tracing::error!(?error, "failed to save archive");
+        }
+    }
+
+    #[culpa::try_fn]
+    fn try_save(&self) -> eyre::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string(&self.stored)?)?;
+    }
+
+    fn placeholder(&self, url: &Url) -> Option<Archived> {
+        let nodes = self.stored.nodes.get(&url.0)?;
+        let mut archived = Archived::default();
+        for node in nodes {
+            archived.add(node.ty);
+        }
+        Some(archived)
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Restore>();
+        app.add_systems(
+            bevy::app::Update,
+            (
+                viewed.run_if(resource_exists::<Nearest>),
+                placeholders,
+                archive.run_if(on_timer(Duration::from_secs(5))),
+                restore,
+            ),
+        );
+    }
+}
+
+fn viewed(nearest: Res<Nearest>, mut commands: Commands) {
+    if nearest.is_changed() {
+        if let Some(mut entity) = commands.get_entity(nearest.entity) {
+            entity.insert(LastViewed(Instant::now()));
+        }
+    }
+}
+
+/// Show placeholders on nodes that had neighbors archived in an earlier run
+fn placeholders(
+    archive: Res<Archive>,
+    nodes: Query<(Entity, &Url), Added<Url>>,
+    mut commands: Commands,
+) {
+    for (entity, url) in &nodes {
+        if let Some(archived) = archive.placeholder(url) {
+            commands.entity(entity).insert(archived);
+        }
+    }
+}
+
+fn archive(
+    args: Res<Args>,
+    mut archive: ResMut<Archive>,
+    nodes: Query<&EntityType>,
+    candidates: Query<
+        (
+            Entity,
+            &EntityType,
+            &Url,
+            &RelationCount,
+            Option<&LastViewed>,
+            Option<&ArtistId>,
+            Option<&ReleaseId>,
+            Option<&UserId>,
+        ),
+        (Without<Selected>, Without<Watched>),
+    >,
+    relationships: Query<(&Relationship, &Multiplicity)>,
+    urls: Query<&Url>,
+    mut placeholders: Query<&mut Archived>,
+    mut notify: EventWriter<Notify>,
+    mut commands: Commands,
+) {
+    let Some(cap) = args.node_cap else { return };
+    let count = nodes.iter().count();
+    if count <= cap {
+        return;
+    }
+    let wanted = count - (cap as f32 * HEADROOM) as usize;
+
+    let mut candidates = Vec::from_iter(
+        candidates
+            .iter()
+            .filter(|(_, &ty, _, relations, ..)| relations.count == 1 && ty != EntityType::Tag),
+    );
+    candidates.sort_by_key(|(_, _, _, _, viewed, ..)| viewed.map(|viewed| viewed.0));
+
+    let mut edges = HashMap::<Entity, (&Relationship, &Multiplicity)>::new();
+    for (relationship, multiplicity) in &relationships {
+        edges.insert(relationship.from, (relationship, multiplicity));
+        edges.insert(relationship.to, (relationship, multiplicity));
+    }
+
+    let mut archived = HashSet::new();
+    let mut new_placeholders = HashMap::<Entity, Archived>::new();
+    for (entity, &ty, url, _, _, artist, release, user) in candidates {
+        if archived.len() == wanted {
+            break;
+        }
+        let Some(&(relationship, multiplicity)) = edges.get(&entity) else {
+            continue;
+        };
+        let from = relationship.from == entity;
+        let neighbor = if from {
+            relationship.to
+        } else {
+            relationship.from
+        };
+        // Two leaves only related to each other would have nowhere left to be stored under, and tags
+        // have no page to be restored from
+        if archived.contains(&neighbor) || matches!(nodes.get(neighbor), Ok(EntityType::Tag)) {
+            continue;
+        }
+        let Ok(neighbor_url) = urls.get(neighbor) else {
+            continue;
+        };
+        let id = artist
+            .map(|id| id.0)
+            .or(release.map(|id| id.0))
+            .or(user.map(|id| id.0))
+            .unwrap();
+
+        archive
+            .stored
+            .nodes
+            .entry(neighbor_url.0.clone())
+            .or_default()
+            .push(ArchivedNode {
+                ty,
+                id,
+                url: url.0.clone(),
+                kinds: multiplicity.kinds().collect(),
+                from,
+            });
+
+        if let Ok(mut placeholder) = placeholders.get_mut(neighbor) {
+            placeholder.add(ty);
+        } else {
+            new_placeholders.entry(neighbor).or_default().add(ty);
+        }
+
+        // The id component hooks unindex the node and despawn its relationship
+        commands.entity(entity).despawn_recursive();
+        archived.insert(entity);
+    }
+
+    for (entity, placeholder) in new_placeholders {
+        commands.entity(entity).insert(placeholder);
+    }
+
+    if !archived.is_empty() {
+        archive.save();
+        notify.send(Notify(format!("archived {} nodes", archived.len())));
+    }
+}
+
+fn restore(
+    mut events: EventReader<Restore>,
+    mut archive: ResMut<Archive>,
+    mut known: ResMut<KnownEntities>,
+    scraper: Res<Scraper>,
+    nodes: Query<(&EntityType, &Url, &PredictedPosition)>,
+    partitions: Res<Partitions>,
+    mut multiplicities: Query<&mut Multiplicity>,
+    relationship_parent: Single<Entity, With<RelationshipParent>>,
+    mut commands: Commands,
+) {
+    for &Restore(neighbor) in events.read() {
+        let Ok((ty, url, position)) = nodes.get(neighbor) else {
+            continue;
+        };
+        let Some(archived) = archive.stored.nodes.remove(&url.0) else {
+            continue;
+        };
+
+        let request = match ty {
+            EntityType::Artist | EntityType::Label => Request::Artist { url: url.0.clone() },
+            EntityType::Release => Request::Release { url: url.0.clone() },
+            EntityType::User => Request::User { url: url.0.clone() },
+            EntityType::Tag => continue,
+        };
+        let via = DiscoveredVia::new(&request, Some(neighbor));
+
+        let mut ring = Ring::around(position.0, archived.len(), &partitions);
+        for node in &archived {
+            // Restored unscraped, so a scrape of it mustn't be dropped as already requested
+            let url = node.url.clone();
+            scraper.forget_request(&match node.ty {
+                EntityType::Artist | EntityType::Label => Request::Artist { url },
+                EntityType::Release => Request::Release { url },
+                EntityType::User => Request::User { url },
+                EntityType::Tag => continue,
+            });
+            let url = Url(node.url.clone());
+            // It may have been rediscovered since it was archived
+            let entity = match node.ty {
+                // Restored as a plain artist, until its page is scraped again and shows its roster
+                EntityType::Artist | EntityType::Label => {
+                    *known.artists.entry(ArtistId(node.id)).or_insert_with(|| {
+                        let artist = Artist {
+                            id: ArtistId(node.id),
+                            url,
+                        };
+                        commands
+                            .spawn((artist, ring.place(), Scrape::None, via.clone()))
+                            .id()
+                    })
+                }
+                EntityType::Release => {
+                    *known.releases.entry(ReleaseId(node.id)).or_insert_with(|| {
+                        let release = Release {
+                            id: ReleaseId(node.id),
+                            url,
+                        };
+                        commands
+                            .spawn((release, ring.place(), Scrape::None, via.clone()))
+                            .id()
+                    })
+                }
+                EntityType::User => *known.users.entry(UserId(node.id)).or_insert_with(|| {
+                    let user = User {
+                        id: UserId(node.id),
+                        url,
+                    };
+                    commands
+                        .spawn((user, ring.place(), Scrape::None, via.clone()))
+                        .id()
+                }),
+                EntityType::Tag => continue,
+            };
+            let relationship = if node.from {
+                Relationship {
+                    from: entity,
+                    to: neighbor,
+                }
+            } else {
+                Relationship {
+                    from: neighbor,
+                    to: entity,
+                }
+            };
+            known.relate_kinds(
+                relationship,
+                &node.kinds,
+                *relationship_parent,
+                &mut multiplicities,
+                &mut commands,
+            );
+        }
+
+        commands.entity(neighbor).remove::<Archived>();
+        archive.save();
+    }
+}
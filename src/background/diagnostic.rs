@@ -5,6 +5,7 @@ use bevy::{
 
 use std::sync::atomic::Ordering;
 
+use super::PageKind;
 use crate::diagnostic::Diagnostics;
 
 pub mod items {
@@ -14,6 +15,25 @@ pub mod items {
     pub const DUPLICATE: DiagnosticPath = DiagnosticPath::const_new("scraper/items/duplicate");
     pub const PROCESSING: DiagnosticPath = DiagnosticPath::const_new("scraper/items/processing");
     pub const QUEUED: DiagnosticPath = DiagnosticPath::const_new("scraper/items/queued");
+
+    /// Time taken to apply the last received response to the graph
+    pub const APPLY_TIME: DiagnosticPath = DiagnosticPath::const_new("scraper/items/apply-time");
+}
+
+pub mod pages {
+    use bevy::diagnostic::DiagnosticPath;
+
+    use super::PageKind;
+
+    /// Total size of the retrieved pages of a kind
+    pub fn bytes(kind: PageKind) -> DiagnosticPath {
+        DiagnosticPath::new(format!("scraper/pages/{}/bytes", kind.as_ref()))
+    }
+
+    /// Total time spent parsing pages of a kind
+    pub fn parse_time(kind: PageKind) -> DiagnosticPath {
+        DiagnosticPath::new(format!("scraper/pages/{}/parse-time", kind.as_ref()))
+    }
 }
 
 pub mod web {
@@ -27,6 +47,10 @@ pub mod web {
     }
 
     pub const REQUESTS: DiagnosticPath = DiagnosticPath::const_new("scraper/web/requests");
+    pub const DELAY: DiagnosticPath = DiagnosticPath::const_new("scraper/web/delay");
+
+    /// Total time spent waiting on the server to respond
+    pub const TIME: DiagnosticPath = DiagnosticPath::const_new("scraper/web/time");
 }
 
 pub struct Plugin;
@@ -44,6 +68,26 @@ impl bevy::app::Plugin for Plugin {
         ] {
             app.register_diagnostic(Diagnostic::new(path).with_smoothing_factor(0.));
         }
+        for path in [self::items::APPLY_TIME, self::web::DELAY, self::web::TIME] {
+            app.register_diagnostic(
+                Diagnostic::new(path)
+                    .with_suffix("ms")
+                    .with_smoothing_factor(0.),
+            );
+        }
+
+        for kind in PageKind::ALL {
+            app.register_diagnostic(
+                Diagnostic::new(self::pages::bytes(kind))
+                    .with_suffix("B")
+                    .with_smoothing_factor(0.),
+            );
+            app.register_diagnostic(
+                Diagnostic::new(self::pages::parse_time(kind))
+                    .with_suffix("ms")
+                    .with_smoothing_factor(0.),
+            );
+        }
 
         app.add_systems(bevy::app::Update, update);
     }
@@ -65,10 +109,26 @@ fn update(mut diagnostics: Diagnostics, scraper: Res<super::Scraper>) {
     diagnostics.add_measurement(&self::web::REQUESTS, || {
         scraper.stats.web_requests.load(Ordering::Relaxed) as f64
     });
+    diagnostics.add_measurement(&self::web::DELAY, || {
+        scraper.stats.web_delay_millis.load(Ordering::Relaxed) as f64
+    });
+    diagnostics.add_measurement(&self::web::TIME, || {
+        scraper.stats.web_nanos.load(Ordering::Relaxed) as f64 / 1_000_000.
+    });
     diagnostics.add_measurement(&self::web::cache::HITS, || {
         scraper.stats.web_cache_hits.load(Ordering::Relaxed) as f64
     });
     diagnostics.add_measurement(&self::web::cache::MISSES, || {
         scraper.stats.web_cache_misses.load(Ordering::Relaxed) as f64
     });
+
+    for kind in PageKind::ALL {
+        let stats = &scraper.stats.pages[kind as usize];
+        diagnostics.add_measurement(&self::pages::bytes(kind), || {
+            stats.bytes.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&self::pages::parse_time(kind), || {
+            stats.parse_nanos.load(Ordering::Relaxed) as f64 / 1_000_000.
+        });
+    }
 }
@@ -0,0 +1,102 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use super::Request;
+
+/// A scrape the user started, or a seed, which every scrape fanning out from it counts towards
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JobId(u32);
+
+impl std::fmt::Display for JobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Progress through the scrapes of a job
+#[derive(Clone, Debug)]
+pub struct JobStats {
+    pub label: String,
+    pub started: Instant,
+    pub queued: usize,
+    pub completed: usize,
+    pub failed: usize,
+    /// When the last of its scrapes finished, until more are queued
+    pub finished: Option<Instant>,
+}
+
+impl JobStats {
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(Instant::now) - self.started
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.completed + self.failed < self.queued
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Jobs {
+    next: u32,
+    jobs: BTreeMap<JobId, JobStats>,
+    /// The job each request was queued for
+    requests: HashMap<Request, JobId>,
+}
+
+impl Jobs {
+    pub(crate) fn start(&mut self, label: String) -> JobId {
+        let job = JobId(self.next);
+        self.next += 1;
+        self.jobs.insert(
+            job,
+            JobStats {
+                label,
+                started: Instant::now(),
+                queued: 0,
+                completed: 0,
+                failed: 0,
+                finished: None,
+            },
+        );
+        job
+    }
+
+    pub(crate) fn queue(&mut self, job: JobId, request: &Request) {
+        let Some(stats) = self.jobs.get_mut(&job) else {
+            return;
+        };
+        stats.queued += 1;
+        stats.finished = None;
+        self.requests.insert(request.clone(), job);
+    }
+
+    pub(crate) fn finish(&mut self, request: &Request, succeeded: bool) {
+        let Some(stats) = self
+            .requests
+            .get(request)
+            .and_then(|job| self.jobs.get_mut(job))
+            // Searches and refreshes sent again outside the job shouldn't count towards it
+            .filter(|stats| stats.is_running())
+        else {
+            return;
+        };
+        if succeeded {
+            stats.completed += 1;
+        } else {
+            stats.failed += 1;
+        }
+        if !stats.is_running() {
+            stats.finished = Some(Instant::now());
+        }
+    }
+
+    pub(crate) fn job_of(&self, request: &Request) -> Option<JobId> {
+        self.requests.get(request).copied()
+    }
+
+    pub(crate) fn all(&self) -> Vec<(JobId, JobStats)> {
+        Vec::from_iter(self.jobs.iter().map(|(&job, stats)| (job, stats.clone())))
+    }
+}
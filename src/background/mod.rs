@@ -3,16 +3,22 @@ use std::{
     collections::HashSet,
     path::Path,
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc, Mutex,
     },
+    time::Duration,
 };
 
 pub mod diagnostic;
+mod job;
 mod scraper;
+mod throughput;
 mod web;
 
-pub use scraper::{Request, Response};
+pub use job::{JobId, JobStats};
+pub use scraper::{selectors::Selectors, FanList, Follow, Request, Response, SearchResult};
+
+use self::scraper::parse::PageKind;
 
 #[derive(Debug, Default)]
 struct Stats {
@@ -24,20 +30,59 @@ struct Stats {
     web_requests: AtomicUsize,
     web_cache_misses: AtomicUsize,
     web_cache_hits: AtomicUsize,
+    /// The delay between requests to the server, adapted to how well it's coping
+    web_delay_millis: AtomicU64,
+    /// Total time spent waiting on the server to respond
+    web_nanos: AtomicU64,
+
+    parse_failures: AtomicUsize,
+
+    /// Indexed by [`PageKind`]
+    pages: [PageStats; PageKind::ALL.len()],
+}
+
+#[derive(Debug, Default)]
+struct PageStats {
+    bytes: AtomicUsize,
+    parse_nanos: AtomicU64,
+}
+
+/// Where the scraper gets its pages from
+#[derive(Debug, Clone, Copy)]
+pub enum Source<'a> {
+    /// Bandcamp itself, through the page cache
+    Network,
+    /// A directory of saved fixtures, bypassing the page cache
+    Fixtures(&'a Path),
+    /// A local http server serving a directory of saved fixtures, exercising the whole pipeline
+    /// through a separate page cache
+    #[cfg(any(test, feature = "mock-server"))]
+    MockServer(&'a Path),
 }
 
 #[derive(Debug, bevy::ecs::system::Resource)]
 pub struct Scraper {
     threads: Vec<std::thread::JoinHandle<()>>,
     stats: Arc<Stats>,
+    readers: Arc<self::web::cache::Readers>,
+    network: Arc<self::web::client::Pause>,
+    quarantine: Arc<Mutex<self::scraper::drift::Quarantine>>,
     done: Mutex<HashSet<Request>>,
+    jobs: Arc<Mutex<self::job::Jobs>>,
+    throughput: Mutex<self::throughput::Throughput>,
     to_scrape_tx: Option<Sender<Request>>,
-    scraped_rx: Option<Receiver<Response>>,
+    scraped_rx: Option<Receiver<(Request, Response)>>,
 }
 
 impl Scraper {
     #[culpa::try_fn]
-    pub fn new(cache_dir: &Path) -> eyre::Result<Self> {
+    pub fn new(
+        cache_dir: &Path,
+        source: Source<'_>,
+        cookies: Option<String>,
+        selectors: Selectors,
+        fan_lists: Vec<FanList>,
+    ) -> eyre::Result<Self> {
         let stats = Arc::new(Stats::default());
 
         let (to_scrape_tx, to_scrape_rx) = crossbeam::channel::unbounded();
@@ -45,86 +90,101 @@ impl Scraper {
         let (web_tx, web_rx) = crossbeam::channel::bounded(1);
         let (web_cache_tx, web_cache_rx) = crossbeam::channel::bounded(1);
 
-        let threads = vec![
-            self::web::client::run(web_rx)?,
-            self::web::cache::run(
-                cache_dir,
-                stats.clone(),
-                web_cache_rx.clone(),
-                web_tx.clone(),
-            )?,
-            self::web::cache::run(
-                cache_dir,
-                stats.clone(),
-                web_cache_rx.clone(),
-                web_tx.clone(),
-            )?,
-            self::web::cache::run(
-                cache_dir,
-                stats.clone(),
-                web_cache_rx.clone(),
-                web_tx.clone(),
-            )?,
-            self::web::cache::run(
-                cache_dir,
-                stats.clone(),
-                web_cache_rx.clone(),
-                web_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
-                stats.clone(),
-                to_scrape_rx.clone(),
-                scraped_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
-                stats.clone(),
-                to_scrape_rx.clone(),
-                scraped_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
-                stats.clone(),
-                to_scrape_rx.clone(),
-                scraped_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
-                stats.clone(),
-                to_scrape_rx.clone(),
-                scraped_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
-                stats.clone(),
-                to_scrape_rx.clone(),
-                scraped_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
-                stats.clone(),
-                to_scrape_rx.clone(),
-                scraped_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
-                stats.clone(),
-                to_scrape_rx.clone(),
-                scraped_tx.clone(),
-            )?,
-            self::scraper::thread::run(
-                web_cache_tx.clone(),
+        let (cache_dir, mock) = match source {
+            Source::Network | Source::Fixtures(_) => (cache_dir.to_owned(), None),
+            #[cfg(any(test, feature = "mock-server"))]
+            Source::MockServer(fixtures) => {
+                // Kept apart so mock pages never get served in place of real ones
+                let cache_dir = cache_dir.join("mock");
+                std::fs::create_dir_all(&cache_dir)?;
+                (cache_dir, Some(self::web::mock::run(fixtures)?))
+            }
+        };
+        let cache_dir = cache_dir.as_path();
+        let readers = Arc::new(self::web::cache::Readers::new(cache_dir));
+        let network = Arc::new(self::web::client::Pause::default());
+
+        let quarantine = Arc::new(Mutex::new(self::scraper::drift::Quarantine::new(
+            cache_dir,
+        )?));
+
+        let checkpoints = Arc::new(Mutex::new(self::scraper::checkpoint::Checkpoints::new(
+            cache_dir,
+        )?));
+        let domains = Arc::new(Mutex::new(self::scraper::domains::Domains::new(cache_dir)?));
+
+        let jobs = Arc::new(Mutex::new(self::job::Jobs::default()));
+
+        let mut threads = if let Source::Fixtures(fixtures) = source {
+            vec![self::web::fixture::run(fixtures, web_cache_rx.clone())?]
+        } else {
+            vec![
+                self::web::client::run(web_rx, stats.clone(), network.clone(), cookies, mock)?,
+                self::web::cache::run(
+                    cache_dir,
+                    readers.clone(),
+                    stats.clone(),
+                    web_cache_rx.clone(),
+                    web_tx.clone(),
+                )?,
+                self::web::cache::run(
+                    cache_dir,
+                    readers.clone(),
+                    stats.clone(),
+                    web_cache_rx.clone(),
+                    web_tx.clone(),
+                )?,
+                self::web::cache::run(
+                    cache_dir,
+                    readers.clone(),
+                    stats.clone(),
+                    web_cache_rx.clone(),
+                    web_tx.clone(),
+                )?,
+                self::web::cache::run(
+                    cache_dir,
+                    readers.clone(),
+                    stats.clone(),
+                    web_cache_rx.clone(),
+                    web_tx.clone(),
+                )?,
+            ]
+        };
+
+        let (parser, parsers) = self::scraper::parse::run(stats.clone())?;
+        // Each scraper thread waits on one parse at a time, so it takes at least as many of them as
+        // there are parser threads for parsing to keep every cpu busy
+        let scrapers = parsers.len().max(8);
+        threads.extend(parsers);
+
+        let pages = self::scraper::PageScraper::new(
+            web_cache_tx,
+            parser,
+            checkpoints,
+            domains,
+            Arc::new(selectors),
+            fan_lists,
+        );
+        for _ in 0..scrapers {
+            threads.push(self::scraper::thread::run(
+                pages.clone(),
                 stats.clone(),
+                quarantine.clone(),
+                jobs.clone(),
                 to_scrape_rx.clone(),
                 scraped_tx.clone(),
-            )?,
-        ];
+            )?);
+        }
 
         Scraper {
             threads,
             stats,
+            readers,
+            network,
+            quarantine,
             done: Mutex::new(HashSet::new()),
+            jobs,
+            throughput: Mutex::default(),
             to_scrape_tx: Some(to_scrape_tx),
             scraped_rx: Some(scraped_rx),
         }
@@ -132,16 +192,152 @@ impl Scraper {
 
     #[culpa::try_fn]
     pub fn send(&self, request: Request) -> eyre::Result<()> {
-        if self.done.lock().unwrap().insert(request.clone()) {
+        self.send_in(None, request)?;
+    }
+
+    /// Send a request counted towards a job, if it isn't a duplicate of one already sent
+    #[culpa::try_fn]
+    pub fn send_in(&self, job: Option<JobId>, request: Request) -> eyre::Result<()> {
+        // Searches and feeds are sent again to pick up anything new since the last time, and
+        // hydration again whenever a shared node's details change
+        let repeat = matches!(
+            request,
+            Request::Refresh(_)
+                | Request::RefreshFans { .. }
+                | Request::Retry(_)
+                | Request::Hydrate(_)
+                | Request::Search { .. }
+                | Request::Feed { .. }
+        );
+        if repeat || self.done.lock().unwrap().insert(request.clone()) {
             self.stats.items_queued.fetch_add(1, Ordering::Relaxed);
+            if let Some(job) = job {
+                self.jobs.lock().unwrap().queue(job, &request);
+            }
             self.to_scrape_tx.as_ref().unwrap().send(request)?;
         } else {
             self.stats.items_duplicate.fetch_add(1, Ordering::Relaxed);
         }
     }
 
+    /// Send a request the user asked for as the start of its own job
+    #[culpa::try_fn]
+    pub fn seed(&self, request: Request) -> eyre::Result<JobId> {
+        let job = self.start_job(format!("seed {}", request.url()));
+        self.send_in(Some(job), request)?;
+        job
+    }
+
+    /// Start a new job for a scrape the user asked for, to count its requests towards
+    pub fn start_job(&self, label: impl Into<String>) -> JobId {
+        self.jobs.lock().unwrap().start(label.into())
+    }
+
+    /// The job a request was sent for, so the scrapes following on from it can count towards it
+    /// too
+    pub fn job_of(&self, request: &Request) -> Option<JobId> {
+        self.jobs.lock().unwrap().job_of(request)
+    }
+
+    /// Every job started so far, oldest first
+    pub fn jobs(&self) -> Vec<(JobId, JobStats)> {
+        self.jobs.lock().unwrap().all()
+    }
+
+    /// Forget which pages have been requested, so they can be requested again after the graph is
+    /// replaced
+    pub fn forget(&self) {
+        self.done.lock().unwrap().clear();
+    }
+
+    /// Forget that a page has been requested, so it can be requested again after its node was
+    /// archived and restored
+    pub fn forget_request(&self, request: &Request) {
+        self.done.lock().unwrap().remove(request);
+    }
+
+    /// The cached copy of a page, if it has been retrieved before
     #[culpa::try_fn]
-    pub fn try_recv(&self) -> eyre::Result<Option<Response>> {
+    pub fn cached_page(&self, url: &str) -> eyre::Result<Option<String>> {
+        self::web::cache::read(&self.readers, &url::Url::parse(url)?)?
+    }
+
+    /// Stop sending requests to the server until resumed, without losing anything queued
+    pub fn pause_network(&self, paused: bool) {
+        self.network.set(paused);
+    }
+
+    pub fn network_paused(&self) -> bool {
+        self.network.is_paused()
+    }
+
+    /// How many pages have failed to parse, most likely because Bandcamp's layout changed
+    pub fn parse_failures(&self) -> usize {
+        self.stats.parse_failures.load(Ordering::Relaxed)
+    }
+
+    /// How many requests failed to parse and are kept to retry, including from earlier runs
+    pub fn failed(&self) -> usize {
+        self.quarantine.lock().unwrap().retry_count()
+    }
+
+    /// Parse the pages of every failed request again from the cache, after the parsing has been
+    /// fixed, returning how many were retried
+    #[culpa::try_fn]
+    pub fn retry_failed(&self) -> eyre::Result<usize> {
+        let requests = self.quarantine.lock().unwrap().retries()?;
+        // Any that fail again are counted again
+        self.stats.parse_failures.store(0, Ordering::Relaxed);
+        for request in &requests {
+            self.send(Request::Retry(Box::new(request.clone())))?;
+        }
+        requests.len()
+    }
+
+    /// How many requests are waiting to be scraped
+    pub fn queued(&self) -> usize {
+        self.stats.items_queued.load(Ordering::Relaxed)
+    }
+
+    /// The time each remaining request is expected to take: the slower of the rate requests have
+    /// been completing at, and the delay between requests to the server for the share of pages
+    /// that aren't cached
+    fn per_item(&self) -> Option<Duration> {
+        let mut throughput = self.throughput.lock().unwrap();
+        throughput.record(self.stats.items_completed.load(Ordering::Relaxed));
+
+        let hits = self.stats.web_cache_hits.load(Ordering::Relaxed);
+        let misses = self.stats.web_cache_misses.load(Ordering::Relaxed);
+        let delay = Duration::from_millis(self.stats.web_delay_millis.load(Ordering::Relaxed));
+        let limited = (hits + misses > 0)
+            .then(|| delay.mul_f64(misses as f64 / (hits + misses) as f64))
+            .filter(|limited| !limited.is_zero());
+
+        match (throughput.per_item(), limited) {
+            (Some(observed), Some(limited)) => Some(observed.max(limited)),
+            (observed, limited) => observed.or(limited),
+        }
+    }
+
+    /// How long until everything queued has been scraped, if there is anything and there's been
+    /// enough scraped to tell
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.queued() + self.stats.items_processing.load(Ordering::Relaxed);
+        let per_item = self.per_item()?;
+        (remaining > 0).then(|| per_item * u32::try_from(remaining).unwrap_or(u32::MAX))
+    }
+
+    /// How long until the rest of a job's requests have been scraped, at the same rate as the
+    /// whole queue
+    pub fn job_eta(&self, stats: &JobStats) -> Option<Duration> {
+        let remaining = stats.queued.saturating_sub(stats.completed + stats.failed);
+        let per_item = self.per_item()?;
+        (remaining > 0).then(|| per_item * u32::try_from(remaining).unwrap_or(u32::MAX))
+    }
+
+    /// Receive a scraped response, along with the request that produced it
+    #[culpa::try_fn]
+    pub fn try_recv(&self) -> eyre::Result<Option<(Request, Response)>> {
         match self.scraped_rx.as_ref().unwrap().try_recv() {
             Ok(response) => Some(response),
             Err(TryRecvError::Empty) => None,
@@ -152,6 +348,8 @@ impl Scraper {
 
 impl Drop for Scraper {
     fn drop(&mut self) {
+        // The client would otherwise never get to see its requests have all closed
+        self.network.set(false);
         self.to_scrape_tx.take();
         self.scraped_rx.take();
         for thread in self.threads.drain(..) {
@@ -159,5 +357,16 @@ impl Drop for Scraper {
                 std::panic::resume_unwind(e);
             }
         }
+        for (job, stats) in self.jobs() {
+            tracing::info!(
+                %job,
+                label = stats.label,
+                queued = stats.queued,
+                completed = stats.completed,
+                failed = stats.failed,
+                elapsed = ?stats.elapsed(),
+                "job summary"
+            );
+        }
     }
 }
@@ -0,0 +1,87 @@
+use rusqlite::{named_params, OptionalExtension};
+use std::{collections::HashMap, path::Path};
+
+/// How far through paging a fan's collection a scrape got, so an interrupted scrape can resume
+/// from where it stopped rather than retrieving thousands of items again, and the newest item
+/// it found so a refresh only has to page back to there
+#[derive(Debug)]
+pub(crate) struct Checkpoints {
+    db: rusqlite::Connection,
+    /// Only kept for this run, the items before a checkpoint from an earlier run never reached
+    /// this graph, so resuming from it would leave them out
+    collections: HashMap<u64, Checkpoint>,
+}
+
+/// Where to resume paging a fan's collection from
+#[derive(Debug, Clone)]
+pub(crate) struct Checkpoint {
+    /// The token to request the next page with
+    pub(crate) last_token: String,
+    /// How many items had been scraped before it
+    pub(crate) scraped: usize,
+}
+
+impl Checkpoints {
+    #[culpa::try_fn]
+    pub(crate) fn new(cache_dir: &Path) -> eyre::Result<Self> {
+        let mut db = rusqlite::Connection::open(cache_dir.join("checkpoints.sqlite"))?;
+
+        let migrations = [
+            "create table collections (fan_id integer primary key, last_token text not null, scraped integer not null) strict",
+            "create table newest (fan_id integer primary key, token text not null) strict",
+            "create table domains (host text primary key, band_id integer not null) strict",
+            "create index domains_band_index on domains (band_id)",
+            "drop table collections",
+            "drop table domains",
+        ];
+
+        let tx = db.transaction()?;
+        let version: u32 =
+            tx.pragma_query_value(None, "user_version", |row| row.get("user_version"))?;
+        for (migration, index) in migrations.into_iter().zip(1u32..) {
+            if version < index {
+                tx.execute(migration, ())?;
+                tx.pragma_update(None, "user_version", index)?;
+            }
+        }
+        tx.commit()?;
+
+        Self {
+            db,
+            collections: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn get(&self, fan_id: u64) -> Option<Checkpoint> {
+        self.collections.get(&fan_id).cloned()
+    }
+
+    pub(crate) fn set(&mut self, fan_id: u64, checkpoint: &Checkpoint) {
+        self.collections.insert(fan_id, checkpoint.clone());
+    }
+
+    /// Forget a fan's checkpoint once their whole collection has been scraped
+    pub(crate) fn clear(&mut self, fan_id: u64) {
+        self.collections.remove(&fan_id);
+    }
+
+    /// The paging token of the newest item in a fan's collection as of their last scrape
+    #[culpa::try_fn]
+    pub(crate) fn newest(&self, fan_id: u64) -> eyre::Result<Option<String>> {
+        self.db
+            .query_row(
+                "select token from newest where fan_id = :fan_id",
+                named_params! { ":fan_id": fan_id },
+                |row| row.get("token"),
+            )
+            .optional()?
+    }
+
+    #[culpa::try_fn]
+    pub(crate) fn set_newest(&self, fan_id: u64, token: &str) -> eyre::Result<()> {
+        self.db.execute(
+            "insert or replace into newest (fan_id, token) values (:fan_id, :token)",
+            named_params! { ":fan_id": fan_id, ":token": token },
+        )?;
+    }
+}
@@ -0,0 +1,66 @@
+use rusqlite::{named_params, OptionalExtension};
+use std::path::Path;
+
+/// Which band each host serves the pages of, to match artists on custom domains up with their
+/// `bandcamp.com` subdomain
+#[derive(Debug)]
+pub(crate) struct Domains {
+    db: rusqlite::Connection,
+}
+
+impl Domains {
+    #[culpa::try_fn]
+    pub(crate) fn new(cache_dir: &Path) -> eyre::Result<Self> {
+        let mut db = rusqlite::Connection::open(cache_dir.join("domains.sqlite"))?;
+
+        let migrations = [
+            "create table domains (host text primary key, band_id integer not null) strict",
+            "create index domains_band_index on domains (band_id)",
+        ];
+
+        let tx = db.transaction()?;
+        let version: u32 =
+            tx.pragma_query_value(None, "user_version", |row| row.get("user_version"))?;
+        for (migration, index) in migrations.into_iter().zip(1u32..) {
+            if version < index {
+                tx.execute(migration, ())?;
+                tx.pragma_update(None, "user_version", index)?;
+            }
+        }
+        tx.commit()?;
+
+        Self { db }
+    }
+
+    #[culpa::try_fn]
+    pub(crate) fn set(&self, host: &str, band_id: u64) -> eyre::Result<()> {
+        self.db.execute(
+            "insert or replace into domains (host, band_id) values (:host, :band_id)",
+            named_params! { ":host": host, ":band_id": band_id },
+        )?;
+    }
+
+    /// The band whose pages a host serves, if any of them have been scraped from it
+    #[culpa::try_fn]
+    pub(crate) fn band_of(&self, host: &str) -> eyre::Result<Option<u64>> {
+        self.db
+            .query_row(
+                "select band_id from domains where host = :host",
+                named_params! { ":host": host },
+                |row| row.get("band_id"),
+            )
+            .optional()?
+    }
+
+    /// Every host a band's pages have been found at
+    #[culpa::try_fn]
+    pub(crate) fn hosts(&self, band_id: u64) -> eyre::Result<Vec<String>> {
+        let mut statement = self
+            .db
+            .prepare("select host from domains where band_id = :band_id order by host")?;
+        let hosts = statement
+            .query_map(named_params! { ":band_id": band_id }, |row| row.get("host"))?
+            .collect::<rusqlite::Result<_>>()?;
+        hosts
+    }
+}
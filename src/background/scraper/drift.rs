@@ -0,0 +1,134 @@
+use super::{parse::PageKind, Request};
+use chrono::offset::Utc;
+use rusqlite::named_params;
+use std::path::Path;
+use url::Url;
+
+/// A page that was retrieved but didn't have the elements or json fields expected of it, most
+/// likely because Bandcamp changed its layout
+#[derive(Debug)]
+pub(crate) struct SchemaDrift {
+    pub(crate) url: Url,
+    pub(crate) kind: PageKind,
+    /// What was missing from it
+    pub(crate) missing: String,
+    pub(crate) page: String,
+}
+
+impl std::fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed to parse {} page {}: {}",
+            self.kind.as_ref(),
+            self.url,
+            self.missing
+        )
+    }
+}
+
+impl std::error::Error for SchemaDrift {}
+
+/// Pages that failed to parse, kept so the layout change can be investigated after the fact, along
+/// with the requests that failed because of them so they can be retried once it's fixed
+#[derive(Debug)]
+pub(crate) struct Quarantine {
+    db: rusqlite::Connection,
+    retries: usize,
+}
+
+impl Quarantine {
+    #[culpa::try_fn]
+    pub(crate) fn new(cache_dir: &Path) -> eyre::Result<Self> {
+        let mut db = rusqlite::Connection::open(cache_dir.join("quarantine.sqlite"))?;
+
+        let migrations = [
+            "create table pages (id integer primary key) strict",
+            "alter table pages add column url text not null",
+            "alter table pages add column kind text not null",
+            "alter table pages add column missing text not null",
+            "alter table pages add column page text not null",
+            "alter table pages add column captured text not null",
+            "create table retries (request text primary key) strict",
+        ];
+
+        let tx = db.transaction()?;
+        let version: u32 =
+            tx.pragma_query_value(None, "user_version", |row| row.get("user_version"))?;
+        for (migration, index) in migrations.into_iter().zip(1u32..) {
+            if version < index {
+                tx.execute(migration, ())?;
+                tx.pragma_update(None, "user_version", index)?;
+            }
+        }
+        tx.commit()?;
+
+        let retries = db.query_row("select count(*) from retries", (), |row| row.get(0))?;
+
+        Self { db, retries }
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self, drift), fields(url=%drift.url, kind=drift.kind.as_ref()))]
+    pub(crate) fn add(&self, drift: &SchemaDrift) -> eyre::Result<()> {
+        self.db.execute(
+            "
+                insert
+                into pages (url, kind, missing, page, captured)
+                values (:url, :kind, :missing, :page, :captured)
+            ",
+            named_params! {
+                ":url": drift.url,
+                ":kind": drift.kind.as_ref(),
+                ":missing": drift.missing,
+                ":page": drift.page,
+                ":captured": Utc::now(),
+            },
+        )?;
+    }
+
+    /// Keep a request that failed to parse to retry later, searches and feeds are always
+    /// retrieved again anyway, and hydration is tried again once its node is back in view
+    #[culpa::try_fn]
+    pub(crate) fn add_retry(&mut self, request: &Request) -> eyre::Result<()> {
+        let release;
+        let request = match request {
+            Request::Search { .. } | Request::Feed { .. } | Request::Hydrate(_) => None,
+            Request::Refresh(request) | Request::Retry(request) => Some(&**request),
+            Request::RefreshFans { url, .. } => {
+                release = Request::Release { url: url.clone() };
+                Some(&release)
+            }
+            request => Some(request),
+        };
+        if let Some(request) = request {
+            self.retries += self.db.execute(
+                "insert or ignore into retries (request) values (:request)",
+                named_params! { ":request": serde_json::to_string(request)? },
+            )?;
+        }
+    }
+
+    #[culpa::try_fn]
+    pub(crate) fn remove_retry(&mut self, request: &Request) -> eyre::Result<()> {
+        self.retries -= self.db.execute(
+            "delete from retries where request = :request",
+            named_params! { ":request": serde_json::to_string(request)? },
+        )?;
+    }
+
+    /// The requests still waiting to be retried
+    #[culpa::try_fn]
+    pub(crate) fn retries(&self) -> eyre::Result<Vec<Request>> {
+        let mut statement = self.db.prepare("select request from retries")?;
+        let requests = statement
+            .query_map((), |row| row.get::<_, String>("request"))?
+            .map(|request| Ok(serde_json::from_str(&request?)?))
+            .collect::<eyre::Result<_>>()?;
+        requests
+    }
+
+    pub(crate) fn retry_count(&self) -> usize {
+        self.retries
+    }
+}
@@ -1,23 +1,297 @@
-use crate::data::{Artist, ArtistDetails, Release, ReleaseDetails, User, UserDetails};
+use bevy::image::Image;
 
+use crate::data::{
+    Acquired, Artist, ArtistDetails, CollectionProgress, RelationKind, Release, ReleaseDetails,
+    Review, Tag, User, UserDetails, UserId,
+};
+
+pub(crate) mod checkpoint;
+pub(crate) mod domains;
+pub(crate) mod drift;
+pub(crate) mod parse;
 mod scraper;
+pub(crate) mod selectors;
+#[cfg(test)]
+mod tests;
 pub mod thread;
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub(crate) use self::scraper::Scraper as PageScraper;
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, serde::Serialize, serde::Deserialize)]
 pub enum Request {
-    Artist { url: String },
-    Release { url: String },
-    User { url: String },
+    Artist {
+        url: String,
+    },
+    Release {
+        url: String,
+    },
+    User {
+        url: String,
+    },
+    /// The top releases of a tag, from the discover api behind its page
+    Tag {
+        url: String,
+    },
+    /// Search Bandcamp for artists, releases and fans matching some text
+    Search {
+        text: String,
+    },
+    /// A release's cover art, from the url in its details
+    Art {
+        url: String,
+    },
+    /// The recent activity in the feed of the fan logged in with the session cookies, from their
+    /// fan page
+    Feed {
+        url: String,
+    },
+
+    /// Scrape again, ignoring any cached pages and earlier requests
+    Refresh(Box<Request>),
+    /// Scrape a release again ignoring the cache, sending only the fans added and removed since
+    /// the ones already related to it in the graph
+    RefreshFans {
+        url: String,
+        known: Vec<u64>,
+    },
+    /// Scrape again from only the cached pages, after an earlier scrape failed to parse them
+    Retry(Box<Request>),
+    /// Fill in the details of a node loaded from an export from only the cached pages, without
+    /// relating it to anything
+    Hydrate(Box<Request>),
+}
+
+impl Request {
+    /// The page to scrape, or for a search the text searched for
+    pub fn url(&self) -> &str {
+        match self {
+            Request::Artist { url }
+            | Request::Release { url }
+            | Request::User { url }
+            | Request::Tag { url }
+            | Request::Art { url }
+            | Request::Feed { url }
+            | Request::RefreshFans { url, .. } => url,
+            Request::Search { text } => text,
+            Request::Refresh(request) | Request::Retry(request) | Request::Hydrate(request) => {
+                request.url()
+            }
+        }
+    }
+
+    /// Work out which kind of page a url is for, or `None` if it isn't a Bandcamp page that can be
+    /// scraped
+    pub fn detect(url: &str) -> Option<Self> {
+        /// Pages on the main site that aren't fans
+        const NOT_FANS: &[&str] = &[
+            "about", "discover", "feed", "guide", "help", "login", "search", "signup", "tag",
+        ];
+
+        let mut url = url::Url::parse(url).ok()?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        url.set_query(None);
+        url.set_fragment(None);
+
+        let host = url.host_str()?.to_owned();
+        let first = url
+            .path_segments()?
+            .find(|segment| !segment.is_empty())
+            .map(str::to_owned);
+
+        if host == "bandcamp.com" || host == "www.bandcamp.com" {
+            // Tag pages moved under discover, which is where release pages link to them
+            if let Some("tag" | "discover") = first.as_deref() {
+                let slug = url.path_segments()?.filter(|s| !s.is_empty()).nth(1)?;
+                return Some(Request::Tag {
+                    url: format!("https://bandcamp.com/discover/{slug}"),
+                });
+            }
+            let username = first.filter(|first| !NOT_FANS.contains(&first.as_str()))?;
+            let feed = url.path_segments()?.filter(|s| !s.is_empty()).nth(1) == Some("feed");
+            let url = format!("https://bandcamp.com/{username}");
+            return Some(if feed {
+                Request::Feed { url }
+            } else {
+                Request::User { url }
+            });
+        }
+
+        let url = url.to_string();
+        match first.as_deref() {
+            // Artists with their own domain still have these paths for releases
+            Some("album" | "track") => Some(Request::Release { url }),
+            None | Some("music") if host.ends_with(".bandcamp.com") => {
+                Some(Request::Artist { url })
+            }
+            _ => None,
+        }
+    }
+
+    /// Urls of Bandcamp pages in some text, with anything around them like punctuation or markup
+    /// stripped
+    pub fn find_all(text: &str) -> Vec<Self> {
+        let mut requests = Vec::new();
+        for word in
+            text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '='))
+        {
+            let word = word.trim_matches(|c: char| matches!(c, '(' | ')' | '[' | ']' | ',' | '.'));
+            if let Some(request) = Self::detect(word) {
+                if !requests.contains(&request) {
+                    requests.push(request);
+                }
+            }
+        }
+        requests
+    }
+}
+
+/// Lists of a fan's that each take paging through another api, so are only scraped when asked for
+/// to keep the number of requests per fan down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FanList {
+    Wishlist,
+    /// The fans they follow and that follow them
+    Follows,
+    /// The artists and labels they follow
+    FollowedArtists,
+}
+
+/// Which way the fans listed on a fan's page follow them
+#[derive(Debug, Clone, Copy)]
+pub enum Follow {
+    /// Fans they follow
+    Following,
+    /// Fans following them
+    Followers,
 }
 
 #[derive(Debug)]
 pub enum Response {
-    Artist(Artist, ArtistDetails),
+    /// An artist along with the other urls their pages are at, like their subdomain and a custom
+    /// domain
+    Artist(Artist, ArtistDetails, Vec<String>),
     Release(Release, ReleaseDetails),
     User(User, UserDetails),
 
-    Fans(Release, Vec<User>),
-    ReleaseArtist(Release, Artist),
-    Collection(User, Vec<Release>),
-    Releases(Artist, Vec<Release>),
+    Fans(Release, RelationKind, Vec<User>),
+    /// The fans added to and removed from a release since it was last scraped
+    FansDelta(Release, RelationKind, Vec<User>, Vec<UserId>),
+    ReleaseArtist(Release, RelationKind, Artist),
+    /// Artists credited on a release by name only, without a page to find them by
+    Credits(Release, Vec<String>),
+    Tags(Release, Vec<Tag>),
+    /// Reviews by fans of a release, sent after the fans themselves
+    Reviews(Release, Vec<(UserId, Review)>),
+    /// Collected releases, with when they were added if it's known
+    Collection(
+        User,
+        RelationKind,
+        Vec<(Release, Option<Acquired>)>,
+        CollectionProgress,
+    ),
+    Wishlist(User, Vec<Release>),
+    Follows(User, Follow, Vec<User>),
+    FollowedArtists(User, Vec<Artist>),
+    Releases(Artist, RelationKind, Vec<Release>),
+    /// The artists on a label's roster
+    Roster(Artist, Vec<Artist>),
+    /// Fans supporting an artist, from across all their releases
+    Supporters(Artist, Vec<User>),
+    SearchResults(Vec<SearchResult>),
+    /// A page of stories from a fan's feed, newest first
+    Feed(User, Vec<FeedStory>),
+    TagReleases(Tag, Vec<Release>),
+    /// Decoded cover art, for the releases with its url
+    Art(String, Image),
+}
+
+/// Something found scraping a release, sent on as it's found rather than once the whole release
+/// has been scraped
+#[derive(Debug)]
+pub(crate) enum ReleasePart {
+    Artist(RelationKind, Artist),
+    /// Artists credited by name only, without a page to find them by
+    Credits(Vec<String>),
+    Tags(Vec<Tag>),
+    Fans(Vec<User>),
+    /// Reviews by fans, sent after the fans themselves
+    Reviews(Vec<(UserId, Review)>),
+}
+
+impl ReleasePart {
+    pub(crate) fn into_response(self, release: &Release) -> Response {
+        let release = release.clone();
+        match self {
+            Self::Artist(kind, artist) => Response::ReleaseArtist(release, kind, artist),
+            Self::Credits(names) => Response::Credits(release, names),
+            Self::Tags(tags) => Response::Tags(release, tags),
+            Self::Fans(fans) => Response::Fans(release, RelationKind::Fan, fans),
+            Self::Reviews(reviews) => Response::Reviews(release, reviews),
+        }
+    }
+}
+
+/// Something found scraping an artist, sent on as it's found
+#[derive(Debug)]
+pub(crate) enum ArtistPart {
+    Releases(RelationKind, Vec<Release>),
+    /// The artists on a label's roster
+    Roster(Vec<Artist>),
+    Supporters(Vec<User>),
+}
+
+impl ArtistPart {
+    pub(crate) fn into_response(self, artist: &Artist) -> Response {
+        let artist = artist.clone();
+        match self {
+            Self::Releases(kind, releases) => Response::Releases(artist, kind, releases),
+            Self::Roster(roster) => Response::Roster(artist, roster),
+            Self::Supporters(fans) => Response::Supporters(artist, fans),
+        }
+    }
+}
+
+/// Something found scraping a fan, sent on as it's found so huge collections can be used before
+/// they're done
+#[derive(Debug)]
+pub(crate) enum FanPart {
+    /// Collected releases, with when they were added if it's known
+    Collection(Vec<(Release, Option<Acquired>)>, CollectionProgress),
+    Wishlist(Vec<Release>),
+    Follows(Follow, Vec<User>),
+    FollowedArtists(Vec<Artist>),
+}
+
+impl FanPart {
+    pub(crate) fn into_response(self, fan: &User) -> Response {
+        let fan = fan.clone();
+        match self {
+            Self::Collection(releases, progress) => {
+                Response::Collection(fan, RelationKind::Collection, releases, progress)
+            }
+            Self::Wishlist(releases) => Response::Wishlist(fan, releases),
+            Self::Follows(follow, fans) => Response::Follows(fan, follow, fans),
+            Self::FollowedArtists(artists) => Response::FollowedArtists(fan, artists),
+        }
+    }
+}
+
+/// Something in a fan's feed, a new release from an artist they follow or a purchase by a fan they
+/// follow
+#[derive(Debug)]
+pub struct FeedStory {
+    pub release: Release,
+    pub artist: Artist,
+    /// Who bought the release, for purchases
+    pub fan: Option<User>,
+}
+
+/// Something found by a search, labels are found as artists
+#[derive(Debug)]
+pub enum SearchResult {
+    Artist(Artist),
+    Release(Release),
+    User(User),
 }
@@ -0,0 +1,131 @@
+use super::super::Stats;
+use super::drift::SchemaDrift;
+use crossbeam::channel::{Receiver, Sender};
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Instant,
+};
+use url::Url;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The kinds of page that get parsed, for attributing failures and stats to
+#[derive(Debug, Clone, Copy, strum::AsRefStr)]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum PageKind {
+    Release,
+    Artist,
+    Fan,
+    CollectorsApi,
+    CollectionsApi,
+    WishlistApi,
+    FollowsApi,
+    Roster,
+    SearchApi,
+    DiscoverApi,
+    Feed,
+    FeedApi,
+    SupportersApi,
+}
+
+impl PageKind {
+    pub(crate) const ALL: [Self; 13] = [
+        Self::Release,
+        Self::Artist,
+        Self::Fan,
+        Self::CollectorsApi,
+        Self::CollectionsApi,
+        Self::WishlistApi,
+        Self::FollowsApi,
+        Self::Roster,
+        Self::SearchApi,
+        Self::DiscoverApi,
+        Self::Feed,
+        Self::FeedApi,
+        Self::SupportersApi,
+    ];
+}
+
+/// Parses pages on a separate pool of threads sized to the cpus, so the scraper threads can wait
+/// on the web without limiting how many cached pages are parsed at once
+#[derive(Debug, Clone)]
+pub(crate) struct Parser {
+    jobs: Sender<Job>,
+    stats: Arc<Stats>,
+}
+
+#[culpa::try_fn]
+pub fn run(stats: Arc<Stats>) -> eyre::Result<(Parser, Vec<std::thread::JoinHandle<()>>)> {
+    let (jobs_tx, jobs_rx) = crossbeam::channel::unbounded();
+    let count = std::thread::available_parallelism().map_or(4, |count| count.get());
+    let threads = eyre::Result::<Vec<_>, _>::from_iter((0..count).map(|_| spawn(jobs_rx.clone())))?;
+    (
+        Parser {
+            jobs: jobs_tx,
+            stats,
+        },
+        threads,
+    )
+}
+
+#[culpa::try_fn]
+fn spawn(jobs: Receiver<Job>) -> eyre::Result<std::thread::JoinHandle<()>> {
+    std::thread::Builder::new()
+        .name("parser".to_owned())
+        .spawn(move || {
+            for job in &jobs {
+                job();
+            }
+        })?
+}
+
+impl Parser {
+    /// Parse a retrieved page, attributing any failure to schema drift along with the page itself
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self, page, parse), fields(%url))]
+    pub(crate) fn parse<T: Send + 'static>(
+        &self,
+        url: &Url,
+        kind: PageKind,
+        page: String,
+        parse: impl FnOnce(&str) -> eyre::Result<T> + Send + 'static,
+    ) -> eyre::Result<T> {
+        self.start(url, kind, page, parse)?.recv()??
+    }
+
+    /// Start parsing a page without waiting for it, for parses that stream their results out
+    /// through a channel of their own as they go
+    #[culpa::try_fn]
+    pub(crate) fn start<T: Send + 'static>(
+        &self,
+        url: &Url,
+        kind: PageKind,
+        page: String,
+        parse: impl FnOnce(&str) -> eyre::Result<T> + Send + 'static,
+    ) -> eyre::Result<Receiver<eyre::Result<T>>> {
+        let (tx, rx) = crossbeam::channel::bounded(1);
+        let url = url.clone();
+        let stats = self.stats.clone();
+        self.jobs
+            .send(Box::new(move || {
+                let start = Instant::now();
+                let result = parse(&page);
+                let page_stats = &stats.pages[kind as usize];
+                page_stats.bytes.fetch_add(page.len(), Ordering::Relaxed);
+                page_stats
+                    .parse_nanos
+                    .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                let result = result.map_err(|error| {
+                    eyre::Report::new(SchemaDrift {
+                        url,
+                        kind,
+                        missing: format!("{error:#}"),
+                        page,
+                    })
+                });
+                let _ = tx.send(result);
+            }))
+            .map_err(|_| eyre::eyre!("parse pool shut down"))?;
+        rx
+    }
+}
@@ -1,15 +1,36 @@
-use super::super::web;
+use super::{
+    super::web::{self, Freshness},
+    checkpoint::{Checkpoint, Checkpoints},
+    domains::Domains,
+    parse::{PageKind, Parser},
+    selectors::Selectors,
+    ArtistPart, FanList, FanPart, FeedStory, Follow, ReleasePart, SearchResult,
+};
 use crate::data::{
-    Artist, ArtistDetails, ArtistId, Release, ReleaseDetails, ReleaseId, ReleaseType, User,
+    Acquired, AlbumTrack, Artist, ArtistDetails, ArtistId, CollectionProgress, Price, RelationKind,
+    Release, ReleaseDetails, ReleaseId, ReleaseType, Review, Tag, TagDetails, TagId, User,
     UserDetails, UserId,
 };
-use crossbeam::channel::Sender;
-use std::collections::HashMap;
+use bevy::{
+    asset::RenderAssetUsages,
+    image::{CompressedImageFormats, Image, ImageSampler, ImageType},
+};
+use crossbeam::channel::{Receiver, Sender};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
 use url::Url;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Scraper {
     web: Sender<web::Request>,
+    parser: Parser,
+    checkpoints: Arc<Mutex<Checkpoints>>,
+    domains: Arc<Mutex<Domains>>,
+    selectors: Arc<Selectors>,
+    fan_lists: Vec<FanList>,
+    freshness: Freshness,
 }
 
 trait JsonExt {
@@ -23,6 +44,76 @@ impl JsonExt for str {
     }
 }
 
+/// The artists featured in a title, like `Song (feat. Someone & Someone Else)`
+fn featured(title: &str) -> Vec<&str> {
+    // Lowercasing only ascii keeps the byte offsets the same as in the title
+    let lower = title.to_ascii_lowercase();
+    let Some(start) = ["feat. ", "ft. ", "featuring "]
+        .into_iter()
+        .find_map(|marker| lower.find(marker).map(|index| index + marker.len()))
+    else {
+        return Vec::new();
+    };
+    let names = title[start..].split([')', ']']).next().unwrap_or_default();
+    Vec::from_iter(
+        names
+            .split([',', '&'])
+            .map(str::trim)
+            .filter(|name| !name.is_empty()),
+    )
+}
+
+/// Artists credited on a release other than the one whose page it's on, with their page if it's
+/// linked
+fn credits(url: &Url, page: &ReleasePage) -> Vec<(String, Option<Url>)> {
+    let own = &page.ld_data.by_artist.name;
+
+    let tracks = page.ld_data.track.iter().flat_map(|track| &track.elements);
+    let linked = tracks
+        .filter_map(|element| element.item.by_artist.as_ref())
+        .chain([&page.ld_data.by_artist])
+        .map(|artist| {
+            let link = artist
+                .id
+                .as_deref()
+                .and_then(|id| Url::parse(id).ok())
+                .filter(|link| link.host() != url.host());
+            (artist.name.as_str(), link)
+        });
+    let named = page
+        .data_tralbum
+        .trackinfo
+        .iter()
+        .filter_map(|track| track.artist.as_deref())
+        .chain(featured(&page.ld_data.name))
+        .chain(
+            page.data_tralbum
+                .trackinfo
+                .iter()
+                .flat_map(|track| featured(&track.title)),
+        )
+        .map(|name| (name, None));
+
+    let mut credits: Vec<(String, Option<Url>)> = Vec::new();
+    for (name, link) in linked.chain(named) {
+        if name.eq_ignore_ascii_case(own) {
+            continue;
+        }
+        match credits
+            .iter_mut()
+            .find(|(credited, _)| credited.eq_ignore_ascii_case(name))
+        {
+            Some((_, credited)) => {
+                if credited.is_none() {
+                    *credited = link;
+                }
+            }
+            None => credits.push((name.to_owned(), link)),
+        }
+    }
+    credits
+}
+
 trait ScraperExt {
     fn try_select(&self, selector: &str) -> eyre::Result<Vec<scraper::ElementRef<'_>>>;
 
@@ -73,6 +164,7 @@ struct ReleasePage {
     collectors: Collectors,
     discography: Option<String>,
     ld_data: ReleaseLdData,
+    tags: Vec<Tag>,
 }
 
 fn parse_rfc2822_date<'de, D>(deserializer: D) -> Result<jiff::Zoned, D::Error>
@@ -137,11 +229,38 @@ struct ReleaseLdData {
     name: String,
     track: Option<ItemList<Track>>,
     duration: Option<BrokenDuration>,
+    #[serde(rename = "inAlbum")]
+    in_album: Option<InAlbum>,
+    /// Tracks have their own offer, albums have an offer per format
+    offers: Option<Offer>,
+    #[serde(rename = "albumRelease", default)]
+    album_release: Vec<AlbumRelease>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AlbumRelease {
+    offers: Option<Offer>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Offer {
+    price: f64,
+    #[serde(rename = "priceCurrency")]
+    price_currency: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct InAlbum {
+    #[serde(rename = "@id")]
+    id: String,
 }
 
 #[derive(Debug, serde::Deserialize)]
 struct ByArtist {
     name: String,
+    /// The artist's own page, when they have one
+    #[serde(rename = "@id")]
+    id: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -160,6 +279,9 @@ struct ItemListElement<T> {
 #[derive(Debug, serde::Deserialize)]
 struct Track {
     duration: BrokenDuration,
+    /// Only set on tracks by a different artist to the release, like on a compilation
+    #[serde(rename = "byArtist")]
+    by_artist: Option<ByArtist>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -179,6 +301,31 @@ struct DataBand {
 #[derive(Debug, serde::Deserialize)]
 struct DataTralbum {
     current: DataTralbumCurrent,
+    #[serde(default)]
+    trackinfo: Vec<TrackInfo>,
+    #[serde(rename = "freeDownloadPage")]
+    free_download_page: Option<String>,
+    /// Physical formats, `null` when there are none
+    packages: Option<Vec<Package>>,
+    /// `null` for releases without any cover art
+    art_id: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Package {
+    /// `null` for unlimited runs
+    quantity_available: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TrackInfo {
+    id: u64,
+    title: String,
+    /// In seconds
+    duration: f64,
+    title_link: Option<String>,
+    /// Only set on tracks by a different artist to the release
+    artist: Option<String>,
 }
 
 #[allow(unused)]
@@ -188,21 +335,56 @@ struct DataTralbumCurrent {
     release_date: jiff::Zoned,
     #[serde(deserialize_with = "parse_rfc2822_date")]
     publish_date: jiff::Zoned,
+    /// The price set by the artist, or for name your price releases the suggested price
+    set_price: Option<f64>,
+    minimum_price: Option<f64>,
 }
 
 #[derive(Debug, serde::Deserialize)]
 struct Collectors {
-    // TODO: load more reviews
-    // more_reviews_available: bool,
+    #[serde(default)]
+    more_reviews_available: bool,
     more_thumbs_available: bool,
-    reviews: Vec<Review>,
+    reviews: Vec<FanReview>,
     thumbs: Vec<Fan>,
 }
 
 #[derive(Debug, serde::Deserialize)]
-struct Review {
+struct FanReview {
     fan_id: u64,
     username: String,
+    /// Missing from the last review of some pages, which then can't be paged on from
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    why: Option<String>,
+    #[serde(default)]
+    fav_track_title: Option<String>,
+}
+
+impl FanReview {
+    fn user(&self) -> User {
+        User {
+            id: UserId(self.fan_id),
+            url: format!("https://bandcamp.com/{}", self.username).into(),
+        }
+    }
+
+    fn review(self) -> (UserId, Review) {
+        (
+            UserId(self.fan_id),
+            Review {
+                text: self.why.unwrap_or_default(),
+                favorite_track: self.fav_track_title.filter(|title| !title.is_empty()),
+            },
+        )
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Reviews {
+    results: Vec<FanReview>,
+    more_available: bool,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -212,6 +394,15 @@ struct Fan {
     token: String,
 }
 
+impl Fan {
+    fn user(self) -> User {
+        User {
+            id: UserId(self.fan_id),
+            url: format!("https://bandcamp.com/{}", self.username).into(),
+        }
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct Thumbs {
     results: Vec<Fan>,
@@ -222,34 +413,361 @@ struct Thumbs {
 pub struct CollectionItem {
     item_id: u64,
     item_url: String,
+    /// Where paging would continue from after this item
+    #[serde(default)]
+    token: Option<String>,
 }
 
-#[derive(Debug, serde::Deserialize)]
-struct ItemCache {
-    collection: HashMap<String, CollectionItem>,
+impl CollectionItem {
+    /// Paging tokens start with the unix time the item was added, e.g. `1623456789:123456:a::`
+    fn acquired(&self) -> Option<Acquired> {
+        let seconds = self.token.as_deref()?.split(':').next()?.parse().ok()?;
+        jiff::Timestamp::from_second(seconds).ok().map(Acquired)
+    }
+
+    /// Whether the item was added after the one with the token `since`, items without a token are
+    /// assumed to be new
+    fn added_after(&self, since: &str) -> bool {
+        let seconds = |token: &str| token.split(':').next()?.parse::<u64>().ok();
+        self.token
+            .as_deref()
+            .is_none_or(|token| token != since && seconds(token) >= seconds(since))
+    }
+}
+
+/// How far a scrape of a fan's collection has to page back, collections are listed newest first so
+/// a refresh can stop at the first item the last scrape already saw
+#[derive(Debug, Default)]
+struct CollectionDelta {
+    /// The token of the newest item seen by the last scrape, only looked for by refreshes
+    since: Option<String>,
+    /// The token of the newest item seen by this scrape
+    newest: Option<String>,
+    caught_up: bool,
+}
+
+impl CollectionDelta {
+    /// The items added since the last scrape, in the order they're listed
+    fn filter(&mut self, items: Vec<CollectionItem>) -> Vec<CollectionItem> {
+        if self.newest.is_none() {
+            self.newest = items.first().and_then(|item| item.token.clone());
+        }
+        let Some(since) = &self.since else {
+            return items;
+        };
+        if self.caught_up {
+            return Vec::new();
+        }
+        let mut fresh = Vec::new();
+        for item in items {
+            if !item.added_after(since) {
+                self.caught_up = true;
+                break;
+            }
+            fresh.push(item);
+        }
+        fresh
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
 struct CollectionData {
     last_token: String,
+    /// The keys of the `item_cache` items in the collection, in the order it's listed, anything
+    /// else in there (like hidden items) isn't part of it
     sequence: Vec<String>,
 }
 
+/// Puts the collection items of a fan page in the order of its sequence, leaving out those not in
+/// it, passing each on as soon as everything before it has been
+#[derive(Debug, Default)]
+struct Sequenced {
+    /// The keys not yet passed on, `None` until the sequence has been decoded
+    sequence: Option<VecDeque<String>>,
+    /// Every key in the sequence
+    listed: HashSet<String>,
+    /// Items decoded before the sequence, or before the items listed ahead of them
+    waiting: HashMap<String, CollectionItem>,
+    /// How many items have been passed on
+    released: usize,
+}
+
+impl Sequenced {
+    fn start(&mut self, sequence: Vec<String>) -> Vec<CollectionItem> {
+        self.listed = HashSet::from_iter(sequence.iter().cloned());
+        self.waiting.retain(|key, _| self.listed.contains(key));
+        self.sequence = Some(sequence.into());
+        self.ready()
+    }
+
+    fn push(&mut self, items: Vec<(String, CollectionItem)>) -> Vec<CollectionItem> {
+        for (key, item) in items {
+            if self.sequence.is_none() || self.listed.contains(&key) {
+                self.waiting.insert(key, item);
+            }
+        }
+        self.ready()
+    }
+
+    fn ready(&mut self) -> Vec<CollectionItem> {
+        let mut ready = Vec::new();
+        let Some(sequence) = &mut self.sequence else {
+            return ready;
+        };
+        while let Some(item) = sequence.front().and_then(|key| self.waiting.remove(key)) {
+            sequence.pop_front();
+            ready.push(item);
+        }
+        self.released += ready.len();
+        ready
+    }
+
+    /// Everything left once the whole blob is decoded, skipping over listed items that weren't in
+    /// the `item_cache`
+    fn finish(&mut self) -> Vec<CollectionItem> {
+        let ready = Vec::from_iter(
+            self.sequence
+                .take()
+                .into_iter()
+                .flatten()
+                .filter_map(|key| self.waiting.remove(&key)),
+        );
+        self.waiting.clear();
+        self.released += ready.len();
+        ready
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct FanData {
     fan_id: u64,
     name: String,
     username: String,
+    #[serde(default)]
+    location: Option<String>,
 }
 
+/// A paging token from before the first page of the fan apis, which page from newest to oldest
+const LATEST_TOKEN: &str = "9999999999:0:a::";
+
+/// How many collection items to decode before sending them on
+const COLLECTION_CHUNK: usize = 500;
+
+/// Pieces of a fan page, sent as they're decoded so that huge collections don't need to be held
+/// in memory all at once before any of them can be used
+#[derive(Debug)]
+enum FanPagePart {
+    Fan(FanData),
+    /// The keys of the collection items in order, from the `collection_data`
+    Sequence(Vec<String>),
+    /// Collection items along with their keys, in the order they're in the `item_cache`
+    Collection(Vec<(String, CollectionItem)>),
+    Done {
+        collection_count: usize,
+        last_token: String,
+    },
+}
+
+/// Decodes the fan page blob, sending parts of it out as it goes
+struct FanPageSeed<'a> {
+    parts: &'a Sender<FanPagePart>,
+}
+
+/// Decodes the `item_cache` of the fan page blob
+struct ItemCacheSeed<'a> {
+    parts: &'a Sender<FanPagePart>,
+}
+
+/// Decodes the collection in the `item_cache`, sending it on in chunks
+struct CollectionSeed<'a> {
+    parts: &'a Sender<FanPagePart>,
+}
+
+fn send_part<E: serde::de::Error>(parts: &Sender<FanPagePart>, part: FanPagePart) -> Result<(), E> {
+    parts
+        .send(part)
+        .map_err(|_| E::custom("fan page parts receiver dropped"))
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for FanPageSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for FanPageSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("a fan page blob")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        use serde::de::{Error, IgnoredAny};
+
+        let mut fan = false;
+        let mut collection_count = None;
+        let mut collection_data = None::<CollectionData>;
+        let mut item_cache = false;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "fan_data" => {
+                    send_part(self.parts, FanPagePart::Fan(map.next_value()?))?;
+                    fan = true;
+                }
+                "collection_count" => collection_count = Some(map.next_value()?),
+                "collection_data" => {
+                    let mut data = map.next_value::<CollectionData>()?;
+                    send_part(
+                        self.parts,
+                        FanPagePart::Sequence(std::mem::take(&mut data.sequence)),
+                    )?;
+                    collection_data = Some(data);
+                }
+                "item_cache" => {
+                    map.next_value_seed(ItemCacheSeed { parts: self.parts })?;
+                    item_cache = true;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !fan {
+            Err(A::Error::missing_field("fan_data"))?;
+        }
+        if !item_cache {
+            Err(A::Error::missing_field("item_cache"))?;
+        }
+        let collection_count =
+            collection_count.ok_or_else(|| A::Error::missing_field("collection_count"))?;
+        let collection_data =
+            collection_data.ok_or_else(|| A::Error::missing_field("collection_data"))?;
+        send_part(
+            self.parts,
+            FanPagePart::Done {
+                collection_count,
+                last_token: collection_data.last_token,
+            },
+        )
+    }
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for ItemCacheSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for ItemCacheSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("an item cache")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        use serde::de::{Error, IgnoredAny};
+
+        let mut collection = false;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "collection" {
+                map.next_value_seed(CollectionSeed { parts: self.parts })?;
+                collection = true;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        if !collection {
+            Err(A::Error::missing_field("collection"))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for CollectionSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for CollectionSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("a map of collection items")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut chunk = Vec::with_capacity(COLLECTION_CHUNK);
+        while let Some(entry) = map.next_entry::<String, CollectionItem>()? {
+            chunk.push(entry);
+            if chunk.len() == COLLECTION_CHUNK {
+                send_part(
+                    self.parts,
+                    FanPagePart::Collection(std::mem::take(&mut chunk)),
+                )?;
+            }
+        }
+        if !chunk.is_empty() {
+            send_part(self.parts, FanPagePart::Collection(chunk))?;
+        }
+        Ok(())
+    }
+}
+
+/// A page of the fans a fan follows, or that follow them
+#[derive(Debug, serde::Deserialize)]
+struct Follows {
+    more_available: bool,
+    last_token: String,
+    #[serde(alias = "followeds", alias = "followers")]
+    fans: Vec<FollowedFan>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct FollowedFan {
+    fan_id: u64,
+    #[serde(alias = "trackpipe_url")]
+    url: String,
+}
+
+/// A page of the artists and labels a fan follows
 #[derive(Debug, serde::Deserialize)]
-struct FanPage {
-    fan_data: FanData,
-    collection_count: usize,
-    collection_data: CollectionData,
-    item_cache: ItemCache,
+struct FollowedBands {
+    more_available: bool,
+    last_token: String,
+    followeds: Vec<FollowedBand>,
 }
 
+#[derive(Debug, serde::Deserialize)]
+struct FollowedBand {
+    band_id: u64,
+    url_hints: UrlHints,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UrlHints {
+    subdomain: String,
+    custom_domain: Option<String>,
+}
+
+impl UrlHints {
+    /// Bands with a custom domain are still found at their subdomain, which is what they're known
+    /// by everywhere else
+    fn url(&self) -> String {
+        format!("https://{}.bandcamp.com/", self.subdomain)
+    }
+}
+
+/// A page of a fan's collection or wishlist
 #[derive(Debug, serde::Deserialize)]
 struct Collections {
     more_available: bool,
@@ -262,6 +780,106 @@ struct ArtistPage {
     data_band: DataBand,
     music_grid_items: Vec<MusicGridItem>,
     client_items: Option<Vec<ClientItem>>,
+    /// Labels link to a page listing the artists on their roster
+    roster: Option<String>,
+    location: Option<String>,
+    supporters: Option<Supporters>,
+}
+
+/// The fans supporting an artist across all their releases
+#[derive(Debug, serde::Deserialize)]
+struct Supporters {
+    more_thumbs_available: bool,
+    thumbs: Vec<Fan>,
+}
+
+#[derive(Debug)]
+struct RosterItem {
+    band_id: u64,
+    href: String,
+}
+
+/// A page of releases from the discover api
+#[derive(Debug, serde::Deserialize)]
+struct Discover {
+    results: Vec<DiscoverItem>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DiscoverItem {
+    id: u64,
+    item_url: String,
+}
+
+/// The fan whose feed page it is, from the identities every page has for the logged in user
+#[derive(Debug, serde::Deserialize)]
+struct FeedPageData {
+    identities: Identities,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Identities {
+    fan: Option<Identity>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Identity {
+    id: u64,
+    username: String,
+}
+
+/// A page of a fan's feed, newest first
+#[derive(Debug, serde::Deserialize)]
+struct Feed {
+    stories: FeedStories,
+    #[serde(default)]
+    fan_info: HashMap<String, FeedFan>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct FeedStories {
+    entries: Vec<FeedEntry>,
+    /// Where paging would continue from after this page
+    oldest_story_date: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct FeedEntry {
+    /// `nr` for new releases from followed artists and labels, `fp` for purchases by followed fans
+    story_type: String,
+    fan_id: Option<u64>,
+    band_id: u64,
+    tralbum_id: u64,
+    item_url: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct FeedFan {
+    fan_id: u64,
+    username: String,
+}
+
+/// How many pages of a feed to go back through, it's the recent activity that's interesting
+const FEED_PAGES: usize = 5;
+
+#[derive(Debug, serde::Deserialize)]
+struct Search {
+    auto: SearchAuto,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SearchAuto {
+    results: Vec<SearchItem>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SearchItem {
+    /// `b` for artists and labels, `a` for albums, `t` for tracks and `f` for fans
+    #[serde(rename = "type")]
+    ty: String,
+    id: u64,
+    item_url_root: Option<String>,
+    item_url_path: Option<String>,
 }
 
 #[allow(unused)]
@@ -286,95 +904,243 @@ struct ClientItem {
 }
 
 impl Scraper {
-    pub(crate) fn new(web: Sender<web::Request>) -> Self {
-        Self { web }
+    pub(crate) fn new(
+        web: Sender<web::Request>,
+        parser: Parser,
+        checkpoints: Arc<Mutex<Checkpoints>>,
+        domains: Arc<Mutex<Domains>>,
+        selectors: Arc<Selectors>,
+        fan_lists: Vec<FanList>,
+    ) -> Self {
+        Self {
+            web,
+            parser,
+            checkpoints,
+            domains,
+            selectors,
+            fan_lists,
+            freshness: Freshness::Cached,
+        }
+    }
+
+    /// A scraper that bypasses the web cache for all pages it retrieves
+    pub(crate) fn refreshing(&self) -> Self {
+        self.with_freshness(Freshness::Refresh)
+    }
+
+    /// A scraper that only uses pages from the web cache, for parsing them again
+    pub(crate) fn offline(&self) -> Self {
+        self.with_freshness(Freshness::Offline)
     }
 
+    fn with_freshness(&self, freshness: Freshness) -> Self {
+        Self {
+            freshness,
+            ..self.clone()
+        }
+    }
+
+    /// Scrape a release, sending everything related to it on as it's found, the release itself is
+    /// returned once it's done
     #[culpa::try_fn]
-    #[tracing::instrument(skip(self, on_release, on_release_artist, on_fans), fields(%url))]
+    #[tracing::instrument(skip(self, on_part), fields(%url))]
     pub(crate) fn scrape_release(
         &self,
         url: &Url,
-        on_release: impl FnOnce(Release, ReleaseDetails) -> eyre::Result<()>,
-        on_release_artist: impl FnOnce(Artist) -> eyre::Result<()>,
-        mut on_fans: impl FnMut(Vec<User>) -> eyre::Result<()>,
-    ) -> eyre::Result<()> {
+        mut on_part: impl FnMut(&Release, ReleasePart) -> eyre::Result<()>,
+    ) -> eyre::Result<(Release, ReleaseDetails)> {
+        let url = &self.canonical(url)?;
         let page = self.scrape_release_page(url)?;
 
+        let credits = credits(url, &page);
+
         let mut more_available = page.collectors.more_thumbs_available;
 
         // For some reason some releases don't have a release date,
         // fallback to the publish date for those
-        let mut released = page.data_tralbum.current.release_date;
+        let mut released = page.data_tralbum.current.release_date.clone();
         if released.timestamp() == jiff::Timestamp::UNIX_EPOCH {
-            released = page.data_tralbum.current.publish_date;
+            released = page.data_tralbum.current.publish_date.clone();
         }
 
-        on_release(
-            Release {
-                id: ReleaseId(page.properties.item_id),
-                url: url.into(),
-            },
-            ReleaseDetails {
-                ty: match page.properties.item_type.as_str() {
-                    "a" => ReleaseType::Album,
-                    "t" => ReleaseType::Track,
-                    other => Err(eyre::eyre!("unknown release type {other}"))?,
+        let ty = match page.properties.item_type.as_str() {
+            "a" => ReleaseType::Album,
+            "t" => ReleaseType::Track,
+            other => Err(eyre::eyre!("unknown release type {other}"))?,
+        };
+
+        // Track pages list just themselves
+        let track_list = if matches!(ty, ReleaseType::Album) {
+            eyre::Result::<Vec<_>, _>::from_iter(page.data_tralbum.trackinfo.into_iter().map(
+                |track| {
+                    eyre::Result::<_>::Ok(AlbumTrack {
+                        id: ReleaseId(track.id),
+                        url: track
+                            .title_link
+                            .map(|link| url.join(&link))
+                            .transpose()?
+                            .map(Into::into),
+                        title: track.title,
+                        length: jiff::SignedDuration::try_from_secs_f64(track.duration)?,
+                    })
                 },
-                title: page.ld_data.name,
-                artist: page.ld_data.by_artist.name,
-                tracks: page.ld_data.track.as_ref().map(|track| track.length),
-                length: page
-                    .ld_data
-                    .duration
-                    .map(|d| d.0)
-                    .or_else(|| {
-                        page.ld_data.track.and_then(|track| {
-                            track
-                                .elements
-                                .iter()
-                                .map(|el| el.item.duration.0)
-                                .reduce(|a, b| a + b)
-                        })
+            ))?
+        } else {
+            Vec::new()
+        };
+
+        let current = &page.data_tralbum.current;
+        // Paying nothing is only possible when naming your price
+        let name_your_price = match (current.minimum_price, current.set_price) {
+            (Some(minimum), Some(set)) => minimum < set || minimum == 0.,
+            (Some(minimum), None) => minimum == 0.,
+            _ => false,
+        };
+        let packages = page.data_tralbum.packages.as_deref().unwrap_or_default();
+        let sold_out = !packages.is_empty()
+            && packages
+                .iter()
+                .all(|package| package.quantity_available == Some(0));
+
+        let release = Release {
+            id: ReleaseId(page.properties.item_id),
+            url: url.into(),
+        };
+        let details = ReleaseDetails {
+            ty,
+            title: page.ld_data.name,
+            artist: page.ld_data.by_artist.name,
+            tracks: page.ld_data.track.as_ref().map(|track| track.length),
+            length: page
+                .ld_data
+                .duration
+                .map(|d| d.0)
+                .or_else(|| {
+                    page.ld_data.track.and_then(|track| {
+                        track
+                            .elements
+                            .iter()
+                            .map(|el| el.item.duration.0)
+                            .reduce(|a, b| a + b)
                     })
-                    .unwrap_or_default(),
-                released: released.round(jiff::Unit::Day)?,
-            },
+                })
+                .unwrap_or_default(),
+            released: released.round(jiff::Unit::Day)?,
+            // Filled in once all the fans have been scraped
+            fan_count: 0,
+            // Standalone tracks list themselves as their album
+            album: page
+                .ld_data
+                .in_album
+                .map(|album| album.id)
+                .filter(|album| album != url.as_str())
+                .map(Into::into),
+            // The digital release is listed first, before any physical formats
+            price: page
+                .ld_data
+                .offers
+                .or_else(|| {
+                    page.ld_data
+                        .album_release
+                        .into_iter()
+                        .find_map(|release| release.offers)
+                })
+                .map(|offer| Price {
+                    amount: offer.price,
+                    currency: offer.price_currency,
+                }),
+            name_your_price,
+            free_download: page.data_tralbum.free_download_page.is_some(),
+            sold_out,
+            // The 150px square thumbnail, the pages only link the full size image
+            art: page
+                .data_tralbum
+                .art_id
+                .map(|id| format!("https://f4.bcbits.com/img/a{id:010}_7.jpg")),
+            // The same tags as the tag nodes, so the details and graph never disagree
+            tags: Vec::from_iter(page.tags.iter().map(|tag| tag.details.name.clone())),
+            track_list,
+        };
+
+        on_part(
+            &release,
+            ReleasePart::Artist(
+                RelationKind::Artist,
+                Artist {
+                    id: ArtistId(page.data_band.id),
+                    url: page
+                        .discography
+                        .map(|discography| url.join(&discography))
+                        .unwrap_or_else(|| url.join("/"))?
+                        .into(),
+                },
+            ),
         )?;
 
-        on_release_artist(Artist {
-            id: ArtistId(page.data_band.id),
-            url: page
-                .discography
-                .map(|discography| url.join(&discography))
-                .unwrap_or_else(|| url.join("/"))?
-                .into(),
-        })?;
+        // Credited artists with their own page are found by id like any other artist, as long as
+        // it's known without retrieving their page, the rest only have their name to go on
+        let mut names = Vec::new();
+        for (name, link) in credits {
+            match link.map(|link| self.known_artist(link)) {
+                Some(Ok(Some(artist))) => {
+                    on_part(&release, ReleasePart::Artist(RelationKind::Credit, artist))?
+                }
+                Some(Ok(None)) | None => names.push(name),
+                Some(Err(error)) => {
+                    tracing::warn!(?error, %name, "failed to find credited artist");
+                    names.push(name);
+                }
+            }
+        }
+        if !names.is_empty() {
+            on_part(&release, ReleasePart::Credits(names))?;
+        }
+
+        on_part(&release, ReleasePart::Tags(page.tags))?;
 
         let token = page
             .collectors
             .thumbs
             .last()
             .map(|thumb| thumb.token.clone());
-        on_fans(
-            page.collectors
-                .reviews
-                .into_iter()
-                .map(|review| User {
-                    id: UserId(review.fan_id),
-                    url: format!("https://bandcamp.com/{}", review.username).into(),
-                })
-                .collect(),
+        let mut review_token = page
+            .collectors
+            .reviews
+            .last()
+            .and_then(|review| review.token.clone());
+        // Reviewers are fans too, sent before their reviews so the reviews have a link to go on
+        on_part(
+            &release,
+            ReleasePart::Fans(
+                page.collectors
+                    .reviews
+                    .iter()
+                    .map(FanReview::user)
+                    .collect(),
+            ),
         )?;
-        on_fans(
-            page.collectors
-                .thumbs
-                .into_iter()
-                .map(|thumb| User {
-                    id: UserId(thumb.fan_id),
-                    url: format!("https://bandcamp.com/{}", thumb.username).into(),
-                })
-                .collect(),
+        on_part(
+            &release,
+            ReleasePart::Reviews(
+                page.collectors
+                    .reviews
+                    .into_iter()
+                    .map(FanReview::review)
+                    .collect(),
+            ),
+        )?;
+        on_part(
+            &release,
+            ReleasePart::Fans(
+                page.collectors
+                    .thumbs
+                    .into_iter()
+                    .map(|thumb| User {
+                        id: UserId(thumb.fan_id),
+                        url: format!("https://bandcamp.com/{}", thumb.username).into(),
+                    })
+                    .collect(),
+            ),
         )?;
 
         if let Some(mut token) = token {
@@ -382,237 +1148,615 @@ impl Scraper {
                 let response = self.scrape_collectors_api(url, &page.properties, &token)?;
                 token = response.results.last().unwrap().token.clone();
                 more_available = response.more_available;
-                on_fans(
+                on_part(
+                    &release,
+                    ReleasePart::Fans(
+                        response
+                            .results
+                            .into_iter()
+                            .map(|thumb| User {
+                                id: UserId(thumb.fan_id),
+                                url: format!("https://bandcamp.com/{}", thumb.username).into(),
+                            })
+                            .collect(),
+                    ),
+                )?;
+            }
+        }
+
+        let mut more_reviews = page.collectors.more_reviews_available;
+        while let Some(token) = review_token.take().filter(|_| more_reviews) {
+            let response = self.scrape_reviews_api(url, &page.properties, &token)?;
+            review_token = response
+                .results
+                .last()
+                .and_then(|review| review.token.clone());
+            more_reviews = response.more_available;
+            on_part(
+                &release,
+                ReleasePart::Fans(response.results.iter().map(FanReview::user).collect()),
+            )?;
+            on_part(
+                &release,
+                ReleasePart::Reviews(
                     response
                         .results
                         .into_iter()
-                        .map(|thumb| User {
-                            id: UserId(thumb.fan_id),
-                            url: format!("https://bandcamp.com/{}", thumb.username).into(),
-                        })
+                        .map(FanReview::review)
                         .collect(),
-                )?;
-            }
+                ),
+            )?;
         }
+
+        (release, details)
     }
 
+    /// Scrape a fan, sending their collection and the lists asked for on as they're found, the fan
+    /// themselves is returned once it's done
     #[culpa::try_fn]
-    #[tracing::instrument(skip(self, on_fan, on_collection))]
+    #[tracing::instrument(skip(self, on_part))]
     pub(crate) fn scrape_fan(
         &self,
         url: &Url,
-        on_fan: impl FnOnce(User, UserDetails) -> eyre::Result<()>,
-        mut on_collection: impl FnMut(Vec<Release>) -> eyre::Result<()>,
-    ) -> eyre::Result<()> {
-        let mut page = self.scrape_fan_page(url)?;
-
-        on_fan(
-            User {
-                id: UserId(page.fan_data.fan_id),
-                url: format!("https://bandcamp.com/{}", page.fan_data.username).into(),
-            },
-            UserDetails {
-                name: page.fan_data.name,
-                username: page.fan_data.username,
+        mut on_part: impl FnMut(&User, FanPart) -> eyre::Result<()>,
+    ) -> eyre::Result<(User, UserDetails)> {
+        let (parts_tx, parts) = crossbeam::channel::unbounded();
+        let result = self.scrape_fan_page(url, parts_tx)?;
+
+        let release = |item: CollectionItem| Release {
+            id: ReleaseId(item.item_id),
+            url: item.item_url.into(),
+        };
+        let collected = |item: CollectionItem| {
+            let acquired = item.acquired();
+            (release(item), acquired)
+        };
+
+        // The collection could come before the fan in the blob, so hold it until the fan arrives
+        let mut fan = None;
+        let mut pending = Vec::new();
+        let mut sequenced = Sequenced::default();
+        let mut delta = CollectionDelta::default();
+        let mut done = None;
+        // The total only arrives at the end of the blob, until then it's at least what's decoded
+        let progress = |scraped, done: &Option<(usize, String)>| CollectionProgress {
+            scraped,
+            total: done
+                .as_ref()
+                .map_or(scraped, |(total, _)| *total)
+                .max(scraped),
+        };
+        for part in &parts {
+            let ready = match part {
+                FanPagePart::Fan(data) => {
+                    if self.freshness == Freshness::Refresh {
+                        delta.since = self.checkpoints.lock().unwrap().newest(data.fan_id)?;
+                    }
+                    fan = Some((
+                        User {
+                            id: UserId(data.fan_id),
+                            url: format!("https://bandcamp.com/{}", data.username).into(),
+                        },
+                        UserDetails {
+                            name: data.name,
+                            username: data.username,
+                            location: data.location.filter(|location| !location.is_empty()),
+                        },
+                    ));
+                    std::mem::take(&mut pending)
+                }
+                FanPagePart::Sequence(sequence) => sequenced.start(sequence),
+                FanPagePart::Collection(items) => sequenced.push(items),
+                FanPagePart::Done {
+                    collection_count,
+                    last_token,
+                } => {
+                    done = Some((collection_count, last_token));
+                    sequenced.finish()
+                }
+            };
+            let Some((user, _)) = &fan else {
+                pending.extend(ready);
+                continue;
+            };
+            let items = delta.filter(ready);
+            if !items.is_empty() {
+                on_part(
+                    user,
+                    FanPart::Collection(
+                        items.into_iter().map(collected).collect(),
+                        progress(sequenced.released, &done),
+                    ),
+                )?;
+            }
+        }
+        // Any error decoding the blob surfaces here, once it has stopped sending parts
+        result.recv()??;
+
+        let ((user, details), (collection_count, last_token)) = fan
+            .zip(done)
+            .ok_or_else(|| eyre::eyre!("fan page ended early"))?;
+        let fan_id = user.id.0;
+        let decoded = sequenced.released;
+        let mut more_available = decoded < collection_count;
+
+        // Resume paging where an earlier, interrupted, scrape of this fan stopped, everything
+        // before there already reached the graph
+        let resumed = if self.freshness == Freshness::Refresh {
+            None
+        } else {
+            self.checkpoints.lock().unwrap().get(fan_id)
+        };
+        let mut checkpoint = match resumed {
+            Some(resumed) => {
+                more_available = true;
+                resumed
+            }
+            None => Checkpoint {
+                last_token,
+                scraped: decoded,
             },
-        )?;
+        };
 
-        let items = eyre::Result::<Vec<_>, _>::from_iter(
-            page.collection_data.sequence.into_iter().map(|s| {
-                page.item_cache
-                    .collection
-                    .remove(&s)
-                    .ok_or_else(|| eyre::eyre!("cache missing collection item"))
-            }),
-        )?;
-        let mut last_token = page.collection_data.last_token;
-        let mut more_available = items.len() < page.collection_count;
-        on_collection(
-            items
-                .into_iter()
-                .map(|item| Release {
-                    id: ReleaseId(item.item_id),
-                    url: item.item_url.into(),
-                })
-                .collect(),
-        )?;
-
-        while more_available {
-            let response = self.scrape_collections_api(page.fan_data.fan_id, &last_token)?;
+        while more_available && !delta.caught_up {
+            let response = self.scrape_collections_api(fan_id, &checkpoint.last_token)?;
             more_available = response.more_available;
-            last_token = response.last_token;
-            on_collection(
-                response
-                    .items
-                    .into_iter()
-                    .map(|item| Release {
-                        id: ReleaseId(item.item_id),
-                        url: item.item_url.into(),
-                    })
-                    .collect(),
+            checkpoint.last_token = response.last_token;
+            checkpoint.scraped += response.items.len();
+            let items = delta.filter(response.items);
+            // Everything past the newest item already seen is known, so the collection is done
+            let scraped = if delta.caught_up {
+                collection_count
+            } else {
+                checkpoint.scraped
+            };
+            on_part(
+                &user,
+                FanPart::Collection(
+                    items.into_iter().map(collected).collect(),
+                    CollectionProgress {
+                        scraped,
+                        total: collection_count.max(scraped),
+                    },
+                ),
             )?;
+            self.checkpoints.lock().unwrap().set(fan_id, &checkpoint);
+        }
+
+        {
+            let mut checkpoints = self.checkpoints.lock().unwrap();
+            checkpoints.clear(fan_id);
+            if let Some(newest) = &delta.newest {
+                checkpoints.set_newest(fan_id, newest)?;
+            }
+        }
+
+        // Tokens start with a timestamp, so one from the far future pages from the most recent
+        if self.fan_lists.contains(&FanList::Wishlist) {
+            let mut token = LATEST_TOKEN.to_owned();
+            loop {
+                let response = self.scrape_wishlist_api(fan_id, &token)?;
+                token = response.last_token;
+                if !response.items.is_empty() {
+                    on_part(
+                        &user,
+                        FanPart::Wishlist(response.items.into_iter().map(release).collect()),
+                    )?;
+                }
+                if !response.more_available {
+                    break;
+                }
+            }
         }
+
+        if self.fan_lists.contains(&FanList::Follows) {
+            for follow in [Follow::Following, Follow::Followers] {
+                let mut token = LATEST_TOKEN.to_owned();
+                loop {
+                    let response = self.scrape_follows_api(fan_id, follow, &token)?;
+                    token = response.last_token;
+                    if !response.fans.is_empty() {
+                        on_part(
+                            &user,
+                            FanPart::Follows(
+                                follow,
+                                Vec::from_iter(response.fans.into_iter().map(|fan| User {
+                                    id: UserId(fan.fan_id),
+                                    url: fan.url.into(),
+                                })),
+                            ),
+                        )?;
+                    }
+                    if !response.more_available {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if self.fan_lists.contains(&FanList::FollowedArtists) {
+            let mut token = LATEST_TOKEN.to_owned();
+            loop {
+                let response = self.scrape_followed_bands_api(fan_id, &token)?;
+                token = response.last_token;
+                if !response.followeds.is_empty() {
+                    {
+                        let domains = self.domains.lock().unwrap();
+                        for band in &response.followeds {
+                            if let Some(domain) = &band.url_hints.custom_domain {
+                                domains.set(domain, band.band_id)?;
+                                domains.set(
+                                    &format!("{}.bandcamp.com", band.url_hints.subdomain),
+                                    band.band_id,
+                                )?;
+                            }
+                        }
+                    }
+                    on_part(
+                        &user,
+                        FanPart::FollowedArtists(Vec::from_iter(
+                            response.followeds.into_iter().map(|band| Artist {
+                                id: ArtistId(band.band_id),
+                                url: band.url_hints.url().into(),
+                            }),
+                        )),
+                    )?;
+                }
+                if !response.more_available {
+                    break;
+                }
+            }
+        }
+
+        (user, details)
     }
 
+    /// Scrape an artist, sending their releases, roster and supporters on as they're found, the
+    /// artist themselves is returned once it's done along with the other urls their pages are at
     #[culpa::try_fn]
-    #[tracing::instrument(skip(self, on_artist, on_releases))]
+    #[tracing::instrument(skip(self, on_part))]
     pub(crate) fn scrape_artist(
         &self,
         url: &Url,
-        on_artist: impl FnOnce(Artist, ArtistDetails) -> eyre::Result<()>,
-        mut on_releases: impl FnMut(Vec<Release>) -> eyre::Result<()>,
-    ) -> eyre::Result<()> {
+        mut on_part: impl FnMut(&Artist, ArtistPart) -> eyre::Result<()>,
+    ) -> eyre::Result<(Artist, ArtistDetails, Vec<String>)> {
+        let url = &self.canonical(url)?;
         let page = self.scrape_artist_page(url)?;
+        let hosts = self.domains.lock().unwrap().hosts(page.data_band.id)?;
 
-        on_artist(
-            Artist {
-                id: ArtistId(page.data_band.id),
-                url: url.into(),
-            },
-            ArtistDetails {
-                name: page.data_band.name,
-            },
-        )?;
+        let artist = Artist {
+            id: ArtistId(page.data_band.id),
+            url: url.into(),
+        };
+        let details = ArtistDetails {
+            name: page.data_band.name,
+            location: page.location,
+        };
+        let aliases = Vec::from_iter(
+            hosts
+                .iter()
+                .filter(|&host| Some(host.as_str()) != url.host_str())
+                .map(|host| format!("https://{host}/")),
+        );
+
+        // Labels list releases hosted on their artists' own pages (and sometimes artists list ones
+        // hosted on their label's), those are kept apart so the release is attributed to both
+        let mut send = |releases: Vec<(ReleaseId, Url)>| {
+            let (own, listed) = releases.into_iter().partition::<Vec<_>, _>(|(_, release)| {
+                release.host() == url.host()
+                    || release
+                        .host_str()
+                        .is_some_and(|host| hosts.iter().any(|own| own == host))
+            });
+            for (kind, releases) in [(RelationKind::Artist, own), (RelationKind::Label, listed)] {
+                if !releases.is_empty() {
+                    on_part(
+                        &artist,
+                        ArtistPart::Releases(
+                            kind,
+                            Vec::from_iter(releases.into_iter().map(|(id, url)| Release {
+                                id,
+                                url: url.into(),
+                            })),
+                        ),
+                    )?;
+                }
+            }
+            eyre::Result::<_>::Ok(())
+        };
 
-        on_releases(eyre::Result::<Vec<_>, _>::from_iter(
+        send(eyre::Result::<Vec<_>, _>::from_iter(
             page.music_grid_items.into_iter().map(|item| {
-                eyre::Result::<_>::Ok(Release {
-                    id: ReleaseId(item.item_id),
-                    url: url.join(&item.href)?.into(),
-                })
+                eyre::Result::<_>::Ok((ReleaseId(item.item_id), url.join(&item.href)?))
             }),
         )?)?;
 
-        on_releases(eyre::Result::<Vec<_>, _>::from_iter(
-            page.client_items.into_iter().flatten().map(|item| {
-                eyre::Result::<_>::Ok(Release {
-                    id: ReleaseId(item.id),
-                    url: url.join(&item.page_url)?.into(),
-                })
-            }),
+        send(eyre::Result::<Vec<_>, _>::from_iter(
+            page.client_items
+                .into_iter()
+                .flatten()
+                .map(|item| eyre::Result::<_>::Ok((ReleaseId(item.id), url.join(&item.page_url)?))),
         )?)?;
+
+        if let Some(roster) = page.roster {
+            let roster = self.scrape_roster_page(&url.join(&roster)?)?;
+            let roster = eyre::Result::<Vec<_>, _>::from_iter(
+                roster
+                    .into_iter()
+                    // Labels sometimes list themselves alongside their artists
+                    .filter(|item| item.band_id != page.data_band.id)
+                    .map(|item| {
+                        // Roster links carry the label in their query, and may go straight to a tab
+                        let mut artist = url.join(&item.href)?;
+                        artist.set_query(None);
+                        artist.set_path("/");
+                        eyre::Result::<_>::Ok(Artist {
+                            id: ArtistId(item.band_id),
+                            url: artist.into(),
+                        })
+                    }),
+            )?;
+            on_part(&artist, ArtistPart::Roster(roster))?;
+        }
+
+        if let Some(supporters) = page.supporters {
+            let mut more_available = supporters.more_thumbs_available;
+            let mut token = supporters.thumbs.last().map(|thumb| thumb.token.clone());
+            on_part(
+                &artist,
+                ArtistPart::Supporters(Vec::from_iter(
+                    supporters.thumbs.into_iter().map(Fan::user),
+                )),
+            )?;
+            while let (true, Some(last)) = (more_available, token) {
+                let response = self.scrape_supporters_api(url, page.data_band.id, &last)?;
+                more_available = response.more_available;
+                token = response.results.last().map(|thumb| thumb.token.clone());
+                on_part(
+                    &artist,
+                    ArtistPart::Supporters(Vec::from_iter(
+                        response.results.into_iter().map(Fan::user),
+                    )),
+                )?;
+            }
+        }
+
+        (artist, details, aliases)
     }
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url))]
     fn scrape_release_page(&self, url: &Url) -> eyre::Result<ReleasePage> {
         let data = self.get(url.clone())?;
-        let document = scraper::Html::parse_document(&data);
-
-        let properties = document
-            .try_select_one("meta[name=bc-page-properties]")?
-            .value()
-            .attr("content")
-            .ok_or_else(|| eyre::eyre!("missing data-blob"))?
-            .parse_json()?;
-
-        let data_band = document
-            .try_select_one("[data-band]")?
-            .value()
-            .attr("data-band")
-            .ok_or_else(|| eyre::eyre!("missing data-band"))?
-            .parse_json()?;
-
-        let data_tralbum = document
-            .try_select_one("[data-tralbum]")?
-            .value()
-            .attr("data-tralbum")
-            .ok_or_else(|| eyre::eyre!("missing data-tralbum"))?
-            .parse_json()?;
-
-        let collectors = document
-            .try_select_one("#collectors-data")?
-            .value()
-            .attr("data-blob")
-            .ok_or_else(|| eyre::eyre!("missing data-blob"))?
-            .parse_json()?;
-
-        let discography = document
-            .try_select_one("#discography a.link-and-title")
-            .ok()
-            .and_then(|el| el.value().attr("href").map(String::from));
-
-        let ld_data = document
-            .try_select_one(r#"script[type="application/ld+json"]"#)?
-            .text()
-            .collect::<String>()
-            .parse_json()?;
-
-        ReleasePage {
-            properties,
-            data_band,
-            data_tralbum,
-            collectors,
-            discography,
-            ld_data,
-        }
+        let selectors = self.selectors.clone();
+        let page_url = url.clone();
+        let page: ReleasePage = self
+            .parser
+            .parse(url, PageKind::Release, data, move |data| {
+                let url = &page_url;
+                let selectors = &selectors.release;
+                let document = scraper::Html::parse_document(data);
+
+                let properties = document
+                    .try_select_one(&selectors.properties)?
+                    .value()
+                    .attr(&selectors.properties_attr)
+                    .ok_or_else(|| eyre::eyre!("missing {}", selectors.properties_attr))?
+                    .parse_json()?;
+
+                let data_band = document
+                    .try_select_one(&selectors.band)?
+                    .value()
+                    .attr(&selectors.band_attr)
+                    .ok_or_else(|| eyre::eyre!("missing {}", selectors.band_attr))?
+                    .parse_json()?;
+
+                let data_tralbum = document
+                    .try_select_one(&selectors.tralbum)?
+                    .value()
+                    .attr(&selectors.tralbum_attr)
+                    .ok_or_else(|| eyre::eyre!("missing {}", selectors.tralbum_attr))?
+                    .parse_json()?;
+
+                let collectors = document
+                    .try_select_one(&selectors.collectors)?
+                    .value()
+                    .attr(&selectors.collectors_attr)
+                    .ok_or_else(|| eyre::eyre!("missing {}", selectors.collectors_attr))?
+                    .parse_json()?;
+
+                let discography = document
+                    .try_select_one(&selectors.discography)
+                    .ok()
+                    .and_then(|el| el.value().attr("href").map(String::from));
+
+                let ld_data = document
+                    .try_select_one(&selectors.ld_data)?
+                    .text()
+                    .collect::<String>()
+                    .parse_json()?;
+
+                // Tags link to their page on the main site, e.g. https://bandcamp.com/discover/ambient
+                let tags = eyre::Result::<Vec<_>, _>::from_iter(
+                    document.try_select(&selectors.tag)?.into_iter().map(|tag| {
+                        let href = tag
+                            .attr("href")
+                            .ok_or_else(|| eyre::eyre!("missing href"))?;
+                        let url = url.join(href)?;
+                        let slug = url
+                            .path_segments()
+                            .and_then(|segments| segments.rev().find(|segment| !segment.is_empty()))
+                            .ok_or_else(|| eyre::eyre!("missing tag slug"))?
+                            .to_owned();
+                        eyre::Result::<_>::Ok(Tag {
+                            id: TagId(slug),
+                            url: url.into(),
+                            details: TagDetails {
+                                name: tag.text().collect::<String>().trim().to_owned(),
+                            },
+                        })
+                    }),
+                )?;
+
+                Ok(ReleasePage {
+                    properties,
+                    data_band,
+                    data_tralbum,
+                    collectors,
+                    discography,
+                    ld_data,
+                    tags,
+                })
+            })?;
+        self.learn_domain(url, page.data_band.id)?;
+        page
     }
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url))]
     pub(crate) fn scrape_artist_page(&self, url: &Url) -> eyre::Result<ArtistPage> {
         let data = self.get(url.clone())?;
-        let document = scraper::Html::parse_document(&data);
-
-        let data_band = document
-            .try_select_one("[data-band]")?
-            .value()
-            .attr("data-band")
-            .ok_or_else(|| eyre::eyre!("missing data-band"))?
-            .parse_json()?;
-
-        let music_grid_items = eyre::Result::<Vec<_>, _>::from_iter(
-            document
-                .try_select("li.music-grid-item")?
-                .into_iter()
-                .map(|item| {
-                    let item_id = item
-                        .value()
-                        .attr("data-item-id")
-                        .ok_or_else(|| eyre::eyre!("missing data-item-id"))?;
-                    let (ty, item_id) = item_id
-                        .split_once("-")
-                        .ok_or_else(|| eyre::eyre!("failed to parse id"))?;
-                    let title = item.try_select_one(".title")?.text().collect();
-                    let href = item
-                        .try_select_one("a")?
-                        .attr("href")
-                        .ok_or_else(|| eyre::eyre!("missing href"))?
-                        .to_owned();
-                    eyre::Result::<_>::Ok(MusicGridItem {
-                        item_id: item_id.parse()?,
-                        href,
-                        ty: ty.to_owned(),
-                        title,
-                    })
-                }),
-        )?;
+        let selectors = self.selectors.clone();
+        let page: ArtistPage = self
+            .parser
+            .parse(url, PageKind::Artist, data, move |data| {
+                let selectors = &selectors.artist;
+                let document = scraper::Html::parse_document(data);
+
+                let data_band = document
+                    .try_select_one(&selectors.band)?
+                    .value()
+                    .attr(&selectors.band_attr)
+                    .ok_or_else(|| eyre::eyre!("missing {}", selectors.band_attr))?
+                    .parse_json()?;
+
+                let music_grid_items = eyre::Result::<Vec<_>, _>::from_iter(
+                    document
+                        .try_select(&selectors.grid_item)?
+                        .into_iter()
+                        .map(|item| {
+                            let item_id =
+                                item.value().attr(&selectors.grid_item_id_attr).ok_or_else(
+                                    || eyre::eyre!("missing {}", selectors.grid_item_id_attr),
+                                )?;
+                            let (ty, item_id) = item_id
+                                .split_once("-")
+                                .ok_or_else(|| eyre::eyre!("failed to parse id"))?;
+                            let title = item
+                                .try_select_one(&selectors.grid_item_title)?
+                                .text()
+                                .collect();
+                            let href = item
+                                .try_select_one(&selectors.grid_item_link)?
+                                .attr("href")
+                                .ok_or_else(|| eyre::eyre!("missing href"))?
+                                .to_owned();
+                            eyre::Result::<_>::Ok(MusicGridItem {
+                                item_id: item_id.parse()?,
+                                href,
+                                ty: ty.to_owned(),
+                                title,
+                            })
+                        }),
+                )?;
 
-        let client_items = document
-            .try_select_one("#music-grid")?
-            .value()
-            .attr("data-client-items")
-            .map(|data| data.parse_json())
-            .transpose()?;
+                let client_items = document
+                    .try_select_one(&selectors.grid)?
+                    .value()
+                    .attr(&selectors.grid_client_items_attr)
+                    .map(|data| data.parse_json())
+                    .transpose()?;
 
-        ArtistPage {
-            data_band,
-            music_grid_items,
-            client_items,
-        }
+                let roster = document
+                    .try_select(&selectors.navbar_link)?
+                    .into_iter()
+                    .filter_map(|link| link.attr("href"))
+                    .find(|href| href.trim_end_matches('/').ends_with("/artists"))
+                    .map(String::from);
+
+                let location = document
+                    .try_select(&selectors.location)?
+                    .into_iter()
+                    .map(|location| location.text().collect::<String>().trim().to_owned())
+                    .find(|location| !location.is_empty());
+
+                let supporters = document
+                    .try_select(&selectors.supporters)?
+                    .into_iter()
+                    .find_map(|supporters| supporters.attr(&selectors.supporters_attr))
+                    .map(|data| data.parse_json())
+                    .transpose()?;
+
+                Ok(ArtistPage {
+                    data_band,
+                    music_grid_items,
+                    client_items,
+                    roster,
+                    location,
+                    supporters,
+                })
+            })?;
+        self.learn_domain(url, page.data_band.id)?;
+        page
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    fn scrape_roster_page(&self, url: &Url) -> eyre::Result<Vec<RosterItem>> {
+        let data = self.get(url.clone())?;
+        let selectors = self.selectors.clone();
+        self.parser
+            .parse(url, PageKind::Roster, data, move |data| {
+                let selectors = &selectors.roster;
+                let document = scraper::Html::parse_document(data);
+
+                eyre::Result::<Vec<_>, _>::from_iter(
+                    document
+                        .try_select(&selectors.item)?
+                        .into_iter()
+                        .map(|item| {
+                            let band_id = item
+                                .value()
+                                .attr(&selectors.item_band_id_attr)
+                                .ok_or_else(|| {
+                                    eyre::eyre!("missing {}", selectors.item_band_id_attr)
+                                })?
+                                .parse()?;
+                            let href = item
+                                .try_select_one(&selectors.item_link)?
+                                .attr("href")
+                                .ok_or_else(|| eyre::eyre!("missing href"))?
+                                .to_owned();
+                            eyre::Result::<_>::Ok(RosterItem { band_id, href })
+                        }),
+                )
+            })?
     }
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url))]
-    fn scrape_fan_page(&self, url: &Url) -> eyre::Result<FanPage> {
+    fn scrape_fan_page(
+        &self,
+        url: &Url,
+        parts: Sender<FanPagePart>,
+    ) -> eyre::Result<Receiver<eyre::Result<()>>> {
+        use serde::de::DeserializeSeed;
+
         let data = self.get(url.clone())?;
-        let document = scraper::Html::parse_document(&data);
-        document
-            .try_select_one("#pagedata")?
-            .value()
-            .attr("data-blob")
-            .ok_or_else(|| eyre::eyre!("missing data-blob"))?
-            .parse_json()?
+        let selectors = self.selectors.clone();
+        self.parser.start(url, PageKind::Fan, data, move |data| {
+            let selectors = &selectors.fan;
+            let document = scraper::Html::parse_document(data);
+            let blob = document
+                .try_select_one(&selectors.page_data)?
+                .value()
+                .attr(&selectors.page_data_attr)
+                .ok_or_else(|| eyre::eyre!("missing {}", selectors.page_data_attr))?;
+            let mut deserializer = serde_json::Deserializer::from_str(blob);
+            FanPageSeed { parts: &parts }.deserialize(&mut deserializer)?;
+            deserializer.end()?;
+            Ok(())
+        })?
     }
 
     #[culpa::try_fn]
@@ -624,38 +1768,402 @@ impl Scraper {
         token: &str,
     ) -> eyre::Result<Thumbs> {
         let url = base_url.join("/api/tralbumcollectors/2/thumbs")?;
-        self.post(
-            url,
+        let data = self.post(
+            url.clone(),
             serde_json::json!({
                 "tralbum_type": props.item_type,
                 "tralbum_id": props.item_id,
                 "token": token,
                 "count": 80,
             }),
-        )?
-        .parse_json()?
+        )?;
+        self.parser
+            .parse(&url, PageKind::CollectorsApi, data, |data| {
+                data.parse_json()
+            })?
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%base_url))]
+    fn scrape_supporters_api(
+        &self,
+        base_url: &Url,
+        band_id: u64,
+        token: &str,
+    ) -> eyre::Result<Thumbs> {
+        let url = base_url.join("/api/bandsupporters/1/thumbs")?;
+        let data = self.post(
+            url.clone(),
+            serde_json::json!({
+                "band_id": band_id,
+                "token": token,
+                "count": 80,
+            }),
+        )?;
+        self.parser
+            .parse(&url, PageKind::SupportersApi, data, |data| {
+                data.parse_json()
+            })?
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%base_url))]
+    fn scrape_reviews_api(
+        &self,
+        base_url: &Url,
+        props: &Properties,
+        token: &str,
+    ) -> eyre::Result<Reviews> {
+        let url = base_url.join("/api/tralbumcollectors/2/reviews")?;
+        let data = self.post(
+            url.clone(),
+            serde_json::json!({
+                "tralbum_type": props.item_type,
+                "tralbum_id": props.item_id,
+                "token": token,
+                "count": 80,
+            }),
+        )?;
+        self.parser
+            .parse(&url, PageKind::CollectorsApi, data, |data| {
+                data.parse_json()
+            })?
     }
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self))]
     fn scrape_collections_api(&self, fan_id: u64, token: &str) -> eyre::Result<Collections> {
         let url = Url::parse("https://bandcamp.com/api/fancollection/1/collection_items")?;
-        self.post(
-            url,
+        let data = self.post(
+            url.clone(),
             serde_json::json!({
                 "fan_id": fan_id,
                 "older_than_token": token,
                 "count": 20,
             }),
+        )?;
+        self.parser
+            .parse(&url, PageKind::CollectionsApi, data, |data| {
+                data.parse_json()
+            })?
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self))]
+    fn scrape_wishlist_api(&self, fan_id: u64, token: &str) -> eyre::Result<Collections> {
+        let url = Url::parse("https://bandcamp.com/api/fancollection/1/wishlist_items")?;
+        let data = self.post(
+            url.clone(),
+            serde_json::json!({
+                "fan_id": fan_id,
+                "older_than_token": token,
+                "count": 20,
+            }),
+        )?;
+        self.parser
+            .parse(&url, PageKind::WishlistApi, data, |data| data.parse_json())?
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self))]
+    fn scrape_follows_api(
+        &self,
+        fan_id: u64,
+        follow: Follow,
+        token: &str,
+    ) -> eyre::Result<Follows> {
+        let url = Url::parse(match follow {
+            Follow::Following => "https://bandcamp.com/api/fancollection/1/following_fans",
+            Follow::Followers => "https://bandcamp.com/api/fancollection/1/followers",
+        })?;
+        let data = self.post(
+            url.clone(),
+            serde_json::json!({
+                "fan_id": fan_id,
+                "older_than_token": token,
+                "count": 40,
+            }),
+        )?;
+        self.parser
+            .parse(&url, PageKind::FollowsApi, data, |data| data.parse_json())?
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self))]
+    fn scrape_followed_bands_api(&self, fan_id: u64, token: &str) -> eyre::Result<FollowedBands> {
+        let url = Url::parse("https://bandcamp.com/api/fancollection/1/following_bands")?;
+        let data = self.post(
+            url.clone(),
+            serde_json::json!({
+                "fan_id": fan_id,
+                "older_than_token": token,
+                "count": 40,
+            }),
+        )?;
+        self.parser
+            .parse(&url, PageKind::FollowsApi, data, |data| data.parse_json())?
+    }
+
+    /// The feed of the fan logged in with the session cookies, `url` is their fan page
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self, on_stories), fields(%url))]
+    pub(crate) fn scrape_feed(
+        &self,
+        url: &Url,
+        mut on_stories: impl FnMut(&User, Vec<FeedStory>) -> eyre::Result<()>,
+    ) -> eyre::Result<()> {
+        let page = url.join(&format!("{}/feed", url.path().trim_end_matches('/')))?;
+        let data = self.get(page.clone())?;
+        let selectors = self.selectors.clone();
+        let data: FeedPageData = self
+            .parser
+            .parse(&page, PageKind::Feed, data, move |data| {
+                let selectors = &selectors.feed;
+                let document = scraper::Html::parse_document(data);
+                document
+                    .try_select_one(&selectors.page_data)?
+                    .value()
+                    .attr(&selectors.page_data_attr)
+                    .ok_or_else(|| eyre::eyre!("missing {}", selectors.page_data_attr))?
+                    .parse_json()
+            })?;
+        let Some(fan) = data.identities.fan else {
+            Err(eyre::eyre!(
+                "not logged in, feeds need session cookies from --cookies"
+            ))?
+        };
+        let fan_id = fan.id;
+        let user = User {
+            id: UserId(fan.id),
+            url: format!("https://bandcamp.com/{}", fan.username).into(),
+        };
+
+        let mut older_than = jiff::Timestamp::now().as_second();
+        for _ in 0..FEED_PAGES {
+            let feed = self.scrape_feed_api(fan_id, older_than)?;
+            let fans = &feed.fan_info;
+            let stories = feed.stories.entries.into_iter().filter_map(|entry| {
+                let url = Url::parse(&entry.item_url).ok()?;
+                let fan = entry
+                    .fan_id
+                    .filter(|&id| id != fan_id && entry.story_type == "fp")
+                    .and_then(|id| fans.get(&id.to_string()))
+                    .map(|fan| User {
+                        id: UserId(fan.fan_id),
+                        url: format!("https://bandcamp.com/{}", fan.username).into(),
+                    });
+                Some(FeedStory {
+                    artist: Artist {
+                        id: ArtistId(entry.band_id),
+                        url: url.join("/").ok()?.into(),
+                    },
+                    release: Release {
+                        id: ReleaseId(entry.tralbum_id),
+                        url: entry.item_url.into(),
+                    },
+                    fan,
+                })
+            });
+            on_stories(&user, stories.collect())?;
+            match feed.stories.oldest_story_date {
+                Some(oldest) if oldest < older_than => older_than = oldest,
+                _ => break,
+            }
+        }
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self))]
+    fn scrape_feed_api(&self, fan_id: u64, older_than: i64) -> eyre::Result<Feed> {
+        let url = Url::parse("https://bandcamp.com/fan_dash_feed_updates")?;
+        let data = self.post(
+            url.clone(),
+            serde_json::json!({
+                "fan_id": fan_id,
+                "older_than": older_than,
+            }),
+        )?;
+        self.parser
+            .parse(&url, PageKind::FeedApi, data, |data| data.parse_json())?
+    }
+
+    /// The top selling releases of a tag, the tag page itself is filled in by javascript from the
+    /// discover api so that is scraped instead
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    pub(crate) fn scrape_tag(&self, url: &Url) -> eyre::Result<(Tag, Vec<Release>)> {
+        let slug = url
+            .path_segments()
+            .and_then(|segments| segments.rev().find(|segment| !segment.is_empty()))
+            .ok_or_else(|| eyre::eyre!("missing tag slug"))?
+            .to_owned();
+
+        let api = Url::parse("https://bandcamp.com/api/discover/1/discover_web")?;
+        let data = self.post(
+            api.clone(),
+            serde_json::json!({
+                "category_id": 0,
+                "tag_norm_names": [slug],
+                "geoname_id": 0,
+                "slice": "top",
+                "cursor": "*",
+                "size": 60,
+                "include_result_types": ["a", "s"],
+            }),
+        )?;
+        let discover: Discover = self
+            .parser
+            .parse(&api, PageKind::DiscoverApi, data, |data| data.parse_json())?;
+
+        let tag = Tag {
+            id: TagId(slug.clone()),
+            url: url.into(),
+            details: TagDetails {
+                // The api only has the slug, release pages have the name as shown
+                name: slug.replace('-', " "),
+            },
+        };
+        let releases = Vec::from_iter(discover.results.into_iter().map(|item| Release {
+            id: ReleaseId(item.id),
+            url: item.item_url.into(),
+        }));
+        (tag, releases)
+    }
+
+    /// Search for artists, releases and fans, in the order Bandcamp ranks them
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn search(&self, text: &str) -> eyre::Result<Vec<SearchResult>> {
+        let url =
+            Url::parse("https://bandcamp.com/api/bcsearch_public_api/1/autocomplete_elastic")?;
+        let data = self.post(
+            url.clone(),
+            serde_json::json!({
+                "search_text": text,
+                "search_filter": "",
+                "full_page": false,
+                "fan_id": null,
+            }),
+        )?;
+        let search: Search = self
+            .parser
+            .parse(&url, PageKind::SearchApi, data, |data| data.parse_json())?;
+
+        search
+            .auto
+            .results
+            .into_iter()
+            .filter_map(|item| {
+                let result = match item.ty.as_str() {
+                    "b" => SearchResult::Artist(Artist {
+                        id: ArtistId(item.id),
+                        url: format!("{}/", item.item_url_root?.trim_end_matches('/')).into(),
+                    }),
+                    "a" | "t" => SearchResult::Release(Release {
+                        id: ReleaseId(item.id),
+                        url: item.item_url_path?.into(),
+                    }),
+                    "f" => SearchResult::User(User {
+                        id: UserId(item.id),
+                        url: item.item_url_root?.into(),
+                    }),
+                    other => {
+                        tracing::debug!(ty = other, "skipping unknown search result type");
+                        return None;
+                    }
+                };
+                Some(result)
+            })
+            .collect()
+    }
+
+    /// Download and decode a release's cover art
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    pub(crate) fn art(&self, url: &Url) -> eyre::Result<Image> {
+        let (tx, rx) = crossbeam::channel::bounded(1);
+        self.web.send(web::Request::GetBytes {
+            url: url.clone(),
+            freshness: self.freshness,
+            response: tx,
+        })?;
+        let data = rx.recv()??;
+        Image::from_buffer(
+            &data,
+            ImageType::Extension("jpg"),
+            CompressedImageFormats::NONE,
+            true,
+            ImageSampler::Default,
+            RenderAssetUsages::RENDER_WORLD,
         )?
-        .parse_json()?
+    }
+
+    /// The same page on a band's `bandcamp.com` subdomain when it's from their custom domain, so
+    /// it's only scraped once whichever it was found at, as long as the subdomain is known
+    #[culpa::try_fn]
+    fn canonical(&self, url: &Url) -> eyre::Result<Url> {
+        let domains = self.domains.lock().unwrap();
+        let subdomain = match url.host_str() {
+            Some(host) if !host.ends_with(".bandcamp.com") => match domains.band_of(host)? {
+                Some(band_id) => domains
+                    .hosts(band_id)?
+                    .into_iter()
+                    .find(|host| host.ends_with(".bandcamp.com")),
+                None => None,
+            },
+            _ => None,
+        };
+        let mut url = url.clone();
+        if let Some(subdomain) = subdomain {
+            tracing::debug!(from = %url, to = subdomain, "using subdomain of custom domain");
+            url.set_host(Some(&subdomain))?;
+            // Only fails for switching between special and non-special schemes
+            let _ = url.set_scheme("https");
+        }
+        url
+    }
+
+    /// The artist whose page is at a url, if their host has been seen serving a band's pages or
+    /// their page is cached, without retrieving anything from the network
+    #[culpa::try_fn]
+    fn known_artist(&self, url: Url) -> eyre::Result<Option<Artist>> {
+        let band_id = match url.host_str() {
+            Some(host) => self.domains.lock().unwrap().band_of(host)?,
+            None => None,
+        };
+        let band_id = match band_id {
+            Some(band_id) => Some(band_id),
+            None => match self.offline().scrape_artist_page(&url) {
+                Ok(page) => Some(page.data_band.id),
+                Err(error) => {
+                    tracing::debug!(?error, %url, "credited artist's page isn't cached");
+                    None
+                }
+            },
+        };
+        band_id.map(|id| Artist {
+            id: ArtistId(id),
+            url: url.into(),
+        })
+    }
+
+    /// Remember which band's pages a host serves
+    #[culpa::try_fn]
+    fn learn_domain(&self, url: &Url, band_id: u64) -> eyre::Result<()> {
+        if let Some(host) = url.host_str() {
+            self.domains.lock().unwrap().set(host, band_id)?;
+        }
     }
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url))]
     fn get(&self, url: Url) -> eyre::Result<String> {
         let (tx, rx) = crossbeam::channel::bounded(1);
-        self.web.send(web::Request::Get { url, response: tx })?;
+        self.web.send(web::Request::Get {
+            url,
+            freshness: self.freshness,
+            response: tx,
+        })?;
         rx.recv()??
     }
 
@@ -666,6 +2174,7 @@ impl Scraper {
         self.web.send(web::Request::Post {
             url,
             data,
+            freshness: self.freshness,
             response: tx,
         })?;
         rx.recv()??
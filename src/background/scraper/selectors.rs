@@ -0,0 +1,285 @@
+use super::{parse::PageKind, Request};
+use eyre::WrapErr;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// The selectors shipped with the scraper, any of which the config file can override
+const DEFAULT: &str = include_str!("selectors.toml");
+
+/// Where on Bandcamp's pages each part that gets scraped is found, loaded at startup so a layout
+/// change can be worked around by editing `selectors.toml` in the config directory
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Selectors {
+    pub(crate) release: ReleaseSelectors,
+    pub(crate) artist: ArtistSelectors,
+    pub(crate) roster: RosterSelectors,
+    pub(crate) fan: PageDataSelectors,
+    pub(crate) feed: PageDataSelectors,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub(crate) struct ReleaseSelectors {
+    pub(crate) properties: String,
+    pub(crate) properties_attr: String,
+    pub(crate) band: String,
+    pub(crate) band_attr: String,
+    pub(crate) tralbum: String,
+    pub(crate) tralbum_attr: String,
+    pub(crate) collectors: String,
+    pub(crate) collectors_attr: String,
+    pub(crate) discography: String,
+    pub(crate) ld_data: String,
+    pub(crate) tag: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub(crate) struct ArtistSelectors {
+    pub(crate) band: String,
+    pub(crate) band_attr: String,
+    pub(crate) grid_item: String,
+    pub(crate) grid_item_id_attr: String,
+    pub(crate) grid_item_title: String,
+    pub(crate) grid_item_link: String,
+    pub(crate) grid: String,
+    pub(crate) grid_client_items_attr: String,
+    pub(crate) navbar_link: String,
+    pub(crate) location: String,
+    pub(crate) supporters: String,
+    pub(crate) supporters_attr: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub(crate) struct RosterSelectors {
+    pub(crate) item: String,
+    pub(crate) item_band_id_attr: String,
+    pub(crate) item_link: String,
+}
+
+/// Pages whose data is all in one json blob
+#[derive(Debug, Clone, serde::Deserialize)]
+pub(crate) struct PageDataSelectors {
+    pub(crate) page_data: String,
+    pub(crate) page_data_attr: String,
+}
+
+/// Override the values in `base` with those in `overrides`, keeping any it leaves out
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match base.get_mut(&key) {
+            Some(toml::Value::Table(inner)) if value.is_table() => {
+                if let toml::Value::Table(value) = value {
+                    merge(inner, value);
+                }
+            }
+            _ => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+impl Selectors {
+    #[culpa::try_fn]
+    pub fn load(config_dir: &Path) -> eyre::Result<Self> {
+        let mut table: toml::Table = DEFAULT.parse()?;
+        let path = config_dir.join("selectors.toml");
+        match std::fs::read_to_string(&path) {
+            Ok(data) => merge(
+                &mut table,
+                data.parse()
+                    .wrap_err_with(|| format!("invalid selectors in {}", path.display()))?,
+            ),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => Err(err)?,
+        }
+        let selectors: Self = toml::Value::Table(table)
+            .try_into()
+            .wrap_err_with(|| format!("invalid selectors in {}", path.display()))?;
+        for (kind, expected) in selectors.all() {
+            for (name, selector, _) in expected {
+                scraper::Selector::parse(selector).map_err(|e| {
+                    eyre::eyre!("invalid {kind} {name} selector {selector:?}: {e:?}")
+                })?;
+            }
+        }
+        selectors
+    }
+
+    /// Every selector with its attribute, if the data is in one
+    fn all(&self) -> [(&'static str, Vec<(&'static str, &str, Option<&str>)>); 5] {
+        let (release, artist, roster) = (&self.release, &self.artist, &self.roster);
+        [
+            (
+                "release",
+                vec![
+                    (
+                        "properties",
+                        &release.properties,
+                        Some(&release.properties_attr),
+                    ),
+                    ("band", &release.band, Some(&release.band_attr)),
+                    ("tralbum", &release.tralbum, Some(&release.tralbum_attr)),
+                    (
+                        "collectors",
+                        &release.collectors,
+                        Some(&release.collectors_attr),
+                    ),
+                    ("ld_data", &release.ld_data, None),
+                    ("discography", &release.discography, None),
+                    ("tag", &release.tag, None),
+                ],
+            ),
+            (
+                "artist",
+                vec![
+                    ("band", &artist.band, Some(&artist.band_attr)),
+                    ("grid", &artist.grid, None),
+                    (
+                        "grid_item",
+                        &artist.grid_item,
+                        Some(&artist.grid_item_id_attr),
+                    ),
+                    ("grid_item_title", &artist.grid_item_title, None),
+                    ("grid_item_link", &artist.grid_item_link, None),
+                    ("navbar_link", &artist.navbar_link, None),
+                    ("location", &artist.location, None),
+                    (
+                        "supporters",
+                        &artist.supporters,
+                        Some(&artist.supporters_attr),
+                    ),
+                ],
+            ),
+            (
+                "roster",
+                vec![
+                    ("item", &roster.item, Some(&roster.item_band_id_attr)),
+                    ("item_link", &roster.item_link, None),
+                ],
+            ),
+            (
+                "fan",
+                vec![(
+                    "page_data",
+                    &self.fan.page_data,
+                    Some(&self.fan.page_data_attr),
+                )],
+            ),
+            (
+                "feed",
+                vec![(
+                    "page_data",
+                    &self.feed.page_data,
+                    Some(&self.feed.page_data_attr),
+                )],
+            ),
+        ]
+        .map(
+            |(kind, expected): (_, Vec<(_, &String, Option<&String>)>)| {
+                let expected = expected.into_iter().map(|(name, selector, attr)| {
+                    (name, selector.as_str(), attr.map(String::as_str))
+                });
+                (kind, Vec::from_iter(expected))
+            },
+        )
+    }
+
+    /// The selectors every page of a kind has to match for it to be scraped, the rest are only
+    /// on some of them
+    fn required(&self, kind: PageKind) -> Vec<(&'static str, &str, Option<&str>)> {
+        let [release, artist, roster, fan, feed] = self.all().map(|(_, expected)| expected);
+        let expected = match kind {
+            PageKind::Release => release,
+            PageKind::Artist => artist,
+            PageKind::Roster => roster,
+            PageKind::Fan => fan,
+            PageKind::Feed => feed,
+            _ => Vec::new(),
+        };
+        Vec::from_iter(expected.into_iter().filter(|(name, ..)| {
+            !matches!(
+                *name,
+                "discography"
+                    | "tag"
+                    | "grid"
+                    | "grid_item"
+                    | "grid_item_title"
+                    | "grid_item_link"
+                    | "navbar_link"
+                    | "location"
+                    | "supporters"
+                    | "item_link"
+            )
+        }))
+    }
+
+    /// Check the selectors against the pages saved in a fixtures directory, returning how many
+    /// pages were checked and what each page that didn't match was missing
+    #[culpa::try_fn]
+    pub fn check(&self, fixtures: &Path) -> eyre::Result<(usize, Vec<String>)> {
+        let mut checked = 0;
+        let mut problems = Vec::new();
+        for path in html_files(fixtures)? {
+            let Some((url, kind)) = fixture_page(fixtures, &path) else {
+                continue;
+            };
+            checked += 1;
+            let document = scraper::Html::parse_document(&std::fs::read_to_string(&path)?);
+            for (name, selector, attr) in self.required(kind) {
+                let selector = scraper::Selector::parse(selector)
+                    .map_err(|e| eyre::eyre!("invalid selector {selector:?}: {e:?}"))?;
+                let found = document.select(&selector).next();
+                let problem = match (found, attr) {
+                    (None, _) => format!("{} {name} doesn't match", kind.as_ref()),
+                    (Some(element), Some(attr)) if element.attr(attr).is_none() => {
+                        format!("{} {name} is missing {attr}", kind.as_ref())
+                    }
+                    _ => continue,
+                };
+                problems.push(format!("{url} ({}): {problem}", path.display()));
+            }
+        }
+        (checked, problems)
+    }
+}
+
+/// Every saved page under a fixtures directory, the posts are json and have no selectors
+#[culpa::try_fn]
+fn html_files(dir: &Path) -> eyre::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(html_files(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "html")
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+/// The url a fixture is saved for and the kind of page it is, undoing how fixtures are named by
+/// host and path
+fn fixture_page(fixtures: &Path, path: &Path) -> Option<(Url, PageKind)> {
+    let relative = path.strip_prefix(fixtures).ok()?.with_extension("");
+    let relative = relative.to_str()?;
+    let (host, page) = relative.split_once(std::path::MAIN_SEPARATOR)?;
+    let page = if page == "index" { "" } else { page };
+    let url = Url::parse(&format!("https://{host}/{page}")).ok()?;
+    let kind = if page.trim_end_matches('/').ends_with("artists") {
+        PageKind::Roster
+    } else {
+        match Request::detect(url.as_str())? {
+            Request::Release { .. } => PageKind::Release,
+            Request::Artist { .. } => PageKind::Artist,
+            Request::User { .. } => PageKind::Fan,
+            Request::Feed { .. } => PageKind::Feed,
+            _ => return None,
+        }
+    };
+    Some((url, kind))
+}
@@ -0,0 +1,232 @@
+use super::{
+    checkpoint::Checkpoints, domains::Domains, parse, scraper::Scraper, selectors::Selectors,
+    ArtistPart, FanList, FanPart, Follow, ReleasePart,
+};
+use crate::{
+    background::{
+        web::fixture::{self, TempDir},
+        Stats,
+    },
+    data::{ArtistId, RelationKind, ReleaseId, UserId},
+};
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// A scraper reading the checked in fixtures directly, along with the directory its checkpoints
+/// and domains are kept in
+fn scraper(name: &str) -> (Scraper, TempDir) {
+    let cache = TempDir::new(name);
+    let (web, requests) = crossbeam::channel::unbounded();
+    fixture::run(&fixture::checked_in(), requests).unwrap();
+    let (parser, _) = parse::run(Arc::new(Stats::default())).unwrap();
+    let checkpoints = Checkpoints::new(&cache).unwrap();
+    let domains = Domains::new(&cache).unwrap();
+    // There's no selectors file in the cache dir, so these are the defaults
+    let selectors = Selectors::load(&cache).unwrap();
+    (
+        Scraper::new(
+            web,
+            parser,
+            Arc::new(Mutex::new(checkpoints)),
+            Arc::new(Mutex::new(domains)),
+            Arc::new(selectors),
+            vec![
+                FanList::Wishlist,
+                FanList::Follows,
+                FanList::FollowedArtists,
+            ],
+        ),
+        cache,
+    )
+}
+
+#[test]
+fn release() {
+    let (scraper, _cache) = scraper("release");
+    let mut parts = Vec::new();
+    let (release, details) = scraper
+        .scrape_release(
+            &Url::parse("https://testartist.bandcamp.com/album/first-album").unwrap(),
+            |release, part| {
+                assert_eq!(release.id, ReleaseId(1001));
+                parts.push(part);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+    assert_eq!(release.id, ReleaseId(1001));
+    assert_eq!(details.title, "First Album");
+    assert_eq!(details.artist, "Test Artist");
+    assert_eq!(details.tracks, Some(2));
+    assert_eq!(details.length, jiff::SignedDuration::from_secs(420));
+    assert_eq!(details.price.map(|price| price.amount), Some(7.0));
+    assert_eq!(details.tags, ["ambient", "drone", "Wellington"]);
+
+    let mut artists = Vec::new();
+    let mut tags = Vec::new();
+    let mut fans = Vec::new();
+    let mut reviews = Vec::new();
+    for part in parts {
+        match part {
+            ReleasePart::Artist(kind, artist) => artists.push((kind, artist.id)),
+            ReleasePart::Tags(scraped) => tags.extend(scraped),
+            ReleasePart::Fans(scraped) => fans.extend(scraped),
+            ReleasePart::Reviews(scraped) => reviews.extend(scraped),
+            ReleasePart::Credits(credits) => panic!("unexpected credits {credits:?}"),
+        }
+    }
+
+    assert_eq!(artists, [(RelationKind::Artist, ArtistId(2001))]);
+    assert_eq!(
+        Vec::from_iter(tags.iter().map(|tag| tag.id.0.as_str())),
+        ["ambient", "drone", "wellington"],
+    );
+    // Reviewers are listed before the rest of the fans
+    assert_eq!(
+        Vec::from_iter(fans.iter().map(|fan| fan.id)),
+        [UserId(4003), UserId(4001), UserId(4002)],
+    );
+    assert_eq!(fans[1].url.0, "https://bandcamp.com/testfan");
+
+    let [(reviewer, review)] = &reviews[..] else {
+        panic!("expected a single review, got {reviews:?}");
+    };
+    assert_eq!(*reviewer, UserId(4003));
+    assert_eq!(review.text, "Lovely record");
+    assert_eq!(review.favorite_track.as_deref(), Some("Opening"));
+}
+
+#[test]
+fn artist() {
+    let (scraper, _cache) = scraper("artist");
+    let mut parts = Vec::new();
+    let (artist, details, aliases) = scraper
+        .scrape_artist(
+            &Url::parse("https://testartist.bandcamp.com/").unwrap(),
+            |artist, part| {
+                assert_eq!(artist.id, ArtistId(2001));
+                parts.push(part);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+    assert_eq!(artist.id, ArtistId(2001));
+    assert_eq!(details.name, "Test Artist");
+    assert_eq!(aliases, Vec::<String>::new());
+
+    let mut releases = Vec::new();
+    let mut supporters = Vec::new();
+    for part in parts {
+        match part {
+            ArtistPart::Releases(kind, scraped) => {
+                releases.extend(scraped.into_iter().map(|release| (kind, release)));
+            }
+            ArtistPart::Supporters(scraped) => supporters.extend(scraped),
+            ArtistPart::Roster(roster) => {
+                panic!("artist scraped as a label with roster {roster:?}")
+            }
+        }
+    }
+
+    assert_eq!(
+        Vec::from_iter(releases.iter().map(|(kind, release)| (
+            *kind,
+            release.id,
+            release.url.0.as_str()
+        ))),
+        [
+            (
+                RelationKind::Artist,
+                ReleaseId(1001),
+                "https://testartist.bandcamp.com/album/first-album",
+            ),
+            (
+                RelationKind::Artist,
+                ReleaseId(1002),
+                "https://testartist.bandcamp.com/track/single",
+            ),
+        ],
+    );
+
+    assert_eq!(
+        Vec::from_iter(supporters.iter().map(|fan| (fan.id, fan.url.0.as_str()))),
+        [(UserId(4001), "https://bandcamp.com/testfan")],
+    );
+}
+
+#[test]
+fn fan() {
+    let (scraper, _cache) = scraper("fan");
+    let mut parts = Vec::new();
+    let (fan, details) = scraper
+        .scrape_fan(
+            &Url::parse("https://bandcamp.com/testfan").unwrap(),
+            |fan, part| {
+                assert_eq!(fan.id, UserId(4001));
+                parts.push(part);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+    assert_eq!(fan.id, UserId(4001));
+    assert_eq!(details.name, "Test Fan");
+    assert_eq!(details.username, "testfan");
+
+    let mut collection = Vec::new();
+    let mut progress = None;
+    let mut wishlist = Vec::new();
+    let mut follows = Vec::new();
+    let mut artists = Vec::new();
+    for part in parts {
+        match part {
+            FanPart::Collection(scraped, scraped_progress) => {
+                collection.extend(scraped);
+                progress = Some(scraped_progress);
+            }
+            FanPart::Wishlist(scraped) => wishlist.extend(scraped),
+            FanPart::Follows(follow, scraped) => {
+                follows.extend(scraped.into_iter().map(|user| (follow, user.id)));
+            }
+            FanPart::FollowedArtists(scraped) => artists.extend(scraped),
+        }
+    }
+
+    // In the order of the page's sequence, which leaves out the hidden item cached before the rest
+    assert_eq!(
+        Vec::from_iter(collection.iter().map(|(release, acquired)| (
+            release.id,
+            acquired.map(|acquired| acquired.0.as_second())
+        ))),
+        [
+            (ReleaseId(1001), Some(1600000001)),
+            (ReleaseId(1002), Some(1600000000)),
+        ],
+    );
+    let progress = progress.unwrap();
+    assert_eq!((progress.scraped, progress.total), (2, 2));
+
+    assert_eq!(
+        Vec::from_iter(wishlist.iter().map(|release| release.id)),
+        [ReleaseId(1002)],
+    );
+
+    assert!(matches!(
+        follows[..],
+        [
+            (Follow::Following, UserId(4002)),
+            (Follow::Followers, UserId(4003)),
+        ],
+    ));
+
+    assert_eq!(
+        Vec::from_iter(
+            artists
+                .iter()
+                .map(|artist| (artist.id, artist.url.0.as_str()))
+        ),
+        [(ArtistId(2001), "https://testartist.bandcamp.com/")],
+    );
+}
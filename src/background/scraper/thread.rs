@@ -1,33 +1,58 @@
-use super::super::{scraper, web, Stats};
-use super::scraper::Scraper;
+use super::super::{job::Jobs, scraper, Stats};
+use super::{
+    drift::{Quarantine, SchemaDrift},
+    scraper::Scraper,
+    ReleasePart,
+};
+use crate::data::{RelationKind, UserId};
 use crossbeam::channel::{Receiver, SendError, Sender};
 use std::{
-    cell::RefCell,
-    sync::{atomic::Ordering, Arc},
+    collections::HashSet,
+    sync::{atomic::Ordering, Arc, Mutex},
 };
 use url::Url;
 
 #[culpa::try_fn]
-pub fn run(
-    web: Sender<web::Request>,
+pub(crate) fn run(
+    scraper: Scraper,
     stats: Arc<Stats>,
+    quarantine: Arc<Mutex<Quarantine>>,
+    jobs: Arc<Mutex<Jobs>>,
     to_scrape: Receiver<scraper::Request>,
-    scraped: Sender<scraper::Response>,
+    scraped: Sender<(scraper::Request, scraper::Response)>,
 ) -> eyre::Result<std::thread::JoinHandle<()>> {
-    let scraper = Scraper::new(web);
-
     std::thread::Builder::new()
         .name("scraper".to_owned())
         .spawn(move || {
             for request in &to_scrape {
                 stats.items_queued.fetch_sub(1, Ordering::Relaxed);
                 stats.items_processing.fetch_add(1, Ordering::Relaxed);
-                if let Err(error) = handle_request(&scraper, request, &scraped) {
-                    if error.is::<SendError<scraper::Response>>() {
+                let result = handle_request(&scraper, request.clone(), &scraped);
+                jobs.lock().unwrap().finish(&request, result.is_ok());
+                if let Err(error) = result {
+                    if error.is::<SendError<(scraper::Request, scraper::Response)>>() {
                         tracing::info!("scraper thread shutdown while still processing an item");
                         return;
                     }
-                    tracing::error!(?error, "failed handling scrape request");
+                    if let Some(drift) = error.downcast_ref::<SchemaDrift>() {
+                        // Counted and kept for the ui to warn about once, rather than logging the
+                        // same error for every page after a layout change
+                        stats.parse_failures.fetch_add(1, Ordering::Relaxed);
+                        tracing::debug!(url = %drift.url, kind = drift.kind.as_ref(), missing = drift.missing, "failed to parse page");
+                        let mut quarantine = quarantine.lock().unwrap();
+                        if let Err(error) = quarantine.add(drift) {
+                            tracing::error!(?error, "failed to quarantine page");
+                        }
+                        if let Err(error) = quarantine.add_retry(&request) {
+                            tracing::error!(?error, "failed to keep request for retrying");
+                        }
+                    } else {
+                        tracing::error!(?error, "failed handling scrape request");
+                    }
+                } else if let scraper::Request::Retry(request) = &request {
+                    if let Err(error) = quarantine.lock().unwrap().remove_retry(request) {
+                        tracing::error!(?error, "failed to forget retried request");
+                    }
                 }
                 stats.items_processing.fetch_sub(1, Ordering::Relaxed);
                 stats.items_completed.fetch_add(1, Ordering::Relaxed);
@@ -40,74 +65,184 @@ pub fn run(
 fn handle_request(
     scraper: &Scraper,
     request: scraper::Request,
-    scraped: &Sender<scraper::Response>,
+    scraped: &Sender<(scraper::Request, scraper::Response)>,
 ) -> eyre::Result<()> {
-    match request {
+    match request.clone() {
         scraper::Request::Artist { url } => {
-            let artist = RefCell::new(None);
-            scraper.scrape_artist(
-                &Url::parse(&url)?,
-                |new_artist, details| {
-                    artist.replace(Some((new_artist, details)));
+            let (artist, details, aliases) =
+                scraper.scrape_artist(&Url::parse(&url)?, |artist, part| {
+                    scraped.send((request.clone(), part.into_response(artist)))?;
                     Ok(())
-                },
-                |releases| {
-                    scraped.send(scraper::Response::Releases(
-                        artist.borrow().as_ref().unwrap().0.clone(),
-                        releases,
-                    ))?;
-                    Ok(())
-                },
-            )?;
-            let (artist, details) = artist.replace(None).take().unwrap();
-            scraped.send(scraper::Response::Artist(artist, details))?;
+                })?;
+            scraped.send((
+                request.clone(),
+                scraper::Response::Artist(artist, details, aliases),
+            ))?;
         }
 
         scraper::Request::Release { url } => {
-            let release = RefCell::new(None);
-            scraper.scrape_release(
-                &Url::parse(&url)?,
-                |new_release, details| {
-                    release.replace(Some((new_release, details)));
-                    Ok(())
-                },
-                |artist| {
-                    scraped.send(scraper::Response::ReleaseArtist(
-                        release.borrow().as_ref().unwrap().0.clone(),
-                        artist,
-                    ))?;
-                    Ok(())
-                },
-                |fans| {
-                    scraped.send(scraper::Response::Fans(
-                        release.borrow().as_ref().unwrap().0.clone(),
-                        fans,
-                    ))?;
+            let mut fans_seen = HashSet::new();
+            let (release, mut details) =
+                scraper.scrape_release(&Url::parse(&url)?, |release, part| {
+                    if let ReleasePart::Fans(fans) = &part {
+                        fans_seen.extend(fans.iter().map(|fan| fan.id));
+                    }
+                    scraped.send((request.clone(), part.into_response(release)))?;
                     Ok(())
-                },
-            )?;
-            let (release, details) = release.replace(None).take().unwrap();
-            scraped.send(scraper::Response::Release(release, details))?;
+                })?;
+            details.fan_count = fans_seen.len();
+            scraped.send((
+                request.clone(),
+                scraper::Response::Release(release, details),
+            ))?;
         }
 
         scraper::Request::User { url } => {
-            let user = RefCell::new(None);
-            scraper.scrape_fan(
-                &Url::parse(&url)?,
-                |fan, details| {
-                    user.replace(Some((fan, details)));
-                    Ok(())
-                },
-                |collection| {
-                    scraped.send(scraper::Response::Collection(
-                        user.borrow().as_ref().unwrap().0.clone(),
-                        collection,
+            let (user, details) = scraper.scrape_fan(&Url::parse(&url)?, |user, part| {
+                scraped.send((request.clone(), part.into_response(user)))?;
+                Ok(())
+            })?;
+            scraped.send((request.clone(), scraper::Response::User(user, details)))?;
+        }
+
+        scraper::Request::Tag { url } => {
+            let (tag, releases) = scraper.scrape_tag(&Url::parse(&url)?)?;
+            scraped.send((
+                request.clone(),
+                scraper::Response::TagReleases(tag, releases),
+            ))?;
+        }
+
+        scraper::Request::Search { text } => {
+            // Always ask the server, a cached search would miss anything added since
+            let results = scraper.refreshing().search(&text)?;
+            scraped.send((request.clone(), scraper::Response::SearchResults(results)))?;
+        }
+
+        scraper::Request::Feed { url } => {
+            // A feed is only interesting for what's new, so it's never taken from the cache
+            scraper
+                .refreshing()
+                .scrape_feed(&Url::parse(&url)?, |user, stories| {
+                    scraped.send((
+                        request.clone(),
+                        scraper::Response::Feed(user.clone(), stories),
                     ))?;
                     Ok(())
-                },
-            )?;
-            let (user, details) = user.replace(None).take().unwrap();
-            scraped.send(scraper::Response::User(user, details))?;
+                })?;
+        }
+
+        scraper::Request::Art { url } => {
+            let image = scraper.art(&Url::parse(&url)?)?;
+            scraped.send((request.clone(), scraper::Response::Art(url, image)))?;
+        }
+
+        scraper::Request::Retry(request) => {
+            handle_request(&scraper.offline(), *request, scraped)?;
+        }
+
+        scraper::Request::Hydrate(inner) => {
+            hydrate(&scraper.offline(), &request, &inner, scraped)?;
+        }
+
+        scraper::Request::Refresh(request) => {
+            handle_request(&scraper.refreshing(), *request, scraped)?;
+        }
+
+        scraper::Request::RefreshFans { url, known } => {
+            refresh_release(scraper, &request, &url, &known, scraped)?;
         }
     }
 }
+
+/// Scrape a page from the cache for only the details of its node, leaving out everything it's
+/// related to, which the graph it was loaded into already has
+#[culpa::try_fn]
+#[tracing::instrument(skip(scraper, scraped))]
+fn hydrate(
+    scraper: &Scraper,
+    request: &scraper::Request,
+    inner: &scraper::Request,
+    scraped: &Sender<(scraper::Request, scraper::Response)>,
+) -> eyre::Result<()> {
+    match inner {
+        scraper::Request::Artist { url } => {
+            let (artist, details, aliases) =
+                scraper.scrape_artist(&Url::parse(url)?, |_, _| Ok(()))?;
+            scraped.send((
+                request.clone(),
+                scraper::Response::Artist(artist, details, aliases),
+            ))?;
+        }
+
+        scraper::Request::Release { url } => {
+            let mut fans_seen = HashSet::new();
+            let (release, mut details) = scraper.scrape_release(&Url::parse(url)?, |_, part| {
+                if let ReleasePart::Fans(fans) = part {
+                    fans_seen.extend(fans.into_iter().map(|fan| fan.id));
+                }
+                Ok(())
+            })?;
+            details.fan_count = fans_seen.len();
+            scraped.send((
+                request.clone(),
+                scraper::Response::Release(release, details),
+            ))?;
+        }
+
+        scraper::Request::User { url } => {
+            let (user, details) = scraper.scrape_fan(&Url::parse(url)?, |_, _| Ok(()))?;
+            scraped.send((request.clone(), scraper::Response::User(user, details)))?;
+        }
+
+        other => Err(eyre::eyre!("nothing to hydrate for {other:?}"))?,
+    }
+}
+
+/// Scrape a release again, sending only the fans that have been added or removed since the ones
+/// the graph already knows rather than the whole list
+#[culpa::try_fn]
+#[tracing::instrument(skip(scraper, known, scraped))]
+fn refresh_release(
+    scraper: &Scraper,
+    request: &scraper::Request,
+    url: &str,
+    known: &[u64],
+    scraped: &Sender<(scraper::Request, scraper::Response)>,
+) -> eyre::Result<()> {
+    let url = Url::parse(url)?;
+    let previous = HashSet::<UserId>::from_iter(known.iter().copied().map(UserId));
+
+    let mut fans_seen = HashSet::new();
+    let mut added = Vec::new();
+    let mut pending_reviews = Vec::new();
+    let (release, mut details) = scraper.refreshing().scrape_release(&url, |release, part| {
+        match part {
+            ReleasePart::Fans(fans) => {
+                for fan in fans {
+                    if fans_seen.insert(fan.id) && !previous.contains(&fan.id) {
+                        added.push(fan);
+                    }
+                }
+            }
+            // Held until after the delta, which is what links the fans they're by
+            ReleasePart::Reviews(reviews) => pending_reviews.extend(reviews),
+            part => scraped.send((request.clone(), part.into_response(release)))?,
+        }
+        Ok(())
+    })?;
+    details.fan_count = fans_seen.len();
+    let removed = Vec::from_iter(previous.difference(&fans_seen).copied());
+    scraped.send((
+        request.clone(),
+        scraper::Response::FansDelta(release.clone(), RelationKind::Fan, added, removed),
+    ))?;
+    scraped.send((
+        request.clone(),
+        scraper::Response::Reviews(release.clone(), pending_reviews),
+    ))?;
+    scraped.send((
+        request.clone(),
+        scraper::Response::Release(release, details),
+    ))?;
+}
@@ -0,0 +1,45 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// How far back completions are counted towards the observed rate
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// How often the completed count is sampled
+const INTERVAL: Duration = Duration::from_secs(1);
+
+/// Samples of how many requests had completed over the last minute, to estimate how long the rest
+/// of the queue will take
+#[derive(Debug, Default)]
+pub(crate) struct Throughput {
+    samples: VecDeque<(Instant, usize)>,
+}
+
+impl Throughput {
+    pub(crate) fn record(&mut self, completed: usize) {
+        let now = Instant::now();
+        if self
+            .samples
+            .back()
+            .is_some_and(|&(at, _)| now - at < INTERVAL)
+        {
+            return;
+        }
+        self.samples.push_back((now, completed));
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(at, _)| now - at > WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The average time between completions, once there's been some
+    pub(crate) fn per_item(&self) -> Option<Duration> {
+        let (&(first_at, first), &(last_at, last)) = (self.samples.front()?, self.samples.back()?);
+        let completed = last.checked_sub(first).filter(|&count| count > 0)?;
+        Some((last_at - first_at) / u32::try_from(completed).ok()?)
+    }
+}
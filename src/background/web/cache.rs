@@ -1,21 +1,43 @@
 use super::super::Stats;
-use super::Request;
+use super::{Freshness, Request};
 use chrono::{offset::Utc, DateTime};
 use crossbeam::channel::{Receiver, Sender};
 use rusqlite::{
+    functions::FunctionFlags,
     named_params,
     types::{ToSqlOutput, ValueRef},
     OptionalExtension, ToSql,
 };
 use std::{
-    path::Path,
-    sync::{atomic::Ordering, Arc},
+    path::{Path, PathBuf},
+    sync::{atomic::Ordering, Arc, Mutex},
+    time::Duration,
 };
 use url::Url;
 
+/// How long to wait on another thread or instance writing to the cache before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Fields of post data that only change how much comes back per page, not which page it is, left
+/// out of the cache key so changing them doesn't refetch every page
+const PAGE_SIZE_FIELDS: &[&str] = &["count", "size"];
+
+/// Most idle read-only connections to keep open, enough for every cache and scraper thread to
+/// look up pages at once
+const MAX_IDLE_READERS: usize = 8;
+
+/// A pool of read-only connections for cache lookups, so hits aren't stuck behind the writer or
+/// behind a cache thread waiting on the server
+#[derive(Debug)]
+pub(crate) struct Readers {
+    path: PathBuf,
+    idle: Mutex<Vec<rusqlite::Connection>>,
+}
+
 #[derive(Debug)]
 pub(crate) struct Cache {
     cache: rusqlite::Connection,
+    readers: Arc<Readers>,
     stats: Arc<Stats>,
     server_requests: Sender<Request>,
 }
@@ -61,40 +83,125 @@ impl DebugExt for serde_json::Value {
 #[culpa::try_fn]
 pub fn run(
     cache_dir: &Path,
+    readers: Arc<Readers>,
     stats: Arc<Stats>,
     requests: Receiver<Request>,
     server_requests: Sender<Request>,
 ) -> eyre::Result<std::thread::JoinHandle<()>> {
-    let cache = Cache::new(cache_dir, stats, server_requests)?;
+    let cache = Cache::new(cache_dir, readers, stats, server_requests)?;
 
     std::thread::Builder::new()
         .name("web-cache".to_owned())
         .spawn(move || {
             for request in &requests {
                 match request {
-                    Request::Get { url, response } => {
-                        let _ = response.send(cache.get(&url));
+                    Request::Get {
+                        url,
+                        freshness,
+                        response,
+                    } => {
+                        let _ = response.send(cache.get(&url, freshness));
                     }
                     Request::Post {
                         url,
                         data,
+                        freshness,
                         response,
                     } => {
-                        let _ = response.send(cache.post(&url, &data));
+                        let _ = response.send(cache.post(&url, &data, freshness));
+                    }
+                    Request::GetBytes {
+                        url,
+                        freshness,
+                        response,
+                    } => {
+                        let _ = response.send(cache.get_bytes(&url, freshness));
                     }
                 }
             }
         })?
 }
 
+/// Read a previously retrieved page straight from the cache, without going through the cache
+/// threads or ever retrieving it from the server
+#[culpa::try_fn]
+pub fn read(readers: &Readers, url: &Url) -> eyre::Result<Option<String>> {
+    readers.with(|cache| {
+        cache
+            .query_row(
+                "
+                    select response
+                    from pages join bodies on bodies.hash = pages.body
+                    where url = :url and method = :method and data is null
+                ",
+                named_params!(":url": url, ":method": Method::Get),
+                |row| row.get("response"),
+            )
+            .optional()
+    })?
+}
+
+impl Readers {
+    /// Connections are only opened once needed, the cache may not exist until a cache thread has
+    /// created it
+    pub(crate) fn new(cache_dir: &Path) -> Self {
+        Self {
+            path: cache_dir.join("web-cache.sqlite"),
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Run a query on an idle connection, or a new one if they're all in use
+    #[culpa::try_fn]
+    fn with<T>(
+        &self,
+        query: impl FnOnce(&rusqlite::Connection) -> rusqlite::Result<T>,
+    ) -> eyre::Result<T> {
+        let idle = self.idle.lock().unwrap().pop();
+        let cache = if let Some(cache) = idle {
+            cache
+        } else {
+            let cache = rusqlite::Connection::open_with_flags(
+                &self.path,
+                rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY
+                    | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )?;
+            cache.busy_timeout(BUSY_TIMEOUT)?;
+            cache
+        };
+
+        let result = query(&cache);
+
+        let mut idle = self.idle.lock().unwrap();
+        if idle.len() < MAX_IDLE_READERS {
+            idle.push(cache);
+        }
+
+        result?
+    }
+}
+
 impl Cache {
     #[culpa::try_fn]
     pub(crate) fn new(
         cache_dir: &Path,
+        readers: Arc<Readers>,
         stats: Arc<Stats>,
         server_requests: Sender<Request>,
     ) -> eyre::Result<Self> {
         let mut cache = rusqlite::Connection::open(cache_dir.join("web-cache.sqlite"))?;
+        cache.busy_timeout(BUSY_TIMEOUT)?;
+        // Readers and the writer don't block each other, and a crash mid-write leaves the last
+        // commit intact rather than a half written page
+        cache
+            .pragma_update_and_check(None, "journal_mode", "wal", |row| row.get::<_, String>(0))?;
+        // Used by the migration moving existing responses into `bodies`
+        cache.create_scalar_function(
+            "content_hash",
+            1,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| Ok(content_hash(ctx.get_raw(0).as_str()?).to_vec()),
+        )?;
 
         let migrations = [
             "create table pages (id integer primary key) strict",
@@ -104,9 +211,50 @@ impl Cache {
             "alter table pages add column response text not null",
             "alter table pages add column retrieved text not null",
             "create unique index pages_index on pages (url, method, data)",
+            "create table files (url text primary key) strict",
+            "alter table files add column response blob not null",
+            "alter table files add column retrieved text not null",
+            // Responses are stored once per distinct content, shared by every page that returned
+            // it, which means rebuilding `pages` without its own copy
+            "create table bodies (hash blob primary key, response text not null) strict",
+            "
+                insert or ignore
+                into bodies (hash, response)
+                select content_hash(response), response
+                from pages
+            ",
+            "
+                create table pages_by_body (
+                    id integer primary key,
+                    url text not null,
+                    method text not null,
+                    data text,
+                    body blob not null references bodies (hash),
+                    retrieved text not null
+                ) strict
+            ",
+            "
+                insert
+                into pages_by_body (id, url, method, data, body, retrieved)
+                select id, url, method, data, content_hash(response), retrieved
+                from pages
+            ",
+            "drop table pages",
+            "alter table pages_by_body rename to pages",
+            "create unique index pages_index on pages (url, method, data)",
+            "create index pages_body_index on pages (body)",
+            // Keys of existing posts, the same as `cache_key`, any duplicates are the same page so
+            // it doesn't matter which is kept
+            "
+                update or replace pages
+                set data = json_remove(data, '$.count', '$.size')
+                where data is not null
+            ",
         ];
 
-        let tx = cache.transaction()?;
+        // Every cache thread migrates at startup, taking the write lock up front stops them
+        // deadlocking when they all try to upgrade from reading the version
+        let tx = cache.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
         let version: u32 =
             tx.pragma_query_value(None, "user_version", |row| row.get("user_version"))?;
         for (migration, index) in migrations.into_iter().zip(1u32..) {
@@ -119,6 +267,7 @@ impl Cache {
 
         Self {
             cache,
+            readers,
             stats,
             server_requests,
         }
@@ -126,26 +275,86 @@ impl Cache {
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url))]
-    pub(crate) fn get(&self, url: &Url) -> eyre::Result<String> {
+    pub(crate) fn get(&self, url: &Url, freshness: Freshness) -> eyre::Result<String> {
         self.stats.web_requests.fetch_add(1, Ordering::Relaxed);
-        if let Some(response) = self.get_from_cache(url, Method::Get, None)? {
+        let cached = if freshness == Freshness::Refresh {
+            None
+        } else {
+            self.get_from_cache(url, Method::Get, None)?
+        };
+        if let Some(response) = cached {
             response
+        } else if freshness == Freshness::Offline {
+            Err(eyre::eyre!("{url} isn't cached"))?
         } else {
             let response = self.get_from_server(url.clone())?;
-            self.add_to_cache(url, Method::Get, None, &response)?;
+            tolerate_busy(self.add_to_cache(url, Method::Get, None, &response))?;
+            response
+        }
+    }
+
+    /// Binary files are kept apart from the pages, which are all text
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    pub(crate) fn get_bytes(&self, url: &Url, freshness: Freshness) -> eyre::Result<Vec<u8>> {
+        self.stats.web_requests.fetch_add(1, Ordering::Relaxed);
+        let cached = if freshness == Freshness::Refresh {
+            None
+        } else {
+            self.readers.with(|cache| {
+                cache
+                    .query_row(
+                        "select response from files where url = :url",
+                        named_params!(":url": url),
+                        |row| row.get::<_, Vec<u8>>("response"),
+                    )
+                    .optional()
+            })?
+        };
+        if let Some(response) = cached {
+            tracing::info!("cache hit");
+            self.stats.web_cache_hits.fetch_add(1, Ordering::Relaxed);
+            response
+        } else {
+            tracing::info!("cache miss");
+            self.stats.web_cache_misses.fetch_add(1, Ordering::Relaxed);
+            if freshness == Freshness::Offline {
+                Err(eyre::eyre!("{url} isn't cached"))?;
+            }
+            let (tx, rx) = crossbeam::channel::bounded(1);
+            self.server_requests.send(Request::GetBytes {
+                url: url.clone(),
+                freshness: Freshness::Refresh,
+                response: tx,
+            })?;
+            let response = rx.recv()??;
+            tolerate_busy(self.add_file_to_cache(url, &response))?;
             response
         }
     }
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url))]
-    pub(crate) fn post(&self, url: &Url, data: &serde_json::Value) -> eyre::Result<String> {
+    pub(crate) fn post(
+        &self,
+        url: &Url,
+        data: &serde_json::Value,
+        freshness: Freshness,
+    ) -> eyre::Result<String> {
         self.stats.web_requests.fetch_add(1, Ordering::Relaxed);
-        if let Some(response) = self.get_from_cache(url, Method::Post, Some(data))? {
+        let key = cache_key(data);
+        let cached = if freshness == Freshness::Refresh {
+            None
+        } else {
+            self.get_from_cache(url, Method::Post, Some(&key))?
+        };
+        if let Some(response) = cached {
             response
+        } else if freshness == Freshness::Offline {
+            Err(eyre::eyre!("{url} with {key} isn't cached"))?
         } else {
             let response = self.post_to_server(url.clone(), data.clone())?;
-            self.add_to_cache(url, Method::Post, Some(data), &response)?;
+            tolerate_busy(self.add_to_cache(url, Method::Post, Some(&key), &response))?;
             response
         }
     }
@@ -158,23 +367,24 @@ impl Cache {
         method: Method,
         data: Option<&serde_json::Value>,
     ) -> eyre::Result<Option<String>> {
-        let result = self
-            .cache
-            .query_row(
-                "
-                    select retrieved, response
-                    from pages
-                    where url = :url and method = :method and data is :data
-                ",
-                named_params!(":url": url, ":method": method, ":data": data),
-                |row| {
-                    Ok((
-                        row.get::<_, DateTime<Utc>>("retrieved")?,
-                        row.get::<_, String>("response")?,
-                    ))
-                },
-            )
-            .optional()?;
+        let result = self.readers.with(|cache| {
+            cache
+                .query_row(
+                    "
+                        select retrieved, response
+                        from pages join bodies on bodies.hash = pages.body
+                        where url = :url and method = :method and data is :data
+                    ",
+                    named_params!(":url": url, ":method": method, ":data": data),
+                    |row| {
+                        Ok((
+                            row.get::<_, DateTime<Utc>>("retrieved")?,
+                            row.get::<_, String>("response")?,
+                        ))
+                    },
+                )
+                .optional()
+        })?;
 
         if let Some((retrieved, response)) = result {
             tracing::info!(%retrieved, "cache hit");
@@ -191,8 +401,11 @@ impl Cache {
     #[tracing::instrument(skip(self), fields(%url))]
     fn get_from_server(&self, url: Url) -> eyre::Result<String> {
         let (tx, rx) = crossbeam::channel::bounded(1);
-        self.server_requests
-            .send(Request::Get { url, response: tx })?;
+        self.server_requests.send(Request::Get {
+            url,
+            freshness: Freshness::Refresh,
+            response: tx,
+        })?;
         rx.recv()??
     }
 
@@ -203,6 +416,7 @@ impl Cache {
         self.server_requests.send(Request::Post {
             url,
             data,
+            freshness: Freshness::Refresh,
             response: tx,
         })?;
         rx.recv()??
@@ -217,19 +431,105 @@ impl Cache {
         data: Option<&serde_json::Value>,
         response: &str,
     ) -> eyre::Result<()> {
-        self.cache.execute(
+        let hash = content_hash(response);
+        let tx = self.cache.unchecked_transaction()?;
+        tx.execute(
+            "
+                insert or ignore
+                into bodies (hash, response)
+                values (:hash, :response)
+            ",
+            named_params!(":hash": hash, ":response": &response),
+        )?;
+        // The unique index treats null `data` as distinct, so explicitly drop any stale entry
+        // being replaced by a refresh.
+        let replaced = tx
+            .query_row(
+                "
+                    delete
+                    from pages
+                    where url = :url and method = :method and data is :data
+                    returning body
+                ",
+                named_params!(":url": url, ":method": method, ":data": data),
+                |row| row.get::<_, Vec<u8>>("body"),
+            )
+            .optional()?;
+        tx.execute(
             "
                 insert
-                into pages (url, method, data, retrieved, response)
-                values (:url, :method, :data, :retrieved, :response)
+                into pages (url, method, data, retrieved, body)
+                values (:url, :method, :data, :retrieved, :hash)
             ",
             named_params! {
                 ":url": url,
                 ":method": method,
                 ":data": data,
                 ":retrieved": Utc::now(),
-                ":response": &response,
+                ":hash": hash,
             },
         )?;
+        if let Some(replaced) = replaced {
+            tx.execute(
+                "
+                    delete
+                    from bodies
+                    where hash = :hash and not exists (select 1 from pages where body = :hash)
+                ",
+                named_params!(":hash": replaced),
+            )?;
+        }
+        tx.commit()?;
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self, response), fields(%url, response_len=response.len()))]
+    fn add_file_to_cache(&self, url: &Url, response: &[u8]) -> eyre::Result<()> {
+        self.cache.execute(
+            "
+                insert or replace
+                into files (url, response, retrieved)
+                values (:url, :response, :retrieved)
+            ",
+            named_params! {
+                ":url": url,
+                ":response": response,
+                ":retrieved": Utc::now(),
+            },
+        )?;
+    }
+}
+
+/// The post data identifying which page a response is, paging tokens and ids are kept but page
+/// sizes are dropped
+fn cache_key(data: &serde_json::Value) -> serde_json::Value {
+    let mut key = data.clone();
+    if let Some(fields) = key.as_object_mut() {
+        for field in PAGE_SIZE_FIELDS {
+            fields.remove(*field);
+        }
+    }
+    key
+}
+
+/// The key a response is stored under in `bodies`
+fn content_hash(response: &str) -> [u8; 32] {
+    *blake3::hash(response.as_bytes()).as_bytes()
+}
+
+/// Another thread or instance holding the cache for too long only loses this response from the
+/// cache, rather than failing the scrape that retrieved it
+#[culpa::try_fn]
+fn tolerate_busy(result: eyre::Result<()>) -> eyre::Result<()> {
+    match result {
+        Err(error)
+            if error
+                .downcast_ref::<rusqlite::Error>()
+                .and_then(rusqlite::Error::sqlite_error_code)
+                == Some(rusqlite::ErrorCode::DatabaseBusy) =>
+        {
+            tracing::warn!(?error, "web cache busy, not caching response");
+        }
+        result => result?,
     }
 }
@@ -1,15 +1,60 @@
+use super::super::Stats;
 use super::Request;
 use crossbeam::channel::Receiver;
 use std::{
     cell::Cell,
+    sync::{atomic::Ordering, Arc, Condvar, Mutex},
     time::{Duration, Instant},
 };
 use url::Url;
 
+/// The shortest delay between requests, kept to whenever the server is responding well
+const MIN_DELAY: Duration = Duration::from_secs(1);
+
+/// The longest the delay between requests backs off to
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Responses slower than this are taken as the server struggling
+const SLOW_RESPONSE: Duration = Duration::from_secs(3);
+
 #[derive(Debug)]
 pub(crate) struct Client {
     client: reqwest::blocking::Client,
+    stats: Arc<Stats>,
     last_request: Cell<Instant>,
+    /// Doubled whenever the server is struggling, and relaxed back towards [`MIN_DELAY`] as it
+    /// recovers
+    delay: Cell<Duration>,
+    /// The `Cookie` header of a logged in session, only sent to Bandcamp itself
+    cookies: Option<reqwest::header::HeaderValue>,
+    /// A mock server to send requests to instead of their real host
+    mock: Option<Url>,
+}
+
+/// Holds the client between requests while the network is paused, leaving the rest queued behind
+/// it
+#[derive(Debug, Default)]
+pub(crate) struct Pause {
+    paused: Mutex<bool>,
+    resumed: Condvar,
+}
+
+impl Pause {
+    pub(crate) fn set(&self, paused: bool) {
+        *self.paused.lock().unwrap() = paused;
+        self.resumed.notify_all();
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    fn wait(&self) {
+        let _unpaused = self
+            .resumed
+            .wait_while(self.paused.lock().unwrap(), |paused| *paused)
+            .unwrap();
+    }
 }
 
 trait DebugExt {
@@ -37,57 +82,170 @@ impl DebugExt for serde_json::Value {
 }
 
 #[culpa::try_fn]
-pub fn run(requests: Receiver<Request>) -> eyre::Result<std::thread::JoinHandle<()>> {
-    let client = Client::new();
+pub fn run(
+    requests: Receiver<Request>,
+    stats: Arc<Stats>,
+    pause: Arc<Pause>,
+    cookies: Option<String>,
+    mock: Option<Url>,
+) -> eyre::Result<std::thread::JoinHandle<()>> {
+    let client = Client::new(stats, cookies, mock)?;
 
     std::thread::Builder::new()
         .name("web-client".to_owned())
         .spawn(move || {
             for request in &requests {
+                pause.wait();
                 match request {
-                    Request::Get { url, response } => {
+                    Request::Get { url, response, .. } => {
                         let _ = response.send(client.get(&url));
                     }
                     Request::Post {
                         url,
                         data,
                         response,
+                        ..
                     } => {
                         let _ = response.send(client.post(&url, &data));
                     }
+                    Request::GetBytes { url, response, .. } => {
+                        let _ = response.send(client.get_bytes(&url));
+                    }
                 }
             }
         })?
 }
 
 impl Client {
-    fn new() -> Self {
+    #[culpa::try_fn]
+    fn new(stats: Arc<Stats>, cookies: Option<String>, mock: Option<Url>) -> eyre::Result<Self> {
+        stats
+            .web_delay_millis
+            .store(MIN_DELAY.as_millis() as u64, Ordering::Relaxed);
+        let cookies = cookies
+            .map(|cookies| {
+                let mut value = reqwest::header::HeaderValue::from_str(cookies.trim())?;
+                // Kept out of any debug logging of the requests
+                value.set_sensitive(true);
+                eyre::Result::<_>::Ok(value)
+            })
+            .transpose()?;
         Self {
             client: reqwest::blocking::Client::new(),
+            stats,
             last_request: Cell::new(Instant::now()),
+            delay: Cell::new(MIN_DELAY),
+            cookies,
+            mock,
+        }
+    }
+
+    /// Start a request for `url`, logged in if it's to Bandcamp and there's a session to use
+    #[culpa::try_fn]
+    fn request(
+        &self,
+        method: reqwest::Method,
+        url: &Url,
+    ) -> eyre::Result<reqwest::blocking::RequestBuilder> {
+        let request = self.client.request(method, self.target(url)?);
+        let bandcamp = url
+            .host_str()
+            .is_some_and(|host| host == "bandcamp.com" || host.ends_with(".bandcamp.com"));
+        match &self.cookies {
+            Some(cookies) if bandcamp => request.header(reqwest::header::COOKIE, cookies.clone()),
+            _ => request,
+        }
+    }
+
+    /// Where to actually send a request for `url`
+    #[culpa::try_fn]
+    fn target(&self, url: &Url) -> eyre::Result<Url> {
+        if let Some(mock) = &self.mock {
+            let host = url.host_str().unwrap_or_default();
+            let mut target = mock.join(&format!("{host}{}", url.path()))?;
+            target.set_query(url.query());
+            target
+        } else {
+            url.clone()
         }
     }
 
     fn check_delay(&self) {
-        const REQUEST_DELAY: Duration = Duration::from_secs(1);
-        if let Some(delay) = REQUEST_DELAY.checked_sub(self.last_request.get().elapsed()) {
+        if self.mock.is_some() {
+            return;
+        }
+        if let Some(delay) = self
+            .delay
+            .get()
+            .checked_sub(self.last_request.get().elapsed())
+        {
             tracing::info!(?delay, "delaying request");
             std::thread::sleep(delay);
         }
         self.last_request.set(Instant::now());
     }
 
+    /// Back off when the server is slow or failing, and relax once it's healthy again
+    fn adapt(&self, elapsed: Duration, healthy: bool) {
+        let delay = self.delay.get();
+        let new = if healthy && elapsed < SLOW_RESPONSE {
+            delay.mul_f64(0.9).max(MIN_DELAY)
+        } else {
+            (delay * 2).min(MAX_DELAY)
+        };
+        if new > delay {
+            tracing::warn!(?elapsed, healthy, ?new, "server struggling, backing off");
+        }
+        self.delay.set(new);
+        self.stats
+            .web_delay_millis
+            .store(new.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    #[culpa::try_fn]
+    fn send(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> eyre::Result<reqwest::blocking::Response> {
+        self.check_delay();
+        let start = Instant::now();
+        let response = request.send();
+        // Rate limiting and server errors are the server asking for less, other client errors are
+        // just bad requests
+        let healthy = response.as_ref().is_ok_and(|response| {
+            let status = response.status();
+            !status.is_server_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+        });
+        let elapsed = start.elapsed();
+        self.stats
+            .web_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        self.adapt(elapsed, healthy);
+        response?
+    }
+
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url))]
     fn get(&self, url: &Url) -> eyre::Result<String> {
-        self.check_delay();
-        self.client.get(url.clone()).send()?.text()?
+        self.send(self.request(reqwest::Method::GET, url)?)?
+            .error_for_status()?
+            .text()?
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    fn get_bytes(&self, url: &Url) -> eyre::Result<Vec<u8>> {
+        self.send(self.request(reqwest::Method::GET, url)?)?
+            .error_for_status()?
+            .bytes()?
+            .into()
     }
 
     #[culpa::try_fn]
     #[tracing::instrument(skip(self), fields(%url, data=%data.dbg()))]
     fn post(&self, url: &Url, data: &serde_json::Value) -> eyre::Result<String> {
-        self.check_delay();
-        self.client.post(url.clone()).json(data).send()?.text()?
+        self.send(self.request(reqwest::Method::POST, url)?.json(data))?
+            .error_for_status()?
+            .text()?
     }
 }
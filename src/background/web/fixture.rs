@@ -0,0 +1,134 @@
+use super::Request;
+use crossbeam::channel::Receiver;
+use eyre::WrapErr;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Serves pages from a directory of saved fixtures instead of the network, so the scraper can be
+/// run reproducibly against known pages
+#[derive(Debug)]
+pub(crate) struct Fixtures {
+    dir: PathBuf,
+}
+
+/// A saved response to a post, which are distinguished by their data
+#[derive(Debug, serde::Deserialize)]
+struct Post {
+    data: serde_json::Value,
+    response: serde_json::Value,
+}
+
+/// The fixtures checked in alongside the crate, which the tests scrape
+#[cfg(test)]
+pub(crate) fn checked_in() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// A directory for a test to keep its cache in, removed again once the test is done
+#[cfg(test)]
+pub(crate) struct TempDir(PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("bc-scraper3-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[culpa::try_fn]
+pub fn run(dir: &Path, requests: Receiver<Request>) -> eyre::Result<std::thread::JoinHandle<()>> {
+    let fixtures = Fixtures::new(dir);
+
+    std::thread::Builder::new()
+        .name("web-fixtures".to_owned())
+        .spawn(move || {
+            for request in &requests {
+                match request {
+                    Request::Get { url, response, .. } => {
+                        let _ = response.send(fixtures.get(&url));
+                    }
+                    Request::Post {
+                        url,
+                        data,
+                        response,
+                        ..
+                    } => {
+                        let _ = response.send(fixtures.post(&url, &data));
+                    }
+                    Request::GetBytes { url, response, .. } => {
+                        let _ = response.send(fixtures.get_bytes(&url));
+                    }
+                }
+            }
+        })?
+}
+
+impl Fixtures {
+    pub(crate) fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_owned(),
+        }
+    }
+
+    /// Fixtures are stored by host and path, e.g. `artist.bandcamp.com/album/name.html` for a get,
+    /// with posts to a url stored together as a json array of `{ "data", "response" }` objects
+    fn path(&self, url: &Url, extension: &str) -> PathBuf {
+        let path = url.path().trim_matches('/');
+        let path = if path.is_empty() { "index" } else { path };
+        self.dir
+            .join(url.host_str().unwrap_or_default())
+            .join(format!("{path}.{extension}"))
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    pub(crate) fn get(&self, url: &Url) -> eyre::Result<String> {
+        let path = self.path(url, "html");
+        std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("missing fixture {}", path.display()))?
+    }
+
+    /// Binary files keep their own extension, e.g. `f4.bcbits.com/img/a0123456789_7.jpg`
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    pub(crate) fn get_bytes(&self, url: &Url) -> eyre::Result<Vec<u8>> {
+        let path = self
+            .dir
+            .join(url.host_str().unwrap_or_default())
+            .join(url.path().trim_matches('/'));
+        std::fs::read(&path).wrap_err_with(|| format!("missing fixture {}", path.display()))?
+    }
+
+    #[culpa::try_fn]
+    #[tracing::instrument(skip(self), fields(%url))]
+    pub(crate) fn post(&self, url: &Url, data: &serde_json::Value) -> eyre::Result<String> {
+        let path = self.path(url, "json");
+        let posts: Vec<Post> = serde_json::from_str(
+            &std::fs::read_to_string(&path)
+                .wrap_err_with(|| format!("missing fixture {}", path.display()))?,
+        )?;
+        let post = posts
+            .into_iter()
+            .find(|post| &post.data == data)
+            .ok_or_else(|| eyre::eyre!("no fixture in {} for {data}", path.display()))?;
+        serde_json::to_string(&post.response)?
+    }
+}
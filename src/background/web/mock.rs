@@ -0,0 +1,179 @@
+use super::fixture::Fixtures;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+};
+use url::Url;
+
+/// Start a local http server serving fixture pages, returning the url the client should send
+/// requests to
+///
+/// Requests for `https://{host}/{path}` are made to `{base}/{host}/{path}`, so that the full
+/// pipeline from client through cache is exercised against known pages.
+#[culpa::try_fn]
+pub fn run(dir: &Path) -> eyre::Result<Url> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let base = Url::parse(&format!("http://{}/", listener.local_addr()?))?;
+    let fixtures = Fixtures::new(dir);
+
+    tracing::info!(%base, dir = %dir.display(), "serving mock pages");
+
+    // Not joined with the other threads, it has no channel to close and serves until exit
+    std::thread::Builder::new()
+        .name("web-mock-server".to_owned())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        if let Err(error) = respond(&fixtures, stream) {
+                            tracing::warn!(?error, "failed responding to mock request");
+                        }
+                    }
+                    Err(error) => {
+                        tracing::warn!(?error, "failed to accept mock connection");
+                    }
+                }
+            }
+        })?;
+
+    base
+}
+
+/// The url a request to the mock server was originally for
+#[culpa::try_fn]
+fn original(target: &str) -> eyre::Result<Url> {
+    let target = target.trim_start_matches('/');
+    let (host, rest) = target.split_once('/').unwrap_or((target, ""));
+    Url::parse(&format!("https://{host}/{rest}"))?
+}
+
+#[culpa::try_fn]
+fn respond(fixtures: &Fixtures, mut stream: TcpStream) -> eyre::Result<()> {
+    let mut reader = BufReader::new(&mut stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts
+        .next()
+        .ok_or_else(|| eyre::eyre!("malformed request line {line:?}"))?
+        .to_owned();
+    let url = original(
+        parts
+            .next()
+            .ok_or_else(|| eyre::eyre!("malformed request line {line:?}"))?,
+    )?;
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse()?;
+            }
+        }
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    let result = match method.as_str() {
+        "GET" => fixtures.get(&url),
+        "POST" => serde_json::from_slice(&body)
+            .map_err(eyre::Report::from)
+            .and_then(|data| fixtures.post(&url, &data)),
+        _ => Err(eyre::eyre!("unsupported method {method}")),
+    };
+
+    let (status, body) = match result {
+        Ok(body) => ("200 OK", body),
+        Err(error) => {
+            tracing::warn!(%url, ?error, "no mock page");
+            ("404 Not Found", format!("{error:?}"))
+        }
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        body.len(),
+    )?;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixture::{checked_in, TempDir};
+    use crate::{
+        background::{Request, Response, Scraper, Selectors, Source},
+        data::{ArtistId, RelationKind, ReleaseId, UserId},
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn release() {
+        let cache = TempDir::new("mock-release");
+        let selectors = Selectors::load(&cache).unwrap();
+        let fixtures = checked_in();
+        let source = Source::MockServer(&fixtures);
+        let scraper = Scraper::new(&cache, source, None, selectors, Vec::new()).unwrap();
+        scraper
+            .send(Request::Release {
+                url: "https://testartist.bandcamp.com/album/first-album".to_owned(),
+            })
+            .unwrap();
+
+        // The release itself is sent last, once all its fans have been found
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let mut responses = Vec::new();
+        while !responses
+            .iter()
+            .any(|response| matches!(response, Response::Release(..)))
+        {
+            assert!(
+                Instant::now() < deadline,
+                "timed out scraping, got {responses:?}"
+            );
+            match scraper.try_recv().unwrap() {
+                Some((_, response)) => responses.push(response),
+                None => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+
+        let mut artists = Vec::new();
+        let mut fans = Vec::new();
+        let mut details = None;
+        for response in responses {
+            match response {
+                Response::ReleaseArtist(release, kind, artist) => {
+                    assert_eq!(release.id, ReleaseId(1001));
+                    artists.push((kind, artist.id));
+                }
+                Response::Fans(release, RelationKind::Fan, scraped) => {
+                    assert_eq!(release.id, ReleaseId(1001));
+                    fans.extend(scraped.into_iter().map(|fan| fan.id));
+                }
+                Response::Release(release, scraped) => {
+                    assert_eq!(release.id, ReleaseId(1001));
+                    details = Some(scraped);
+                }
+                _ => {}
+            }
+        }
+
+        assert_eq!(artists, [(RelationKind::Artist, ArtistId(2001))]);
+        assert_eq!(fans, [UserId(4003), UserId(4001), UserId(4002)]);
+        let details = details.unwrap();
+        assert_eq!(details.title, "First Album");
+        assert_eq!(details.fan_count, 3);
+    }
+}
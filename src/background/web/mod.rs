@@ -3,16 +3,39 @@ use url::Url;
 
 pub mod cache;
 pub mod client;
+pub mod fixture;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock;
+
+/// Whether a request can be answered from the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Use a cached response if there is one, retrieving it otherwise
+    Cached,
+    /// Skip any cached response and replace it with a freshly retrieved one
+    Refresh,
+    /// Only use a cached response, failing rather than retrieving it
+    Offline,
+}
 
 pub enum Request {
     Get {
         url: Url,
+        freshness: Freshness,
         response: Sender<eyre::Result<String>>,
     },
 
     Post {
         url: Url,
         data: serde_json::Value,
+        freshness: Freshness,
         response: Sender<eyre::Result<String>>,
     },
+
+    /// A binary file like an image, rather than a page
+    GetBytes {
+        url: Url,
+        freshness: Freshness,
+        response: Sender<eyre::Result<Vec<u8>>>,
+    },
 }
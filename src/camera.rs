@@ -2,23 +2,36 @@ use bevy::{
     core_pipeline::core_2d::Camera2d,
     ecs::{
         change_detection::DetectChangesMut,
-        change_detection::{Res, ResMut},
-        query::With,
+        change_detection::{DetectChanges, Mut, Ref, Res, ResMut},
+        component::Component,
+        entity::Entity,
+        query::{Has, With, Without},
         schedule::IntoSystemConfigs,
-        system::{Commands, Resource, Single},
+        system::{Commands, Query, Resource, Single},
     },
     input::keyboard::KeyCode,
     input::{
         mouse::{AccumulatedMouseScroll, MouseButton, MouseScrollUnit},
         ButtonInput,
     },
-    math::Vec2,
-    render::camera::Camera,
-    time::{Time, Virtual},
+    math::{Rect, UVec2, Vec2},
+    render::camera::{Camera, Viewport},
+    time::{Real, Time, Virtual},
     transform::components::{GlobalTransform, Transform},
+    ui::IsDefaultUiCamera,
+    utils::default,
     window::{PrimaryWindow, Window},
 };
 
+/// How far past the zoom limits a single scroll can go, before being eased back to them
+const ZOOM_OVERSHOOT: f32 = 1.5;
+
+/// Rate at which the camera eases back within its limits, higher is snappier
+const RUBBER_BAND_RATE: f32 = 8.0;
+
+/// Rate at which the camera eases towards the scale it's zooming to, higher is snappier
+const ZOOM_RATE: f32 = 16.0;
+
 #[derive(Default, Resource, PartialEq)]
 pub struct Cursor {
     pub screen_delta: Vec2,
@@ -26,24 +39,122 @@ pub struct Cursor {
     pub world_position: Vec2,
 }
 
+/// The camera that's always shown, on the left while the view is split
+#[derive(Debug, Component)]
+pub struct MainCamera;
+
+/// The camera shown on the right while the view is split
+#[derive(Debug, Component)]
+struct SecondaryCamera;
+
+/// The camera the cursor is over, which panning, zooming and interacting apply to
+#[derive(Debug, Component)]
+pub struct ActiveCamera;
+
+/// Whether the view is split into two side-by-side viewports, each with their own pan and zoom
+/// over the same world
+#[derive(Debug, Default, Resource)]
+pub struct SplitView(pub bool);
+
+/// A zoom in progress, eased towards over a few frames
+#[derive(Default, Resource)]
+struct Zooming(Option<ZoomTarget>);
+
+#[derive(Clone, Copy)]
+struct ZoomTarget {
+    camera: Entity,
+    scale: f32,
+    /// The world position that stays under the cursor while zooming
+    anchor: Vec2,
+}
+
 pub struct CameraPlugin;
 
 impl bevy::app::Plugin for CameraPlugin {
     fn build(&self, app: &mut bevy::app::App) {
+        app.insert_resource(Zooming::default());
+        app.insert_resource(SplitView::default());
         app.add_systems(bevy::app::Startup, setup).add_systems(
             bevy::app::PreUpdate,
-            (update_cursor_position, drag, zoom).chain(),
+            (
+                split_view,
+                update_cursor_position,
+                drag,
+                zoom,
+                animate_zoom,
+                rubber_band,
+            )
+                .chain(),
         );
     }
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d);
+    // The ui stays on the main camera while the view is split, rather than following the higher
+    // order secondary camera
+    commands.spawn((Camera2d, MainCamera, ActiveCamera, IsDefaultUiCamera));
+}
+
+fn split_view(
+    split: Res<SplitView>,
+    window: Single<Ref<Window>, With<PrimaryWindow>>,
+    main: Single<(Entity, &mut Camera, &Transform), With<MainCamera>>,
+    mut secondary: Query<(Entity, &mut Camera), (With<SecondaryCamera>, Without<MainCamera>)>,
+    mut commands: Commands,
+) {
+    if !split.is_changed() && !window.is_changed() {
+        return;
+    }
+
+    let (main, mut main_camera, transform) = main.into_inner();
+
+    if !split.0 {
+        main_camera.viewport = None;
+        for (entity, _) in &secondary {
+            commands.entity(entity).despawn();
+        }
+        commands.entity(main).insert(ActiveCamera);
+        return;
+    }
+
+    let size = window.physical_size();
+    if size.x < 2 || size.y == 0 {
+        return;
+    }
+
+    let left = Viewport {
+        physical_position: UVec2::ZERO,
+        physical_size: UVec2::new(size.x / 2, size.y),
+        ..default()
+    };
+    let right = Viewport {
+        physical_position: UVec2::new(size.x / 2, 0),
+        physical_size: UVec2::new(size.x - size.x / 2, size.y),
+        ..default()
+    };
+
+    main_camera.viewport = Some(left);
+    if let Ok((_, mut camera)) = secondary.get_single_mut() {
+        camera.viewport = Some(right);
+    } else {
+        // Starts out looking at the same place, to be panned away from
+        commands.spawn((
+            Camera2d,
+            Camera {
+                order: 1,
+                viewport: Some(right),
+                ..default()
+            },
+            *transform,
+            SecondaryCamera,
+        ));
+    }
 }
 
 fn update_cursor_position(
     window: Single<&Window, With<PrimaryWindow>>,
-    camera: Single<(&GlobalTransform, &Camera), ()>,
+    button: Res<ButtonInput<MouseButton>>,
+    cameras: Query<(Entity, &GlobalTransform, &Camera, Has<ActiveCamera>)>,
     cursor: Option<ResMut<Cursor>>,
     mut commands: Commands,
 ) {
@@ -52,9 +163,33 @@ fn update_cursor_position(
         return;
     };
 
-    let (global_transform, camera) = camera.into_inner();
+    let hovered = cameras.iter().find(|(_, _, camera, _)| {
+        camera
+            .logical_viewport_rect()
+            .is_some_and(|rect| rect.contains(screen_position))
+    });
+    let active = cameras.iter().find(|&(.., active)| active);
+
+    // Keep dragging in the view the drag started in, even once the cursor leaves it
+    let camera = if button.pressed(MouseButton::Left) {
+        active.or(hovered)
+    } else {
+        hovered.or(active)
+    };
 
-    let Ok(world_position) = camera.viewport_to_world_2d(&global_transform, screen_position) else {
+    let Some((entity, global_transform, camera, is_active)) = camera else {
+        commands.remove_resource::<Cursor>();
+        return;
+    };
+
+    if !is_active {
+        if let Some((previous, ..)) = active {
+            commands.entity(previous).remove::<ActiveCamera>();
+        }
+        commands.entity(entity).insert(ActiveCamera);
+    }
+
+    let Ok(world_position) = camera.viewport_to_world_2d(global_transform, screen_position) else {
         commands.remove_resource::<Cursor>();
         return;
     };
@@ -78,7 +213,7 @@ fn update_cursor_position(
 fn drag(
     button: Res<ButtonInput<MouseButton>>,
     cursor: Option<Res<Cursor>>,
-    camera: Single<(&mut Transform, &mut GlobalTransform), With<Camera>>,
+    camera: Single<(&mut Transform, &mut GlobalTransform), With<ActiveCamera>>,
     dragged: Res<crate::interact::Dragged>,
 ) {
     let (mut transform, mut global_transform) = camera.into_inner();
@@ -105,11 +240,11 @@ fn zoom(
     scroll: Res<AccumulatedMouseScroll>,
     keyboard: Res<ButtonInput<KeyCode>>,
     cursor: Option<Res<Cursor>>,
-    camera: Single<(&mut Transform, &mut GlobalTransform), With<Camera>>,
+    camera: Single<(Entity, &Transform), With<ActiveCamera>>,
     mut time: ResMut<Time<Virtual>>,
+    mut zooming: ResMut<Zooming>,
+    args: Res<crate::Args>,
 ) {
-    let (mut transform, mut global_transform) = camera.into_inner();
-
     if keyboard.pressed(KeyCode::ShiftLeft) {
         if scroll.unit == MouseScrollUnit::Line && scroll.delta.y != 0.0 {
             let new_value = time.relative_speed() + scroll.delta.y.signum() * 0.125;
@@ -122,14 +257,113 @@ fn zoom(
 
     let Some(cursor) = cursor else { return };
 
-    let position = cursor.world_position.extend(0.0);
-
     // TODO: Handle trackpads nicely
     if scroll.unit == MouseScrollUnit::Line && scroll.delta.y != 0.0 {
-        let zoom_ratio = if scroll.delta.y < 0.0 { 1.5 } else { 1.0 / 1.5 };
-        transform.scale *= zoom_ratio;
-        transform.translation = position + (transform.translation - position) * zoom_ratio;
+        let zoom_ratio = if scroll.delta.y < 0.0 {
+            args.zoom_factor
+        } else {
+            1.0 / args.zoom_factor
+        };
+        let (camera, transform) = camera.into_inner();
+        // Scrolling again mid-zoom continues on from where the last scroll was going
+        let from = zooming
+            .0
+            .filter(|target| target.camera == camera)
+            .map_or(transform.scale.x, |target| target.scale);
+        let (min_scale, max_scale) = args.scale_limits();
+        zooming.0 = Some(ZoomTarget {
+            camera,
+            scale: (from * zoom_ratio)
+                .clamp(min_scale / ZOOM_OVERSHOOT, max_scale * ZOOM_OVERSHOOT),
+            anchor: cursor.world_position,
+        });
+    }
+}
 
-        *global_transform = GlobalTransform::from(*transform)
+/// Ease the camera towards the scale it's zooming to, keeping the anchor in the same place on
+/// screen
+fn animate_zoom(
+    mut cameras: Query<(&mut Transform, &mut GlobalTransform), With<Camera>>,
+    mut zooming: ResMut<Zooming>,
+    time: Res<Time<Real>>,
+) {
+    let Some(target) = zooming.0 else { return };
+
+    let Ok((mut transform, mut global_transform)) = cameras.get_mut(target.camera) else {
+        zooming.0 = None;
+        return;
+    };
+
+    let scale = transform.scale.x;
+    let t = 1.0 - (-ZOOM_RATE * time.delta_secs()).exp();
+    // Ease in log space so zooming in and out feel the same
+    let remaining = target.scale / scale;
+    let zoom_ratio = if (remaining - 1.0).abs() < 0.001 {
+        zooming.0 = None;
+        remaining
+    } else {
+        remaining.powf(t)
+    };
+
+    let anchor = target.anchor.extend(0.0);
+    transform.scale *= zoom_ratio;
+    transform.translation = anchor + (transform.translation - anchor) * zoom_ratio;
+
+    *global_transform = GlobalTransform::from(*transform)
+}
+
+/// Ease the camera back within the zoom limits and world bounds whenever it has gone past them
+fn rubber_band(
+    mut cameras: Query<(Entity, &mut Transform, &mut GlobalTransform), With<Camera>>,
+    zooming: Res<Zooming>,
+    args: Res<crate::Args>,
+    time: Res<Time<Real>>,
+) {
+    for (camera, transform, global_transform) in &mut cameras {
+        // Let a zoom past the limits play out before easing back from it
+        if zooming.0.is_some_and(|target| target.camera == camera) {
+            continue;
+        }
+
+        rubber_band_camera(transform, global_transform, &args, &time);
+    }
+}
+
+fn rubber_band_camera(
+    mut transform: Mut<Transform>,
+    mut global_transform: Mut<GlobalTransform>,
+    args: &crate::Args,
+    time: &Time<Real>,
+) {
+    let (min_scale, max_scale) = args.scale_limits();
+    let scale = transform.scale.x;
+    let target_scale = scale.clamp(min_scale, max_scale);
+
+    let position = transform.translation.truncate();
+    let target_position = args
+        .world_bounds
+        .map(|bounds| Rect::from_center_half_size(Vec2::ZERO, Vec2::splat(bounds)))
+        .map_or(position, |bounds| position.clamp(bounds.min, bounds.max));
+
+    if target_scale == scale && target_position == position {
+        return;
     }
+
+    let t = 1.0 - (-RUBBER_BAND_RATE * time.delta_secs()).exp();
+    let (scale, position) = if (target_scale / scale - 1.0).abs() < 0.001
+        && target_position.distance_squared(position) < 0.01
+    {
+        (target_scale, target_position)
+    } else {
+        (
+            scale + (target_scale - scale) * t,
+            position.lerp(target_position, t),
+        )
+    };
+
+    transform.scale.x = scale;
+    transform.scale.y = scale;
+    transform.translation = position.extend(transform.translation.z);
+
+    *global_transform = GlobalTransform::from(*transform)
 }
@@ -0,0 +1,275 @@
+use bevy::{
+    ecs::{
+        event::{EventReader, EventWriter},
+        observer::Trigger,
+        system::{Commands, Res, ResMut, Resource},
+    },
+    input::keyboard::{Key, KeyboardInput},
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+    time::{Real, Time},
+};
+
+use std::{
+    io::Write,
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    ui::{editor::Editor, notify::Notify},
+    Args,
+};
+
+/// What captured frames are encoded into
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CaptureFormat {
+    #[default]
+    Mp4,
+    Gif,
+}
+
+impl CaptureFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Gif => "gif",
+        }
+    }
+}
+
+/// Frames captured from the window, piped into ffmpeg as they arrive
+#[derive(Debug)]
+pub(crate) struct Encoder {
+    path: String,
+    format: CaptureFormat,
+    fps: f32,
+    /// Scale frames to this many pixels wide, keeping their aspect ratio
+    width: Option<u32>,
+    ffmpeg: Option<Child>,
+    /// Size of the first frame, every later frame must match it
+    size: Option<(u32, u32)>,
+    /// Set once the screenshot of the latest frame has been written, so the next can be shown
+    pub(crate) captured: bool,
+    pub(crate) failed: bool,
+    /// Screenshots still in flight once encoding has finished are dropped
+    finished: bool,
+}
+
+impl Encoder {
+    /// An encoder writing to a timestamped file named after what's being encoded
+    pub(crate) fn new(name: &str, format: CaptureFormat, fps: f32, width: Option<u32>) -> Self {
+        Self {
+            path: format!(
+                "{name}-{}.{}",
+                jiff::Timestamp::now().strftime("%Y%m%dT%H%M%SZ"),
+                format.extension(),
+            ),
+            format,
+            fps,
+            width,
+            ffmpeg: None,
+            size: None,
+            captured: false,
+            failed: false,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    #[culpa::try_fn]
+    fn spawn(&self, width: u32, height: u32) -> eyre::Result<Child> {
+        tracing::info!(path = %self.path, "encoding frames");
+        let filter = match self.format {
+            // yuv420p needs both dimensions to be even
+            CaptureFormat::Mp4 => match self.width {
+                Some(scaled) => format!("scale={}:-2", scaled & !1),
+                None => "scale=trunc(iw/2)*2:trunc(ih/2)*2".to_owned(),
+            },
+            // Gifs look terrible with the default palette, so generate one from the frames
+            CaptureFormat::Gif => format!(
+                "scale={}:-1:flags=lanczos,split[a][b];[a]palettegen[p];[b][p]paletteuse",
+                self.width
+                    .map_or("iw".to_owned(), |scaled| scaled.to_string()),
+            ),
+        };
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-y", "-loglevel", "error", "-f", "rawvideo"])
+            .args(["-pix_fmt", "rgba", "-s", &format!("{width}x{height}")])
+            .args(["-r", &self.fps.to_string()])
+            .args(["-i", "-", "-vf", &filter]);
+        if self.format == CaptureFormat::Mp4 {
+            command.args(["-pix_fmt", "yuv420p"]);
+        }
+        command.arg(&self.path).stdin(Stdio::piped()).spawn()?
+    }
+
+    #[culpa::try_fn]
+    pub(crate) fn write(&mut self, width: u32, height: u32, rgba: &[u8]) -> eyre::Result<()> {
+        match self.size {
+            None => {
+                self.ffmpeg = Some(self.spawn(width, height)?);
+                self.size = Some((width, height));
+            }
+            Some(size) if size != (width, height) => {
+                Err(eyre::eyre!("window resized while encoding"))?;
+            }
+            Some(_) => {}
+        }
+        let stdin = self
+            .ffmpeg
+            .as_mut()
+            .and_then(|ffmpeg| ffmpeg.stdin.as_mut())
+            .ok_or_else(|| eyre::eyre!("ffmpeg has no stdin"))?;
+        stdin.write_all(rgba)?;
+    }
+
+    #[culpa::try_fn]
+    pub(crate) fn finish(&mut self) -> eyre::Result<()> {
+        self.finished = true;
+        if let Some(mut ffmpeg) = self.ffmpeg.take() {
+            drop(ffmpeg.stdin.take());
+            let status = ffmpeg.wait()?;
+            if !status.success() {
+                Err(eyre::eyre!("ffmpeg failed with {status}"))?;
+            }
+        }
+    }
+
+    /// Take a screenshot of the window and write it as the next frame once it's captured
+    pub(crate) fn request(encoder: &Arc<Mutex<Self>>, commands: &mut Commands) {
+        let encoder = encoder.clone();
+        commands.spawn(Screenshot::primary_window()).observe(
+            move |trigger: Trigger<ScreenshotCaptured>| {
+                let mut encoder = encoder.lock().unwrap();
+                if encoder.finished {
+                    return;
+                }
+                let result = trigger
+                    .event()
+                    .0
+                    .clone()
+                    .try_into_dynamic()
+                    .map_err(|error| eyre::eyre!("{error}"))
+                    .and_then(|image| {
+                        let image = image.to_rgba8();
+                        encoder.write(image.width(), image.height(), image.as_raw())
+                    });
+                if let Err(error) = result {
+                    tracing::error!(?error, "failed writing frame");
+                    encoder.failed = true;
+                }
+                encoder.captured = true;
+            },
+        );
+    }
+}
+
+/// Recording the window for `--capture-seconds`, started and stopped early with `x`
+#[derive(Resource)]
+struct Capturing {
+    encoder: Arc<Mutex<Encoder>>,
+    started: Duration,
+    length: Duration,
+    interval: Duration,
+    /// When the next frame is due, relative to `started`
+    next: Duration,
+    frames: u32,
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Update, (toggle, capture));
+    }
+}
+
+fn toggle(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    args: Res<Args>,
+    time: Res<Time<Real>>,
+    capturing: Option<ResMut<Capturing>>,
+    mut notify: EventWriter<Notify>,
+    mut commands: Commands,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+    let mut pressed = false;
+    for event in events.read() {
+        pressed |= event.state.is_pressed() && event.logical_key == Key::Character("x".into());
+    }
+    if !pressed {
+        return;
+    }
+
+    if let Some(mut capturing) = capturing {
+        // Stopped on the next update, the same as running out of time
+        capturing.length = Duration::ZERO;
+        return;
+    }
+
+    let encoder = Encoder::new(
+        "capture",
+        args.capture_format,
+        args.capture_fps,
+        args.capture_width,
+    );
+    notify.send(Notify(format!(
+        "capturing {}s to {}",
+        args.capture_seconds,
+        encoder.path()
+    )));
+    commands.insert_resource(Capturing {
+        encoder: Arc::new(Mutex::new(encoder)),
+        started: time.elapsed(),
+        length: Duration::from_secs_f32(args.capture_seconds),
+        interval: Duration::from_secs_f32(1. / args.capture_fps),
+        next: Duration::ZERO,
+        frames: 0,
+    });
+}
+
+fn capture(
+    time: Res<Time<Real>>,
+    capturing: Option<ResMut<Capturing>>,
+    mut notify: EventWriter<Notify>,
+    mut commands: Commands,
+) {
+    let Some(mut capturing) = capturing else {
+        return;
+    };
+
+    let elapsed = time.elapsed() - capturing.started;
+    let mut encoder = capturing.encoder.lock().unwrap();
+    if encoder.failed || elapsed >= capturing.length {
+        let message = match encoder.finish() {
+            Ok(()) if !encoder.failed => {
+                format!("captured {} frames to {}", capturing.frames, encoder.path())
+            }
+            Ok(()) => "failed capturing window".to_owned(),
+            Err(error) => format!("failed capturing window: {error}"),
+        };
+        notify.send(Notify(message));
+        drop(encoder);
+        commands.remove_resource::<Capturing>();
+        return;
+    }
+    drop(encoder);
+
+    // Frames that couldn't be taken in time because rendering was too slow are skipped, rather
+    // than bunched up afterwards
+    if elapsed >= capturing.next {
+        Encoder::request(&capturing.encoder, &mut commands);
+        capturing.frames += 1;
+        let due = (elapsed.as_secs_f32() / capturing.interval.as_secs_f32()) as u32 + 1;
+        capturing.next = capturing.interval * due;
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::{Res, ResMut},
+};
+
+use crate::{
+    background::{Request, Scraper},
+    ui::{
+        editor::{Editor, Target},
+        notify::Notify,
+    },
+};
+
+/// Send to look for Bandcamp urls in the clipboard, and offer to add any found as seeds
+#[derive(Debug, Event)]
+pub struct Paste;
+
+/// Send with whitespace separated urls to add them as seeds
+#[derive(Debug, Event)]
+pub struct AddSeeds(pub String);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Paste>();
+        app.add_event::<AddSeeds>();
+        app.add_systems(bevy::app::Update, (paste, add_seeds));
+    }
+}
+
+#[culpa::try_fn]
+fn read() -> eyre::Result<String> {
+    arboard::Clipboard::new()?.get_text()?
+}
+
+fn paste(
+    mut events: EventReader<Paste>,
+    mut editor: ResMut<Editor>,
+    mut notify: EventWriter<Notify>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    let text = match read() {
+        Ok(text) => text,
+        Err(error) => {
+            tracing::warn!(?error, "failed reading clipboard");
+            notify.send(Notify("couldn't read the clipboard".to_owned()));
+            return;
+        }
+    };
+
+    let found = Request::find_all(&text);
+    if found.is_empty() {
+        notify.send(Notify("no Bandcamp urls in the clipboard".to_owned()));
+        return;
+    }
+
+    // Opened for confirmation, so any unwanted urls can be removed before adding them
+    let urls = Vec::from_iter(found.iter().map(Request::url));
+    editor.open(Target::Seeds, urls.join(" "));
+}
+
+fn add_seeds(
+    mut events: EventReader<AddSeeds>,
+    scraper: Res<Scraper>,
+    mut notify: EventWriter<Notify>,
+) {
+    for AddSeeds(urls) in events.read() {
+        let requests = Request::find_all(urls);
+        let count = requests.len();
+        for request in requests {
+            scraper.send(request).unwrap();
+        }
+        notify.send(Notify(format!("added {count} seeds")));
+    }
+}
@@ -0,0 +1,333 @@
+use bevy::{
+    ecs::{
+        change_detection::{DetectChanges, Ref},
+        event::EventReader,
+        query::With,
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Commands, Query, Res, Resource, Single},
+    },
+    transform::components::Transform,
+};
+
+use crossbeam::channel::{Receiver, Sender};
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use std::path::PathBuf;
+
+use crate::{
+    background::Request,
+    camera::ActiveCamera,
+    data::{
+        events::{DetailsUpdated, EdgeAdded, NodeAdded, NodeRemoved},
+        EntityType, Scrape, Url,
+    },
+    graph::GraphQuery,
+    sim::PredictedPosition,
+    Args, Runtime,
+};
+
+/// A command received from a remote client
+#[derive(Debug)]
+enum Command {
+    Seed(Request),
+    Scrape { url: String },
+    Export { path: PathBuf },
+    Focus { url: String },
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Seed {
+    Artist { url: String },
+    Release { url: String },
+    User { url: String },
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UrlParams {
+    url: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PathParams {
+    path: PathBuf,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Call {
+    #[serde(default)]
+    id: serde_json::Value,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+/// Bridge between the websocket server running on the [`Runtime`] and the app
+#[derive(Debug, Resource)]
+pub struct Control {
+    commands: Receiver<Command>,
+    events: broadcast::Sender<String>,
+}
+
+impl Control {
+    fn event(&self, method: &str, params: serde_json::Value) {
+        // Errors only when there are no connected clients
+        let _ = self.events.send(
+            serde_json::json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string(),
+        );
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (receive, send).run_if(resource_exists::<Control>),
+        );
+    }
+}
+
+fn setup(args: Res<Args>, runtime: Res<Runtime>, mut commands: Commands) {
+    let Some(port) = args.control else { return };
+
+    let (commands_tx, commands_rx) = crossbeam::channel::unbounded();
+    let (events, _) = broadcast::channel(1024);
+
+    runtime.spawn_background(serve(port, commands_tx, events.clone()));
+
+    commands.insert_resource(Control {
+        commands: commands_rx,
+        events,
+    });
+}
+
+async fn serve(port: u16, commands: Sender<Command>, events: broadcast::Sender<String>) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            tracing::error!(?error, port, "failed to listen for control connections");
+            return;
+        }
+    };
+
+    tracing::info!(port, "listening for control connections");
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                tracing::info!(%peer, "control client connected");
+                tokio::spawn(connection(stream, commands.clone(), events.subscribe()));
+            }
+            Err(error) => {
+                tracing::warn!(?error, "failed to accept control connection");
+            }
+        }
+    }
+}
+
+async fn connection(
+    stream: TcpStream,
+    commands: Sender<Command>,
+    mut events: broadcast::Receiver<String>,
+) {
+    let socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(error) => {
+            tracing::warn!(?error, "control websocket handshake failed");
+            return;
+        }
+    };
+
+    let (mut tx, mut rx) = socket.split();
+
+    loop {
+        let outgoing = tokio::select! {
+            message = rx.next() => match message {
+                Some(Ok(Message::Text(text))) => handle(&text, &commands).to_string(),
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => {
+                    tracing::warn!(?error, "control connection failed");
+                    break;
+                }
+            },
+            event = events.recv() => match event {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(count)) => {
+                    tracing::warn!(count, "control client missed events");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+
+        if let Err(error) = tx.send(Message::text(outgoing)).await {
+            tracing::warn!(?error, "control connection failed");
+            break;
+        }
+    }
+
+    tracing::info!("control client disconnected");
+}
+
+/// Parse a json-rpc call and queue its command, returning the response to send back
+fn handle(text: &str, commands: &Sender<Command>) -> serde_json::Value {
+    let error = |id: serde_json::Value, code: i64, message: String| {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        })
+    };
+
+    let call = match serde_json::from_str::<Call>(text) {
+        Ok(call) => call,
+        Err(e) => return error(serde_json::Value::Null, -32700, e.to_string()),
+    };
+
+    let command = match call.method.as_str() {
+        "add_seed" => serde_json::from_value(call.params).map(|seed: Seed| {
+            Command::Seed(match seed {
+                Seed::Artist { url } => Request::Artist { url },
+                Seed::Release { url } => Request::Release { url },
+                Seed::User { url } => Request::User { url },
+            })
+        }),
+        "scrape" => {
+            serde_json::from_value(call.params).map(|UrlParams { url }| Command::Scrape { url })
+        }
+        "export" => {
+            serde_json::from_value(call.params).map(|PathParams { path }| Command::Export { path })
+        }
+        "focus" => {
+            serde_json::from_value(call.params).map(|UrlParams { url }| Command::Focus { url })
+        }
+        method => return error(call.id, -32601, format!("unknown method {method}")),
+    };
+
+    match command {
+        Ok(command) => {
+            let _ = commands.send(command);
+            serde_json::json!({ "jsonrpc": "2.0", "id": call.id, "result": null })
+        }
+        Err(e) => error(call.id, -32602, e.to_string()),
+    }
+}
+
+fn receive(
+    control: Res<Control>,
+    scraper: Res<crate::background::Scraper>,
+    mut graph: GraphQuery,
+    positions: Query<&PredictedPosition>,
+    mut camera: Single<&mut Transform, With<ActiveCamera>>,
+) {
+    for command in control.commands.try_iter() {
+        match command {
+            Command::Seed(request) => {
+                scraper.seed(request).unwrap();
+            }
+            Command::Scrape { url } => {
+                if let Some(entity) = graph.find(&url) {
+                    let job = scraper.start_job(format!("scrape {url}"));
+                    graph.scrape_in(entity, Some(job), &scraper);
+                }
+            }
+            Command::Export { path } => {
+                if let Err(error) = graph.snapshot().export(&path) {
+                    tracing::error!(?error, "failed exporting graph");
+                }
+            }
+            Command::Focus { url } => {
+                if let Some(position) = graph
+                    .find(&url)
+                    .and_then(|entity| positions.get(entity).ok())
+                {
+                    camera.translation = position.0.extend(camera.translation.z);
+                }
+            }
+        }
+    }
+}
+
+fn send(
+    control: Res<Control>,
+    mut nodes: EventReader<NodeAdded>,
+    mut removed: EventReader<NodeRemoved>,
+    mut edges: EventReader<EdgeAdded>,
+    mut updated: EventReader<DetailsUpdated>,
+    scrapes: Query<(&EntityType, &Url, Ref<Scrape>)>,
+    urls: Query<&Url>,
+) {
+    if control.events.receiver_count() == 0 {
+        nodes.clear();
+        removed.clear();
+        edges.clear();
+        updated.clear();
+        return;
+    }
+
+    let ty = |ty: &EntityType| format!("{ty:?}").to_lowercase();
+
+    for &NodeAdded {
+        entity,
+        ty: entity_type,
+    } in nodes.read()
+    {
+        let Ok(url) = urls.get(entity) else {
+            continue;
+        };
+        control.event(
+            "node_added",
+            serde_json::json!({ "type": ty(&entity_type), "url": url.0 }),
+        );
+    }
+
+    for NodeRemoved {
+        ty: entity_type,
+        url,
+        ..
+    } in removed.read()
+    {
+        control.event(
+            "node_removed",
+            serde_json::json!({ "type": ty(entity_type), "url": url.0 }),
+        );
+    }
+
+    for (entity_type, url, scrape) in &scrapes {
+        if scrape.is_changed() && *scrape >= Scrape::Shallow {
+            control.event(
+                "node_scraped",
+                serde_json::json!({ "type": ty(entity_type), "url": url.0 }),
+            );
+        }
+    }
+
+    for EdgeAdded { relationship } in edges.read() {
+        let (Ok(from), Ok(to)) = (urls.get(relationship.from), urls.get(relationship.to)) else {
+            continue;
+        };
+        control.event(
+            "relationship_added",
+            serde_json::json!({ "from": from.0, "to": to.0 }),
+        );
+    }
+
+    for &DetailsUpdated { entity } in updated.read() {
+        let Ok((entity_type, url, _)) = scrapes.get(entity) else {
+            continue;
+        };
+        control.event(
+            "details_updated",
+            serde_json::json!({ "type": ty(entity_type), "url": url.0 }),
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::ecs::system::Resource;
+
+use std::{collections::HashMap, path::Path};
+
+use super::Price;
+
+/// Approximate value of one unit of each currency Bandcamp sells in, in US dollars
+const DEFAULT_RATES: &[(&str, f64)] = &[
+    ("AUD", 0.65),
+    ("CAD", 0.73),
+    ("CHF", 1.13),
+    ("CZK", 0.043),
+    ("DKK", 0.145),
+    ("EUR", 1.08),
+    ("GBP", 1.27),
+    ("HKD", 0.128),
+    ("HUF", 0.0027),
+    ("ILS", 0.27),
+    ("JPY", 0.0066),
+    ("MXN", 0.05),
+    ("NOK", 0.093),
+    ("NZD", 0.6),
+    ("PLN", 0.25),
+    ("SEK", 0.095),
+    ("SGD", 0.74),
+    ("USD", 1.0),
+];
+
+/// Converts prices into a single currency so they can be compared
+#[derive(Debug, Resource)]
+pub struct Rates {
+    /// The currency prices are converted to
+    pub target: String,
+    /// Value of one unit of each currency in US dollars
+    usd: HashMap<String, f64>,
+}
+
+impl Rates {
+    /// Use the built-in rates, with any from a json object of currency code to US dollar value
+    /// overriding them
+    #[culpa::try_fn]
+    pub fn load(path: Option<&Path>, target: &str) -> eyre::Result<Self> {
+        let mut usd = HashMap::from_iter(
+            DEFAULT_RATES
+                .iter()
+                .map(|&(currency, rate)| (currency.to_owned(), rate)),
+        );
+        if let Some(path) = path {
+            let overrides: HashMap<String, f64> =
+                serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            usd.extend(overrides);
+        }
+        let target = target.to_uppercase();
+        if !usd.contains_key(&target) {
+            Err(eyre::eyre!("no exchange rate for {target}"))?;
+        }
+        Self { target, usd }
+    }
+
+    /// The price in the target currency, if its currency has a known rate
+    pub fn convert(&self, price: &Price) -> Option<f64> {
+        let from = self.usd.get(&price.currency.to_uppercase())?;
+        Some(price.amount * from / self.usd[&self.target])
+    }
+}
@@ -1,12 +1,29 @@
 use bevy::{
     diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
-    ecs::{query::With, system::Query},
+    ecs::{
+        query::{Has, With},
+        system::Query,
+    },
 };
 
 pub const ARTISTS: DiagnosticPath = DiagnosticPath::const_new("data/artists");
 pub const RELEASES: DiagnosticPath = DiagnosticPath::const_new("data/releases");
 pub const USERS: DiagnosticPath = DiagnosticPath::const_new("data/users");
 
+/// How complete the current picture is
+pub mod coverage {
+    use bevy::diagnostic::DiagnosticPath;
+
+    /// Percentage of releases whose fans have all been enumerated
+    pub const RELEASE_FANS: DiagnosticPath =
+        DiagnosticPath::const_new("data/coverage/release-fans");
+    /// Percentage of users whose whole collection has been scraped
+    pub const USER_COLLECTIONS: DiagnosticPath =
+        DiagnosticPath::const_new("data/coverage/user-collections");
+    /// Nodes that have been discovered but not scraped yet
+    pub const FRONTIER: DiagnosticPath = DiagnosticPath::const_new("data/coverage/frontier");
+}
+
 pub struct Plugin;
 
 impl bevy::app::Plugin for Plugin {
@@ -14,6 +31,14 @@ impl bevy::app::Plugin for Plugin {
         for path in [ARTISTS, RELEASES, USERS] {
             app.register_diagnostic(Diagnostic::new(path).with_smoothing_factor(0.));
         }
+        for path in [coverage::RELEASE_FANS, coverage::USER_COLLECTIONS] {
+            app.register_diagnostic(
+                Diagnostic::new(path)
+                    .with_suffix("%")
+                    .with_smoothing_factor(0.),
+            );
+        }
+        app.register_diagnostic(Diagnostic::new(coverage::FRONTIER).with_smoothing_factor(0.));
         app.add_systems(bevy::app::Update, update);
     }
 }
@@ -23,8 +48,41 @@ fn update(
     artists: Query<(), With<super::ArtistId>>,
     releases: Query<(), With<super::ReleaseId>>,
     users: Query<(), With<super::UserId>>,
+    release_fans: Query<Has<super::ReleaseDetails>, With<super::ReleaseId>>,
+    user_collections: Query<
+        (Has<super::UserDetails>, Option<&super::CollectionProgress>),
+        With<super::UserId>,
+    >,
+    scrapes: Query<&super::Scrape>,
 ) {
     diagnostics.add_measurement(&ARTISTS, || artists.iter().count() as f64);
     diagnostics.add_measurement(&RELEASES, || releases.iter().count() as f64);
     diagnostics.add_measurement(&USERS, || users.iter().count() as f64);
+
+    // Release details are only sent after all of the release's fans
+    diagnostics.add_measurement(&coverage::RELEASE_FANS, || percentage(release_fans.iter()));
+    // Details are sent after the last page of the collection, but a resumed scrape could still be
+    // catching up
+    diagnostics.add_measurement(&coverage::USER_COLLECTIONS, || {
+        percentage(user_collections.iter().map(|(details, progress)| {
+            details && progress.is_none_or(|progress| progress.scraped >= progress.total)
+        }))
+    });
+    diagnostics.add_measurement(&coverage::FRONTIER, || {
+        scrapes
+            .iter()
+            .filter(|&&scrape| scrape == super::Scrape::None)
+            .count() as f64
+    });
+}
+
+fn percentage(complete: impl Iterator<Item = bool>) -> f64 {
+    let (complete, total) = complete.fold((0, 0), |(complete, total), done| {
+        (complete + usize::from(done), total + 1)
+    });
+    if total == 0 {
+        0.
+    } else {
+        complete as f64 * 100. / total as f64
+    }
 }
@@ -0,0 +1,77 @@
+use bevy::ecs::{component::ComponentId, entity::Entity, event::Event, world::DeferredWorld};
+
+use super::{ArtistId, EntityType, ReleaseId, TagId, Url, UserId};
+use crate::{
+    graph::{bandcamp_id, BandcampId},
+    sim::Relationship,
+};
+
+#[derive(Debug, Clone, Copy, Event)]
+pub struct NodeAdded {
+    pub entity: Entity,
+    pub ty: EntityType,
+}
+
+/// A node was despawned, with what it was since it can't be looked up any more by the time this
+/// is read
+#[derive(Debug, Clone, Event)]
+pub struct NodeRemoved {
+    pub ty: EntityType,
+    pub url: Url,
+    pub id: Option<BandcampId>,
+}
+
+#[derive(Debug, Clone, Copy, Event)]
+pub struct EdgeAdded {
+    pub relationship: Relationship,
+}
+
+/// A node's details were scraped, for the first time or again
+#[derive(Debug, Clone, Copy, Event)]
+pub struct DetailsUpdated {
+    pub entity: Entity,
+}
+
+/// Events for every change to the graph, sent from component hooks so that they cover every path
+/// that mutates it (scrape responses, batch spawns, pruning, ...) without each having to send them
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<NodeAdded>();
+        app.add_event::<NodeRemoved>();
+        app.add_event::<EdgeAdded>();
+        app.add_event::<DetailsUpdated>();
+    }
+}
+
+/// Add hook for the id components
+pub fn node_added(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    let ty = *world.get::<EntityType>(entity).unwrap();
+    world.send_event(NodeAdded { entity, ty });
+}
+
+/// Called from the id component removal hooks
+pub fn node_removed(world: &mut DeferredWorld, entity: Entity) {
+    let (Some(&ty), Some(url)) = (world.get::<EntityType>(entity), world.get::<Url>(entity)) else {
+        return;
+    };
+    let url = url.clone();
+    let id = bandcamp_id(
+        world.get::<ArtistId>(entity),
+        world.get::<ReleaseId>(entity),
+        world.get::<UserId>(entity),
+        world.get::<TagId>(entity),
+    );
+    world.send_event(NodeRemoved { ty, url, id });
+}
+
+/// Called from the [`Relationship`] add hook
+pub fn edge_added(world: &mut DeferredWorld, relationship: Relationship) {
+    world.send_event(EdgeAdded { relationship });
+}
+
+/// Insert hook for the details components
+pub fn details_updated(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    world.send_event(DetailsUpdated { entity });
+}
@@ -1,5 +1,11 @@
 use bevy::{
-    ecs::{bundle::Bundle, component::Component, entity::Entity, system::Commands},
+    ecs::{
+        bundle::Bundle,
+        component::{Component, ComponentId},
+        entity::Entity,
+        system::Commands,
+        world::DeferredWorld,
+    },
     hierarchy::BuildChildren,
     picking::PickingBehavior,
     render::view::Visibility,
@@ -8,9 +14,17 @@ use bevy::{
 use rand::{distr::Distribution, seq::IndexedRandom, Rng};
 use rand_distr::Poisson;
 
-use crate::sim::{MotionBundle, Relationship, Weight};
+use std::collections::HashSet;
 
+use crate::{
+    background::Request,
+    sim::{MotionBundle, Multiplicity, Relationship, Weight},
+};
+
+pub mod currency;
 mod diagnostic;
+pub mod events;
+pub mod stats;
 
 #[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
 pub struct Url(pub String);
@@ -39,20 +53,40 @@ impl From<&url::Url> for Url {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialOrd,
+    Ord,
+    PartialEq,
+    Eq,
+    Hash,
+    Component,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum EntityType {
     Artist,
     Release,
     User,
+    Tag,
+    Label,
 }
 
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
 #[require(EntityType(|| EntityType::Artist))]
+#[component(
+    on_add = self::events::node_added,
+    on_remove = crate::known::unindex_artist
+)]
 pub struct ArtistId(pub u64);
 
 #[derive(Clone, Debug, Component)]
+#[component(on_insert = self::events::details_updated)]
 pub struct ArtistDetails {
     pub name: String,
+    pub location: Option<String>,
 }
 
 #[derive(Debug, Clone, Bundle)]
@@ -61,8 +95,31 @@ pub struct Artist {
     pub url: Url,
 }
 
+/// Marks an artist account as a label, found once its page shows a roster of artists; it keeps its
+/// [`ArtistId`] as its page and releases are the same as any artist's
+#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
+#[component(on_add = label_added)]
+pub struct LabelId(pub u64);
+
+fn label_added(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    if let Some(mut ty) = world.get_mut::<EntityType>(entity) {
+        *ty = EntityType::Label;
+    }
+}
+
+#[derive(Clone, Debug, Component)]
+#[component(on_insert = self::events::details_updated)]
+pub struct LabelDetails {
+    /// How many artists are listed on the label's roster
+    pub roster: usize,
+}
+
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
 #[require(EntityType(|| EntityType::Release))]
+#[component(
+    on_add = self::events::node_added,
+    on_remove = crate::known::unindex_release
+)]
 pub struct ReleaseId(pub u64);
 
 #[derive(Copy, Clone, Debug)]
@@ -72,6 +129,7 @@ pub enum ReleaseType {
 }
 
 #[derive(Clone, Debug, Component)]
+#[component(on_insert = self::events::details_updated)]
 pub struct ReleaseDetails {
     pub ty: ReleaseType,
     pub title: String,
@@ -81,6 +139,42 @@ pub struct ReleaseDetails {
     pub tracks: Option<u32>,
     pub length: jiff::SignedDuration,
     pub released: jiff::Zoned,
+    /// The number of unique fans found, even if only some of them were spawned
+    pub fan_count: usize,
+    /// For tracks, the album they are part of
+    pub album: Option<Url>,
+    /// The digital price set by the artist, in their own currency
+    pub price: Option<Price>,
+    /// Whether fans can pay more than the price, which is then the minimum
+    pub name_your_price: bool,
+    /// Whether there's a free download, possibly in exchange for an email address
+    pub free_download: bool,
+    /// Whether every physical format is sold out, releases without any aren't
+    pub sold_out: bool,
+    /// The url of a thumbnail of the cover art, if it has any
+    pub art: Option<String>,
+    /// The tags the artist gave the release, in the order shown on its page
+    pub tags: Vec<String>,
+    /// For albums, their tracks in order
+    pub track_list: Vec<AlbumTrack>,
+}
+
+/// A track as listed on its album's page
+#[derive(Clone, Debug)]
+pub struct AlbumTrack {
+    /// The same id as the track's own page has as a release
+    pub id: ReleaseId,
+    /// Tracks that aren't out yet have no page
+    pub url: Option<Url>,
+    pub title: String,
+    pub length: jiff::SignedDuration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Price {
+    pub amount: f64,
+    /// ISO 4217 currency code
+    pub currency: String,
 }
 
 #[derive(Debug, Clone, Bundle)]
@@ -91,12 +185,26 @@ pub struct Release {
 
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
 #[require(EntityType(|| EntityType::User))]
+#[component(
+    on_add = self::events::node_added,
+    on_remove = crate::known::unindex_user
+)]
 pub struct UserId(pub u64);
 
 #[derive(Clone, Debug, Component)]
+#[component(on_insert = self::events::details_updated)]
 pub struct UserDetails {
     pub name: String,
     pub username: String,
+    /// Where the fan says they are, free text as written on their page
+    pub location: Option<String>,
+}
+
+/// How much of a user's collection has been scraped so far, large collections take many pages
+#[derive(Copy, Clone, Debug, Component)]
+pub struct CollectionProgress {
+    pub scraped: usize,
+    pub total: usize,
 }
 
 #[derive(Debug, Clone, Bundle)]
@@ -105,6 +213,29 @@ pub struct User {
     pub url: Url,
 }
 
+/// A genre, location or other tag given to releases, keyed on the slug from its url
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
+#[require(EntityType(|| EntityType::Tag))]
+#[component(
+    on_add = self::events::node_added,
+    on_remove = crate::known::unindex_tag
+)]
+pub struct TagId(pub String);
+
+#[derive(Clone, Debug, Component)]
+#[component(on_insert = self::events::details_updated)]
+pub struct TagDetails {
+    pub name: String,
+}
+
+/// Tags are only found as links on release pages, so they come with their details
+#[derive(Debug, Clone, Bundle)]
+pub struct Tag {
+    pub id: TagId,
+    pub url: Url,
+    pub details: TagDetails,
+}
+
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Component)]
 pub enum Scrape {
     None,
@@ -122,20 +253,149 @@ impl Scrape {
     }
 }
 
+/// What a relationship between two nodes represents
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum RelationKind {
+    /// A user who bought a release, as listed on the release
+    Fan,
+    /// A release in a user's collection, as listed on the user
+    Collection,
+    /// A release by an artist
+    Artist,
+    /// A release listed on an artist's page but hosted on another's, like a label listing the
+    /// releases it put out on its artists' own pages
+    Label,
+    /// A tag given to a release
+    Tag,
+    /// A release on a user's wishlist, wanted but not bought
+    Wishlist,
+    /// A user following another, or following an artist or label
+    Follow,
+    /// An artist on a label's roster
+    Roster,
+    /// A track on an album
+    Track,
+    /// An artist credited on another's release, like a featured artist, one side of a split or a
+    /// track on a compilation
+    Credit,
+    /// A user supporting an artist, as listed on the artist across all their releases
+    Support,
+}
+
+impl RelationKind {
+    pub fn weight(self) -> f32 {
+        match self {
+            RelationKind::Fan | RelationKind::Collection => 1.0,
+            RelationKind::Artist => 3.0,
+            RelationKind::Label => 2.0,
+            RelationKind::Tag => 0.5,
+            RelationKind::Wishlist => 0.5,
+            RelationKind::Follow => 0.3,
+            RelationKind::Roster => 2.0,
+            RelationKind::Track => 3.0,
+            RelationKind::Credit => 1.5,
+            RelationKind::Support => 1.0,
+        }
+    }
+}
+
+/// A fan relationship whose user no longer has the release in their collection, as found when
+/// refreshing the release
+#[derive(Copy, Clone, Debug, Default, Component)]
+pub struct Lapsed;
+
+/// A fan's review of a release, on their relationship to it
+#[derive(Clone, Debug, Component)]
+pub struct Review {
+    pub text: String,
+    /// The track they picked out as their favorite, if they did
+    pub favorite_track: Option<String>,
+}
+
+/// When a fan added a release to their collection, on their relationship to it
+#[derive(Copy, Clone, Debug, Component)]
+pub struct Acquired(pub jiff::Timestamp);
+
+/// Why a node is in the graph
+#[derive(Clone, Debug, Component)]
+pub struct DiscoveredVia {
+    /// The scrape whose response first mentioned this node
+    pub request: Request,
+    /// The node that scrape was for, unless it was for this node itself
+    pub parent_entity: Option<Entity>,
+    pub timestamp: jiff::Timestamp,
+}
+
+impl DiscoveredVia {
+    pub fn new(request: &Request, parent_entity: Option<Entity>) -> Self {
+        Self {
+            request: request.clone(),
+            parent_entity,
+            timestamp: jiff::Timestamp::now(),
+        }
+    }
+}
+
+/// How many scrapes of each node to remember
+const HISTORY_LENGTH: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct ScrapeRecord {
+    pub level: Scrape,
+    pub timestamp: jiff::Timestamp,
+    /// The request that scraped the node's page, deeper levels are reached by queuing its
+    /// neighbors instead
+    pub request: Option<Request>,
+}
+
+/// The most recent scrapes of a node, oldest first
+#[derive(Clone, Debug, Default, Component)]
+pub struct ScrapeHistory(pub Vec<ScrapeRecord>);
+
+impl ScrapeHistory {
+    pub fn new(level: Scrape, request: Option<&Request>) -> Self {
+        let mut history = Self::default();
+        history.push(level, request);
+        history
+    }
+
+    pub fn push(&mut self, level: Scrape, request: Option<&Request>) {
+        if self.0.len() == HISTORY_LENGTH {
+            self.0.remove(0);
+        }
+        self.0.push(ScrapeRecord {
+            level,
+            timestamp: jiff::Timestamp::now(),
+            request: request.cloned(),
+        });
+    }
+
+    /// When the node's own page was last scraped
+    pub fn last_scraped(&self) -> Option<jiff::Timestamp> {
+        self.0
+            .iter()
+            .rev()
+            .find(|record| record.request.is_some())
+            .map(|record| record.timestamp)
+    }
+}
+
 #[derive(Bundle)]
 pub struct RelationshipBundle {
     relationship: Relationship,
     picking_behavior: PickingBehavior,
     weight: Weight,
+    multiplicity: Multiplicity,
     visibility: Visibility,
 }
 
 impl Relationship {
-    pub fn bundle(self, weight: f32) -> RelationshipBundle {
+    pub fn bundle(self, weight: f32, multiplicity: Multiplicity) -> RelationshipBundle {
         RelationshipBundle {
             relationship: self,
             picking_behavior: PickingBehavior::IGNORE,
             weight: Weight(weight),
+            multiplicity,
             visibility: Visibility::Inherited,
         }
     }
@@ -146,6 +406,8 @@ pub struct Plugin;
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.add_plugins(self::diagnostic::Plugin);
+        app.add_plugins(self::events::Plugin);
+        app.add_plugins(self::stats::Plugin);
     }
 }
 
@@ -196,27 +458,33 @@ pub fn create_random(
 
     let mut user_releases = releases.clone();
     let mut user_linked_releases = Vec::new();
+    let mut user_relationships = HashSet::new();
 
     for from in &users {
         let count: f64 = Poisson::new(20.0).unwrap().sample(&mut rng);
         for to in user_releases.drain(..(count as usize).min(user_releases.len())) {
             user_linked_releases.push(to);
+            let relationship = Relationship { from: *from, to };
+            user_relationships.insert(relationship);
             commands
                 .entity(relationship_parent)
-                .with_child(Relationship { from: *from, to }.bundle(1.0));
+                .with_child(relationship.bundle(1.0, Multiplicity::RANDOM));
         }
     }
 
     for from in &users {
         let count: f64 = Poisson::new(3.0).unwrap().sample(&mut rng);
         for to in user_linked_releases.choose_multiple(&mut rng, count as usize) {
-            commands.entity(relationship_parent).with_child(
-                Relationship {
-                    from: *from,
-                    to: *to,
-                }
-                .bundle(1.0),
-            );
+            let relationship = Relationship {
+                from: *from,
+                to: *to,
+            };
+            // The release may already be in this user's collection
+            if user_relationships.insert(relationship) {
+                commands
+                    .entity(relationship_parent)
+                    .with_child(relationship.bundle(1.0, Multiplicity::RANDOM));
+            }
         }
     }
 
@@ -227,7 +495,7 @@ pub fn create_random(
                 from: *from,
                 to: *to,
             }
-            .bundle(1.0),
+            .bundle(1.0, Multiplicity::RANDOM),
         );
     }
 
@@ -238,7 +506,7 @@ pub fn create_random(
         let to = artist_releases.swap_remove(index);
         commands
             .entity(relationship_parent)
-            .with_child(Relationship { from: *from, to }.bundle(1.0));
+            .with_child(Relationship { from: *from, to }.bundle(1.0, Multiplicity::RANDOM));
     }
 
     for to in &artist_releases {
@@ -248,7 +516,7 @@ pub fn create_random(
                 from: *from,
                 to: *to,
             }
-            .bundle(5.0),
+            .bundle(5.0, Multiplicity::RANDOM),
         );
     }
 }
@@ -0,0 +1,167 @@
+use bevy::ecs::{
+    change_detection::DetectChanges,
+    component::Component,
+    entity::Entity,
+    query::{Added, Changed, Or, With},
+    schedule::IntoSystemConfigs,
+    system::{Commands, Query, Res},
+};
+
+use std::{collections::HashMap, time::Duration};
+
+use super::{currency::Rates, ArtistId, RelationKind, ReleaseDetails, ReleaseType, UserId};
+use crate::sim::{Multiplicity, Relationship};
+
+/// Totals across all of an artist's scraped releases
+#[derive(Clone, Debug, Default, PartialEq, Component)]
+pub struct ArtistStats {
+    pub releases: u32,
+    pub albums: u32,
+    pub tracks: u32,
+    pub runtime: jiff::SignedDuration,
+    album_runtime: jiff::SignedDuration,
+    /// Sum of album prices in [`Rates::target`], for the albums that have a convertible price
+    album_price: f64,
+    priced_albums: u32,
+}
+
+impl ArtistStats {
+    fn add(&mut self, release: &ReleaseDetails, rates: &Rates) {
+        self.releases += 1;
+        self.tracks += release.tracks.unwrap_or(1);
+        self.runtime += release.length;
+        if matches!(release.ty, ReleaseType::Album) {
+            self.albums += 1;
+            self.album_runtime += release.length;
+            if let Some(price) = release
+                .price
+                .as_ref()
+                .and_then(|price| rates.convert(price))
+            {
+                self.priced_albums += 1;
+                self.album_price += price;
+            }
+        }
+    }
+
+    pub fn average_album_length(&self) -> Option<jiff::SignedDuration> {
+        (self.albums > 0).then(|| self.album_runtime / self.albums as i32)
+    }
+
+    /// In [`Rates::target`]
+    pub fn average_album_price(&self) -> Option<f64> {
+        (self.priced_albums > 0).then(|| self.album_price / f64::from(self.priced_albums))
+    }
+}
+
+/// What a user's scraped collection would cost to buy at current prices, only an estimate as
+/// prices change and are often "name your price" minimums
+#[derive(Clone, Debug, Default, PartialEq, Component)]
+pub struct CollectionValue {
+    pub releases: u32,
+    /// How many of the releases had a convertible price to sum
+    pub priced: u32,
+    /// In [`Rates::target`]
+    pub total: f64,
+}
+
+impl CollectionValue {
+    fn add(&mut self, release: &ReleaseDetails, rates: &Rates) {
+        self.releases += 1;
+        if let Some(price) = release
+            .price
+            .as_ref()
+            .and_then(|price| rates.convert(price))
+        {
+            self.priced += 1;
+            self.total += price;
+        }
+    }
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(
+            bevy::app::Update,
+            (update, update_collections).run_if(bevy::time::common_conditions::on_timer(
+                Duration::from_secs(1),
+            )),
+        );
+    }
+}
+
+/// Recalculate every artist's stats whenever a release is scraped or related to an artist
+fn update(
+    changed: Query<(), Or<(Changed<ReleaseDetails>, Added<Relationship>)>>,
+    relationships: Query<&Relationship>,
+    artists: Query<(), With<ArtistId>>,
+    releases: Query<&ReleaseDetails>,
+    existing: Query<&ArtistStats>,
+    rates: Res<Rates>,
+    mut commands: Commands,
+) {
+    if changed.is_empty() {
+        return;
+    }
+
+    let mut stats = HashMap::<Entity, ArtistStats>::new();
+    for relationship in &relationships {
+        if !artists.contains(relationship.from) {
+            continue;
+        }
+        let Ok(release) = releases.get(relationship.to) else {
+            continue;
+        };
+        stats
+            .entry(relationship.from)
+            .or_default()
+            .add(release, &rates);
+    }
+
+    for (artist, stats) in stats {
+        if existing.get(artist).ok() != Some(&stats) {
+            commands.entity(artist).insert(stats);
+        }
+    }
+}
+
+/// Recalculate every user's collection value whenever a release is scraped or owned by a user
+fn update_collections(
+    changed: Query<(), Or<(Changed<ReleaseDetails>, Changed<Multiplicity>)>>,
+    relationships: Query<(&Relationship, &Multiplicity)>,
+    users: Query<(), With<UserId>>,
+    releases: Query<&ReleaseDetails>,
+    existing: Query<&CollectionValue>,
+    rates: Res<Rates>,
+    mut commands: Commands,
+) {
+    if changed.is_empty() && !rates.is_changed() {
+        return;
+    }
+
+    let mut values = HashMap::<Entity, CollectionValue>::new();
+    for (relationship, multiplicity) in &relationships {
+        // Wishlisted and followed releases aren't owned
+        let owned = multiplicity
+            .kinds()
+            .any(|kind| matches!(kind, RelationKind::Fan | RelationKind::Collection));
+        if !owned || !users.contains(relationship.from) {
+            continue;
+        }
+        let Ok(release) = releases.get(relationship.to) else {
+            continue;
+        };
+        values
+            .entry(relationship.from)
+            .or_default()
+            .add(release, &rates);
+    }
+
+    for (user, value) in values {
+        if existing.get(user).ok() != Some(&value) {
+            commands.entity(user).insert(value);
+        }
+    }
+}
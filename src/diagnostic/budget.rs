@@ -0,0 +1,98 @@
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore},
+    ecs::{
+        event::EventWriter,
+        system::{Local, Res},
+    },
+};
+
+use std::{collections::HashMap, time::Instant};
+
+use crate::{render, sim, ui::notify::Notify, Args};
+
+/// Systems to watch, along with a suggestion for how the user can reduce their load
+const WATCHED: [(DiagnosticPath, &str); 5] = [
+    // Integrates every awake node each fixed update
+    (
+        sim::diagnostic::update::POSITIONS,
+        "Shift+Scroll down to slow the simulation so it updates less often",
+    ),
+    // Grows with the number of nodes
+    (
+        sim::diagnostic::update::REPEL,
+        "press P to prune leaf users or unscraped nodes",
+    ),
+    // Grows with the number of relationships, most of which are between fans and releases
+    (
+        sim::diagnostic::update::ATTRACT,
+        "press P to prune leaf users and their relationships",
+    ),
+    // Moves every node's mesh each frame, unless paused
+    (
+        render::diagnostic::NODES,
+        "press Space to pause the simulation",
+    ),
+    (render::diagnostic::RELATIONS, "press L to hide lines"),
+];
+
+/// Consecutive over budget measurements for a watched system
+#[derive(Debug, Default)]
+struct Overrun {
+    latest: Option<Instant>,
+    count: u32,
+}
+
+/// Warns when a system takes longer than the frame budget for too many frames in a row
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Last, watchdog);
+    }
+}
+
+fn watchdog(
+    args: Res<Args>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut overruns: Local<HashMap<DiagnosticPath, Overrun>>,
+    mut notify: EventWriter<Notify>,
+) {
+    for (path, suggestion) in WATCHED {
+        let overrun = overruns.entry(path.clone()).or_default();
+
+        let Some(measurement) = diagnostics
+            .get(&path)
+            .and_then(|diagnostic| diagnostic.measurement())
+        else {
+            // History is cleared while the system isn't running
+            overrun.count = 0;
+            continue;
+        };
+
+        // Only count each measurement once, not every frame it is the latest
+        if overrun.latest == Some(measurement.time) {
+            continue;
+        }
+        overrun.latest = Some(measurement.time);
+
+        if measurement.value <= args.frame_budget {
+            overrun.count = 0;
+            continue;
+        }
+
+        overrun.count += 1;
+        if overrun.count == args.frame_budget_frames {
+            tracing::warn!(
+                %path,
+                value = measurement.value,
+                budget = args.frame_budget,
+                frames = overrun.count,
+                "system over frame budget",
+            );
+            notify.send(Notify(format!(
+                "{path} over {}ms budget for {} frames, {suggestion}",
+                args.frame_budget, overrun.count,
+            )));
+        }
+    }
+}
@@ -1,12 +1,18 @@
+mod budget;
 mod diagnostics;
-mod frame;
+pub mod frame;
+mod record;
 
-pub use self::diagnostics::Diagnostics;
+pub use self::{diagnostics::Diagnostics, record::Recorder};
 
 pub struct Plugin;
 
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut bevy::app::App) {
-        app.add_plugins(self::frame::Plugin);
+        app.add_plugins((
+            self::budget::Plugin,
+            self::frame::Plugin,
+            self::record::Plugin,
+        ));
     }
 }
@@ -0,0 +1,87 @@
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore},
+    ecs::{
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Res, ResMut, Resource},
+    },
+};
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+/// Appends every diagnostic measurement to a csv file for the session, see
+/// `--record-diagnostics`
+#[derive(Debug, Resource)]
+pub struct Recorder {
+    file: BufWriter<File>,
+    start: Instant,
+    /// Time of the latest measurement already written for each path
+    recorded: HashMap<DiagnosticPath, Instant>,
+}
+
+impl Recorder {
+    #[culpa::try_fn]
+    pub fn create(data_dir: &Path) -> eyre::Result<Self> {
+        let dir = data_dir.join("diagnostics");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "{}.csv",
+            jiff::Timestamp::now().strftime("%Y%m%dT%H%M%SZ")
+        ));
+        tracing::info!(path = %path.display(), "recording diagnostics");
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "seconds,path,value")?;
+        Self {
+            file,
+            start: Instant::now(),
+            recorded: HashMap::new(),
+        }
+    }
+
+    #[culpa::try_fn]
+    fn record(&mut self, diagnostics: &DiagnosticsStore) -> eyre::Result<()> {
+        for diagnostic in diagnostics.iter() {
+            let recorded = self.recorded.get(diagnostic.path()).copied();
+            let mut latest = recorded;
+            for measurement in diagnostic.measurements() {
+                if recorded.is_some_and(|recorded| measurement.time <= recorded) {
+                    continue;
+                }
+                writeln!(
+                    self.file,
+                    "{},{},{}",
+                    measurement
+                        .time
+                        .saturating_duration_since(self.start)
+                        .as_secs_f64(),
+                    diagnostic.path(),
+                    measurement.value,
+                )?;
+                latest = Some(measurement.time);
+            }
+            if let Some(latest) = latest {
+                self.recorded.insert(diagnostic.path().clone(), latest);
+            }
+        }
+        self.file.flush()?;
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Last, record.run_if(resource_exists::<Recorder>));
+    }
+}
+
+fn record(diagnostics: Res<DiagnosticsStore>, mut recorder: ResMut<Recorder>) {
+    if let Err(error) = recorder.record(&diagnostics) {
+        tracing::error!(?error, "failed recording diagnostics");
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::{
+    ecs::{
+        event::{EventReader, EventWriter},
+        system::Res,
+    },
+    window::FileDragAndDrop,
+};
+
+use std::{collections::HashSet, path::Path};
+
+use crate::{
+    background::{Request, Scraper},
+    data::EntityType,
+    graph::read_export,
+    ui::notify::Notify,
+};
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Update, dropped);
+    }
+}
+
+/// The pages whose scrapes built an exported graph, scraping them again (mostly from the page
+/// cache) rebuilds it
+#[culpa::try_fn]
+pub fn export_requests(export: &serde_json::Value) -> eyre::Result<Vec<Request>> {
+    let nodes = read_export(export)?.nodes;
+    let scraped = HashSet::<&str>::from_iter(
+        nodes
+            .iter()
+            .filter_map(|node| node.discovered_via.as_deref()),
+    );
+    let mut requests = Vec::new();
+    for node in &nodes {
+        if !scraped.contains(node.url.as_str()) {
+            continue;
+        }
+        let url = node.url.clone();
+        requests.push(match node.ty.as_str() {
+            "artist" | "label" => Request::Artist { url },
+            "release" => Request::Release { url },
+            "user" => Request::User { url },
+            "tag" => Request::Tag { url },
+            other => Err(eyre::eyre!("unknown node type {other:?}"))?,
+        });
+    }
+    requests
+}
+
+/// What to do with a dropped file, and the message confirming it
+#[culpa::try_fn]
+fn read(path: &Path) -> eyre::Result<(Vec<Request>, String)> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let text = std::fs::read_to_string(path)?;
+
+    let export = serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .filter(|export| export.get("nodes").is_some());
+
+    if let Some(export) = export {
+        let requests = export_requests(&export)?;
+        let message = format!("loading {} scraped pages from {name}", requests.len());
+        (requests, message)
+    } else {
+        let requests = Request::find_all(&text);
+        let message = match &requests[..] {
+            [] => format!("no Bandcamp urls in {name}"),
+            [request] => {
+                let ty = match request {
+                    Request::Artist { .. } => EntityType::Artist,
+                    Request::Release { .. } => EntityType::Release,
+                    Request::Tag { .. } => EntityType::Tag,
+                    _ => EntityType::User,
+                };
+                format!("added {ty:?} {} as a seed", request.url())
+            }
+            requests => format!("added {} seeds from {name}", requests.len()),
+        };
+        (requests, message)
+    }
+}
+
+/// Dropped export files are loaded, other files are searched for Bandcamp urls to add as seeds,
+/// including the link files browsers and file managers create for a dropped url
+fn dropped(
+    mut events: EventReader<FileDragAndDrop>,
+    scraper: Res<Scraper>,
+    mut notify: EventWriter<Notify>,
+) {
+    for event in events.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+        match read(path_buf) {
+            Ok((requests, message)) => {
+                for request in requests {
+                    scraper.send(request).unwrap();
+                }
+                notify.send(Notify(message));
+            }
+            Err(error) => {
+                tracing::warn!(?error, path = %path_buf.display(), "failed reading dropped file");
+                notify.send(Notify(format!("couldn't read {}", path_buf.display())));
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::{
+    ecs::{
+        event::EventReader,
+        system::{Query, Res, ResMut, Resource},
+    },
+    input::keyboard::{Key, KeyboardInput},
+    time::{Real, Time},
+};
+
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{background::Scraper, graph::GraphQuery, sim::RelationCount, ui::editor::Editor};
+
+/// Window the request budget applies over
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Continuously scrapes the unscraped node with the most neighbors, toggled with `e`
+#[derive(Debug, Resource)]
+pub struct AutoExplore {
+    pub enabled: bool,
+    /// How many scrapes can be started per minute
+    pub budget: usize,
+    /// When each scrape in the current window was started
+    started: VecDeque<Duration>,
+    /// How many unscraped nodes there were when last checked
+    frontier: usize,
+}
+
+impl AutoExplore {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            enabled: false,
+            budget,
+            started: VecDeque::new(),
+            frontier: 0,
+        }
+    }
+
+    /// How many scrapes were started in the last minute
+    pub fn used(&self) -> usize {
+        self.started.len()
+    }
+
+    pub fn frontier(&self) -> usize {
+        self.frontier
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Update, (toggle, explore));
+    }
+}
+
+fn toggle(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    mut explore: ResMut<AutoExplore>,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("e".into()) {
+            explore.enabled ^= true;
+        }
+    }
+}
+
+fn explore(
+    mut explore: ResMut<AutoExplore>,
+    time: Res<Time<Real>>,
+    relations: Query<&RelationCount>,
+    mut graph: GraphQuery,
+    scraper: Res<Scraper>,
+) {
+    let now = time.elapsed();
+    while explore
+        .started
+        .front()
+        .is_some_and(|&started| now - started > WINDOW)
+    {
+        explore.started.pop_front();
+    }
+
+    if !explore.enabled {
+        return;
+    }
+
+    let frontier = Vec::from_iter(graph.unscraped());
+    explore.frontier = frontier.len();
+
+    if explore.started.len() >= explore.budget {
+        return;
+    }
+
+    let next = frontier
+        .into_iter()
+        .max_by_key(|&entity| relations.get(entity).map_or(0, |relations| relations.count));
+    if let Some(entity) = next {
+        graph.scrape(entity, &scraper);
+        explore.started.push_back(now);
+    }
+}
@@ -0,0 +1,452 @@
+use bevy::{
+    ecs::{
+        change_detection::DetectChanges,
+        entity::Entity,
+        system::{Query, Res, SystemParam},
+    },
+    math::Vec2,
+};
+
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    annotation::Annotation,
+    background::{JobId, Request, Scraper},
+    data::{
+        currency::Rates,
+        stats::{ArtistStats, CollectionValue},
+        ArtistDetails, ArtistId, DiscoveredVia, EntityType, RelationKind, ReleaseDetails,
+        ReleaseId, Scrape, ScrapeHistory, TagDetails, TagId, Url, UserDetails, UserId,
+    },
+    sim::{Multiplicity, OriginForceMode, PredictedPosition, Relationship},
+    KnownEntities,
+};
+
+/// Bumped whenever a field of the export changes meaning or is removed, added fields don't need it
+pub const EXPORT_VERSION: u64 = 2;
+
+/// The id Bandcamp gives a node (band_id, item_id or fan_id, or the slug for tags), which unlike
+/// its url or entity stays the same across sessions
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum BandcampId {
+    Number(u64),
+    Slug(String),
+}
+
+#[derive(Debug)]
+pub struct Node {
+    pub entity: Entity,
+    pub ty: EntityType,
+    pub id: Option<BandcampId>,
+    pub url: String,
+    pub name: Option<String>,
+    pub scrape: Option<Scrape>,
+    pub discovered_via: Option<DiscoveredVia>,
+    pub stats: Option<ArtistStats>,
+    pub value: Option<CollectionValue>,
+    pub annotation: Option<Annotation>,
+    /// Where the node currently is in the layout
+    pub position: Option<Vec2>,
+    /// Indexes into [`Graph::nodes`]
+    pub neighbors: Vec<usize>,
+}
+
+/// Snapshot of the whole graph, for features that work on it outside of the ECS
+#[derive(Debug, Default)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    index: HashMap<String, usize>,
+    entities: HashMap<Entity, usize>,
+    /// Indexes into [`Graph::nodes`] of each relationship's ends, and how they're related
+    edges: Vec<(usize, usize, Multiplicity)>,
+    /// The currency prices are converted to
+    pub currency: String,
+    /// The origin force the layout was settling under
+    pub origin_force: OriginForceMode,
+}
+
+impl Graph {
+    pub fn get(&self, url: &str) -> Option<&Node> {
+        self.index.get(url).map(|&i| &self.nodes[i])
+    }
+
+    pub fn get_entity(&self, entity: Entity) -> Option<&Node> {
+        self.entities.get(&entity).map(|&i| &self.nodes[i])
+    }
+
+    /// Write the nodes and edges out as json
+    #[culpa::try_fn]
+    pub fn export(&self, path: &Path) -> eyre::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.to_json())?)?;
+    }
+
+    /// The nodes and edges as json, keyed on their type and Bandcamp id
+    pub fn to_json(&self) -> serde_json::Value {
+        let key = |node: &Node| serde_json::json!([export_type(node.ty), node.id]);
+        let nodes = Vec::from_iter(self.nodes.iter().map(|node| {
+            serde_json::json!({
+                "type": export_type(node.ty),
+                "id": node.id,
+                "url": node.url,
+                "name": node.name,
+                "discovered_via": node.discovered_via.as_ref().map(|via| serde_json::json!({
+                    "request": via.request.url(),
+                    "parent": via
+                        .parent_entity
+                        .and_then(|parent| self.get_entity(parent))
+                        .map(key),
+                    "timestamp": via.timestamp.to_string(),
+                })),
+                "stats": node.stats.as_ref().map(|stats| serde_json::json!({
+                    "releases": stats.releases,
+                    "albums": stats.albums,
+                    "tracks": stats.tracks,
+                    "runtime_seconds": stats.runtime.as_secs(),
+                    "average_album_seconds": stats
+                        .average_album_length()
+                        .map(|length| length.as_secs()),
+                    "average_album_price": stats.average_album_price(),
+                })),
+                // Summed from current prices of the releases that have one, so only an estimate
+                "estimated_collection_value": node.value.as_ref().map(|value| serde_json::json!({
+                    "total": value.total,
+                    "priced_releases": value.priced,
+                    "releases": value.releases,
+                })),
+                "note": node.annotation.as_ref().map(|annotation| &annotation.note),
+                "tags": node.annotation.as_ref().map(|annotation| &annotation.tags),
+                "x": node.position.map(|position| position.x),
+                "y": node.position.map(|position| position.y),
+            })
+        }));
+        let edges = Vec::from_iter(self.edges.iter().map(|&(from, to, multiplicity)| {
+            serde_json::json!([
+                key(&self.nodes[from]),
+                key(&self.nodes[to]),
+                Vec::from_iter(multiplicity.kinds()),
+            ])
+        }));
+        serde_json::json!({
+            "version": EXPORT_VERSION,
+            "currency": self.currency,
+            "layout": {
+                "origin_force": self.origin_force,
+            },
+            "nodes": nodes,
+            "edges": edges,
+        })
+    }
+}
+
+/// The name a node type has in exports, kept separate from the [`EntityType`] variant names so
+/// renaming one doesn't change the export format
+pub fn export_type(ty: EntityType) -> &'static str {
+    match ty {
+        EntityType::Artist => "artist",
+        EntityType::Release => "release",
+        EntityType::User => "user",
+        EntityType::Tag => "tag",
+        EntityType::Label => "label",
+    }
+}
+
+/// A node's Bandcamp id from whichever of the id components it has
+pub fn bandcamp_id(
+    artist: Option<&ArtistId>,
+    release: Option<&ReleaseId>,
+    user: Option<&UserId>,
+    tag: Option<&TagId>,
+) -> Option<BandcampId> {
+    artist
+        .map(|id| BandcampId::Number(id.0))
+        .or(release.map(|id| BandcampId::Number(id.0)))
+        .or(user.map(|id| BandcampId::Number(id.0)))
+        .or(tag.map(|id| BandcampId::Slug(id.0.clone())))
+}
+
+/// An export read back, whichever version it was written by
+#[derive(Debug)]
+pub struct Export {
+    pub version: u64,
+    pub nodes: Vec<ExportedNode>,
+    /// Only version 2 and later exports have edges that can be read back, earlier ones can only be
+    /// rebuilt by scraping their pages again
+    pub edges: Vec<ExportedEdge>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ExportedNode {
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Missing from version 1 exports
+    #[serde(default)]
+    pub id: Option<BandcampId>,
+    pub url: String,
+    pub name: Option<String>,
+    /// The url of the page whose scrape found the node, the same in every version
+    #[serde(default, deserialize_with = "via_request")]
+    pub discovered_via: Option<String>,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+}
+
+/// A relationship read back from an export, with indexes into [`Export::nodes`]
+#[derive(Debug)]
+pub struct ExportedEdge {
+    pub from: usize,
+    pub to: usize,
+    pub kinds: Vec<RelationKind>,
+}
+
+fn via_request<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(serde::Deserialize)]
+    struct Via {
+        request: String,
+    }
+    let via = <Option<Via> as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(via.map(|via| via.request))
+}
+
+/// Read an export, those from before exports were versioned are version 1 and keyed on urls,
+/// which the nodes still have
+#[culpa::try_fn]
+pub fn read_export(export: &serde_json::Value) -> eyre::Result<Export> {
+    type Key = (String, BandcampId);
+
+    let version = match export.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| eyre::eyre!("invalid export version {version}"))?,
+    };
+    if version > EXPORT_VERSION {
+        Err(eyre::eyre!(
+            "export is version {version}, newer than the supported {EXPORT_VERSION}"
+        ))?;
+    }
+
+    let nodes: Vec<ExportedNode> = serde_json::from_value(export["nodes"].clone())?;
+    let edges = if version == 1 {
+        Vec::new()
+    } else {
+        let index = HashMap::<(&str, &BandcampId), usize>::from_iter(
+            nodes
+                .iter()
+                .enumerate()
+                .filter_map(|(i, node)| Some(((node.ty.as_str(), node.id.as_ref()?), i))),
+        );
+        let edges: Vec<(Key, Key, Vec<RelationKind>)> =
+            serde_json::from_value(export["edges"].clone())?;
+        Vec::from_iter(
+            edges
+                .into_iter()
+                .filter_map(|((from_ty, from), (to_ty, to), kinds)| {
+                    Some(ExportedEdge {
+                        from: *index.get(&(from_ty.as_str(), &from))?,
+                        to: *index.get(&(to_ty.as_str(), &to))?,
+                        kinds,
+                    })
+                }),
+        )
+    };
+
+    Export {
+        version,
+        nodes,
+        edges,
+    }
+}
+
+#[derive(SystemParam)]
+pub struct GraphQuery<'w, 's> {
+    nodes: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static EntityType,
+            &'static Url,
+            Option<&'static mut Scrape>,
+            Option<&'static ArtistDetails>,
+            Option<&'static ReleaseDetails>,
+            Option<&'static UserDetails>,
+            Option<&'static DiscoveredVia>,
+            Option<&'static mut ScrapeHistory>,
+            Option<&'static ArtistStats>,
+            Option<&'static Annotation>,
+            Option<&'static TagDetails>,
+            Option<&'static CollectionValue>,
+            (
+                Option<&'static ArtistId>,
+                Option<&'static ReleaseId>,
+                Option<&'static UserId>,
+                Option<&'static TagId>,
+            ),
+        ),
+    >,
+    relationships: Query<'w, 's, (&'static Relationship, &'static Multiplicity)>,
+    positions: Query<'w, 's, &'static PredictedPosition>,
+    known: Res<'w, KnownEntities>,
+    rates: Res<'w, Rates>,
+    origin_force: Res<'w, OriginForceMode>,
+}
+
+impl GraphQuery<'_, '_> {
+    pub fn snapshot(&self) -> Graph {
+        let mut graph = Graph {
+            currency: self.rates.target.clone(),
+            origin_force: *self.origin_force,
+            ..Graph::default()
+        };
+
+        for (
+            entity,
+            ty,
+            url,
+            scrape,
+            artist,
+            release,
+            user,
+            via,
+            _,
+            stats,
+            annotation,
+            tag,
+            value,
+            (artist_id, release_id, user_id, tag_id),
+        ) in &self.nodes
+        {
+            let id = bandcamp_id(artist_id, release_id, user_id, tag_id);
+            let name = artist
+                .map(|artist| artist.name.clone())
+                .or(release.map(|release| release.title.clone()))
+                .or(user.map(|user| user.name.clone()))
+                .or(tag.map(|tag| tag.name.clone()));
+            graph.entities.insert(entity, graph.nodes.len());
+            graph.index.insert(url.0.clone(), graph.nodes.len());
+            graph.nodes.push(Node {
+                entity,
+                ty: *ty,
+                id,
+                url: url.0.clone(),
+                name,
+                scrape: scrape.copied(),
+                discovered_via: via.cloned(),
+                stats: stats.cloned(),
+                value: value.cloned(),
+                annotation: annotation.cloned(),
+                position: self.positions.get(entity).ok().map(|position| position.0),
+                neighbors: Vec::new(),
+            });
+        }
+
+        for (alias, entity) in &self.known.aliases {
+            if let Some(&i) = graph.entities.get(entity) {
+                graph.index.insert(alias.clone(), i);
+            }
+        }
+
+        for (rel, &multiplicity) in &self.relationships {
+            let (Some(&from), Some(&to)) =
+                (graph.entities.get(&rel.from), graph.entities.get(&rel.to))
+            else {
+                continue;
+            };
+            graph.nodes[from].neighbors.push(to);
+            graph.nodes[to].neighbors.push(from);
+            graph.edges.push((from, to, multiplicity));
+        }
+
+        graph
+    }
+
+    pub fn find(&self, url: &str) -> Option<Entity> {
+        self.nodes
+            .iter()
+            .find(|(_, _, node_url, ..)| node_url.0 == url)
+            .map(|(entity, ..)| entity)
+            .or_else(|| self.known.aliases.get(url).copied())
+    }
+
+    /// Nodes which have finished scraping since the calling system last ran
+    pub fn scraped(&mut self) -> Vec<Entity> {
+        Vec::from_iter(
+            self.nodes
+                .iter_mut()
+                .filter_map(|(entity, _, _, scrape, ..)| {
+                    let scrape = scrape?;
+                    (scrape.is_changed() && *scrape >= Scrape::Shallow).then_some(entity)
+                }),
+        )
+    }
+
+    /// Nodes that have been discovered but not scraped
+    pub fn unscraped(&self) -> impl Iterator<Item = Entity> + use<'_> {
+        self.nodes
+            .iter()
+            .filter(|(_, _, _, scrape, ..)| scrape.is_some_and(|scrape| *scrape == Scrape::None))
+            .map(|(entity, ..)| entity)
+    }
+
+    /// Nodes directly related to a node
+    pub fn neighbors(&self, entity: Entity) -> impl Iterator<Item = Entity> + use<'_> {
+        self.relationships.iter().filter_map(move |(rel, _)| {
+            (rel.from == entity)
+                .then_some(rel.to)
+                .or((rel.to == entity).then_some(rel.from))
+        })
+    }
+
+    pub fn entity_type(&self, entity: Entity) -> Option<EntityType> {
+        self.nodes.get(entity).ok().map(|(_, ty, ..)| *ty)
+    }
+
+    pub fn scrape_level(&self, entity: Entity) -> Option<Scrape> {
+        self.nodes
+            .get(entity)
+            .ok()
+            .and_then(|(_, _, _, scrape, ..)| scrape.copied())
+    }
+
+    /// Raise a node's scrape level without queuing a scrape of it
+    pub fn clamp_scrape(&mut self, entity: Entity, level: Scrape) {
+        let Ok((_, _, _, Some(mut scrape), _, _, _, _, history, ..)) = self.nodes.get_mut(entity)
+        else {
+            return;
+        };
+        if *scrape < level {
+            scrape.clamp_to(level..);
+            if let Some(mut history) = history {
+                history.push(level, None);
+            }
+        }
+    }
+
+    /// Queue a scrape of an existing node, counted towards the job that discovered it
+    pub fn scrape(&mut self, entity: Entity, scraper: &Scraper) {
+        self.scrape_in(entity, None, scraper);
+    }
+
+    /// Queue a scrape of an existing node, counted towards a job, or the job that discovered it if
+    /// none is given
+    pub fn scrape_in(&mut self, entity: Entity, job: Option<JobId>, scraper: &Scraper) {
+        let Ok((_, ty, Url(url), scrape, _, _, _, via, ..)) = self.nodes.get_mut(entity) else {
+            return;
+        };
+        let job = job.or_else(|| via.and_then(|via| scraper.job_of(&via.request)));
+        let url = url.clone();
+        let request = match ty {
+            EntityType::Artist | EntityType::Label => Request::Artist { url },
+            EntityType::Release => Request::Release { url },
+            EntityType::User => Request::User { url },
+            EntityType::Tag => Request::Tag { url },
+        };
+        if let Some(mut scrape) = scrape {
+            scrape.clamp_to(Scrape::InProgress..);
+        }
+        scraper.send_in(job, request).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use bevy::{
     ecs::{
         change_detection::DetectChangesMut,
+        component::Component,
         entity::Entity,
         observer::Trigger,
         query::{With, Without},
@@ -14,7 +15,7 @@ use bevy::{
 };
 
 use crate::{
-    camera::Cursor,
+    camera::{ActiveCamera, Cursor},
     sim::{Pinned, PredictedPosition},
 };
 
@@ -24,6 +25,10 @@ pub struct Dragged(pub Option<Entity>);
 #[derive(Default, Resource)]
 pub struct Hovered(pub Option<Entity>);
 
+/// Marks nodes picked out by the user for closer attention
+#[derive(Debug, Default, Component)]
+pub struct Selected;
+
 #[derive(Debug, Resource, PartialEq)]
 pub struct Nearest {
     pub entity: Entity,
@@ -152,7 +157,7 @@ fn pointer_out(
 fn pointer_drag(
     trigger: Trigger<Pointer<Drag>>,
     dragged: Res<Dragged>,
-    camera_transform: Single<&mut Transform, With<Camera>>,
+    camera_transform: Single<&mut Transform, (With<Camera>, With<ActiveCamera>)>,
     mut positions: Query<(&mut crate::sim::Position, &mut Transform), Without<Camera>>,
 ) {
     if dragged.0 != Some(trigger.entity()) {
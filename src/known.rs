@@ -0,0 +1,343 @@
+use bevy::{
+    ecs::{
+        component::ComponentId,
+        entity::{Entities, Entity},
+        event::EventWriter,
+        system::{Commands, Query, Resource},
+        world::DeferredWorld,
+    },
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    math::Vec2,
+};
+
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::{
+    alias,
+    data::{ArtistId, DiscoveredVia, RelationKind, ReleaseId, Scrape, TagId, Url, User, UserId},
+    sim::{Multiplicity, Partitions, Relationship, Ring},
+};
+
+/// Indexes from ids to the entities representing them, kept consistent by component hooks as
+/// entities are despawned
+#[derive(Debug, Resource, Default)]
+pub struct KnownEntities {
+    pub artists: HashMap<ArtistId, Entity>,
+    pub releases: HashMap<ReleaseId, Entity>,
+    pub users: HashMap<UserId, Entity>,
+    pub tags: HashMap<TagId, Entity>,
+    pub relationships: HashMap<Relationship, Entity>,
+    /// Urls that nodes were previously found at
+    pub aliases: HashMap<String, Entity>,
+    /// The relationships each node is part of, so removing a node doesn't have to search through
+    /// every relationship
+    edges: HashMap<Entity, HashSet<Relationship>>,
+}
+
+impl KnownEntities {
+    /// Check whether an already known node has been found at a new url, keeping the old url as an
+    /// alias so it still resolves
+    pub fn check_moved(
+        &mut self,
+        entity: Entity,
+        url: &Url,
+        urls: &Query<&Url>,
+        moved: &mut EventWriter<alias::Moved>,
+        commands: &mut Commands,
+    ) {
+        let Ok(old) = urls.get(entity) else { return };
+        if old == url {
+            return;
+        }
+        self.aliases.remove(&url.0);
+        self.aliases.insert(old.0.clone(), entity);
+        commands.entity(entity).insert(url.clone());
+        moved.send(alias::Moved {
+            entity,
+            from: old.0.clone(),
+        });
+    }
+
+    /// Record another url an already known node is listed at, such as a release listed on a label's
+    /// page at the label's url, keeping its current url as the one it's scraped from
+    pub fn add_alias(
+        &mut self,
+        entity: Entity,
+        url: &Url,
+        urls: &Query<&Url>,
+        moved: &mut EventWriter<alias::Moved>,
+    ) {
+        let Ok(current) = urls.get(entity) else {
+            return;
+        };
+        if current == url || self.aliases.get(&url.0) == Some(&entity) {
+            return;
+        }
+        self.aliases.insert(url.0.clone(), entity);
+        moved.send(alias::Moved {
+            entity,
+            from: url.0.clone(),
+        });
+    }
+
+    /// Relate fans to a release, spawning any that aren't known yet near it
+    pub fn add_fans(
+        &mut self,
+        release: Entity,
+        position: Vec2,
+        users: &[User],
+        kind: RelationKind,
+        via: &DiscoveredVia,
+        relationship_parent: Entity,
+        entities: &Entities,
+        partitions: &Partitions,
+        multiplicities: &mut Query<&mut Multiplicity>,
+        commands: &mut Commands,
+    ) {
+        // Popular releases can have thousands of fans, so spawn them as a single batch
+        // rather than a command each to avoid a hitch when applying them
+        let mut new_users = HashMap::new();
+        for user in users {
+            if !self.users.contains_key(&user.id) {
+                new_users.entry(user.id).or_insert_with(|| user.clone());
+            }
+        }
+        let reserved = entities.reserve_entities(new_users.len() as u32);
+        let mut ring = Ring::around(position, new_users.len(), partitions);
+        commands.insert_or_spawn_batch(Vec::from_iter(new_users.into_values().zip(reserved).map(
+            |(user, entity)| {
+                self.users.insert(user.id, entity);
+                (entity, (user, ring.place(), Scrape::None, via.clone()))
+            },
+        )));
+        let relationships = Vec::from_iter(users.iter().map(|user| Relationship {
+            from: self.users[&user.id],
+            to: release,
+        }));
+        self.relate_batch(
+            relationships,
+            kind,
+            relationship_parent,
+            entities,
+            multiplicities,
+            commands,
+        );
+    }
+
+    /// The relationships a node is part of
+    pub fn edges_of(&self, entity: Entity) -> impl Iterator<Item = Relationship> + use<'_> {
+        self.edges.get(&entity).into_iter().flatten().copied()
+    }
+
+    /// Spawn a relationship, or if it's already known record the new way it was found
+    pub fn relate(
+        &mut self,
+        relationship: Relationship,
+        kind: RelationKind,
+        relationship_parent: Entity,
+        multiplicities: &mut Query<&mut Multiplicity>,
+        commands: &mut Commands,
+    ) {
+        self.relate_kinds(
+            relationship,
+            &[kind],
+            relationship_parent,
+            multiplicities,
+            commands,
+        );
+    }
+
+    /// [`Self::relate`] for a relationship found in several ways at once, a new one is spawned
+    /// with all of them since it can't be updated until the commands are applied
+    pub fn relate_kinds(
+        &mut self,
+        relationship: Relationship,
+        kinds: &[RelationKind],
+        relationship_parent: Entity,
+        multiplicities: &mut Query<&mut Multiplicity>,
+        commands: &mut Commands,
+    ) {
+        match self.relationships.entry(relationship) {
+            Entry::Occupied(entry) => {
+                if let Ok(mut existing) = multiplicities.get_mut(*entry.get()) {
+                    for &kind in kinds {
+                        existing.insert(kind);
+                    }
+                }
+            }
+            Entry::Vacant(entry) => {
+                let Some(weight) = kinds.iter().map(|kind| kind.weight()).reduce(f32::max) else {
+                    return;
+                };
+                let mut multiplicity = Multiplicity::default();
+                for &kind in kinds {
+                    multiplicity.insert(kind);
+                }
+                for node in [relationship.from, relationship.to] {
+                    self.edges.entry(node).or_default().insert(relationship);
+                }
+                entry.insert(
+                    commands
+                        .spawn(relationship.bundle(weight, multiplicity))
+                        .set_parent(relationship_parent)
+                        .id(),
+                );
+            }
+        }
+    }
+
+    /// [`Self::relate`] for many relationships at once, spawning any new ones as a single batch
+    pub fn relate_batch(
+        &mut self,
+        relationships: Vec<Relationship>,
+        kind: RelationKind,
+        relationship_parent: Entity,
+        entities: &Entities,
+        multiplicities: &mut Query<&mut Multiplicity>,
+        commands: &mut Commands,
+    ) {
+        let mut new = HashSet::new();
+        for relationship in relationships {
+            if let Some(&existing) = self.relationships.get(&relationship) {
+                if let Ok(mut existing) = multiplicities.get_mut(existing) {
+                    existing.insert(kind);
+                }
+            } else {
+                new.insert(relationship);
+            }
+        }
+
+        let reserved = entities.reserve_entities(new.len() as u32);
+        let spawned =
+            Vec::from_iter(new.into_iter().zip(reserved).map(|(relationship, entity)| {
+                self.relationships.insert(relationship, entity);
+                for node in [relationship.from, relationship.to] {
+                    self.edges.entry(node).or_default().insert(relationship);
+                }
+                (entity, relationship.bundle(kind.weight(), kind.into()))
+            }));
+        let children = Vec::from_iter(spawned.iter().map(|&(entity, _)| entity));
+        commands.insert_or_spawn_batch(spawned);
+        commands.entity(relationship_parent).add_children(&children);
+    }
+}
+
+/// Remove an entry from one of the indexes, as long as it still points at the entity being removed
+fn unindex<K: Eq + Hash>(index: &mut HashMap<K, Entity>, key: &K, entity: Entity) {
+    if index.get(key) == Some(&entity) {
+        index.remove(key);
+    }
+}
+
+/// Common cleanup for any node being removed, its aliases and relationships go with it
+fn unindex_node(world: &mut DeferredWorld, entity: Entity) {
+    crate::data::events::node_removed(world, entity);
+    let Some(mut known) = world.get_resource_mut::<KnownEntities>() else {
+        return;
+    };
+    known.aliases.retain(|_, aliased| *aliased != entity);
+    let edges = known.edges.remove(&entity).unwrap_or_default();
+    let relationships = Vec::from_iter(
+        edges
+            .iter()
+            .filter_map(|relationship| known.relationships.get(relationship).copied()),
+    );
+    let mut commands = world.commands();
+    for relationship in relationships {
+        if let Some(relationship) = commands.get_entity(relationship) {
+            relationship.despawn_recursive();
+        }
+    }
+}
+
+pub fn unindex_artist(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    let id = *world.get::<ArtistId>(entity).unwrap();
+    if let Some(mut known) = world.get_resource_mut::<KnownEntities>() {
+        unindex(&mut known.artists, &id, entity);
+    }
+    unindex_node(&mut world, entity);
+}
+
+pub fn unindex_release(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    let id = *world.get::<ReleaseId>(entity).unwrap();
+    if let Some(mut known) = world.get_resource_mut::<KnownEntities>() {
+        unindex(&mut known.releases, &id, entity);
+    }
+    unindex_node(&mut world, entity);
+}
+
+pub fn unindex_user(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    let id = *world.get::<UserId>(entity).unwrap();
+    if let Some(mut known) = world.get_resource_mut::<KnownEntities>() {
+        unindex(&mut known.users, &id, entity);
+    }
+    unindex_node(&mut world, entity);
+}
+
+pub fn unindex_tag(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    let id = world.get::<TagId>(entity).unwrap().clone();
+    if let Some(mut known) = world.get_resource_mut::<KnownEntities>() {
+        unindex(&mut known.tags, &id, entity);
+    }
+    unindex_node(&mut world, entity);
+}
+
+/// Called from the [`Relationship`] removal hook
+pub fn unindex_relationship(world: &mut DeferredWorld, relationship: Relationship, entity: Entity) {
+    if let Some(mut known) = world.get_resource_mut::<KnownEntities>() {
+        if known.relationships.get(&relationship) == Some(&entity) {
+            known.relationships.remove(&relationship);
+            for node in [relationship.from, relationship.to] {
+                if let Entry::Occupied(mut edges) = known.edges.entry(node) {
+                    edges.get_mut().remove(&relationship);
+                    if edges.get().is_empty() {
+                        edges.remove();
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.insert_resource(KnownEntities::default());
+
+        #[cfg(debug_assertions)]
+        {
+            use bevy::ecs::schedule::IntoSystemConfigs;
+
+            app.add_systems(
+                bevy::app::Last,
+                validate.run_if(bevy::time::common_conditions::on_timer(
+                    std::time::Duration::from_secs(5),
+                )),
+            );
+        }
+    }
+}
+
+/// Check every index only refers to entities that still exist, catching any despawn path that
+/// bypasses the hooks
+#[cfg(debug_assertions)]
+fn validate(known: bevy::ecs::system::Res<KnownEntities>, entities: &Entities) {
+    let dangling = known
+        .artists
+        .values()
+        .chain(known.releases.values())
+        .chain(known.users.values())
+        .chain(known.tags.values())
+        .chain(known.relationships.values())
+        .chain(known.aliases.values())
+        .chain(known.edges.keys())
+        .filter(|&&entity| !entities.contains(entity))
+        .count();
+    if dangling > 0 {
+        tracing::error!(dangling, "known entity indexes refer to despawned entities");
+    }
+}
@@ -0,0 +1,264 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Has, With},
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource, Single},
+    },
+    math::{Rect, Vec2},
+    render::camera::Camera,
+    time::common_conditions::on_timer,
+    transform::components::GlobalTransform,
+};
+
+use std::{collections::HashSet, path::Path, time::Duration};
+
+use crate::{
+    background::{Request, Scraper},
+    data::{
+        Artist, ArtistDetails, ArtistId, EntityType, LabelId, Release, ReleaseId, Scrape, Tag,
+        TagDetails, TagId, Url, User, UserId,
+    },
+    graph::{read_export, BandcampId, Export, ExportedNode},
+    interact::{Nearest, Selected},
+    sim::{MotionBundle, Multiplicity, PredictedPosition, Relationship},
+    KnownEntities, RelationshipParent,
+};
+
+/// Most pages to queue for hydration each time round, so panning across a big graph doesn't flood
+/// the scraper with everything that passes through the view
+const HYDRATE_BATCH: usize = 50;
+
+/// A graph read from an export, spawned as soon as the world is ready
+#[derive(Debug, Resource)]
+pub struct Loading {
+    export: Export,
+    /// Version 1 exports have no ids to spawn from, so their pages are scraped again instead
+    requests: Vec<Request>,
+}
+
+impl Loading {
+    #[culpa::try_fn]
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        Self::from_json(&serde_json::from_str(&std::fs::read_to_string(path)?)?)?
+    }
+
+    #[culpa::try_fn]
+    pub fn from_json(value: &serde_json::Value) -> eyre::Result<Self> {
+        let export = read_export(value)?;
+        let requests = if export.version == 1 {
+            crate::drop::export_requests(value)?
+        } else {
+            Vec::new()
+        };
+        tracing::info!(
+            version = export.version,
+            nodes = export.nodes.len(),
+            edges = export.edges.len(),
+            "read export to load",
+        );
+        Self { export, requests }
+    }
+}
+
+/// Marks a node loaded from an export whose page was scraped, but whose details haven't been
+/// filled in from the page cache yet
+#[derive(Debug, Default, Component)]
+pub struct Dehydrated;
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(
+            bevy::app::Update,
+            (
+                spawn.run_if(resource_exists::<Loading>),
+                hydrate.run_if(on_timer(Duration::from_millis(250))),
+            ),
+        );
+    }
+}
+
+fn spawn(
+    loading: Res<Loading>,
+    mut known: ResMut<KnownEntities>,
+    scraper: Res<Scraper>,
+    mut multiplicities: Query<&mut Multiplicity>,
+    relationship_parent: Single<Entity, With<RelationshipParent>>,
+    mut commands: Commands,
+) {
+    commands.remove_resource::<Loading>();
+
+    if loading.export.version == 1 {
+        for request in &loading.requests {
+            scraper.send(request.clone()).unwrap();
+        }
+        return;
+    }
+
+    let scraped = HashSet::<&str>::from_iter(
+        loading
+            .export
+            .nodes
+            .iter()
+            .filter_map(|node| node.discovered_via.as_deref()),
+    );
+
+    let entities = Vec::from_iter(loading.export.nodes.iter().map(|node| {
+        spawn_node(
+            node,
+            scraped.contains(node.url.as_str()),
+            &mut known,
+            &mut commands,
+        )
+    }));
+
+    for edge in &loading.export.edges {
+        let (Some(from), Some(to)) = (entities[edge.from], entities[edge.to]) else {
+            continue;
+        };
+        known.relate_kinds(
+            Relationship { from, to },
+            &edge.kinds,
+            *relationship_parent,
+            &mut multiplicities,
+            &mut commands,
+        );
+    }
+}
+
+/// The node a type and id from an export refers to, if it's known
+pub fn find(known: &KnownEntities, ty: &str, id: &BandcampId) -> Option<Entity> {
+    match (ty, id) {
+        ("artist" | "label", &BandcampId::Number(id)) => known.artists.get(&ArtistId(id)),
+        ("release", &BandcampId::Number(id)) => known.releases.get(&ReleaseId(id)),
+        ("user", &BandcampId::Number(id)) => known.users.get(&UserId(id)),
+        ("tag", BandcampId::Slug(slug)) => known.tags.get(&TagId(slug.clone())),
+        _ => None,
+    }
+    .copied()
+}
+
+/// Spawn a node read from an export, or get it if it's already known, marking it to be hydrated
+/// if its page was scraped
+pub fn spawn_node(
+    node: &ExportedNode,
+    scraped: bool,
+    known: &mut KnownEntities,
+    commands: &mut Commands,
+) -> Option<Entity> {
+    let id = node.id.as_ref()?;
+    if let Some(entity) = find(known, &node.ty, id) {
+        return Some(entity);
+    }
+
+    let mut motion = MotionBundle::random();
+    if let (Some(x), Some(y)) = (node.x, node.y) {
+        motion.position.0 = Vec2::new(x, y);
+    }
+    let url = Url(node.url.clone());
+    let name = node.name.clone().unwrap_or_default();
+    let scrape = if scraped {
+        Scrape::Shallow
+    } else {
+        Scrape::None
+    };
+
+    let mut entity = match (node.ty.as_str(), id) {
+        ("artist" | "label", &BandcampId::Number(id)) => {
+            let artist = Artist {
+                id: ArtistId(id),
+                url,
+            };
+            let mut entity = commands.spawn((
+                artist,
+                motion,
+                scrape,
+                ArtistDetails {
+                    name,
+                    location: None,
+                },
+            ));
+            if node.ty == "label" {
+                entity.insert(LabelId(id));
+            }
+            known.artists.insert(ArtistId(id), entity.id());
+            entity
+        }
+        ("release", &BandcampId::Number(id)) => {
+            let release = Release {
+                id: ReleaseId(id),
+                url,
+            };
+            let entity = commands.spawn((release, motion, scrape));
+            known.releases.insert(ReleaseId(id), entity.id());
+            entity
+        }
+        ("user", &BandcampId::Number(id)) => {
+            let user = User {
+                id: UserId(id),
+                url,
+            };
+            let entity = commands.spawn((user, motion, scrape));
+            known.users.insert(UserId(id), entity.id());
+            entity
+        }
+        // Everything there is to know about a tag is in the export
+        ("tag", BandcampId::Slug(slug)) => {
+            let tag = Tag {
+                id: TagId(slug.clone()),
+                url,
+                details: TagDetails { name },
+            };
+            let entity = commands.spawn((tag, motion, Scrape::Shallow));
+            known.tags.insert(TagId(slug.clone()), entity.id());
+            return Some(entity.id());
+        }
+        _ => return None,
+    };
+    if scraped {
+        entity.insert(Dehydrated);
+    }
+    Some(entity.id())
+}
+
+/// Fill in the details of loaded nodes once they're on screen or being looked at, by parsing their
+/// pages from the cache, rather than parsing every page of a big graph at startup
+fn hydrate(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    nodes: Query<(Entity, &EntityType, &Url, &PredictedPosition, Has<Selected>), With<Dehydrated>>,
+    nearest: Option<Res<Nearest>>,
+    scraper: Res<Scraper>,
+    mut commands: Commands,
+) {
+    let views = Vec::from_iter(cameras.iter().filter_map(|(camera, transform)| {
+        let viewport = camera.logical_viewport_rect()?;
+        let min = camera.viewport_to_world_2d(transform, viewport.min).ok()?;
+        let max = camera.viewport_to_world_2d(transform, viewport.max).ok()?;
+        Some(Rect::from_corners(min, max))
+    }));
+    let inspected = nearest.map(|nearest| nearest.entity);
+
+    let wanted = nodes
+        .iter()
+        .filter(|&(entity, _, _, position, selected)| {
+            selected
+                || inspected == Some(entity)
+                || views.iter().any(|view| view.contains(position.0))
+        })
+        .take(HYDRATE_BATCH);
+
+    for (entity, ty, url, ..) in wanted {
+        let url = url.0.clone();
+        let request = match ty {
+            EntityType::Artist | EntityType::Label => Request::Artist { url },
+            EntityType::Release => Request::Release { url },
+            EntityType::User => Request::User { url },
+            EntityType::Tag => continue,
+        };
+        scraper.send(Request::Hydrate(Box::new(request))).unwrap();
+        commands.entity(entity).remove::<Dehydrated>();
+    }
+}
@@ -3,12 +3,10 @@ use bevy::{
     ecs::{
         change_detection::ResMut,
         component::Component,
-        entity::Entity,
-        event::EventReader,
+        event::{EventReader, EventWriter},
         query::With,
-        system::{Commands, Query, Res, Resource, Single},
+        system::{Commands, Res, Resource, Single},
     },
-    hierarchy::BuildChildren,
     input::keyboard::{Key, KeyboardInput},
     picking::mesh_picking::MeshPickingPlugin,
     render::view::Visibility,
@@ -20,27 +18,48 @@ use bevy::{
 
 use clap::Parser;
 
-use std::{
-    collections::{hash_map::Entry, HashMap},
-    time::Duration,
-};
+use std::time::Duration;
 
+mod alias;
+mod analysis;
+mod annotation;
+mod archive;
 mod background;
 mod camera;
+#[cfg(feature = "ffmpeg")]
+mod capture;
+mod clipboard;
+mod control;
 mod data;
 mod diagnostic;
+mod drop;
+mod explore;
+mod graph;
 mod interact;
+mod known;
+mod load;
+#[cfg(feature = "prometheus")]
+mod metrics;
+mod open;
+mod policy;
+mod preset;
+mod quality;
+mod receive;
 mod render;
+mod replay;
 mod runtime;
+mod sample;
+mod script;
+mod search;
+mod seed;
+mod share;
 mod sim;
+mod snapshot;
+mod tracks;
 mod ui;
+mod watch;
 
-use crate::{
-    background::Response,
-    data::{ArtistId, ReleaseId, Scrape, UserId},
-    runtime::Runtime,
-    sim::{MotionBundle, PredictedPosition, Relationship},
-};
+use crate::{known::KnownEntities, runtime::Runtime};
 
 #[derive(Parser, Debug, Resource)]
 #[command(
@@ -59,8 +78,62 @@ At least one option must be passed to select initial data
   <bold>Shift+Scroll</bold> to scale timestep
   <bold>Right-Click</bold> to show/hide action menu for nearest node (indicated by line from cursor)
   <bold>Space</bold> to (un)pause simulation
+  <bold>Pause</bold> or the network button to (un)pause retrieving pages, keeping everything queued
   <bold>L</bold> to hide lines
   <bold>O</bold> to cycle origin force scaling (unit, squared, cubed)
+  <bold>G</bold> to show/hide diagnostic history plots
+  <bold>T</bold> to show/hide tracks
+  <bold>M</bold> to merge tracks into their albums when both are shown
+  <bold>P</bold> to show/hide the prune dialog, for removing leaf users or unscraped nodes
+  <bold>V</bold> to split the view in two, each side panned and zoomed on its own
+  <bold>C</bold> to cycle color palettes (default, high-contrast, colorblind-safe)
+  <bold>Tab</bold> to show/hide a text list of the graph, navigated with the arrow keys, <bold>Enter</bold> to expand
+  <bold>R</bold> to edit the color rules, saved in the config directory
+  <bold>S</bold> to save the view settings as a preset, <bold>1</bold>-<bold>9</bold> to switch to one
+  <bold>A</bold> to add Bandcamp urls from the clipboard as seeds
+  <bold>H</bold> to tint releases with a hue picked from their artist
+  <bold>F</bold> to thaw nodes pinned by <bold>--layout</bold>
+  <bold>U</bold> to show/hide the frontier of unscraped nodes, most related first
+  <bold>E</bold> to toggle auto-exploring the frontier, limited by <bold>--explore-budget</bold>
+  <bold>Y</bold> to show/hide links between tags that occur together on releases
+  <bold>K</bold> to pull releases into rings by the year they were released
+  <bold>W</bold> to pin fans to a map by their location
+  <bold>Z</bold> to play back the recorded track, or the one passed to <bold>--replay</bold>
+  <bold>X</bold> to capture the window to a video, when built with the ffmpeg feature
+  <bold>Q</bold> to query the graph, selecting the nodes that match
+  <bold>/</bold> to search Bandcamp and seed the graph from the results
+  <bold>D</bold> to take a named snapshot of the graph, <bold>J</bold> to replace the graph with one, entered twice to confirm
+  <bold>I</bold> to group artists by their country
+  <bold>N</bold> to cycle through showing only the nodes reached from each seed, <bold>B</bold> to ring nodes reached from more than one
+
+<bold><underline>Scripting:</underline></bold>
+
+  Scripts passed to <bold>--script</bold> are written in rhai, the top level runs once at startup
+  and <bold>fn on_scraped(node)</bold> is called whenever a node finishes scraping.
+
+  <bold>scrape_artist(url)</bold>, <bold>scrape_release(url)</bold>, <bold>scrape_user(url)</bold> to queue scrapes
+  <bold>nodes()</bold> to list all nodes, <bold>node(url)</bold> to look one up, <bold>neighbors(url)</bold> for its links
+  <bold>select(url)</bold>, <bold>deselect(url)</bold> to highlight nodes
+  <bold>export(path)</bold> to write the graph as json
+
+  Nodes are maps with <bold>type</bold>, <bold>url</bold>, <bold>name</bold>, <bold>scrape</bold> and <bold>degree</bold> fields.
+
+<bold><underline>Remote control:</underline></bold>
+
+  With <bold>--control</bold> a websocket on localhost accepts json-rpc 2.0 calls:
+
+  <bold>add_seed</bold> <dim>{\"type\": \"artist\" | \"release\" | \"user\", \"url\": ...}</dim>
+  <bold>scrape</bold> <dim>{\"url\": ...}</dim>
+  <bold>export</bold> <dim>{\"path\": ...}</dim>
+  <bold>focus</bold> <dim>{\"url\": ...}</dim>
+
+  and sends <bold>node_added</bold>, <bold>node_removed</bold>, <bold>node_scraped</bold>, <bold>details_updated</bold> and
+  <bold>relationship_added</bold> notifications.
+
+<bold><underline>Sharing:</underline></bold>
+
+  With <bold>--share</bold> a websocket on all interfaces sends the graph, its updates and where the camera is
+  looking to any instance started with <bold>--view ws://host:port</bold>, which follows along read-only.
 
 "),
 )]
@@ -74,8 +147,257 @@ struct Args {
     #[arg(long("user"), value_name("username"))]
     users: Vec<String>,
 
+    /// Start from the top releases of a tag, e.g. `ambient` or `post-rock`
+    #[arg(long("tag"), value_name("name"))]
+    tags: Vec<String>,
+
+    /// Start from the recent activity in your feed, which needs `--cookies` from a session logged
+    /// in as that user
+    #[arg(long("feed"), value_name("username"))]
+    feeds: Vec<String>,
+
     #[arg(long, value_names(["artists", "releases", "users"]), num_args(3))]
     random: Vec<u64>,
+
+    /// Diagnostic paths to plot the recent history of
+    #[arg(
+        long("plot"),
+        value_name("path"),
+        default_values(["frame/time", "sim/update/repel", "scraper/items/queued"]),
+    )]
+    plots: Vec<String>,
+
+    /// Warn when a simulation or render system takes longer than this
+    #[arg(long, value_name("ms"), default_value_t = 16.0)]
+    frame_budget: f64,
+
+    /// How many consecutive frames a system must exceed the frame budget for before warning
+    #[arg(long, value_name("frames"), default_value_t = 30)]
+    frame_budget_frames: u32,
+
+    /// Hide lines then lower the simulation rate while the frame rate is below this, 0 to disable
+    #[arg(long, value_name("hz"), default_value_t = 30.0)]
+    min_fps: f64,
+
+    /// Serve pages from a directory of saved fixtures instead of the network, for reproducing
+    /// scrapes of known pages
+    #[arg(long, value_name("dir"))]
+    fixtures: Option<std::path::PathBuf>,
+
+    /// Serve a directory of saved fixtures from a local http server and scrape that instead of the
+    /// network, using a separate page cache
+    #[cfg(feature = "mock-server")]
+    #[arg(long, value_name("dir"), conflicts_with("fixtures"))]
+    mock_server: Option<std::path::PathBuf>,
+
+    /// A file holding the `Cookie` header of a logged in Bandcamp session, copied from a browser,
+    /// to scrape private collections and the full data of your own account
+    #[arg(long, value_name("file"))]
+    cookies: Option<std::path::PathBuf>,
+
+    /// Lists of each fan's to scrape along with their collection, each is paged through separately
+    /// so adds requests to every fan scraped
+    #[arg(long, value_enum, value_delimiter(','))]
+    fan_lists: Vec<background::FanList>,
+
+    /// Check the page selectors, including any overridden in the config directory, against a
+    /// directory of saved fixtures and exit, failing if any page doesn't match them
+    #[arg(long, value_name("dir"))]
+    check_selectors: Option<std::path::PathBuf>,
+
+    /// Append every diagnostic measurement to a csv file in the data directory
+    #[arg(long)]
+    record_diagnostics: bool,
+
+    /// Record the position of every node this often to a track file in the data directory, which
+    /// can be played back with `z`
+    #[arg(long, value_name("seconds"))]
+    record_track: Option<f32>,
+
+    /// Play back a previously recorded track file with `z`, instead of recording a new one
+    #[arg(long, value_name("file"), conflicts_with("record_track"))]
+    replay: Option<std::path::PathBuf>,
+
+    /// How long `x` captures the window for, it can be pressed again to stop early
+    #[cfg(feature = "ffmpeg")]
+    #[arg(long, value_name("seconds"), default_value_t = 10.0)]
+    capture_seconds: f32,
+
+    /// Frames per second to capture the window at
+    #[cfg(feature = "ffmpeg")]
+    #[arg(long, value_name("hz"), default_value_t = 15.0)]
+    capture_fps: f32,
+
+    /// Scale captures to this many pixels wide, instead of the size of the window
+    #[cfg(feature = "ffmpeg")]
+    #[arg(long, value_name("pixels"))]
+    capture_width: Option<u32>,
+
+    /// What to encode captures into
+    #[cfg(feature = "ffmpeg")]
+    #[arg(long, value_enum, default_value_t)]
+    capture_format: capture::CaptureFormat,
+
+    /// Listen for json-rpc commands over a websocket on localhost
+    #[arg(long, value_name("port"))]
+    control: Option<u16>,
+
+    /// Share the graph and camera with read-only viewers over a websocket on all interfaces
+    #[arg(long, value_name("port"))]
+    share: Option<u16>,
+
+    /// Follow a graph shared by another instance, its details are filled in from this instance's
+    /// page cache
+    #[arg(long, value_name("url"), conflicts_with_all(["share", "load"]))]
+    view: Option<String>,
+
+    /// Serve diagnostics as prometheus metrics on localhost
+    #[cfg(feature = "prometheus")]
+    #[arg(long, value_name("port"))]
+    metrics: Option<u16>,
+
+    /// Serve the metrics on all interfaces, for scraping from another machine
+    #[cfg(feature = "prometheus")]
+    #[arg(long, requires("metrics"))]
+    metrics_public: bool,
+
+    /// Run a rhai script at startup, see the long help for the available functions
+    #[arg(long, value_name("file"))]
+    script: Option<std::path::PathBuf>,
+
+    /// Only spawn a random sample of this many new fans of each release, the rest can be expanded
+    /// from the menu
+    #[arg(long, value_name("count"))]
+    sample_fans: Option<usize>,
+
+    /// Don't spawn the fans of a release until it is deeply scraped, showing how many there are
+    /// with a ring around it instead
+    #[arg(long)]
+    defer_fans: bool,
+
+    /// Currency to show prices in
+    #[arg(long, value_name("code"), default_value = "USD")]
+    currency: String,
+
+    /// Json object of currency codes to the value of one unit in US dollars, overriding the
+    /// built-in approximate rates
+    #[arg(long, value_name("file"))]
+    currency_rates: Option<std::path::PathBuf>,
+
+    /// Archive the least recently viewed leaf nodes once there are more than this many nodes,
+    /// they can be restored from the menu of the node they were related to
+    #[arg(long, value_name("count"))]
+    node_cap: Option<usize>,
+
+    /// Colors to draw nodes and links with
+    #[arg(long, value_enum, default_value_t)]
+    palette: render::palette::Palette,
+
+    /// Furthest the camera can zoom out, as screen pixels per world unit
+    #[arg(long, value_name("zoom"), default_value_t = 0.001)]
+    min_zoom: f32,
+
+    /// Furthest the camera can zoom in, as screen pixels per world unit
+    #[arg(long, value_name("zoom"), default_value_t = 100.0)]
+    max_zoom: f32,
+
+    /// How much each scroll step zooms the camera by
+    #[arg(long, value_name("factor"), default_value_t = 1.5)]
+    zoom_factor: f32,
+
+    /// Keep the camera centered within this distance of the origin
+    #[arg(long, value_name("distance"))]
+    world_bounds: Option<f32>,
+
+    /// How often to re-scrape watched artists and releases
+    #[arg(long, value_name("minutes"), default_value_t = 60)]
+    watch_interval: u64,
+
+    /// Program to preview pages with, given the path of a cached snapshot of the page, instead of
+    /// showing its text in a panel
+    #[arg(long, value_name("command"))]
+    preview_helper: Option<String>,
+
+    /// How long to wait between opening each url when opening all selected nodes
+    #[arg(long, value_name("ms"), default_value_t = 1000)]
+    open_delay: u64,
+
+    /// Ask for confirmation before opening more than this many selected nodes at once
+    #[arg(long, value_name("count"), default_value_t = 10)]
+    open_cap: usize,
+
+    /// Tint each artist's releases, and the links to them, with a hue picked from the artist
+    #[arg(long)]
+    artist_hues: bool,
+
+    /// Download the cover art of releases as they come into view and show it on their nodes
+    #[arg(long)]
+    album_art: bool,
+
+    /// Place nodes at the coordinates recorded in an earlier export, pinned until `f` thaws them
+    #[arg(long, value_name("file"))]
+    layout: Option<std::path::PathBuf>,
+
+    /// Load a graph from an earlier export without scraping it, filling in the details of its
+    /// scraped nodes from the page cache as they come into view or are inspected
+    #[arg(long, value_name("file"))]
+    load: Option<std::path::PathBuf>,
+
+    /// How many nodes auto-explore (toggled with `e`) can start scraping per minute
+    #[arg(long, value_name("count"), default_value_t = 30)]
+    explore_budget: usize,
+
+    /// Deep scrape this many hops out from the artists, releases, users, tags and feeds given on the
+    /// command line, like scraping them deeply from the menu once they've been scraped
+    #[arg(long, value_name("hops"), default_value_t = 0)]
+    depth: usize,
+}
+
+impl Args {
+    fn source(&self) -> background::Source<'_> {
+        #[cfg(feature = "mock-server")]
+        if let Some(dir) = &self.mock_server {
+            return background::Source::MockServer(dir);
+        }
+        if let Some(dir) = &self.fixtures {
+            return background::Source::Fixtures(dir);
+        }
+        background::Source::Network
+    }
+
+    #[culpa::try_fn]
+    fn cookies(&self) -> eyre::Result<Option<String>> {
+        self.cookies
+            .as_deref()
+            .map(std::fs::read_to_string)
+            .transpose()?
+    }
+
+    /// Reject combinations of arguments that clap can't check on its own
+    #[culpa::try_fn]
+    fn validate(&self) -> eyre::Result<()> {
+        // Written so NaNs fail the checks too
+        let zoom_limits = self.min_zoom > 0.0 && self.min_zoom <= self.max_zoom;
+        if !zoom_limits {
+            Err(eyre::eyre!(
+                "--min-zoom ({}) must be above 0 and no more than --max-zoom ({})",
+                self.min_zoom,
+                self.max_zoom,
+            ))?;
+        }
+        let zoom_factor = self.zoom_factor > 0.0;
+        if !zoom_factor {
+            Err(eyre::eyre!(
+                "--zoom-factor ({}) must be above 0",
+                self.zoom_factor
+            ))?;
+        }
+    }
+
+    /// The smallest and largest camera scale allowed by the zoom limits
+    fn scale_limits(&self) -> (f32, f32) {
+        (1.0 / self.max_zoom, 1.0 / self.min_zoom)
+    }
 }
 
 #[culpa::try_fn]
@@ -84,16 +406,89 @@ fn main() -> eyre::Result<()> {
 
     color_eyre::install()?;
 
+    args.validate()?;
+
     let dirs = directories::ProjectDirs::from("com", "nemo157", "bc-scraper3").unwrap();
 
     std::fs::create_dir_all(dirs.cache_dir())?;
+    std::fs::create_dir_all(dirs.data_dir())?;
+    std::fs::create_dir_all(dirs.config_dir())?;
+
+    let selectors = background::Selectors::load(dirs.config_dir())?;
+    if let Some(fixtures) = &args.check_selectors {
+        let (checked, problems) = selectors.check(fixtures)?;
+        for problem in &problems {
+            println!("{problem}");
+        }
+        println!("checked {checked} pages, {} problems", problems.len());
+        std::process::exit(if problems.is_empty() { 0 } else { 1 });
+    }
+
+    let mut app = bevy::app::App::new();
 
-    bevy::app::App::new()
-        .insert_resource(Time::<Fixed>::from_hz(20.0))
+    if let Some(path) = &args.script {
+        app.insert_resource(script::Script::load(path)?);
+    }
+
+    if let Some(path) = &args.layout {
+        let (layout, origin_force) = sim::frozen::FrozenLayout::load(path)?;
+        app.insert_resource(layout);
+        if let Some(origin_force) = origin_force {
+            app.insert_resource(origin_force);
+        }
+    }
+
+    if let Some(path) = &args.load {
+        app.insert_resource(load::Loading::read(path)?);
+    }
+
+    if let Some(path) = &args.replay {
+        app.insert_resource(replay::Track::load(path)?);
+    } else if let Some(seconds) = args.record_track {
+        app.insert_resource(replay::Track::create(
+            dirs.data_dir(),
+            Duration::from_secs_f32(seconds),
+        )?);
+    }
+
+    if args.record_diagnostics {
+        app.insert_resource(diagnostic::Recorder::create(dirs.data_dir())?);
+    }
+
+    #[cfg(feature = "prometheus")]
+    app.add_plugins(self::metrics::Plugin);
+
+    #[cfg(feature = "ffmpeg")]
+    app.add_plugins(self::capture::Plugin);
+
+    app.insert_resource(Time::<Fixed>::from_hz(20.0))
         .insert_resource(Time::<Virtual>::from_max_delta(Duration::from_millis(50)))
+        .insert_resource(sample::FanSampling::new(args.sample_fans, args.defer_fans))
+        .insert_resource(data::currency::Rates::load(
+            args.currency_rates.as_deref(),
+            &args.currency,
+        )?)
+        .insert_resource(background::Scraper::new(
+            dirs.cache_dir(),
+            args.source(),
+            args.cookies()?,
+            selectors,
+            args.fan_lists.clone(),
+        )?)
+        .insert_resource(open::Opening::new(Duration::from_millis(args.open_delay)))
+        .insert_resource(explore::AutoExplore::new(args.explore_budget))
+        .insert_resource(policy::ScrapePolicy::load(dirs.config_dir(), args.depth)?)
+        .insert_resource(args.palette)
+        .insert_resource(render::hue::ArtistHues(args.artist_hues))
+        .insert_resource(render::art::ShowArt(args.album_art))
         .insert_resource(args)
-        .insert_resource(background::Scraper::new(dirs.cache_dir())?)
-        .insert_resource(KnownEntities::default())
+        .insert_resource(watch::Watchlist::load(dirs.data_dir())?)
+        .insert_resource(snapshot::Snapshots::load(dirs.data_dir())?)
+        .insert_resource(alias::AliasTable::load(dirs.data_dir())?)
+        .insert_resource(archive::Archive::load(dirs.data_dir())?)
+        .insert_resource(annotation::Annotations::load(dirs.data_dir())?)
+        .insert_resource(render::rules::ColorRules::load(dirs.config_dir())?)
+        .insert_resource(preset::Presets::load(dirs.config_dir())?)
         .insert_resource(Runtime::new())
         .add_plugins((
             DefaultPlugins.set(bevy::log::LogPlugin {
@@ -101,47 +496,91 @@ fn main() -> eyre::Result<()> {
                 ..default()
             }),
             MeshPickingPlugin,
+        ))
+        .add_plugins((
+            self::alias::Plugin,
             self::background::diagnostic::Plugin,
             self::camera::CameraPlugin,
+            self::control::Plugin,
             self::data::Plugin,
             self::diagnostic::Plugin,
             self::interact::Plugin,
+            self::known::Plugin,
+            self::quality::Plugin,
             self::render::Plugin,
+            self::sample::Plugin,
+            self::script::Plugin,
             self::sim::Plugin,
             self::ui::Plugin,
+            self::watch::Plugin,
         ))
         .add_systems(bevy::app::Startup, setup)
         .add_systems(bevy::app::PreUpdate, keyinput)
-        .add_systems(bevy::app::Update, receive)
+        .add_plugins((
+            self::analysis::Plugin,
+            self::annotation::Plugin,
+            self::archive::Plugin,
+            self::clipboard::Plugin,
+            self::drop::Plugin,
+            self::explore::Plugin,
+            self::load::Plugin,
+            self::open::Plugin,
+            self::preset::Plugin,
+            self::receive::Plugin,
+            self::replay::Plugin,
+            self::search::Plugin,
+            self::seed::Plugin,
+            self::snapshot::Plugin,
+            self::tracks::Plugin,
+        ))
+        .add_plugins((self::policy::Plugin, self::share::Plugin))
         .run();
 }
 
 #[derive(Component)]
 struct RelationshipParent;
 
-fn setup(mut commands: Commands, args: Res<Args>, scraper: Res<background::Scraper>) {
+fn setup(
+    mut commands: Commands,
+    args: Res<Args>,
+    scraper: Res<background::Scraper>,
+    mut deep_seeds: ResMut<policy::DeepSeeds>,
+) {
     let relationship_parent = commands
         .spawn((Visibility::Visible, Transform::IDENTITY, RelationshipParent))
         .id();
 
+    let mut seed = |request: background::Request| {
+        let url = request.url().to_owned();
+        let job = scraper.seed(request).unwrap();
+        deep_seeds.add(url, job, args.depth);
+    };
+
     for url in &args.releases {
-        scraper
-            .send(background::Request::Release { url: url.clone() })
-            .unwrap();
+        seed(background::Request::Release { url: url.clone() });
     }
 
     for username in &args.users {
-        scraper
-            .send(background::Request::User {
-                url: format!("https://bandcamp.com/{username}"),
-            })
-            .unwrap();
+        seed(background::Request::User {
+            url: format!("https://bandcamp.com/{username}"),
+        });
+    }
+
+    for name in &args.tags {
+        let slug = name.trim().to_lowercase().replace(' ', "-");
+        seed(background::Request::Tag {
+            url: format!("https://bandcamp.com/discover/{slug}"),
+        });
+    }
+
+    for username in &args.feeds {
+        seed(background::Request::Feed {
+            url: format!("https://bandcamp.com/{username}"),
+        });
     }
 
     for url in &args.artists {
-        scraper
-            .send(background::Request::Artist { url: url.clone() })
-            .unwrap();
+        seed(background::Request::Artist { url: url.clone() });
     }
 
     if let [artists, releases, users] = args.random[..] {
@@ -149,20 +588,29 @@ fn setup(mut commands: Commands, args: Res<Args>, scraper: Res<background::Scrap
     }
 }
 
-#[derive(Resource, Default)]
-struct KnownEntities {
-    artists: HashMap<ArtistId, Entity>,
-    releases: HashMap<ReleaseId, Entity>,
-    users: HashMap<UserId, Entity>,
-    relationships: HashMap<Relationship, Entity>,
-}
-
 fn keyinput(
     mut events: EventReader<KeyboardInput>,
     mut relationship_parent: Single<&mut Visibility, With<RelationshipParent>>,
     mut paused: ResMut<sim::Paused>,
     mut origin_force_mode: ResMut<sim::OriginForceMode>,
+    mut plots: ResMut<ui::diagnostic::Plots>,
+    mut release_filter: ResMut<render::filter::ReleaseFilter>,
+    mut prune_dialog: ResMut<ui::prune::PruneDialog>,
+    mut split_view: ResMut<camera::SplitView>,
+    mut palette: ResMut<render::palette::Palette>,
+    mut list_view: ResMut<ui::list::ListView>,
+    mut editor: ResMut<ui::editor::Editor>,
+    color_rules: Res<render::rules::ColorRules>,
+    mut apply_preset: EventWriter<preset::ApplyPreset>,
+    mut paste: EventWriter<clipboard::Paste>,
+    mut artist_hues: ResMut<render::hue::ArtistHues>,
 ) {
+    // Typing a note shouldn't toggle everything
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
     for event in events.read() {
         if event.state.is_pressed() {
             if event.logical_key == Key::Character("l".into()) {
@@ -171,204 +619,30 @@ fn keyinput(
                 paused.0 ^= true;
             } else if event.logical_key == Key::Character("o".into()) {
                 origin_force_mode.go_to_next();
-            }
-        }
-    }
-}
-
-fn receive(
-    mut commands: Commands,
-    scraper: Res<background::Scraper>,
-    mut known: ResMut<KnownEntities>,
-    positions: Query<&PredictedPosition>,
-    mut scrape: Query<&mut Scrape>,
-    relationship_parent: Single<Entity, With<RelationshipParent>>,
-) {
-    if let Some(response) = scraper.try_recv().unwrap() {
-        match response {
-            Response::Artist(artist, details) => match known.artists.entry(artist.id) {
-                Entry::Occupied(entry) => {
-                    commands.entity(*entry.get()).insert(details);
-                    if let Ok(mut scrape) = scrape.get_mut(*entry.get()) {
-                        scrape.clamp_to(Scrape::Shallow..);
-                    }
-                }
-                Entry::Vacant(entry) => {
-                    let motion = MotionBundle::random();
-                    entry.insert(
-                        commands
-                            .spawn((artist, motion, details, Scrape::Shallow))
-                            .id(),
-                    );
-                }
-            },
-
-            Response::Release(release, details) => match known.releases.entry(release.id) {
-                Entry::Occupied(entry) => {
-                    commands.entity(*entry.get()).insert(details);
-                    if let Ok(mut scrape) = scrape.get_mut(*entry.get()) {
-                        scrape.clamp_to(Scrape::Shallow..);
-                    }
-                }
-                Entry::Vacant(entry) => {
-                    let motion = MotionBundle::random();
-                    entry.insert(
-                        commands
-                            .spawn((release, motion, details, Scrape::Shallow))
-                            .id(),
-                    );
-                }
-            },
-
-            Response::User(user, details) => match known.users.entry(user.id) {
-                Entry::Occupied(entry) => {
-                    commands.entity(*entry.get()).insert(details);
-                    if let Ok(mut scrape) = scrape.get_mut(*entry.get()) {
-                        scrape.clamp_to(Scrape::Shallow..);
-                    }
-                }
-                Entry::Vacant(entry) => {
-                    let motion = MotionBundle::random();
-                    entry.insert(
-                        commands
-                            .spawn((user, motion, details, Scrape::Shallow))
-                            .id(),
-                    );
-                }
-            },
-
-            Response::Fans(release, users) => {
-                let (release, position) = match known.releases.entry(release.id) {
-                    Entry::Occupied(entry) => {
-                        let release = *entry.get();
-                        let position = *positions.get(release).unwrap();
-                        (release, position.0)
-                    }
-                    Entry::Vacant(entry) => {
-                        let motion = MotionBundle::random();
-                        let position = motion.position;
-                        let release = commands.spawn((release, motion, Scrape::Shallow)).id();
-                        entry.insert(release);
-                        (release, position.0)
-                    }
-                };
-                for user in users {
-                    let user = *known.users.entry(user.id).or_insert_with(|| {
-                        commands
-                            .spawn((user, MotionBundle::random_near(position), Scrape::None))
-                            .id()
-                    });
-                    let relationship = Relationship {
-                        from: user,
-                        to: release,
-                    };
-                    known.relationships.entry(relationship).or_insert_with(|| {
-                        commands
-                            .entity(*relationship_parent)
-                            .with_child(relationship.bundle(1.0))
-                            .id()
-                    });
-                }
-            }
-
-            Response::ReleaseArtist(release, artist) => {
-                let (release, position) = match known.releases.entry(release.id) {
-                    Entry::Occupied(entry) => {
-                        let release = *entry.get();
-                        let position = *positions.get(release).unwrap();
-                        (release, position.0)
-                    }
-                    Entry::Vacant(entry) => {
-                        let motion = MotionBundle::random();
-                        let position = motion.position;
-                        let release = commands.spawn((release, motion, Scrape::InProgress)).id();
-                        entry.insert(release);
-                        (release, position.0)
-                    }
-                };
-                let artist = *known.artists.entry(artist.id).or_insert_with(|| {
-                    commands
-                        .spawn((artist, MotionBundle::random_near(position), Scrape::None))
-                        .id()
-                });
-                let relationship = Relationship {
-                    from: artist,
-                    to: release,
-                };
-                known.relationships.entry(relationship).or_insert_with(|| {
-                    commands
-                        .entity(*relationship_parent)
-                        .with_child(relationship.bundle(3.0))
-                        .id()
-                });
-            }
-
-            Response::Releases(artist, releases) => {
-                let (artist, position) = match known.artists.entry(artist.id) {
-                    Entry::Occupied(entry) => {
-                        let artist = *entry.get();
-                        let position = *positions.get(artist).unwrap();
-                        (artist, position.0)
-                    }
-                    Entry::Vacant(entry) => {
-                        let motion = MotionBundle::random();
-                        let position = motion.position;
-                        let artist = commands.spawn((artist, motion, Scrape::InProgress)).id();
-                        entry.insert(artist);
-                        (artist, position.0)
-                    }
-                };
-                for release in releases {
-                    let release = *known.releases.entry(release.id).or_insert_with(|| {
-                        commands
-                            .spawn((release, MotionBundle::random_near(position), Scrape::None))
-                            .id()
-                    });
-                    let relationship = Relationship {
-                        from: artist,
-                        to: release,
-                    };
-                    known.relationships.entry(relationship).or_insert_with(|| {
-                        commands
-                            .entity(*relationship_parent)
-                            .with_child(relationship.bundle(3.0))
-                            .id()
-                    });
-                }
-            }
-
-            Response::Collection(user, releases) => {
-                let (user, position) = match known.users.entry(user.id) {
-                    Entry::Occupied(entry) => {
-                        let user = *entry.get();
-                        let position = *positions.get(user).unwrap();
-                        (user, position.0)
-                    }
-                    Entry::Vacant(entry) => {
-                        let motion = MotionBundle::random();
-                        let position = motion.position;
-                        let user = commands.spawn((user, motion, Scrape::InProgress)).id();
-                        entry.insert(user);
-                        (user, position.0)
-                    }
-                };
-                for release in releases {
-                    let release = *known.releases.entry(release.id).or_insert_with(|| {
-                        commands
-                            .spawn((release, MotionBundle::random_near(position), Scrape::None))
-                            .id()
-                    });
-                    let relationship = Relationship {
-                        from: user,
-                        to: release,
-                    };
-                    known.relationships.entry(relationship).or_insert_with(|| {
-                        commands
-                            .entity(*relationship_parent)
-                            .with_child(relationship.bundle(1.0))
-                            .id()
-                    });
-                }
+            } else if event.logical_key == Key::Character("g".into()) {
+                plots.visible ^= true;
+            } else if event.logical_key == Key::Character("t".into()) {
+                release_filter.hide_tracks ^= true;
+            } else if event.logical_key == Key::Character("m".into()) {
+                release_filter.merge_tracks ^= true;
+            } else if event.logical_key == Key::Character("p".into()) {
+                prune_dialog.visible ^= true;
+            } else if event.logical_key == Key::Character("v".into()) {
+                split_view.0 ^= true;
+            } else if event.logical_key == Key::Character("c".into()) {
+                palette.go_to_next();
+            } else if event.logical_key == Key::Tab {
+                list_view.visible ^= true;
+            } else if event.logical_key == Key::Character("r".into()) {
+                editor.open(ui::editor::Target::ColorRules, color_rules.text());
+            } else if event.logical_key == Key::Character("s".into()) {
+                editor.open(ui::editor::Target::SavePreset, String::new());
+            } else if event.logical_key == Key::Character("h".into()) {
+                artist_hues.0 ^= true;
+            } else if event.logical_key == Key::Character("a".into()) {
+                paste.send(clipboard::Paste);
+            } else if let Some(index) = preset::number_key(&event.logical_key) {
+                apply_preset.send(preset::ApplyPreset(index));
             }
         }
     }
@@ -0,0 +1,123 @@
+use bevy::{
+    diagnostic::DiagnosticsStore,
+    ecs::{
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Commands, Res, Resource},
+    },
+    time::common_conditions::on_timer,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{Args, Runtime};
+
+/// Latest rendering of the diagnostics in the prometheus text format, shared with the server
+#[derive(Debug, Resource)]
+struct Metrics(Arc<Mutex<String>>);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            update.run_if(resource_exists::<Metrics>.and(on_timer(Duration::from_secs(1)))),
+        );
+    }
+}
+
+fn setup(args: Res<Args>, runtime: Res<Runtime>, mut commands: Commands) {
+    let Some(port) = args.metrics else { return };
+    let host = if args.metrics_public {
+        "0.0.0.0"
+    } else {
+        "127.0.0.1"
+    };
+
+    let metrics = Arc::new(Mutex::new(String::new()));
+    runtime.spawn_background(serve(host, port, metrics.clone()));
+    commands.insert_resource(Metrics(metrics));
+}
+
+/// `scraper/web/cache/hits` -> `bc_scraper3_scraper_web_cache_hits`
+fn metric_name(path: &str) -> String {
+    let path = path.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    format!("bc_scraper3_{path}")
+}
+
+fn update(diagnostics: Res<DiagnosticsStore>, metrics: Res<Metrics>) {
+    use std::fmt::Write;
+
+    let mut body = String::new();
+    for diagnostic in diagnostics.iter() {
+        if !diagnostic.is_enabled {
+            continue;
+        }
+        let Some(value) = diagnostic.smoothed() else {
+            continue;
+        };
+        let path = diagnostic.path().as_str();
+        let name = metric_name(path);
+        let suffix = &diagnostic.suffix;
+        writeln!(&mut body, "# HELP {name} {path} {suffix}").unwrap();
+        writeln!(&mut body, "# TYPE {name} gauge").unwrap();
+        writeln!(&mut body, "{name} {value}").unwrap();
+    }
+
+    *metrics.0.lock().unwrap() = body;
+}
+
+async fn serve(host: &'static str, port: u16, metrics: Arc<Mutex<String>>) {
+    let listener = match TcpListener::bind((host, port)).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            tracing::error!(?error, port, "failed to listen for metrics scrapes");
+            return;
+        }
+    };
+
+    tracing::info!(host, port, "serving prometheus metrics");
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let body = metrics.lock().unwrap().clone();
+                tokio::spawn(async move {
+                    if let Err(error) = respond(stream, body).await {
+                        tracing::warn!(?error, "failed responding to metrics scrape");
+                    }
+                });
+            }
+            Err(error) => {
+                tracing::warn!(?error, "failed to accept metrics connection");
+            }
+        }
+    }
+}
+
+async fn respond(mut stream: TcpStream, body: String) -> std::io::Result<()> {
+    // Every path serves the metrics, so the request itself doesn't matter
+    let mut request = [0; 1024];
+    let _ = stream.read(&mut request).await?;
+
+    let response = format!(
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        body.len(),
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
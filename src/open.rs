@@ -0,0 +1,119 @@
+use bevy::{
+    ecs::{
+        event::{Event, EventReader, EventWriter},
+        query::With,
+        system::{Query, Res, ResMut, Resource},
+    },
+    time::{Real, Time},
+};
+
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{data::Url, interact::Selected, ui::notify::Notify, Runtime};
+
+/// Send to open the urls of every selected node, when there are more than `--open-cap` this
+/// only asks for confirmation and sending it again opens them
+#[derive(Debug, Event)]
+pub struct OpenSelected;
+
+/// Urls waiting to be opened, launched one at a time so a large selection doesn't flood the
+/// browser with tabs all at once
+#[derive(Debug, Resource)]
+pub struct Opening {
+    queue: VecDeque<String>,
+    delay: Duration,
+    /// Time left until the next url is opened
+    wait: Duration,
+    /// How many urls are waiting on confirmation to be opened
+    unconfirmed: Option<usize>,
+}
+
+impl Opening {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            delay,
+            wait: Duration::ZERO,
+            unconfirmed: None,
+        }
+    }
+
+    pub fn unconfirmed(&self) -> Option<usize> {
+        self.unconfirmed
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<OpenSelected>();
+        app.add_systems(bevy::app::Update, (queue, launch));
+    }
+}
+
+/// Open a url with the desktop's default handler
+pub fn open_url(runtime: &Runtime, url: &str) {
+    let url = url::Url::parse(url).unwrap();
+    runtime.spawn_background(async move {
+        use ashpd::desktop::open_uri::OpenFileRequest;
+        match OpenFileRequest::default()
+            .send_uri(&url)
+            .await
+            .and_then(|req| req.response())
+        {
+            Ok(()) => tracing::info!("opened {url}"),
+            Err(err) => {
+                tracing::error!("failed to open {url}: {:?}", eyre::Report::from(err));
+            }
+        }
+    });
+}
+
+fn queue(
+    mut events: EventReader<OpenSelected>,
+    selected: Query<&Url, With<Selected>>,
+    args: Res<crate::Args>,
+    mut opening: ResMut<Opening>,
+    mut notify: EventWriter<Notify>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    let urls = Vec::from_iter(selected.iter().map(|url| url.0.clone()));
+    if urls.is_empty() {
+        notify.send(Notify("no nodes are selected".to_owned()));
+        return;
+    }
+
+    if urls.len() > args.open_cap && opening.unconfirmed != Some(urls.len()) {
+        opening.unconfirmed = Some(urls.len());
+        notify.send(Notify(format!(
+            "{} urls selected, open them again to confirm",
+            urls.len()
+        )));
+        return;
+    }
+
+    opening.unconfirmed = None;
+    notify.send(Notify(format!("opening {} urls", urls.len())));
+    if opening.queue.is_empty() {
+        opening.wait = Duration::ZERO;
+    }
+    opening.queue.extend(urls);
+}
+
+fn launch(time: Res<Time<Real>>, runtime: Res<Runtime>, mut opening: ResMut<Opening>) {
+    if opening.queue.is_empty() {
+        return;
+    }
+
+    opening.wait = opening.wait.saturating_sub(time.delta());
+    if opening.wait.is_zero() {
+        if let Some(url) = opening.queue.pop_front() {
+            open_url(&runtime, &url);
+        }
+        opening.wait = opening.delay;
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        schedule::IntoSystemConfigs,
+        system::{Res, ResMut, Resource},
+    },
+    time::common_conditions::on_timer,
+};
+
+use std::{path::Path, time::Duration};
+
+use crate::{
+    background::{JobId, Scraper},
+    data::{EntityType, Scrape},
+    graph::GraphQuery,
+};
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<DeepSeeds>();
+        app.add_systems(
+            bevy::app::Update,
+            deepen_seeds.run_if(on_timer(Duration::from_millis(500))),
+        );
+    }
+}
+
+/// Which neighbors to follow for one hop outwards from a node being deeply scraped
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Hop {
+    /// Only follow neighbors of these types
+    #[serde(default = "Hop::all_types")]
+    pub types: Vec<EntityType>,
+    /// Follow at most this many neighbors of each node
+    #[serde(default)]
+    pub cap: Option<usize>,
+}
+
+impl Hop {
+    /// Every type of node with a page to scrape, tags are only ever followed from when a scrape
+    /// starts at one
+    pub fn all() -> Self {
+        Self {
+            types: Self::all_types(),
+            cap: None,
+        }
+    }
+
+    fn all_types() -> Vec<EntityType> {
+        vec![
+            EntityType::Artist,
+            EntityType::Label,
+            EntityType::Release,
+            EntityType::User,
+        ]
+    }
+}
+
+/// Rules for how deep scrapes propagate out through the graph
+#[derive(Debug, Resource)]
+pub struct ScrapePolicy {
+    /// A scrape `n` deep follows the first `n` hops, then queues every node it reached
+    pub hops: Vec<Hop>,
+}
+
+impl Default for ScrapePolicy {
+    fn default() -> Self {
+        Self {
+            hops: vec![Hop::all(), Hop::all()],
+        }
+    }
+}
+
+impl ScrapePolicy {
+    /// The policy configured in the config directory, or the default if there is none, following
+    /// every type of neighbor for any hops past its own
+    #[culpa::try_fn]
+    pub fn load(config_dir: &Path, depth: usize) -> eyre::Result<Self> {
+        let path = config_dir.join("scrape-policy.json");
+        let mut policy = match std::fs::read_to_string(&path) {
+            Ok(data) => Self {
+                hops: serde_json::from_str(&data).map_err(|error| {
+                    eyre::eyre!("invalid scrape policy in {}: {error}", path.display())
+                })?,
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => Err(err)?,
+        };
+        if policy.hops.len() < depth {
+            policy.hops.resize(depth, Hop::all());
+        }
+        policy
+    }
+
+    /// The level a node is marked with once everything `depth` hops from it is queued
+    fn level(depth: usize) -> Scrape {
+        match depth {
+            0 => Scrape::InProgress,
+            1 => Scrape::Deep,
+            _ => Scrape::ExtraDeep,
+        }
+    }
+
+    /// How many hops out a node marked with `level` has had its scrape propagated, the inverse of
+    /// [`Self::level`] for deep scrapes
+    pub fn depth(level: Scrape) -> Option<usize> {
+        match level {
+            Scrape::Deep => Some(1),
+            Scrape::ExtraDeep => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Scrape the nodes `depth` hops out from a node as part of a job, marking the nodes along the
+    /// way as deeply scraped
+    pub fn propagate(
+        &self,
+        entity: Entity,
+        depth: usize,
+        job: JobId,
+        graph: &mut GraphQuery,
+        scraper: &Scraper,
+    ) {
+        let depth = depth.min(self.hops.len());
+        if depth == 0 {
+            graph.scrape_in(entity, Some(job), scraper);
+            return;
+        }
+
+        graph.clamp_scrape(entity, Self::level(depth));
+
+        let mut frontier = vec![entity];
+        for (i, hop) in self.hops[..depth].iter().enumerate() {
+            let remaining = depth - i - 1;
+            let next = Vec::from_iter(frontier.iter().flat_map(|&entity| {
+                graph
+                    .neighbors(entity)
+                    .filter(|&neighbor| {
+                        graph
+                            .entity_type(neighbor)
+                            .is_some_and(|ty| hop.types.contains(&ty))
+                    })
+                    .take(hop.cap.unwrap_or(usize::MAX))
+            }));
+            for &entity in &next {
+                if remaining == 0 {
+                    graph.scrape_in(entity, Some(job), scraper);
+                } else {
+                    // Nodes that were never scraped have no neighbors to go on to yet, so they're
+                    // queued too, like fans only just expanded from their release
+                    if graph.scrape_level(entity) == Some(Scrape::None) {
+                        graph.scrape_in(entity, Some(job), scraper);
+                    }
+                    graph.clamp_scrape(entity, Self::level(remaining));
+                }
+            }
+            frontier = next;
+        }
+    }
+}
+
+/// Seeds given on the command line with `--depth`, which are deep scraped a hop further each time
+/// the scrapes of their job so far have finished
+#[derive(Debug, Default, Resource)]
+pub struct DeepSeeds {
+    pending: Vec<DeepSeed>,
+}
+
+#[derive(Debug)]
+struct DeepSeed {
+    url: String,
+    job: JobId,
+    /// How many hops out have been scraped so far
+    reached: usize,
+    depth: usize,
+}
+
+impl DeepSeeds {
+    pub fn add(&mut self, url: String, job: JobId, depth: usize) {
+        if depth > 0 {
+            self.pending.push(DeepSeed {
+                url,
+                job,
+                reached: 0,
+                depth,
+            });
+        }
+    }
+}
+
+fn deepen_seeds(
+    mut seeds: ResMut<DeepSeeds>,
+    policy: Res<ScrapePolicy>,
+    scraper: Res<Scraper>,
+    mut graph: GraphQuery,
+) {
+    if seeds.pending.is_empty() {
+        return;
+    }
+
+    let jobs = scraper.jobs();
+    seeds.pending.retain_mut(|seed| {
+        let Some((_, stats)) = jobs.iter().find(|(job, _)| *job == seed.job) else {
+            return false;
+        };
+        if stats.is_running() {
+            return true;
+        }
+        let Some(entity) = graph.find(&seed.url) else {
+            // Its response may not have been received yet, unless the seed's own scrape failed
+            // and there's nothing to go out from
+            if seed.reached == 0 && stats.failed > 0 {
+                tracing::warn!(url = %seed.url, "seed failed to scrape, not going any deeper");
+                return false;
+            }
+            return true;
+        };
+        seed.reached += 1;
+        policy.propagate(entity, seed.reached, seed.job, &mut graph, &scraper);
+        seed.reached < seed.depth
+    });
+}
@@ -0,0 +1,162 @@
+use bevy::{
+    ecs::{
+        event::{Event, EventReader, EventWriter},
+        query::With,
+        system::{Res, ResMut, Resource, Single, SystemParam},
+    },
+    input::keyboard::Key,
+    render::view::Visibility,
+};
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    render::{filter::ReleaseFilter, palette::Palette, rules::ColorRules},
+    sim::OriginForceMode,
+    ui::notify::Notify,
+    RelationshipParent,
+};
+
+/// Send to save the current view settings as a named preset, replacing any with the same name
+#[derive(Debug, Event)]
+pub struct SavePreset(pub String);
+
+/// Send to switch to the preset at an index
+#[derive(Debug, Event)]
+pub struct ApplyPreset(pub usize);
+
+/// A named combination of view settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Preset {
+    name: String,
+    filter: ReleaseFilter,
+    color_rules: Vec<String>,
+    palette: Palette,
+    lines: bool,
+    origin_force: OriginForceMode,
+}
+
+/// Saved presets, persisted in the config directory, the first nine can be switched to with the
+/// number keys
+#[derive(Debug, Resource)]
+pub struct Presets {
+    path: PathBuf,
+    stored: Vec<Preset>,
+}
+
+impl Presets {
+    #[culpa::try_fn]
+    pub fn load(config_dir: &Path) -> eyre::Result<Self> {
+        let path = config_dir.join("presets.json");
+        let stored = match std::fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => Err(err)?,
+        };
+        Self { path, stored }
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.try_save() {
+            tracing::error!(?error, "failed saving presets");
+        }
+    }
+
+    #[culpa::try_fn]
+    fn try_save(&self) -> eyre::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.stored)?)?;
+    }
+}
+
+/// The index of the preset a number key switches to
+pub fn number_key(key: &Key) -> Option<usize> {
+    let Key::Character(character) = key else {
+        return None;
+    };
+    let number: usize = character.parse().ok()?;
+    (1..=9).contains(&number).then(|| number - 1)
+}
+
+/// Everything a preset saves
+#[derive(SystemParam)]
+struct Settings<'w> {
+    filter: ResMut<'w, ReleaseFilter>,
+    color_rules: ResMut<'w, ColorRules>,
+    palette: ResMut<'w, Palette>,
+    lines: Single<'w, &'static mut Visibility, With<RelationshipParent>>,
+    origin_force: ResMut<'w, OriginForceMode>,
+}
+
+impl Settings<'_> {
+    fn capture(&self, name: String) -> Preset {
+        Preset {
+            name,
+            filter: self.filter.clone(),
+            color_rules: Vec::from_iter(self.color_rules.sources().map(str::to_owned)),
+            palette: *self.palette,
+            lines: **self.lines != Visibility::Hidden,
+            origin_force: *self.origin_force,
+        }
+    }
+
+    fn apply(&mut self, preset: &Preset) {
+        *self.filter = preset.filter.clone();
+        if let Err(error) = self
+            .color_rules
+            .set(preset.color_rules.iter().map(String::as_str))
+        {
+            tracing::warn!(%error, preset = preset.name, "invalid color rule in preset");
+        }
+        *self.palette = preset.palette;
+        **self.lines = if preset.lines {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        *self.origin_force = preset.origin_force;
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<SavePreset>();
+        app.add_event::<ApplyPreset>();
+        app.add_systems(bevy::app::Update, (save, apply));
+    }
+}
+
+fn save(
+    mut events: EventReader<SavePreset>,
+    mut presets: ResMut<Presets>,
+    settings: Settings,
+    mut notify: EventWriter<Notify>,
+) {
+    for SavePreset(name) in events.read() {
+        let preset = settings.capture(name.clone());
+        let index = if let Some(index) = presets.stored.iter().position(|p| &p.name == name) {
+            presets.stored[index] = preset;
+            index
+        } else {
+            presets.stored.push(preset);
+            presets.stored.len() - 1
+        };
+        presets.save();
+        notify.send(Notify(format!("saved preset {}: {name}", index + 1)));
+    }
+}
+
+fn apply(
+    mut events: EventReader<ApplyPreset>,
+    presets: Res<Presets>,
+    mut settings: Settings,
+    mut notify: EventWriter<Notify>,
+) {
+    for &ApplyPreset(index) in events.read() {
+        if let Some(preset) = presets.stored.get(index) {
+            settings.apply(preset);
+            notify.send(Notify(format!("switched to preset {}", preset.name)));
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::{
+    diagnostic::DiagnosticsStore,
+    ecs::{
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::{Res, ResMut, Resource, Single},
+    },
+    render::view::Visibility,
+    time::{common_conditions::on_timer, Fixed, Time},
+};
+
+use std::time::Duration;
+
+use crate::{diagnostic::frame, Args, RelationshipParent};
+
+/// How often the frame rate is checked, long enough that a change in quality has time to take
+/// effect before the next
+const INTERVAL: Duration = Duration::from_secs(2);
+
+/// The frame rate must exceed the minimum by this factor before restoring quality, to avoid
+/// flip-flopping around the threshold
+const HEADROOM: f64 = 1.5;
+
+/// Optional work is degraded in order from the top as the frame rate drops, and restored in
+/// reverse as it recovers
+///
+/// Reducing the label count is deferred until nodes have labels drawn in the graph, the only
+/// labels so far are the panels for the nearest and selected nodes, which don't scale with it
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    #[default]
+    Full,
+    HideLines,
+    LowerSimRate,
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct Quality {
+    pub level: Level,
+    /// Whether the lines were hidden by us, rather than already hidden by the user
+    hid_lines: bool,
+    /// Simulation timestep to restore to
+    timestep: Option<Duration>,
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.insert_resource(Quality::default());
+        app.add_systems(bevy::app::Update, update.run_if(on_timer(INTERVAL)));
+    }
+}
+
+fn update(
+    args: Res<Args>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut quality: ResMut<Quality>,
+    mut relationship_parent: Single<&mut Visibility, With<RelationshipParent>>,
+    mut time: ResMut<Time<Fixed>>,
+) {
+    if args.min_fps <= 0. {
+        return;
+    }
+
+    let Some(fps) = diagnostics
+        .get(&frame::Plugin::FPS)
+        .and_then(|diagnostic| diagnostic.smoothed())
+    else {
+        return;
+    };
+
+    let level = quality.level;
+    if fps < args.min_fps {
+        match level {
+            Level::Full => {
+                if **relationship_parent != Visibility::Hidden {
+                    **relationship_parent = Visibility::Hidden;
+                    quality.hid_lines = true;
+                }
+                quality.level = Level::HideLines;
+            }
+            Level::HideLines => {
+                quality.timestep = Some(time.timestep());
+                let timestep = time.timestep() * 2;
+                time.set_timestep(timestep);
+                quality.level = Level::LowerSimRate;
+            }
+            Level::LowerSimRate => {}
+        }
+    } else if fps > args.min_fps * HEADROOM {
+        match level {
+            Level::Full => {}
+            Level::HideLines => {
+                if std::mem::take(&mut quality.hid_lines) {
+                    **relationship_parent = Visibility::Visible;
+                }
+                quality.level = Level::Full;
+            }
+            Level::LowerSimRate => {
+                if let Some(timestep) = quality.timestep.take() {
+                    time.set_timestep(timestep);
+                }
+                quality.level = Level::HideLines;
+            }
+        }
+    }
+
+    if quality.level != level {
+        tracing::info!(fps, from = ?level, to = ?quality.level, "changed quality level");
+    }
+}
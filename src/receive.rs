@@ -0,0 +1,914 @@
+use bevy::{
+    ecs::{
+        bundle::Bundle,
+        change_detection::ResMut,
+        entity::{Entities, Entity},
+        event::{Event, EventReader, EventWriter},
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, Res, Resource, Single, SystemParam},
+    },
+    math::Vec2,
+};
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+    time::Instant,
+};
+
+use crate::{
+    alias::{self, Aliases},
+    background::{diagnostic, Follow, Request, Response, Scraper, SearchResult},
+    data::{
+        ArtistDetails, DiscoveredVia, LabelDetails, LabelId, Lapsed, RelationKind, Scrape,
+        ScrapeHistory, Url, User, UserId,
+    },
+    diagnostic::Diagnostics,
+    interact::Selected,
+    sample::FanSampling,
+    sim::{MotionBundle, Multiplicity, Partitions, PredictedPosition, Relationship, Ring},
+    ui::notify::Notify,
+    watch::Watchlist,
+    KnownEntities, RelationshipParent,
+};
+
+/// A response from the scraper, sent for the system handling its variant
+#[derive(Debug, Event)]
+pub struct Received {
+    pub request: Request,
+    pub response: Response,
+}
+
+/// When the response being handled this frame was dispatched
+#[derive(Debug, Default, Resource)]
+struct Dispatched(Option<Instant>);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Received>();
+        app.init_resource::<Dispatched>();
+        app.add_systems(
+            bevy::app::Update,
+            (
+                dispatch,
+                (
+                    artist,
+                    release,
+                    user,
+                    fans,
+                    fans_delta,
+                    release_artist,
+                    credits,
+                    tags,
+                    reviews,
+                    releases,
+                    collection,
+                    wishlist,
+                    follows,
+                    followed_artists,
+                    roster,
+                    supporters,
+                    search_results,
+                    tag_releases,
+                    feed,
+                )
+                    .after(dispatch),
+                applied,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// A node that a response refers to
+struct Node {
+    entity: Entity,
+    position: Vec2,
+    /// Whether this is the first the graph has heard of it
+    spawned: bool,
+}
+
+/// Everything the response handlers share for adding to the graph
+#[derive(SystemParam)]
+struct Graph<'w, 's> {
+    known: ResMut<'w, KnownEntities>,
+    positions: Query<'w, 's, &'static PredictedPosition>,
+    scrape: Query<'w, 's, &'static mut Scrape>,
+    histories: Query<'w, 's, &'static mut ScrapeHistory>,
+    multiplicities: Query<'w, 's, &'static mut Multiplicity>,
+    urls: Query<'w, 's, &'static Url>,
+    moved: EventWriter<'w, alias::Moved>,
+    partitions: Res<'w, Partitions>,
+    entities: &'w Entities,
+    relationship_parent: Single<'w, Entity, With<RelationshipParent>>,
+    commands: Commands<'w, 's>,
+}
+
+impl Graph<'_, '_> {
+    /// Get the node for an id, spawning it at a random position if it isn't known yet
+    fn spawn_or_get<K: Eq + Hash, B: Bundle>(
+        &mut self,
+        index: fn(&mut KnownEntities) -> &mut HashMap<K, Entity>,
+        id: K,
+        bundle: impl FnOnce(MotionBundle) -> B,
+    ) -> Node {
+        match index(&mut self.known).entry(id) {
+            Entry::Occupied(entry) => {
+                let entity = *entry.get();
+                // Nodes loaded or restored this frame are indexed before their spawn is applied
+                let position = self
+                    .positions
+                    .get(entity)
+                    .map_or(Vec2::ZERO, |position| position.0);
+                Node {
+                    entity,
+                    position,
+                    spawned: false,
+                }
+            }
+            Entry::Vacant(entry) => {
+                let motion = MotionBundle::random();
+                let position = motion.position.0;
+                let entity = self.commands.spawn(bundle(motion)).id();
+                entry.insert(entity);
+                Node {
+                    entity,
+                    position,
+                    spawned: true,
+                }
+            }
+        }
+    }
+
+    /// Get the node for an id, spawning it on `ring` if it isn't known yet
+    fn spawn_or_get_near<K: Eq + Hash, B: Bundle>(
+        &mut self,
+        index: fn(&mut KnownEntities) -> &mut HashMap<K, Entity>,
+        id: K,
+        ring: &mut Ring,
+        bundle: impl FnOnce(MotionBundle) -> B,
+    ) -> Entity {
+        *index(&mut self.known)
+            .entry(id)
+            .or_insert_with(|| self.commands.spawn(bundle(ring.place())).id())
+    }
+
+    /// Update the node whose page was scraped, spawning it if it isn't known yet
+    fn scraped<K: Eq + Hash, B: Bundle>(
+        &mut self,
+        index: fn(&mut KnownEntities) -> &mut HashMap<K, Entity>,
+        id: K,
+        url: &Url,
+        node: B,
+        details: impl Bundle,
+        request: &Request,
+    ) -> Node {
+        let via = DiscoveredVia::new(request, None);
+        let node = self.spawn_or_get(index, id, |motion| (node, motion, Scrape::Shallow, via));
+        if !node.spawned {
+            self.known.check_moved(
+                node.entity,
+                url,
+                &self.urls,
+                &mut self.moved,
+                &mut self.commands,
+            );
+            if let Ok(mut scrape) = self.scrape.get_mut(node.entity) {
+                scrape.clamp_to(Scrape::Shallow..);
+            }
+        }
+        self.commands.entity(node.entity).insert(details);
+        self.record_scrape(node.entity, request);
+        node
+    }
+
+    /// Fill in the details of a node loaded without them, unless it has gone since
+    fn hydrated<K: Eq + Hash>(
+        &mut self,
+        index: fn(&mut KnownEntities) -> &mut HashMap<K, Entity>,
+        id: &K,
+        details: impl Bundle,
+    ) {
+        if let Some(&entity) = index(&mut self.known).get(id) {
+            self.commands.entity(entity).insert(details);
+        }
+    }
+
+    /// Record a scrape of a node's page, which may not have been scraped before
+    fn record_scrape(&mut self, entity: Entity, request: &Request) {
+        if let Ok(mut history) = self.histories.get_mut(entity) {
+            history.push(Scrape::Shallow, Some(request));
+        } else {
+            self.commands
+                .entity(entity)
+                .insert(ScrapeHistory::new(Scrape::Shallow, Some(request)));
+        }
+    }
+
+    fn relate(&mut self, from: Entity, to: Entity, kind: RelationKind) {
+        self.known.relate(
+            Relationship { from, to },
+            kind,
+            *self.relationship_parent,
+            &mut self.multiplicities,
+            &mut self.commands,
+        );
+    }
+
+    fn add_fans(
+        &mut self,
+        release: &Node,
+        users: &[User],
+        kind: RelationKind,
+        via: &DiscoveredVia,
+    ) {
+        self.known.add_fans(
+            release.entity,
+            release.position,
+            users,
+            kind,
+            via,
+            *self.relationship_parent,
+            self.entities,
+            &self.partitions,
+            &mut self.multiplicities,
+            &mut self.commands,
+        );
+    }
+
+    /// The relationship between a user and a release, if both are known and related
+    fn edge(&self, user: UserId, release: Entity) -> Option<Entity> {
+        let from = *self.known.users.get(&user)?;
+        self.known
+            .relationships
+            .get(&Relationship { from, to: release })
+            .copied()
+    }
+
+    /// A ring around `parent` for placing those of `ids` that aren't known yet
+    fn ring<K: Eq + Hash>(
+        &mut self,
+        index: fn(&mut KnownEntities) -> &mut HashMap<K, Entity>,
+        parent: &Node,
+        ids: impl IntoIterator<Item = K>,
+    ) -> Ring {
+        let index = index(&mut self.known);
+        let new = ids.into_iter().filter(|id| !index.contains_key(id)).count();
+        Ring::around(parent.position, new, &self.partitions)
+    }
+}
+
+fn dispatch(
+    scraper: Res<Scraper>,
+    mut dispatched: ResMut<Dispatched>,
+    mut received: EventWriter<Received>,
+) {
+    if let Some((request, response)) = scraper.try_recv().unwrap() {
+        dispatched.0 = Some(Instant::now());
+        received.send(Received { request, response });
+    }
+}
+
+/// Measures how long the handlers took to apply the dispatched response to the graph
+fn applied(mut dispatched: ResMut<Dispatched>, mut diagnostics: Diagnostics) {
+    if let Some(start) = dispatched.0.take() {
+        diagnostics.add_measurement(&diagnostic::items::APPLY_TIME, || {
+            start.elapsed().as_secs_f64() * 1000.
+        });
+    }
+}
+
+fn artist(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Artist(artist, details, aliases) = response else {
+            continue;
+        };
+        if let Request::Hydrate(_) = request {
+            graph.hydrated(|known| &mut known.artists, &artist.id, details.clone());
+            continue;
+        }
+        let node = graph.scraped(
+            |known| &mut known.artists,
+            artist.id,
+            &artist.url,
+            artist.clone(),
+            details.clone(),
+            request,
+        );
+        // A custom domain and its subdomain are the same artist, whichever it was found at
+        if node.spawned {
+            for alias in aliases {
+                graph.known.aliases.insert(alias.clone(), node.entity);
+            }
+            if !aliases.is_empty() {
+                graph
+                    .commands
+                    .entity(node.entity)
+                    .insert(Aliases(aliases.iter().cloned().collect()));
+            }
+        } else {
+            for alias in aliases {
+                graph.known.add_alias(
+                    node.entity,
+                    &Url::from(alias.as_str()),
+                    &graph.urls,
+                    &mut graph.moved,
+                );
+            }
+        }
+    }
+}
+
+fn release(mut events: EventReader<Received>, mut sampling: ResMut<FanSampling>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Release(release, details) = response else {
+            continue;
+        };
+        if let Request::Hydrate(_) = request {
+            graph.hydrated(|known| &mut known.releases, &release.id, details.clone());
+            continue;
+        }
+        let node = graph.scraped(
+            |known| &mut known.releases,
+            release.id,
+            &release.url,
+            release.clone(),
+            details.clone(),
+            request,
+        );
+        // The release details are sent after all its fans, so the sample can be taken
+        if sampling.holding() {
+            let deep = graph
+                .scrape
+                .get(node.entity)
+                .is_ok_and(|scrape| *scrape >= Scrape::Deep);
+            let (sample, unspawned) = sampling.sample(release.id, deep, request);
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            graph.add_fans(&node, &sample, RelationKind::Fan, &via);
+            if let Some(unspawned) = unspawned {
+                graph.commands.entity(node.entity).insert(unspawned);
+            }
+        }
+    }
+}
+
+fn user(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::User(user, details) = response else {
+            continue;
+        };
+        if let Request::Hydrate(_) = request {
+            graph.hydrated(|known| &mut known.users, &user.id, details.clone());
+            continue;
+        }
+        graph.scraped(
+            |known| &mut known.users,
+            user.id,
+            &user.url,
+            user.clone(),
+            details.clone(),
+            request,
+        );
+    }
+}
+
+fn fans(
+    mut events: EventReader<Received>,
+    mut watchlist: ResMut<Watchlist>,
+    mut sampling: ResMut<FanSampling>,
+    mut graph: Graph,
+) {
+    for Received { request, response } in events.read() {
+        let Response::Fans(release, kind, users) = response else {
+            continue;
+        };
+        watchlist.saw_fans(release.id, users);
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.releases,
+            release.id,
+            |motion| (release.clone(), motion, Scrape::Shallow, via),
+        );
+        let users = sampling.hold(release.id, users.clone(), &graph.known);
+        let via = DiscoveredVia::new(request, Some(node.entity));
+        graph.add_fans(&node, &users, *kind, &via);
+    }
+}
+
+fn fans_delta(
+    mut events: EventReader<Received>,
+    mut watchlist: ResMut<Watchlist>,
+    mut sampling: ResMut<FanSampling>,
+    mut graph: Graph,
+) {
+    for Received { request, response } in events.read() {
+        let Response::FansDelta(release, kind, added, removed) = response else {
+            continue;
+        };
+        watchlist.saw_fans(release.id, added);
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.releases,
+            release.id,
+            |motion| (release.clone(), motion, Scrape::Shallow, via),
+        );
+
+        // Fans that have come back to collecting it are no longer lapsed
+        for user in added {
+            if let Some(edge) = graph.edge(user.id, node.entity) {
+                graph.commands.entity(edge).remove::<Lapsed>();
+            }
+        }
+        let added = sampling.hold(release.id, added.clone(), &graph.known);
+        let via = DiscoveredVia::new(request, Some(node.entity));
+        graph.add_fans(&node, &added, *kind, &via);
+
+        // Their edges are kept so the history is still visible, but marked as lapsed
+        for &user in removed {
+            if let Some(edge) = graph.edge(user, node.entity) {
+                graph.commands.entity(edge).insert(Lapsed);
+            }
+        }
+    }
+}
+
+fn release_artist(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::ReleaseArtist(release, kind, artist) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.releases,
+            release.id,
+            |motion| (release.clone(), motion, Scrape::InProgress, via),
+        );
+        let mut ring = graph.ring(|known| &mut known.artists, &node, [artist.id]);
+        let via = DiscoveredVia::new(request, Some(node.entity));
+        let artist = graph.spawn_or_get_near(
+            |known| &mut known.artists,
+            artist.id,
+            &mut ring,
+            |motion| (artist.clone(), motion, Scrape::None, via),
+        );
+        graph.relate(artist, node.entity, *kind);
+    }
+}
+
+/// Credits without a page to find the artist by are only linked to artists already in the graph
+/// going by the same name
+fn credits(
+    mut events: EventReader<Received>,
+    artists: Query<(Entity, &ArtistDetails)>,
+    mut graph: Graph,
+) {
+    for Received { request, response } in events.read() {
+        let Response::Credits(release, names) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.releases,
+            release.id,
+            |motion| (release.clone(), motion, Scrape::InProgress, via),
+        );
+        for (artist, details) in &artists {
+            if names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&details.name))
+            {
+                graph.relate(artist, node.entity, RelationKind::Credit);
+            }
+        }
+    }
+}
+
+fn tags(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Tags(release, tags) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.releases,
+            release.id,
+            |motion| (release.clone(), motion, Scrape::InProgress, via),
+        );
+        let mut ring = graph.ring(
+            |known| &mut known.tags,
+            &node,
+            tags.iter().map(|tag| tag.id.clone()),
+        );
+        for tag in tags {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            // Everything there is to know about a tag comes with the link to it
+            let tag = graph.spawn_or_get_near(
+                |known| &mut known.tags,
+                tag.id.clone(),
+                &mut ring,
+                |motion| (tag.clone(), motion, Scrape::Shallow, via),
+            );
+            graph.relate(tag, node.entity, RelationKind::Tag);
+        }
+    }
+}
+
+/// Reviews by fans that weren't spawned, because they were sampled out, have no link to go on
+fn reviews(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { response, .. } in events.read() {
+        let Response::Reviews(release, reviews) = response else {
+            continue;
+        };
+        let Some(&release) = graph.known.releases.get(&release.id) else {
+            continue;
+        };
+        for (user, review) in reviews {
+            if let Some(edge) = graph.edge(*user, release) {
+                graph.commands.entity(edge).insert(review.clone());
+            }
+        }
+    }
+}
+
+fn releases(mut events: EventReader<Received>, mut watchlist: ResMut<Watchlist>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Releases(artist, kind, releases) = response else {
+            continue;
+        };
+        watchlist.saw_releases(artist.id, releases);
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.artists,
+            artist.id,
+            |motion| (artist.clone(), motion, Scrape::InProgress, via),
+        );
+        let mut ring = graph.ring(
+            |known| &mut known.releases,
+            &node,
+            releases.iter().map(|release| release.id),
+        );
+        for release in releases {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let entity = graph.spawn_or_get_near(
+                |known| &mut known.releases,
+                release.id,
+                &mut ring,
+                |motion| (release.clone(), motion, Scrape::None, via),
+            );
+            // Releases are keyed on their item id, the same one listed by a label and its artist
+            // may have a different url on each
+            graph
+                .known
+                .add_alias(entity, &release.url, &graph.urls, &mut graph.moved);
+            graph.relate(node.entity, entity, *kind);
+        }
+    }
+}
+
+fn collection(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Collection(user, kind, releases, progress) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.users,
+            user.id,
+            |motion| (user.clone(), motion, Scrape::InProgress, via),
+        );
+        graph.commands.entity(node.entity).insert(*progress);
+        let mut ring = graph.ring(
+            |known| &mut known.releases,
+            &node,
+            releases.iter().map(|(release, _)| release.id),
+        );
+        for (release, acquired) in releases {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let release = graph.spawn_or_get_near(
+                |known| &mut known.releases,
+                release.id,
+                &mut ring,
+                |motion| (release.clone(), motion, Scrape::None, via),
+            );
+            graph.relate(node.entity, release, *kind);
+            if let Some(acquired) = acquired {
+                let relationship = graph.known.relationships[&Relationship {
+                    from: node.entity,
+                    to: release,
+                }];
+                graph.commands.entity(relationship).insert(*acquired);
+            }
+        }
+    }
+}
+
+fn wishlist(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Wishlist(user, releases) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.users,
+            user.id,
+            |motion| (user.clone(), motion, Scrape::InProgress, via),
+        );
+        let mut ring = graph.ring(
+            |known| &mut known.releases,
+            &node,
+            releases.iter().map(|release| release.id),
+        );
+        for release in releases {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let release = graph.spawn_or_get_near(
+                |known| &mut known.releases,
+                release.id,
+                &mut ring,
+                |motion| (release.clone(), motion, Scrape::None, via),
+            );
+            graph.relate(node.entity, release, RelationKind::Wishlist);
+        }
+    }
+}
+
+fn follows(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Follows(user, follow, fans) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.users,
+            user.id,
+            |motion| (user.clone(), motion, Scrape::InProgress, via),
+        );
+        let mut ring = graph.ring(
+            |known| &mut known.users,
+            &node,
+            fans.iter().map(|fan| fan.id),
+        );
+        for fan in fans {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let fan = graph.spawn_or_get_near(
+                |known| &mut known.users,
+                fan.id,
+                &mut ring,
+                |motion| (fan.clone(), motion, Scrape::None, via),
+            );
+            if fan == node.entity {
+                continue;
+            }
+            let (mut from, mut to) = match follow {
+                Follow::Following => (node.entity, fan),
+                Follow::Followers => (fan, node.entity),
+            };
+            // Fans following each other share one link, whichever way round it was first found
+            if graph
+                .known
+                .relationships
+                .contains_key(&Relationship { from: to, to: from })
+            {
+                (from, to) = (to, from);
+            }
+            graph.relate(from, to, RelationKind::Follow);
+        }
+    }
+}
+
+fn followed_artists(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::FollowedArtists(user, artists) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.users,
+            user.id,
+            |motion| (user.clone(), motion, Scrape::InProgress, via),
+        );
+        let mut ring = graph.ring(
+            |known| &mut known.artists,
+            &node,
+            artists.iter().map(|artist| artist.id),
+        );
+        for artist in artists {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let artist = graph.spawn_or_get_near(
+                |known| &mut known.artists,
+                artist.id,
+                &mut ring,
+                |motion| (artist.clone(), motion, Scrape::None, via),
+            );
+            graph.relate(node.entity, artist, RelationKind::Follow);
+        }
+    }
+}
+
+fn roster(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Roster(label, artists) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.artists,
+            label.id,
+            |motion| (label.clone(), motion, Scrape::InProgress, via),
+        );
+        graph.commands.entity(node.entity).insert((
+            LabelId(label.id.0),
+            LabelDetails {
+                roster: artists.len(),
+            },
+        ));
+        let mut ring = graph.ring(
+            |known| &mut known.artists,
+            &node,
+            artists.iter().map(|artist| artist.id),
+        );
+        for artist in artists {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let artist = graph.spawn_or_get_near(
+                |known| &mut known.artists,
+                artist.id,
+                &mut ring,
+                |motion| (artist.clone(), motion, Scrape::None, via),
+            );
+            graph.relate(node.entity, artist, RelationKind::Roster);
+        }
+    }
+}
+
+fn supporters(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Supporters(artist, users) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.artists,
+            artist.id,
+            |motion| (artist.clone(), motion, Scrape::InProgress, via),
+        );
+        let mut ring = graph.ring(
+            |known| &mut known.users,
+            &node,
+            users.iter().map(|user| user.id),
+        );
+        for user in users {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let user = graph.spawn_or_get_near(
+                |known| &mut known.users,
+                user.id,
+                &mut ring,
+                |motion| (user.clone(), motion, Scrape::None, via),
+            );
+            graph.relate(user, node.entity, RelationKind::Support);
+        }
+    }
+}
+
+/// Search results aren't related to anything, they're left unscraped and selected so they can be
+/// found in the graph and scraped from there
+fn search_results(
+    mut events: EventReader<Received>,
+    mut graph: Graph,
+    selected: Query<Entity, With<Selected>>,
+    mut notify: EventWriter<Notify>,
+) {
+    for Received { request, response } in events.read() {
+        let Response::SearchResults(results) = response else {
+            continue;
+        };
+        for entity in &selected {
+            graph.commands.entity(entity).remove::<Selected>();
+        }
+        for result in results {
+            let via = DiscoveredVia::new(request, None);
+            let node = match result {
+                SearchResult::Artist(artist) => graph.spawn_or_get(
+                    |known| &mut known.artists,
+                    artist.id,
+                    |motion| (artist.clone(), motion, Scrape::None, via),
+                ),
+                SearchResult::Release(release) => graph.spawn_or_get(
+                    |known| &mut known.releases,
+                    release.id,
+                    |motion| (release.clone(), motion, Scrape::None, via),
+                ),
+                SearchResult::User(user) => graph.spawn_or_get(
+                    |known| &mut known.users,
+                    user.id,
+                    |motion| (user.clone(), motion, Scrape::None, via),
+                ),
+            };
+            graph.commands.entity(node.entity).insert(Selected);
+        }
+        notify.send(Notify(format!(
+            "found {} results for {:?}",
+            results.len(),
+            request.url()
+        )));
+    }
+}
+
+fn tag_releases(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::TagReleases(tag, releases) = response else {
+            continue;
+        };
+        let node = graph.scraped(
+            |known| &mut known.tags,
+            tag.id.clone(),
+            &tag.url,
+            (tag.id.clone(), tag.url.clone()),
+            tag.details.clone(),
+            request,
+        );
+        let mut ring = graph.ring(
+            |known| &mut known.releases,
+            &node,
+            releases.iter().map(|release| release.id),
+        );
+        for release in releases {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let release = graph.spawn_or_get_near(
+                |known| &mut known.releases,
+                release.id,
+                &mut ring,
+                |motion| (release.clone(), motion, Scrape::None, via),
+            );
+            graph.relate(node.entity, release, RelationKind::Tag);
+        }
+    }
+}
+
+/// Stories in a fan's feed link the fan to the artists and fans they follow, and those to the
+/// releases the stories are about
+fn feed(mut events: EventReader<Received>, mut graph: Graph) {
+    for Received { request, response } in events.read() {
+        let Response::Feed(user, stories) = response else {
+            continue;
+        };
+        let via = DiscoveredVia::new(request, None);
+        let node = graph.spawn_or_get(
+            |known| &mut known.users,
+            user.id,
+            |motion| (user.clone(), motion, Scrape::None, via),
+        );
+        let mut releases = graph.ring(
+            |known| &mut known.releases,
+            &node,
+            stories.iter().map(|story| story.release.id),
+        );
+        let mut artists = graph.ring(
+            |known| &mut known.artists,
+            &node,
+            stories.iter().map(|story| story.artist.id),
+        );
+        let mut fans = graph.ring(
+            |known| &mut known.users,
+            &node,
+            stories
+                .iter()
+                .filter_map(|story| Some(story.fan.as_ref()?.id)),
+        );
+        for story in stories {
+            let via = DiscoveredVia::new(request, Some(node.entity));
+            let release = graph.spawn_or_get_near(
+                |known| &mut known.releases,
+                story.release.id,
+                &mut releases,
+                |motion| (story.release.clone(), motion, Scrape::None, via.clone()),
+            );
+            let artist = graph.spawn_or_get_near(
+                |known| &mut known.artists,
+                story.artist.id,
+                &mut artists,
+                |motion| (story.artist.clone(), motion, Scrape::None, via.clone()),
+            );
+            graph.relate(artist, release, RelationKind::Artist);
+            let Some(fan) = &story.fan else {
+                graph.relate(node.entity, artist, RelationKind::Follow);
+                continue;
+            };
+            let fan = graph.spawn_or_get_near(
+                |known| &mut known.users,
+                fan.id,
+                &mut fans,
+                |motion| (fan.clone(), motion, Scrape::None, via),
+            );
+            graph.relate(fan, release, RelationKind::Collection);
+            // Fans following each other share one link, whichever way round it was first found
+            if !graph.known.relationships.contains_key(&Relationship {
+                from: fan,
+                to: node.entity,
+            }) {
+                graph.relate(node.entity, fan, RelationKind::Follow);
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        query::{With, Without},
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, Res, ResMut, Resource},
+    },
+    hierarchy::{BuildChildren, ChildBuild},
+    image::Image,
+    math::{primitives::Rectangle, Rect},
+    picking::PickingBehavior,
+    render::{
+        camera::Camera,
+        mesh::{Mesh, Mesh2d},
+    },
+    sprite::{ColorMaterial, MeshMaterial2d},
+    time::common_conditions::on_timer,
+    transform::components::{GlobalTransform, Transform},
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use crate::{
+    background::{Request, Response, Scraper},
+    data::{ReleaseDetails, ReleaseType},
+    receive::Received,
+    sim::PredictedPosition,
+};
+
+static ALBUM_ART_MESH_HANDLE: Handle<Mesh> =
+    Handle::weak_from_u128(0x2c6e9a1f7d3b4e85a0f4c8d2b6e1a973);
+static TRACK_ART_MESH_HANDLE: Handle<Mesh> =
+    Handle::weak_from_u128(0xd81f4b6a2e9c47d3b5a0e7c3f2d8b146);
+
+/// Most cover art to request each time round, the images share the scraper's rate limit with the
+/// pages
+const REQUEST_BATCH: usize = 10;
+
+/// Whether to show the cover art of releases on their nodes
+#[derive(Debug, Resource)]
+pub struct ShowArt(pub bool);
+
+/// Cover art downloaded so far, keyed on its url, which the tracks of an album share
+#[derive(Debug, Default, Resource)]
+struct ArtMaterials {
+    loaded: HashMap<String, Handle<ColorMaterial>>,
+    requested: HashSet<String>,
+}
+
+/// A quad showing a release's cover art over its circle, which is left as the fallback until the
+/// art is downloaded
+#[derive(Component)]
+struct ArtQuad;
+
+/// Marks a release that has its art shown
+#[derive(Component)]
+struct ArtQuadAttached;
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<ArtMaterials>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (
+                request.run_if(on_timer(Duration::from_millis(250))),
+                receive,
+                attach,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn setup(mut meshes: ResMut<Assets<Mesh>>) {
+    // Sized to cover the release and track circles
+    meshes.insert(&ALBUM_ART_MESH_HANDLE, Rectangle::new(20.0, 20.0).into());
+    meshes.insert(&TRACK_ART_MESH_HANDLE, Rectangle::new(12.0, 12.0).into());
+}
+
+/// Ask for the art of releases in view, rather than every release in a big graph
+fn request(
+    show: Res<ShowArt>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    releases: Query<(&ReleaseDetails, &PredictedPosition), Without<ArtQuadAttached>>,
+    mut art: ResMut<ArtMaterials>,
+    scraper: Res<Scraper>,
+) {
+    if !show.0 {
+        return;
+    }
+
+    let views = Vec::from_iter(cameras.iter().filter_map(|(camera, transform)| {
+        let viewport = camera.logical_viewport_rect()?;
+        let min = camera.viewport_to_world_2d(transform, viewport.min).ok()?;
+        let max = camera.viewport_to_world_2d(transform, viewport.max).ok()?;
+        Some(Rect::from_corners(min, max))
+    }));
+
+    let wanted = Vec::from_iter(
+        releases
+            .iter()
+            .filter(|(_, position)| views.iter().any(|view| view.contains(position.0)))
+            .filter_map(|(details, _)| details.art.as_ref())
+            .filter(|url| !art.requested.contains(*url))
+            .take(REQUEST_BATCH)
+            .cloned(),
+    );
+    for url in wanted {
+        scraper.send(Request::Art { url: url.clone() }).unwrap();
+        art.requested.insert(url);
+    }
+}
+
+fn receive(
+    mut events: EventReader<Received>,
+    mut art: ResMut<ArtMaterials>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for Received { response, .. } in events.read() {
+        let Response::Art(url, image) = response else {
+            continue;
+        };
+        let material = materials.add(ColorMaterial {
+            color: Color::WHITE,
+            texture: Some(images.add(image.clone())),
+            ..ColorMaterial::default()
+        });
+        art.loaded.insert(url.clone(), material);
+    }
+}
+
+fn attach(
+    show: Res<ShowArt>,
+    art: Res<ArtMaterials>,
+    releases: Query<(Entity, &ReleaseDetails), (With<Mesh2d>, Without<ArtQuadAttached>)>,
+    mut commands: Commands,
+) {
+    if !show.0 || art.loaded.is_empty() {
+        return;
+    }
+
+    for (entity, details) in &releases {
+        let Some(material) = details.art.as_ref().and_then(|url| art.loaded.get(url)) else {
+            continue;
+        };
+        let mesh = match details.ty {
+            ReleaseType::Album => &ALBUM_ART_MESH_HANDLE,
+            ReleaseType::Track => &TRACK_ART_MESH_HANDLE,
+        };
+        commands
+            .entity(entity)
+            .insert(ArtQuadAttached)
+            .with_children(|release| {
+                release.spawn((
+                    Mesh2d(mesh.clone()),
+                    MeshMaterial2d(material.clone()),
+                    Transform::from_xyz(0.0, 0.0, 0.1),
+                    PickingBehavior::IGNORE,
+                    ArtQuad,
+                ));
+            });
+    }
+}
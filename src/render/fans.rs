@@ -0,0 +1,85 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Changed, With},
+        removal_detection::RemovedComponents,
+        system::{Commands, Query, ResMut},
+    },
+    hierarchy::{BuildChildren, ChildBuild, Children, DespawnRecursiveExt},
+    math::primitives::Annulus,
+    picking::PickingBehavior,
+    render::mesh::{Mesh, Mesh2d},
+    sprite::{ColorMaterial, MeshMaterial2d},
+    transform::components::Transform,
+};
+
+use crate::sample::UnspawnedFans;
+
+static FAN_RING_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x9c1e0f5d6b7a4e3c8f2d1a0b9e8c7d6f);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, update);
+    }
+}
+
+/// A ring around a release whose thickness shows how many of its fans haven't been spawned
+#[derive(Component)]
+struct FanRing;
+
+fn setup(mut materials: ResMut<Assets<ColorMaterial>>) {
+    materials.insert(
+        &FAN_RING_COLOR_MATERIAL_HANDLE,
+        Color::hsla(0., 0.5, 0.7, 0.4).into(),
+    );
+}
+
+fn despawn_rings(
+    release: Entity,
+    children: &Query<&Children>,
+    rings: &Query<(), With<FanRing>>,
+    commands: &mut Commands,
+) {
+    for &child in children.get(release).into_iter().flatten() {
+        if rings.contains(child) {
+            commands.entity(child).despawn_recursive();
+        }
+    }
+}
+
+fn update(
+    releases: Query<(Entity, &UnspawnedFans), Changed<UnspawnedFans>>,
+    mut expanded: RemovedComponents<UnspawnedFans>,
+    children: Query<&Children>,
+    rings: Query<(), With<FanRing>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut commands: Commands,
+) {
+    for release in expanded.read() {
+        despawn_rings(release, &children, &rings, &mut commands);
+    }
+
+    for (release, unspawned) in &releases {
+        despawn_rings(release, &children, &rings, &mut commands);
+
+        // Grows by a pixel per order of magnitude, just outside the release's circle
+        let thickness = (unspawned.users.len() as f32).log10().max(0.5);
+        let mesh = meshes.add(Annulus::new(12.0, 12.0 + thickness));
+        commands.entity(release).with_children(|release| {
+            release.spawn((
+                Mesh2d(mesh),
+                MeshMaterial2d(FAN_RING_COLOR_MATERIAL_HANDLE.clone()),
+                Transform::from_xyz(0.0, 0.0, -0.5),
+                PickingBehavior::IGNORE,
+                FanRing,
+            ));
+        });
+    }
+}
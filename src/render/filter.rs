@@ -0,0 +1,94 @@
+use bevy::{
+    ecs::{
+        change_detection::{DetectChanges, DetectChangesMut},
+        entity::Entity,
+        query::{Added, Changed, Or, With, Without},
+        system::{Query, Res, Resource},
+    },
+    render::view::Visibility,
+};
+
+use std::collections::HashSet;
+
+use crate::{
+    data::{EntityType, ReleaseDetails, ReleaseType, Url},
+    seed::{SeedMask, Seeds},
+    sim::Relationship,
+};
+
+/// Which releases to show, based on their type
+#[derive(Debug, Default, Clone, Resource, serde::Serialize, serde::Deserialize)]
+pub struct ReleaseFilter {
+    /// Hide every track, leaving only albums
+    pub hide_tracks: bool,
+    /// Hide tracks whose album is also in the graph, Bandcamp lists them separately but they're
+    /// usually the same thing
+    pub merge_tracks: bool,
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<ReleaseFilter>();
+        app.add_systems(bevy::app::Update, apply);
+    }
+}
+
+/// Hides filtered out releases, and while showing a single seed (see [`Seeds::only`]) every node
+/// not reached from it
+fn apply(
+    filter: Res<ReleaseFilter>,
+    seeds: Res<Seeds>,
+    changed: Query<
+        (),
+        Or<(
+            Changed<ReleaseDetails>,
+            Added<Relationship>,
+            Changed<SeedMask>,
+        )>,
+    >,
+    releases: Query<(Entity, &Url, &ReleaseDetails)>,
+    mut nodes: Query<(Entity, &mut Visibility, Option<&SeedMask>), With<EntityType>>,
+    mut relationships: Query<(&Relationship, &mut Visibility), Without<EntityType>>,
+) {
+    if !filter.is_changed() && !seeds.is_changed() && changed.is_empty() {
+        return;
+    }
+
+    let urls = HashSet::<&Url>::from_iter(releases.iter().map(|(_, url, _)| url));
+    let mut hidden =
+        HashSet::<Entity>::from_iter(releases.iter().filter_map(|(entity, _, details)| {
+            let merged = filter.merge_tracks
+                && details
+                    .album
+                    .as_ref()
+                    .is_some_and(|album| urls.contains(album));
+            let hide = matches!(details.ty, ReleaseType::Track) && (filter.hide_tracks || merged);
+            hide.then_some(entity)
+        }));
+
+    let visibility = |hide: bool| {
+        if hide {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        }
+    };
+
+    if let Some(seed) = seeds.only {
+        hidden.extend(nodes.iter().filter_map(|(entity, _, mask)| {
+            (!mask.is_some_and(|mask| mask.contains(seed))).then_some(entity)
+        }));
+    }
+
+    for (entity, mut node, _) in &mut nodes {
+        node.set_if_neq(visibility(hidden.contains(&entity)));
+    }
+
+    for (relationship, mut line) in &mut relationships {
+        line.set_if_neq(visibility(
+            hidden.contains(&relationship.from) || hidden.contains(&relationship.to),
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::{Alpha, Color},
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        query::{With, Without},
+        schedule::{common_conditions::resource_changed, Condition, IntoSystemConfigs},
+        system::{Commands, Local, Query, Res, ResMut, Resource},
+    },
+    sprite::{ColorMaterial, MeshMaterial2d},
+    time::common_conditions::on_timer,
+};
+
+use std::{collections::HashMap, time::Duration};
+
+use crate::{
+    data::{ArtistId, Lapsed, RelationKind},
+    sim::{Multiplicity, Relationship},
+};
+
+/// Whether releases, and the links to them, are tinted with a hue picked from their artist, so
+/// discographies interleaved in one cluster can be told apart
+#[derive(Debug, Default, Clone, Copy, Resource)]
+pub struct ArtistHues(pub bool);
+
+/// The tint of a release's artist
+#[derive(Debug, Clone, Component)]
+pub struct ArtistHue {
+    pub node: Handle<ColorMaterial>,
+    link: Handle<ColorMaterial>,
+}
+
+impl ArtistHue {
+    fn new(artist: ArtistId, materials: &mut Assets<ColorMaterial>) -> Self {
+        // Scatter neighboring ids around the color wheel
+        let hash = artist.0.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 40;
+        let hue = hash as f32 / (1 << 24) as f32 * 360.;
+        let color = Color::hsl(hue, 0.85, 0.65);
+        Self {
+            node: materials.add(color),
+            link: materials.add(color.with_alpha(0.6)),
+        }
+    }
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(
+            bevy::app::Update,
+            (assign, tint_links.after(assign))
+                .after(super::init_meshes)
+                .run_if(resource_changed::<ArtistHues>.or(on_timer(Duration::from_secs(1)))),
+        );
+    }
+}
+
+pub(super) fn assign(
+    hues: Res<ArtistHues>,
+    relationships: Query<(&Relationship, &Multiplicity)>,
+    artists: Query<&ArtistId>,
+    tinted: Query<(), With<ArtistHue>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut cache: Local<HashMap<ArtistId, ArtistHue>>,
+    mut commands: Commands,
+) {
+    if !hues.0 {
+        return;
+    }
+
+    for (relationship, multiplicity) in &relationships {
+        if !multiplicity
+            .kinds()
+            .any(|kind| kind == RelationKind::Artist)
+        {
+            continue;
+        }
+        // Artist relationships are always from the artist to the release
+        let Ok(&artist) = artists.get(relationship.from) else {
+            continue;
+        };
+        if tinted.contains(relationship.to) {
+            continue;
+        }
+        let hue = cache
+            .entry(artist)
+            .or_insert_with(|| ArtistHue::new(artist, &mut materials))
+            .clone();
+        commands.entity(relationship.to).insert(hue);
+    }
+}
+
+/// Links to a tinted release take on its tint, lapsed and wishlist links keep their own colors
+fn tint_links(
+    hues: Res<ArtistHues>,
+    mut links: Query<
+        (
+            &Relationship,
+            &Multiplicity,
+            &mut MeshMaterial2d<ColorMaterial>,
+        ),
+        Without<Lapsed>,
+    >,
+    tints: Query<&ArtistHue>,
+) {
+    if !hues.0 && !hues.is_changed() {
+        return;
+    }
+
+    for (relationship, &multiplicity, mut material) in &mut links {
+        if multiplicity == Multiplicity::WISHLIST {
+            continue;
+        }
+        let tint = if hues.0 {
+            tints.get(relationship.to).ok()
+        } else {
+            None
+        };
+        let handle = tint.map_or(&super::LINK_COLOR_MATERIAL_HANDLE, |tint| &tint.link);
+        if material.0 != *handle {
+            material.0 = handle.clone();
+        }
+    }
+}
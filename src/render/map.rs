@@ -0,0 +1,100 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::Color,
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        query::With,
+        system::{Commands, Res, ResMut, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild},
+    math::{Vec2, Vec3},
+    picking::PickingBehavior,
+    render::{mesh::Mesh2d, view::Visibility},
+    sprite::{ColorMaterial, MeshMaterial2d},
+    transform::components::Transform,
+};
+
+use crate::sim::geo::{CountryGroups, MapLayout, SCALE};
+
+static MAP_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x2b8f4e61c7d04a9e9a3c5f17e0d6b483);
+
+/// Degrees between the drawn lines of longitude and latitude
+const GRATICULE: usize = 30;
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, update);
+    }
+}
+
+/// Lines of longitude and latitude drawn behind the graph while fans are laid out on a map
+#[derive(Component)]
+struct MapBackground;
+
+fn setup(mut materials: ResMut<Assets<ColorMaterial>>, mut commands: Commands) {
+    materials.insert(
+        &MAP_COLOR_MATERIAL_HANDLE,
+        Color::srgba(0.4, 0.5, 0.6, 0.3).into(),
+    );
+
+    let line = |from: Vec2, to: Vec2, width: f32| {
+        let delta = to - from;
+        (
+            Mesh2d(super::LINK_MESH_HANDLE.clone()),
+            MeshMaterial2d(MAP_COLOR_MATERIAL_HANDLE.clone()),
+            Transform {
+                translation: from.midpoint(to).extend(0.),
+                scale: Vec3::new(delta.x.abs().max(width), delta.y.abs().max(width), 1.),
+                ..Transform::default()
+            },
+            PickingBehavior::IGNORE,
+        )
+    };
+
+    commands
+        .spawn((
+            MapBackground,
+            Transform::from_translation(Vec3::new(0., 0., -2.)),
+            Visibility::Hidden,
+        ))
+        .with_children(|map| {
+            for longitude in (-180i16..=180).step_by(GRATICULE) {
+                let x = f32::from(longitude) * SCALE;
+                let width = if longitude == 0 { 4. } else { 2. };
+                map.spawn(line(
+                    Vec2::new(x, -90. * SCALE),
+                    Vec2::new(x, 90. * SCALE),
+                    width,
+                ));
+            }
+            for latitude in (-90i16..=90).step_by(GRATICULE) {
+                let y = f32::from(latitude) * SCALE;
+                let width = if latitude == 0 { 4. } else { 2. };
+                map.spawn(line(
+                    Vec2::new(-180. * SCALE, y),
+                    Vec2::new(180. * SCALE, y),
+                    width,
+                ));
+            }
+        });
+}
+
+/// Shown for artists grouped by country as well, so there's something to see where they're headed
+fn update(
+    map: Res<MapLayout>,
+    countries: Res<CountryGroups>,
+    mut background: Single<&mut Visibility, With<MapBackground>>,
+) {
+    if map.is_changed() || countries.is_changed() {
+        **background = if map.enabled || countries.0 {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
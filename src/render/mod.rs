@@ -1,14 +1,15 @@
 use bevy::{
     asset::{Assets, Handle},
-    color::Color,
     diagnostic::Diagnostics,
     ecs::{
         change_detection::{DetectChanges, Ref},
         entity::Entity,
-        query::{With, Without},
+        query::{Added, Changed, Or, With, Without},
+        removal_detection::RemovedComponents,
+        schedule::IntoSystemConfigs,
         system::{Commands, Query, Res, ResMut, Single},
     },
-    math::primitives::{Circle, Rectangle, Triangle2d},
+    math::primitives::{Circle, Rectangle, RegularPolygon, Rhombus, Triangle2d},
     math::{Quat, Vec2, Vec3},
     render::mesh::{Mesh, Mesh2d},
     render::view::Visibility,
@@ -17,15 +18,24 @@ use bevy::{
 };
 
 use crate::{
-    data::{ArtistId, ReleaseId, UserId},
-    sim::{Paused, PredictedPosition, Relationship},
+    data::{ArtistId, LabelId, Lapsed, ReleaseDetails, ReleaseId, ReleaseType, TagId, UserId},
+    interact::Selected,
+    sim::{Multiplicity, Paused, PredictedPosition, Relationship},
     RelationshipParent,
 };
 
 use std::time::Instant;
 
-mod diagnostic;
+pub mod art;
+pub mod diagnostic;
+mod fans;
+pub mod filter;
+pub mod hue;
+mod map;
 mod nearest;
+pub mod palette;
+pub mod rules;
+mod shared;
 
 static ARTIST_MESH_HANDLE: Handle<Mesh> =
     Handle::weak_from_u128(0x3fc46e8efa014a19808ae833b2a2b5bd);
@@ -37,13 +47,27 @@ static RELEASE_MESH_HANDLE: Handle<Mesh> =
 static RELEASE_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
     Handle::weak_from_u128(0x3d3b3dfff39b42a39e7af2d5f1f80ad6);
 
+static TRACK_MESH_HANDLE: Handle<Mesh> = Handle::weak_from_u128(0x5b0a7c2e91d84f6a8e3b4c1d2f6a9e07);
+
 static USER_MESH_HANDLE: Handle<Mesh> = Handle::weak_from_u128(0x48daf856c5c742eeaf609e4ad20bc5fc);
 static USER_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
     Handle::weak_from_u128(0x531591f539514109bd0aa36c2231ded4);
 
+static TAG_MESH_HANDLE: Handle<Mesh> = Handle::weak_from_u128(0x8e41c3a95f2d4b07a6d1e2f3c4b5a697);
+static TAG_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x1f6b9d2e84c34a5f9e07b8c1d2a3f4e5);
+
+static LABEL_MESH_HANDLE: Handle<Mesh> = Handle::weak_from_u128(0xb27e4c91d3a84f06a5e8c2d7f1b9a364);
+static LABEL_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x7c05e3a1f94b4d28b6d2a8e4c1f07b93);
+
 static LINK_MESH_HANDLE: Handle<Mesh> = Handle::weak_from_u128(0x003550e416a740c886de78b65200b0f6);
 static LINK_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
     Handle::weak_from_u128(0x4d9f259f1e2841a0988b14dce5b76f91);
+static LAPSED_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x9c2e71b04a5d4f3e8b6a1d07e53fc248);
+static WISHLIST_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x6a0d3f8e2b1c47d59e84a7c3b2f1d06e);
 
 pub struct Plugin;
 
@@ -55,22 +79,37 @@ impl bevy::app::Plugin for Plugin {
             bevy::app::Update,
             (
                 init_meshes,
+                update_release_meshes,
                 init_node_transforms,
                 update_node_transforms,
                 init_relationship_transforms,
                 update_relationship_transforms,
+                update_lapsed_materials.after(init_meshes),
+                update_wishlist_materials.after(init_meshes),
+                highlight_selected,
             ),
         );
 
+        app.add_plugins(self::art::Plugin);
         app.add_plugins(self::diagnostic::Plugin);
+        app.add_plugins(self::fans::Plugin);
+        app.add_plugins(self::filter::Plugin);
+        app.add_plugins(self::hue::Plugin);
+        app.add_plugins(self::map::Plugin);
         app.add_plugins(self::nearest::Plugin);
+        app.add_plugins(self::palette::Plugin);
+        app.add_plugins(self::rules::Plugin);
+        app.add_plugins(self::shared::Plugin);
     }
 }
 
 pub fn setup_meshes(
+    palette: Res<self::palette::Palette>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
+    let colors = palette.colors();
+
     meshes.insert(
         &ARTIST_MESH_HANDLE,
         Triangle2d::new(
@@ -80,34 +119,34 @@ pub fn setup_meshes(
         )
         .into(),
     );
-    materials.insert(
-        &ARTIST_COLOR_MATERIAL_HANDLE,
-        Color::hsl(270., 0.95, 0.7).into(),
-    );
+    materials.insert(&ARTIST_COLOR_MATERIAL_HANDLE, colors.artist.into());
 
     meshes.insert(&RELEASE_MESH_HANDLE, Circle::new(10.0).into());
-    materials.insert(
-        &RELEASE_COLOR_MATERIAL_HANDLE,
-        Color::hsl(0., 0.95, 0.7).into(),
-    );
+    meshes.insert(&TRACK_MESH_HANDLE, Circle::new(6.0).into());
+    materials.insert(&RELEASE_COLOR_MATERIAL_HANDLE, colors.release.into());
 
     meshes.insert(&USER_MESH_HANDLE, Rectangle::new(10.0, 10.0).into());
-    materials.insert(
-        &USER_COLOR_MATERIAL_HANDLE,
-        Color::hsl(180., 0.95, 0.7).into(),
-    );
+    materials.insert(&USER_COLOR_MATERIAL_HANDLE, colors.user.into());
+
+    meshes.insert(&TAG_MESH_HANDLE, Rhombus::new(16.0, 16.0).into());
+    materials.insert(&TAG_COLOR_MATERIAL_HANDLE, colors.tag.into());
+
+    meshes.insert(&LABEL_MESH_HANDLE, RegularPolygon::new(12.0, 5).into());
+    materials.insert(&LABEL_COLOR_MATERIAL_HANDLE, colors.label.into());
 
     meshes.insert(&LINK_MESH_HANDLE, Rectangle::new(1.0, 1.0).into());
-    materials.insert(
-        &LINK_COLOR_MATERIAL_HANDLE,
-        Color::hsl(90., 0.95, 0.7).into(),
-    );
+    materials.insert(&LINK_COLOR_MATERIAL_HANDLE, colors.link.into());
+    materials.insert(&LAPSED_COLOR_MATERIAL_HANDLE, colors.lapsed().into());
+    materials.insert(&WISHLIST_COLOR_MATERIAL_HANDLE, colors.wishlist().into());
 }
 
 fn init_meshes(
     artists: Query<Entity, (With<ArtistId>, Without<Mesh2d>)>,
     releases: Query<Entity, (With<ReleaseId>, Without<Mesh2d>)>,
     users: Query<Entity, (With<UserId>, Without<Mesh2d>)>,
+    tags: Query<Entity, (With<TagId>, Without<Mesh2d>)>,
+    // Labels are found to be labels after they're spawned as an artist
+    labels: Query<Entity, Added<LabelId>>,
     relationships: Query<Entity, (With<Relationship>, Without<Mesh2d>)>,
     mut commands: Commands,
 ) {
@@ -132,6 +171,20 @@ fn init_meshes(
         ));
     }
 
+    for entity in &tags {
+        commands.entity(entity).insert((
+            Mesh2d(TAG_MESH_HANDLE.clone()),
+            MeshMaterial2d(TAG_COLOR_MATERIAL_HANDLE.clone()),
+        ));
+    }
+
+    for entity in &labels {
+        commands.entity(entity).insert((
+            Mesh2d(LABEL_MESH_HANDLE.clone()),
+            MeshMaterial2d(LABEL_COLOR_MATERIAL_HANDLE.clone()),
+        ));
+    }
+
     for entity in &relationships {
         commands.entity(entity).insert((
             Mesh2d(LINK_MESH_HANDLE.clone()),
@@ -140,6 +193,68 @@ fn init_meshes(
     }
 }
 
+/// Fans that no longer collect a release have their link to it faded out, until they collect it
+/// again
+fn update_lapsed_materials(
+    mut lapsed: Query<
+        &mut MeshMaterial2d<ColorMaterial>,
+        (
+            With<Lapsed>,
+            Or<(Added<Lapsed>, Added<MeshMaterial2d<ColorMaterial>>)>,
+        ),
+    >,
+    mut restored: RemovedComponents<Lapsed>,
+    mut links: Query<&mut MeshMaterial2d<ColorMaterial>, Without<Lapsed>>,
+) {
+    for mut material in &mut lapsed {
+        material.0 = LAPSED_COLOR_MATERIAL_HANDLE.clone();
+    }
+
+    for entity in restored.read() {
+        if let Ok(mut material) = links.get_mut(entity) {
+            material.0 = LINK_COLOR_MATERIAL_HANDLE.clone();
+        }
+    }
+}
+
+/// Links only found on a fan's wishlist are drawn apart from those in their collection, the same
+/// link can later be found in their collection once they buy it
+fn update_wishlist_materials(
+    mut links: Query<
+        (&Multiplicity, &mut MeshMaterial2d<ColorMaterial>),
+        (
+            Without<Lapsed>,
+            Or<(Changed<Multiplicity>, Added<MeshMaterial2d<ColorMaterial>>)>,
+        ),
+    >,
+) {
+    for (&multiplicity, mut material) in &mut links {
+        if multiplicity == Multiplicity::WISHLIST {
+            material.0 = WISHLIST_COLOR_MATERIAL_HANDLE.clone();
+        } else if material.0 == WISHLIST_COLOR_MATERIAL_HANDLE {
+            material.0 = LINK_COLOR_MATERIAL_HANDLE.clone();
+        }
+    }
+}
+
+/// Tracks are drawn smaller than albums, once the release details say which they are
+fn update_release_meshes(
+    mut releases: Query<
+        (&ReleaseDetails, &mut Mesh2d),
+        Or<(Changed<ReleaseDetails>, Added<Mesh2d>)>,
+    >,
+) {
+    for (details, mut mesh) in &mut releases {
+        let handle = match details.ty {
+            ReleaseType::Album => &RELEASE_MESH_HANDLE,
+            ReleaseType::Track => &TRACK_MESH_HANDLE,
+        };
+        if mesh.0 != *handle {
+            mesh.0 = handle.clone();
+        }
+    }
+}
+
 fn init_node_transforms(
     query: Query<(Entity, &PredictedPosition), Without<Transform>>,
     mut commands: Commands,
@@ -171,7 +286,26 @@ fn update_node_transforms(
     });
 }
 
-fn relationship_transform(from: &PredictedPosition, to: &PredictedPosition) -> Transform {
+fn highlight_selected(
+    mut selected: Query<&mut Transform, Added<Selected>>,
+    mut deselected: RemovedComponents<Selected>,
+    mut transforms: Query<&mut Transform, Without<Selected>>,
+) {
+    for mut transform in &mut selected {
+        transform.scale = Vec3::splat(2.0);
+    }
+
+    for entity in deselected.read() {
+        if let Ok(mut transform) = transforms.get_mut(entity) {
+            transform.scale = Vec3::ONE;
+        }
+    }
+}
+
+pub(crate) fn relationship_transform(
+    from: &PredictedPosition,
+    to: &PredictedPosition,
+) -> Transform {
     let from = from.0;
     let to = to.0;
     let delta = to - from;
@@ -3,7 +3,7 @@ use bevy::{
     color::Color,
     ecs::{
         component::Component,
-        query::{QueryData, Without},
+        query::{QueryData, With, Without},
         system::{Commands, Res, ResMut, Single},
     },
     math::primitives::Rectangle,
@@ -18,7 +18,10 @@ use bevy::{
     ui::Val,
 };
 
-use crate::{camera::Cursor, interact::Nearest};
+use crate::{
+    camera::{ActiveCamera, Cursor},
+    interact::Nearest,
+};
 
 pub struct Plugin;
 
@@ -59,7 +62,7 @@ fn update(
     nearest: Option<Res<Nearest>>,
     cursor: Option<Res<Cursor>>,
     menu: Single<crate::ui::menu::Menu, Without<NearestLineMarker>>,
-    camera: Single<(&GlobalTransform, &Camera), ()>,
+    camera: Single<(&GlobalTransform, &Camera), With<ActiveCamera>>,
 ) {
     let Some(nearest) = nearest else { return };
 
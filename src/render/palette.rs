@@ -0,0 +1,140 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::{Alpha, Color},
+    ecs::{
+        schedule::{common_conditions::resource_changed, IntoSystemConfigs},
+        system::{Res, ResMut, Resource},
+    },
+    sprite::ColorMaterial,
+};
+
+/// Colors nodes and links are drawn with, node types also have distinct shapes so they can be told
+/// apart without relying on the colors
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Resource,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Palette {
+    #[default]
+    Default,
+    HighContrast,
+    /// Safe for red-green colorblindness with weak green cones
+    Deuteranopia,
+    /// Safe for red-green colorblindness with weak red cones
+    Protanopia,
+    /// Safe for blue-yellow colorblindness
+    Tritanopia,
+}
+
+pub(super) struct Colors {
+    pub(super) artist: Color,
+    pub(super) release: Color,
+    pub(super) user: Color,
+    pub(super) tag: Color,
+    pub(super) label: Color,
+    pub(super) link: Color,
+}
+
+impl Colors {
+    /// Links of fans that no longer collect a release, faded out
+    pub(super) fn lapsed(&self) -> Color {
+        self.link.with_alpha(0.2)
+    }
+
+    /// Links of fans to releases on their wishlist, in their own color so they stand out from
+    /// what was bought
+    pub(super) fn wishlist(&self) -> Color {
+        self.user.with_alpha(0.5)
+    }
+}
+
+impl Palette {
+    pub(super) fn colors(self) -> Colors {
+        // The colorblind-safe palettes are picked from the Okabe-Ito set
+        match self {
+            Self::Default => Colors {
+                artist: Color::hsl(270., 0.95, 0.7),
+                release: Color::hsl(0., 0.95, 0.7),
+                user: Color::hsl(180., 0.95, 0.7),
+                tag: Color::hsl(45., 0.95, 0.7),
+                label: Color::hsl(315., 0.95, 0.7),
+                link: Color::hsl(90., 0.95, 0.7),
+            },
+            Self::HighContrast => Colors {
+                artist: Color::srgb(1.0, 0.25, 1.0),
+                release: Color::srgb(1.0, 1.0, 0.0),
+                user: Color::srgb(0.0, 1.0, 1.0),
+                tag: Color::srgb(1.0, 0.5, 0.0),
+                label: Color::srgb(0.6, 0.4, 1.0),
+                link: Color::srgb(0.7, 0.7, 0.7),
+            },
+            Self::Deuteranopia => Colors {
+                artist: Color::srgb_u8(0xe6, 0x9f, 0x00),
+                release: Color::srgb_u8(0x56, 0xb4, 0xe9),
+                user: Color::srgb_u8(0xf5, 0xf5, 0xf5),
+                tag: Color::srgb_u8(0xcc, 0x79, 0xa7),
+                label: Color::srgb_u8(0xd5, 0x5e, 0x00),
+                link: Color::srgb_u8(0x99, 0x99, 0x99),
+            },
+            Self::Protanopia => Colors {
+                artist: Color::srgb_u8(0xf0, 0xe4, 0x42),
+                release: Color::srgb_u8(0x56, 0xb4, 0xe9),
+                user: Color::srgb_u8(0xf5, 0xf5, 0xf5),
+                tag: Color::srgb_u8(0xcc, 0x79, 0xa7),
+                label: Color::srgb_u8(0xd5, 0x5e, 0x00),
+                link: Color::srgb_u8(0x99, 0x99, 0x99),
+            },
+            Self::Tritanopia => Colors {
+                artist: Color::srgb_u8(0xcc, 0x79, 0xa7),
+                release: Color::srgb_u8(0xd5, 0x5e, 0x00),
+                user: Color::srgb_u8(0x00, 0x9e, 0x73),
+                tag: Color::srgb_u8(0x56, 0xb4, 0xe9),
+                label: Color::srgb_u8(0xf0, 0xe4, 0x42),
+                link: Color::srgb_u8(0x99, 0x99, 0x99),
+            },
+        }
+    }
+
+    pub fn go_to_next(&mut self) {
+        *self = match *self {
+            Palette::Default => Palette::HighContrast,
+            Palette::HighContrast => Palette::Deuteranopia,
+            Palette::Deuteranopia => Palette::Protanopia,
+            Palette::Protanopia => Palette::Tritanopia,
+            Palette::Tritanopia => Palette::Default,
+        }
+    }
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Update, apply.run_if(resource_changed::<Palette>));
+    }
+}
+
+fn apply(palette: Res<Palette>, mut materials: ResMut<Assets<ColorMaterial>>) {
+    let colors = palette.colors();
+    let mut set = |handle: &Handle<ColorMaterial>, color| {
+        if let Some(material) = materials.get_mut(handle) {
+            material.color = color;
+        }
+    };
+    set(&super::ARTIST_COLOR_MATERIAL_HANDLE, colors.artist);
+    set(&super::RELEASE_COLOR_MATERIAL_HANDLE, colors.release);
+    set(&super::USER_COLOR_MATERIAL_HANDLE, colors.user);
+    set(&super::TAG_COLOR_MATERIAL_HANDLE, colors.tag);
+    set(&super::LABEL_COLOR_MATERIAL_HANDLE, colors.label);
+    set(&super::LINK_COLOR_MATERIAL_HANDLE, colors.link);
+    set(&super::LAPSED_COLOR_MATERIAL_HANDLE, colors.lapsed());
+    set(&super::WISHLIST_COLOR_MATERIAL_HANDLE, colors.wishlist());
+}
@@ -0,0 +1,182 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::{Color, ColorToPacked, Srgba},
+    ecs::{
+        change_detection::DetectChanges,
+        schedule::{common_conditions::resource_changed, Condition, IntoSystemConfigs},
+        system::{Local, Query, Res, ResMut, Resource},
+    },
+    sprite::{ColorMaterial, MeshMaterial2d},
+    time::common_conditions::on_timer,
+};
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::hue::{ArtistHue, ArtistHues};
+use crate::{
+    analysis::query::{Query as NodeQuery, Subject},
+    data::EntityType,
+};
+
+/// A query and the color to draw the nodes it matches, written like
+/// `type:release year:<2010 -> blue` or `tag:ambient -> #30c080`
+#[derive(Debug, Clone)]
+struct Rule {
+    source: String,
+    query: NodeQuery,
+    color: Color,
+}
+
+impl std::str::FromStr for Rule {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, String> {
+        let source = source.trim();
+        let (condition, color) = source
+            .split_once("->")
+            .ok_or_else(|| format!("{source:?} is missing `-> color`"))?;
+
+        Ok(Self {
+            source: source.to_owned(),
+            query: condition.parse::<NodeQuery>()?,
+            color: parse_color(color.trim())
+                .ok_or_else(|| format!("{source:?} has unknown color {:?}", color.trim()))?,
+        })
+    }
+}
+
+fn parse_color(color: &str) -> Option<Color> {
+    Some(match color {
+        "red" => Color::srgb(0.95, 0.3, 0.3),
+        "orange" => Color::srgb(0.95, 0.6, 0.2),
+        "yellow" => Color::srgb(0.95, 0.9, 0.3),
+        "green" => Color::srgb(0.3, 0.85, 0.4),
+        "cyan" => Color::srgb(0.3, 0.9, 0.9),
+        "blue" => Color::srgb(0.35, 0.5, 0.95),
+        "purple" => Color::srgb(0.7, 0.4, 0.95),
+        "pink" => Color::srgb(0.95, 0.5, 0.8),
+        "white" => Color::WHITE,
+        "gray" | "grey" => Color::srgb(0.5, 0.5, 0.5),
+        hex => Srgba::hex(hex).ok()?.into(),
+    })
+}
+
+/// Rules for coloring nodes, the first that matches a node picks its color, persisted in the config
+/// directory
+#[derive(Debug, Resource)]
+pub struct ColorRules {
+    path: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl ColorRules {
+    #[culpa::try_fn]
+    pub fn load(config_dir: &Path) -> eyre::Result<Self> {
+        let path = config_dir.join("color-rules.json");
+        let sources: Vec<String> = match std::fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => Err(err)?,
+        };
+        let rules = sources
+            .iter()
+            .map(|source| source.parse())
+            .collect::<Result<_, String>>()
+            .map_err(|error| eyre::eyre!("invalid color rule in {}: {error}", path.display()))?;
+        Self { path, rules }
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|rule| rule.source.as_str())
+    }
+
+    /// The rules as they're edited, separated by semicolons
+    pub fn text(&self) -> String {
+        Vec::from_iter(self.sources()).join("; ")
+    }
+
+    /// Replace the rules, leaving them unchanged if any are invalid
+    pub fn set<'a>(&mut self, sources: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+        self.rules = sources
+            .into_iter()
+            .filter(|source| !source.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        self.save();
+        Ok(())
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.try_save() {
+            tracing::error!(?error, "failed saving color rules");
+        }
+    }
+
+    #[culpa::try_fn]
+    fn try_save(&self) -> eyre::Result<()> {
+        let sources = Vec::from_iter(self.rules.iter().map(|rule| &rule.source));
+        std::fs::write(&self.path, serde_json::to_string_pretty(&sources)?)?;
+    }
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(
+            bevy::app::Update,
+            apply
+                .after(super::init_meshes)
+                .after(super::hue::assign)
+                .run_if(
+                    resource_changed::<ColorRules>
+                        .or(resource_changed::<ArtistHues>)
+                        .or(on_timer(Duration::from_secs(1))),
+                ),
+        );
+    }
+}
+
+fn apply(
+    rules: Res<ColorRules>,
+    hues: Res<ArtistHues>,
+    mut nodes: Query<(
+        Subject,
+        Option<&ArtistHue>,
+        &mut MeshMaterial2d<ColorMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut cache: Local<HashMap<[u8; 4], Handle<ColorMaterial>>>,
+) {
+    // Without any rules or tints everything already has its default material, unless they were
+    // just removed
+    if rules.rules.is_empty() && !rules.is_changed() && !hues.0 && !hues.is_changed() {
+        return;
+    }
+
+    for (subject, hue, mut material) in &mut nodes {
+        let handle = match rules.rules.iter().find(|rule| rule.query.matches(&subject)) {
+            Some(rule) => cache
+                .entry(rule.color.to_srgba().to_u8_array())
+                .or_insert_with(|| materials.add(rule.color))
+                .clone(),
+            None => match hue.filter(|_| hues.0) {
+                Some(hue) => hue.node.clone(),
+                None => match subject.ty {
+                    EntityType::Artist => super::ARTIST_COLOR_MATERIAL_HANDLE.clone(),
+                    EntityType::Release => super::RELEASE_COLOR_MATERIAL_HANDLE.clone(),
+                    EntityType::User => super::USER_COLOR_MATERIAL_HANDLE.clone(),
+                    EntityType::Tag => super::TAG_COLOR_MATERIAL_HANDLE.clone(),
+                    EntityType::Label => super::LABEL_COLOR_MATERIAL_HANDLE.clone(),
+                },
+            },
+        };
+        if material.0 != handle {
+            material.0 = handle;
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::Color,
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        query::{Changed, With},
+        system::{Commands, Query, Res, ResMut},
+    },
+    hierarchy::{BuildChildren, ChildBuild, Children, DespawnRecursiveExt},
+    math::primitives::Annulus,
+    picking::PickingBehavior,
+    render::mesh::{Mesh, Mesh2d},
+    sprite::{ColorMaterial, MeshMaterial2d},
+    transform::components::Transform,
+};
+
+use crate::seed::{SeedMask, Seeds};
+
+static SHARED_RING_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x5e3a7c1d9b2f4860a4c8e2f6b1d3a597);
+
+static SHARED_RING_MESH_HANDLE: Handle<Mesh> =
+    Handle::weak_from_u128(0x0d4f8a2e6c1b4b97b35e9a7c2f8d1e64);
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, update);
+    }
+}
+
+/// A ring around a node reached from more than one seed, shown while highlighting shared nodes
+#[derive(Component)]
+struct SharedRing;
+
+fn setup(mut materials: ResMut<Assets<ColorMaterial>>, mut meshes: ResMut<Assets<Mesh>>) {
+    materials.insert(
+        &SHARED_RING_COLOR_MATERIAL_HANDLE,
+        Color::hsla(50., 0.9, 0.6, 0.8).into(),
+    );
+    meshes.insert(&SHARED_RING_MESH_HANDLE, Annulus::new(14.0, 17.0).into());
+}
+
+fn update(
+    seeds: Res<Seeds>,
+    all: Query<(Entity, &SeedMask)>,
+    changed: Query<(Entity, &SeedMask), Changed<SeedMask>>,
+    children: Query<&Children>,
+    rings: Query<(), With<SharedRing>>,
+    mut commands: Commands,
+) {
+    let nodes = if seeds.is_changed() {
+        all.iter().collect::<Vec<_>>()
+    } else {
+        changed.iter().collect()
+    };
+
+    for (node, mask) in nodes {
+        let ringed = children
+            .get(node)
+            .into_iter()
+            .flatten()
+            .copied()
+            .find(|&child| rings.contains(child));
+        match (ringed, seeds.highlight_shared && mask.is_shared()) {
+            (Some(ring), false) => commands.entity(ring).despawn_recursive(),
+            (None, true) => {
+                commands.entity(node).with_children(|node| {
+                    node.spawn((
+                        Mesh2d(SHARED_RING_MESH_HANDLE.clone()),
+                        MeshMaterial2d(SHARED_RING_COLOR_MATERIAL_HANDLE.clone()),
+                        Transform::from_xyz(0.0, 0.0, -0.5),
+                        PickingBehavior::IGNORE,
+                        SharedRing,
+                    ));
+                });
+            }
+            _ => {}
+        }
+    }
+}
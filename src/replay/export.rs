@@ -0,0 +1,110 @@
+use bevy::{
+    ecs::{
+        event::EventWriter,
+        observer::Trigger,
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, Res, ResMut, Resource},
+    },
+    picking::{
+        events::{Click, Pointer},
+        pointer::PointerButton,
+    },
+    ui::widget::Button,
+};
+
+use std::sync::{Arc, Mutex};
+
+use super::{Control, Playback, Track, FRAME_TIME};
+use crate::{
+    capture::{CaptureFormat, Encoder},
+    ui::notify::Notify,
+};
+
+/// A replay being rendered into a video, a frame at a time
+#[derive(Resource)]
+struct Exporting {
+    encoder: Arc<Mutex<Encoder>>,
+    /// Whether a screenshot has been requested for the current frame yet
+    requested: bool,
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Update, export.before(super::show));
+        app.add_observer(start);
+    }
+}
+
+fn start(
+    trigger: Trigger<Pointer<Click>>,
+    buttons: Query<&Control, With<Button>>,
+    exporting: Option<Res<Exporting>>,
+    mut playback: ResMut<Playback>,
+    mut commands: Commands,
+) {
+    if !matches!(buttons.get(trigger.entity()), Ok(Control::Export))
+        || trigger.event.button != PointerButton::Primary
+        || exporting.is_some()
+    {
+        return;
+    }
+
+    playback.playing = false;
+    playback.frame = 0;
+    commands.insert_resource(Exporting {
+        encoder: Arc::new(Mutex::new(Encoder::new(
+            "replay",
+            CaptureFormat::Mp4,
+            1. / FRAME_TIME.as_secs_f32(),
+            None,
+        ))),
+        requested: false,
+    });
+}
+
+fn export(
+    exporting: Option<ResMut<Exporting>>,
+    track: Option<Res<Track>>,
+    mut playback: ResMut<Playback>,
+    mut notify: EventWriter<Notify>,
+    mut commands: Commands,
+) {
+    let (Some(mut exporting), Some(track)) = (exporting, track) else {
+        return;
+    };
+
+    let mut encoder = exporting.encoder.lock().unwrap();
+    if encoder.failed {
+        let _ = encoder.finish();
+        notify.send(Notify("failed exporting replay".to_owned()));
+        drop(encoder);
+        commands.remove_resource::<Exporting>();
+        return;
+    }
+
+    if exporting.requested && !encoder.captured {
+        return;
+    }
+
+    if std::mem::take(&mut encoder.captured) {
+        if playback.frame + 1 >= track.len() {
+            let message = match encoder.finish() {
+                Ok(()) => format!("exported {} frames to {}", track.len(), encoder.path()),
+                Err(error) => format!("failed exporting replay: {error}"),
+            };
+            notify.send(Notify(message));
+            drop(encoder);
+            commands.remove_resource::<Exporting>();
+            return;
+        }
+        playback.frame += 1;
+    }
+    drop(encoder);
+
+    // The screenshot is taken once the frame set above has been rendered
+    Encoder::request(&exporting.encoder, &mut commands);
+    exporting.requested = true;
+}
@@ -0,0 +1,481 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        event::{EventReader, EventWriter},
+        observer::Trigger,
+        query::{With, Without},
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild},
+    input::keyboard::{Key, KeyboardInput},
+    math::Vec2,
+    picking::{
+        events::{Click, Pointer},
+        pointer::PointerButton,
+        PickingBehavior,
+    },
+    render::view::Visibility,
+    text::TextFont,
+    time::{Real, Time},
+    transform::components::Transform,
+    ui::widget::{Button, Label, Text},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, JustifyContent, Node, PositionType,
+        UiRect, Val,
+    },
+};
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    data::Url,
+    sim::{Paused, PredictedPosition, Relationship},
+    ui::{editor::Editor, notify::Notify},
+};
+
+#[cfg(feature = "ffmpeg")]
+mod export;
+
+/// How long each recorded frame is shown for while playing back
+const FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// One line of a track file, urls are only written the first time they're seen and referred to by
+/// their index after that
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Line {
+    seconds: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    urls: Vec<String>,
+    /// Index of the url, and the position rounded to whole world units
+    positions: Vec<(u32, i32, i32)>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    seconds: f32,
+    positions: Vec<(u32, Vec2)>,
+}
+
+#[derive(Debug)]
+struct Recording {
+    file: BufWriter<File>,
+    interval: Duration,
+    next: Duration,
+}
+
+/// Positions of every node at intervals through a session, either being recorded to a file in the
+/// data directory with `--record-track` or loaded from one with `--replay`
+#[derive(Debug, Resource)]
+pub struct Track {
+    urls: Vec<String>,
+    indices: HashMap<String, u32>,
+    frames: Vec<Frame>,
+    recording: Option<Recording>,
+}
+
+impl Track {
+    #[culpa::try_fn]
+    pub fn create(data_dir: &Path, interval: Duration) -> eyre::Result<Self> {
+        let dir = data_dir.join("tracks");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "{}.jsonl",
+            jiff::Timestamp::now().strftime("%Y%m%dT%H%M%SZ")
+        ));
+        tracing::info!(path = %path.display(), "recording track");
+        Self {
+            urls: Vec::new(),
+            indices: HashMap::new(),
+            frames: Vec::new(),
+            recording: Some(Recording {
+                file: BufWriter::new(File::create(path)?),
+                interval,
+                next: Duration::ZERO,
+            }),
+        }
+    }
+
+    #[culpa::try_fn]
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let mut track = Self {
+            urls: Vec::new(),
+            indices: HashMap::new(),
+            frames: Vec::new(),
+            recording: None,
+        };
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line: Line = serde_json::from_str(&line?)?;
+            for url in line.urls {
+                track.index(url);
+            }
+            track.frames.push(Frame {
+                seconds: line.seconds,
+                positions: Vec::from_iter(
+                    line.positions
+                        .into_iter()
+                        .map(|(i, x, y)| (i, Vec2::new(x as f32, y as f32))),
+                ),
+            });
+        }
+        track
+    }
+
+    /// The index of a url, and whether it was newly added
+    fn index(&mut self, url: String) -> (u32, bool) {
+        if let Some(&i) = self.indices.get(&url) {
+            return (i, false);
+        }
+        let i = self.urls.len() as u32;
+        self.indices.insert(url.clone(), i);
+        self.urls.push(url);
+        (i, true)
+    }
+
+    fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[culpa::try_fn]
+    fn record<'a>(
+        &mut self,
+        seconds: f32,
+        nodes: impl Iterator<Item = (&'a str, Vec2)>,
+    ) -> eyre::Result<()> {
+        let mut line = Line {
+            seconds,
+            urls: Vec::new(),
+            positions: Vec::new(),
+        };
+        let mut positions = Vec::new();
+        for (url, position) in nodes {
+            let (i, new) = self.index(url.to_owned());
+            if new {
+                line.urls.push(url.to_owned());
+            }
+            line.positions
+                .push((i, position.x.round() as i32, position.y.round() as i32));
+            positions.push((i, position));
+        }
+        self.frames.push(Frame { seconds, positions });
+
+        if let Some(recording) = &mut self.recording {
+            serde_json::to_writer(&mut recording.file, &line)?;
+            writeln!(recording.file)?;
+            recording.file.flush()?;
+        }
+    }
+}
+
+/// Replaying a [`Track`] in place of the live simulation, toggled with `z`
+#[derive(Debug, Default, Resource)]
+pub struct Playback {
+    active: bool,
+    playing: bool,
+    frame: usize,
+    /// Whether the simulation was paused before playback started, to restore afterwards
+    was_paused: bool,
+    /// Time since the current frame was shown while playing
+    shown: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+enum Control {
+    Back,
+    Play,
+    Forward,
+    #[cfg(feature = "ffmpeg")]
+    Export,
+}
+
+impl Control {
+    const ALL: &[Self] = &[
+        Self::Back,
+        Self::Play,
+        Self::Forward,
+        #[cfg(feature = "ffmpeg")]
+        Self::Export,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Back => "<<",
+            Self::Play => "play/pause",
+            Self::Forward => ">>",
+            #[cfg(feature = "ffmpeg")]
+            Self::Export => "export mp4",
+        }
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<Playback>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (toggle, advance, show.after(toggle).after(advance), update),
+        );
+        app.add_systems(bevy::app::Last, record.run_if(resource_exists::<Track>));
+        app.add_observer(button_click);
+        #[cfg(feature = "ffmpeg")]
+        app.add_plugins(self::export::Plugin);
+    }
+}
+
+#[derive(Default, Component)]
+struct PlaybackUi;
+
+/// The frame counter in the playback panel
+#[derive(Default, Component)]
+struct PlaybackLabel;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::Start,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                left: Val::Percent(40.),
+                bottom: Val::Px(0.),
+                ..Node::default()
+            },
+            BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+            PickingBehavior::IGNORE,
+            PlaybackUi,
+            Visibility::Hidden,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::default(),
+                TextFont::default(),
+                Label,
+                PickingBehavior::IGNORE,
+                PlaybackLabel,
+            ));
+            for &control in Control::ALL {
+                panel
+                    .spawn((
+                        Node {
+                            padding: UiRect::all(Val::Px(6.)),
+                            ..Node::default()
+                        },
+                        Button,
+                        BackgroundColor(Color::NONE),
+                        control,
+                    ))
+                    .with_child((
+                        Text::new(control.label()),
+                        TextFont::default(),
+                        Label,
+                        PickingBehavior::IGNORE,
+                    ));
+            }
+        });
+}
+
+fn toggle(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    track: Option<Res<Track>>,
+    mut playback: ResMut<Playback>,
+    mut paused: ResMut<Paused>,
+    mut notify: EventWriter<Notify>,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+    let mut pressed = false;
+    for event in events.read() {
+        pressed |= event.state.is_pressed() && event.logical_key == Key::Character("z".into());
+    }
+    if !pressed {
+        return;
+    }
+
+    if playback.active {
+        playback.active = false;
+        playback.playing = false;
+        paused.0 = playback.was_paused;
+        return;
+    }
+
+    let Some(track) = track.filter(|track| track.len() > 0) else {
+        notify.send(Notify(
+            "nothing to replay, record a track with --record-track".to_owned(),
+        ));
+        return;
+    };
+
+    playback.active = true;
+    playback.was_paused = paused.0;
+    playback.frame = track.len() - 1;
+    paused.0 = true;
+}
+
+fn advance(time: Res<Time<Real>>, track: Option<Res<Track>>, mut playback: ResMut<Playback>) {
+    let Some(track) = track else { return };
+    if !playback.active || !playback.playing {
+        return;
+    }
+
+    playback.shown += time.delta();
+    if playback.shown >= FRAME_TIME {
+        playback.shown = Duration::ZERO;
+        if playback.frame + 1 < track.len() {
+            playback.frame += 1;
+        } else {
+            playback.playing = false;
+        }
+    }
+}
+
+/// Moves the nodes and links to where they were in the current frame, or back to where they are
+/// once playback stops
+fn show(
+    playback: Res<Playback>,
+    track: Option<Res<Track>>,
+    mut nodes: Query<(Entity, &Url, &PredictedPosition, &mut Transform)>,
+    mut links: Query<(&Relationship, &mut Transform), Without<PredictedPosition>>,
+) {
+    if !playback.is_changed() {
+        return;
+    }
+
+    let mut positions = HashMap::<Entity, Vec2>::new();
+    let frame = track
+        .as_deref()
+        .filter(|_| playback.active)
+        .and_then(|track| Some((track, track.frames.get(playback.frame)?)));
+    if let Some((track, frame)) = frame {
+        let recorded = HashMap::<u32, Vec2>::from_iter(frame.positions.iter().copied());
+        for (entity, url, predicted, mut transform) in &mut nodes {
+            let position = track
+                .indices
+                .get(&url.0)
+                .and_then(|i| recorded.get(i))
+                .copied()
+                .unwrap_or(predicted.0);
+            transform.translation = position.extend(0.0);
+            positions.insert(entity, position);
+        }
+    } else {
+        for (entity, _, predicted, mut transform) in &mut nodes {
+            transform.translation = predicted.0.extend(0.0);
+            positions.insert(entity, predicted.0);
+        }
+    }
+
+    for (rel, mut transform) in &mut links {
+        let (Some(&from), Some(&to)) = (positions.get(&rel.from), positions.get(&rel.to)) else {
+            continue;
+        };
+        *transform =
+            crate::render::relationship_transform(&PredictedPosition(from), &PredictedPosition(to));
+    }
+}
+
+fn update(
+    playback: Res<Playback>,
+    track: Option<Res<Track>>,
+    mut ui: Single<&mut Visibility, With<PlaybackUi>>,
+    mut label: Single<&mut Text, With<PlaybackLabel>>,
+) {
+    let visibility = if playback.active {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    if **ui != visibility {
+        **ui = visibility;
+    }
+
+    let Some(track) = track.filter(|_| playback.active) else {
+        return;
+    };
+    let seconds = track
+        .frames
+        .get(playback.frame)
+        .map_or(0., |frame| frame.seconds);
+    let new = format!(
+        "frame {} / {} ({seconds:.0}s)",
+        playback.frame + 1,
+        track.len(),
+    );
+    if label.0 != new {
+        label.0 = new;
+    }
+}
+
+fn button_click(
+    trigger: Trigger<Pointer<Click>>,
+    buttons: Query<&Control, With<Button>>,
+    track: Option<Res<Track>>,
+    mut playback: ResMut<Playback>,
+) {
+    let Ok(&control) = buttons.get(trigger.entity()) else {
+        return;
+    };
+
+    if trigger.event.button != PointerButton::Primary {
+        return;
+    }
+
+    let last = track.map_or(0, |track| track.len().saturating_sub(1));
+    match control {
+        Control::Back => {
+            playback.frame = playback.frame.saturating_sub(1);
+        }
+        Control::Play => {
+            // Playing from the end starts again from the beginning
+            if !playback.playing && playback.frame == last {
+                playback.frame = 0;
+            }
+            playback.playing ^= true;
+        }
+        Control::Forward => {
+            playback.frame = (playback.frame + 1).min(last);
+        }
+        // Handled by the exporter's own observer
+        #[cfg(feature = "ffmpeg")]
+        Control::Export => {}
+    }
+}
+
+fn record(
+    mut track: ResMut<Track>,
+    playback: Res<Playback>,
+    time: Res<Time<Real>>,
+    nodes: Query<(&Url, &PredictedPosition)>,
+) {
+    let now = time.elapsed();
+    let Some(recording) = &mut track.recording else {
+        return;
+    };
+    if playback.active || now < recording.next {
+        return;
+    }
+    recording.next = now + recording.interval;
+
+    let nodes = nodes
+        .iter()
+        .map(|(url, position)| (url.0.as_str(), position.0));
+    if let Err(error) = track.record(now.as_secs_f32(), nodes) {
+        tracing::error!(?error, "failed recording track");
+    }
+}
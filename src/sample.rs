@@ -0,0 +1,201 @@
+use bevy::ecs::{
+    component::Component,
+    entity::{Entities, Entity},
+    event::{Event, EventReader, EventWriter},
+    query::{Changed, With},
+    system::{Commands, Query, Res, ResMut, Resource, Single},
+};
+
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    background::{JobId, Request, Scraper},
+    data::{DiscoveredVia, RelationKind, ReleaseId, Scrape, User},
+    graph::GraphQuery,
+    policy::ScrapePolicy,
+    sim::{Multiplicity, Partitions, PredictedPosition},
+    KnownEntities, RelationshipParent,
+};
+
+/// Limits how many new fans of each release are spawned, so releases with huge fan lists keep the
+/// graph representative but tractable
+#[derive(Debug, Resource)]
+pub struct FanSampling {
+    /// How many new fans to spawn per release, or `None` to spawn all of them
+    pub limit: Option<usize>,
+    /// Don't spawn any new fans until a release is deeply scraped
+    pub defer: bool,
+    /// New fans of releases still being scraped, sampled once the release details arrive after
+    /// the last of them
+    pending: HashMap<ReleaseId, Vec<User>>,
+}
+
+/// Fans of a release that were left out of its sample
+#[derive(Debug, Component)]
+pub struct UnspawnedFans {
+    /// The scrape that found them
+    pub request: Request,
+    pub users: Vec<User>,
+    /// Whether these are all the new fans, held back until the release is deeply scraped
+    pub deferred: bool,
+}
+
+/// Sent to spawn all of a release's [`UnspawnedFans`]
+#[derive(Debug, Event)]
+pub struct ExpandFans {
+    pub release: Entity,
+    /// Also queue scrapes of the fans, as a deep scrape of the release would have
+    pub scrape: bool,
+}
+
+impl FanSampling {
+    pub fn new(limit: Option<usize>, defer: bool) -> Self {
+        Self {
+            limit,
+            defer,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Whether any new fans are being held back
+    pub fn holding(&self) -> bool {
+        self.limit.is_some() || self.defer
+    }
+
+    /// Hold back any fans that aren't already in the graph until they can be sampled, fans that
+    /// are already known cost nothing to relate and keep the graph connected so are returned to
+    /// relate straight away
+    pub fn hold(
+        &mut self,
+        release: ReleaseId,
+        users: Vec<User>,
+        known: &KnownEntities,
+    ) -> Vec<User> {
+        if !self.holding() {
+            return users;
+        }
+        let (existing, new): (Vec<_>, Vec<_>) = users
+            .into_iter()
+            .partition(|user| known.users.contains_key(&user.id));
+        self.pending.entry(release).or_default().extend(new);
+        existing
+    }
+
+    /// Pick a random sample of the fans held back for a release, returning it along with the rest,
+    /// a release that isn't deeply scraped gets an empty sample while deferring
+    pub fn sample(
+        &mut self,
+        release: ReleaseId,
+        deep: bool,
+        request: &Request,
+    ) -> (Vec<User>, Option<UnspawnedFans>) {
+        let mut users = self.pending.remove(&release).unwrap_or_default();
+        let mut seen = HashSet::new();
+        users.retain(|user| seen.insert(user.id));
+        let deferred = self.defer && !deep;
+        let amount = if deferred {
+            0
+        } else {
+            self.limit.unwrap_or(usize::MAX).min(users.len())
+        };
+        let (sample, rest) = users.partial_shuffle(&mut rand::rng(), amount);
+        let unspawned = (!rest.is_empty()).then(|| UnspawnedFans {
+            request: request.clone(),
+            users: rest.to_vec(),
+            deferred,
+        });
+        (sample.to_vec(), unspawned)
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<ExpandFans>();
+        app.add_event::<Expanded>();
+        app.add_systems(
+            bevy::app::Update,
+            (expand_deep, expand, scrape_expanded).chain(),
+        );
+    }
+}
+
+/// Fans deferred until their release was deeply scraped are spawned once it is
+fn expand_deep(
+    releases: Query<(Entity, &Scrape, &UnspawnedFans), Changed<Scrape>>,
+    mut expand: EventWriter<ExpandFans>,
+) {
+    for (release, scrape, unspawned) in &releases {
+        if unspawned.deferred && *scrape >= Scrape::Deep {
+            expand.send(ExpandFans {
+                release,
+                scrape: true,
+            });
+        }
+    }
+}
+
+/// A release's fans were spawned by an [`ExpandFans`] that also scrapes them, once they're in the
+/// graph the release's deep scrape is carried on out to them
+#[derive(Debug, Event)]
+struct Expanded {
+    release: Entity,
+    job: JobId,
+}
+
+fn expand(
+    mut events: EventReader<ExpandFans>,
+    mut known: ResMut<KnownEntities>,
+    releases: Query<(&UnspawnedFans, &PredictedPosition)>,
+    mut multiplicities: Query<&mut Multiplicity>,
+    scraper: Res<Scraper>,
+    partitions: Res<Partitions>,
+    entities: &Entities,
+    relationship_parent: Single<Entity, With<RelationshipParent>>,
+    mut expanded: EventWriter<Expanded>,
+    mut commands: Commands,
+) {
+    for &ExpandFans { release, scrape } in events.read() {
+        let Ok((unspawned, position)) = releases.get(release) else {
+            continue;
+        };
+        known.add_fans(
+            release,
+            position.0,
+            &unspawned.users,
+            RelationKind::Fan,
+            &DiscoveredVia::new(&unspawned.request, Some(release)),
+            *relationship_parent,
+            entities,
+            &partitions,
+            &mut multiplicities,
+            &mut commands,
+        );
+        commands.entity(release).remove::<UnspawnedFans>();
+
+        if scrape {
+            let job = scraper.job_of(&unspawned.request).unwrap_or_else(|| {
+                scraper.start_job(format!("expand fans of {}", unspawned.request.url()))
+            });
+            expanded.send(Expanded { release, job });
+        }
+    }
+}
+
+/// Propagate the deep scrape of releases whose fans were just expanded, the same as it would have
+/// if they had been spawned when the release was scraped
+fn scrape_expanded(
+    mut events: EventReader<Expanded>,
+    policy: Res<ScrapePolicy>,
+    scraper: Res<Scraper>,
+    mut graph: GraphQuery,
+) {
+    for &Expanded { release, job } in events.read() {
+        let Some(depth) = graph.scrape_level(release).and_then(ScrapePolicy::depth) else {
+            continue;
+        };
+        policy.propagate(release, depth, job, &mut graph, &scraper);
+    }
+}
@@ -0,0 +1,281 @@
+use bevy::ecs::{
+    schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+    system::{Commands, Res, ResMut, Resource},
+};
+
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    background::Request,
+    graph::{Graph, GraphQuery, Node},
+    interact::Selected,
+};
+
+/// Name of the script function called whenever a node finishes scraping
+const ON_SCRAPED: &str = "on_scraped";
+
+fn to_map(node: &Node) -> Map {
+    let mut map = Map::new();
+    map.insert(
+        "type".into(),
+        format!("{:?}", node.ty).to_lowercase().into(),
+    );
+    map.insert("url".into(), node.url.clone().into());
+    map.insert(
+        "name".into(),
+        node.name.clone().map_or(Dynamic::UNIT, Dynamic::from),
+    );
+    map.insert(
+        "scrape".into(),
+        node.scrape.map_or(Dynamic::UNIT, |scrape| {
+            format!("{scrape:?}").to_lowercase().into()
+        }),
+    );
+    map.insert("degree".into(), (node.neighbors.len() as i64).into());
+    map
+}
+
+#[derive(Debug)]
+enum Action {
+    Scrape(Request),
+    Select(String),
+    Deselect(String),
+}
+
+#[derive(Debug, Default)]
+struct Host {
+    graph: Graph,
+    actions: Vec<Action>,
+}
+
+/// A user script driving the session, see `--script`
+#[derive(Resource)]
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    host: Arc<Mutex<Host>>,
+}
+
+impl Script {
+    #[culpa::try_fn]
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let host = Arc::new(Mutex::new(Host::default()));
+        let engine = Self::engine(&host);
+        let ast = engine
+            .compile_file(path.to_owned())
+            .map_err(|e| eyre::eyre!("failed to compile {}: {e}", path.display()))?;
+        Self {
+            engine,
+            ast,
+            scope: Scope::new(),
+            host,
+        }
+    }
+
+    fn engine(host: &Arc<Mutex<Host>>) -> Engine {
+        let mut engine = Engine::new();
+
+        engine.on_print(|message| tracing::info!("script: {message}"));
+
+        let enqueue = |host: &Arc<Mutex<Host>>, request: fn(String) -> Request| {
+            let host = host.clone();
+            move |url: &str| {
+                let mut host = host.lock().unwrap();
+                host.actions.push(Action::Scrape(request(url.to_owned())));
+            }
+        };
+        engine.register_fn(
+            "scrape_artist",
+            enqueue(host, |url| Request::Artist { url }),
+        );
+        engine.register_fn(
+            "scrape_release",
+            enqueue(host, |url| Request::Release { url }),
+        );
+        engine.register_fn("scrape_user", enqueue(host, |url| Request::User { url }));
+
+        let host_ = host.clone();
+        engine.register_fn("nodes", move || -> Array {
+            let host = host_.lock().unwrap();
+            host.graph
+                .nodes
+                .iter()
+                .map(|node| to_map(node).into())
+                .collect()
+        });
+
+        let host_ = host.clone();
+        engine.register_fn("node", move |url: &str| -> Dynamic {
+            let host = host_.lock().unwrap();
+            host.graph
+                .get(url)
+                .map_or(Dynamic::UNIT, |node| to_map(node).into())
+        });
+
+        let host_ = host.clone();
+        engine.register_fn("neighbors", move |url: &str| -> Array {
+            let host = host_.lock().unwrap();
+            let Some(node) = host.graph.get(url) else {
+                return Array::new();
+            };
+            node.neighbors
+                .iter()
+                .map(|&i| to_map(&host.graph.nodes[i]).into())
+                .collect()
+        });
+
+        let host_ = host.clone();
+        engine.register_fn("select", move |url: &str| {
+            let mut host = host_.lock().unwrap();
+            host.actions.push(Action::Select(url.to_owned()));
+        });
+
+        let host_ = host.clone();
+        engine.register_fn("deselect", move |url: &str| {
+            let mut host = host_.lock().unwrap();
+            host.actions.push(Action::Deselect(url.to_owned()));
+        });
+
+        let host_ = host.clone();
+        engine.register_fn(
+            "export",
+            move |path: &str| -> Result<(), Box<EvalAltResult>> {
+                let host = host_.lock().unwrap();
+                host.graph
+                    .export(Path::new(path))
+                    .map_err(|e| e.to_string().into())
+            },
+        );
+
+        engine
+    }
+
+    fn has_fn(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name)
+    }
+
+    /// Run some script code with the graph available to it, returning the graph and any actions
+    /// the script requested
+    fn run(
+        &mut self,
+        graph: Graph,
+        f: impl FnOnce(&Engine, &mut Scope<'static>, &AST),
+    ) -> (Graph, Vec<Action>) {
+        self.host.lock().unwrap().graph = graph;
+        f(&self.engine, &mut self.scope, &self.ast);
+        let mut host = self.host.lock().unwrap();
+        (
+            std::mem::take(&mut host.graph),
+            std::mem::take(&mut host.actions),
+        )
+    }
+}
+
+fn apply(
+    actions: Vec<Action>,
+    snapshot: &Graph,
+    graph: &mut GraphQuery,
+    scraper: &crate::background::Scraper,
+    commands: &mut Commands,
+) {
+    for action in actions {
+        match action {
+            Action::Scrape(request) => {
+                let (Request::Artist { url } | Request::Release { url } | Request::User { url }) =
+                    &request
+                else {
+                    continue;
+                };
+                if let Some(node) = snapshot.get(url) {
+                    graph.scrape(node.entity, scraper);
+                } else {
+                    scraper.send(request).unwrap();
+                }
+            }
+            Action::Select(url) => {
+                if let Some(node) = snapshot.get(&url) {
+                    commands.entity(node.entity).insert(Selected);
+                }
+            }
+            Action::Deselect(url) => {
+                if let Some(node) = snapshot.get(&url) {
+                    commands.entity(node.entity).remove::<Selected>();
+                }
+            }
+        }
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(
+            bevy::app::PostStartup,
+            run_main.run_if(resource_exists::<Script>),
+        );
+        app.add_systems(
+            bevy::app::Update,
+            run_on_scraped.run_if(resource_exists::<Script>),
+        );
+    }
+}
+
+fn run_main(
+    mut script: ResMut<Script>,
+    mut graph: GraphQuery,
+    scraper: Res<crate::background::Scraper>,
+    mut commands: Commands,
+) {
+    let (snapshot, actions) = script.run(graph.snapshot(), |engine, scope, ast| {
+        if let Err(error) = engine.run_ast_with_scope(scope, ast) {
+            tracing::error!(%error, "script failed");
+        }
+    });
+
+    apply(actions, &snapshot, &mut graph, &scraper, &mut commands);
+}
+
+fn run_on_scraped(
+    mut script: ResMut<Script>,
+    mut graph: GraphQuery,
+    scraper: Res<crate::background::Scraper>,
+    mut commands: Commands,
+) {
+    if !script.has_fn(ON_SCRAPED) {
+        return;
+    }
+
+    let scraped = graph.scraped();
+    if scraped.is_empty() {
+        return;
+    }
+
+    let snapshot = graph.snapshot();
+    let nodes = Vec::from_iter(
+        snapshot
+            .nodes
+            .iter()
+            .filter(|node| scraped.contains(&node.entity))
+            .map(to_map),
+    );
+
+    let (snapshot, actions) = script.run(snapshot, |engine, scope, ast| {
+        for node in nodes {
+            let options = CallFnOptions::new().eval_ast(false).rewind_scope(false);
+            if let Err(error) =
+                engine.call_fn_with_options::<Dynamic>(options, scope, ast, ON_SCRAPED, (node,))
+            {
+                tracing::error!(%error, "script {ON_SCRAPED} failed");
+            }
+        }
+    });
+
+    apply(actions, &snapshot, &mut graph, &scraper, &mut commands);
+}
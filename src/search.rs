@@ -0,0 +1,65 @@
+use bevy::ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::{Res, ResMut, Resource},
+};
+use bevy::input::keyboard::{Key, KeyboardInput};
+
+use crate::{
+    background::{Request, Scraper},
+    ui::{
+        editor::{Editor, Target},
+        notify::Notify,
+    },
+};
+
+/// Search Bandcamp, spawning the artists, releases and fans found unscraped and selecting them
+#[derive(Debug, Event)]
+pub struct Search(pub String);
+
+/// The last search made, to edit when the search box is opened again
+#[derive(Debug, Default, Resource)]
+struct LastSearch(String);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Search>();
+        app.init_resource::<LastSearch>();
+        app.add_systems(bevy::app::Update, (keys, search));
+    }
+}
+
+fn keys(mut events: EventReader<KeyboardInput>, mut editor: ResMut<Editor>, last: Res<LastSearch>) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("/".into()) {
+            editor.open(Target::Search, last.0.clone());
+        }
+    }
+}
+
+fn search(
+    mut events: EventReader<Search>,
+    mut last: ResMut<LastSearch>,
+    scraper: Res<Scraper>,
+    mut notify: EventWriter<Notify>,
+) {
+    for Search(text) in events.read() {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        last.0 = text.to_owned();
+        scraper
+            .send(Request::Search {
+                text: text.to_owned(),
+            })
+            .unwrap();
+        notify.send(Notify(format!("searching for {text:?}")));
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::{
+    ecs::{
+        change_detection::DetectChangesMut,
+        component::Component,
+        entity::Entity,
+        event::{EventReader, EventWriter},
+        query::Added,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, Res, ResMut, Resource},
+    },
+    input::keyboard::{Key, KeyboardInput},
+};
+
+use std::collections::HashMap;
+
+use crate::{
+    data::{events::EdgeAdded, DiscoveredVia, EntityType, Scrape, Url},
+    ui::{editor::Editor, notify::Notify},
+};
+
+/// Which seeds a node was reached by scraping out from, as bits indexing [`Seeds::urls`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub struct SeedMask(pub u64);
+
+impl SeedMask {
+    pub fn contains(self, seed: usize) -> bool {
+        self.0 & (1 << seed) != 0
+    }
+
+    /// Reached from more than one seed, where their audiences overlap
+    pub fn is_shared(self) -> bool {
+        self.0.count_ones() > 1
+    }
+}
+
+/// The nodes scraping started from, in the order they were found, only the first 64 are told
+/// apart
+#[derive(Debug, Default, Resource)]
+pub struct Seeds {
+    pub urls: Vec<String>,
+    /// Only show nodes reached from this seed, cycled through with `n`
+    pub only: Option<usize>,
+    /// Ring nodes reached from more than one seed, toggled with `b`
+    pub highlight_shared: bool,
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<Seeds>();
+        app.add_systems(bevy::app::Update, (keys, label, spread.after(label)));
+    }
+}
+
+fn keys(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    mut seeds: ResMut<Seeds>,
+    mut notify: EventWriter<Notify>,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        if event.logical_key == Key::Character("n".into()) {
+            seeds.only = match seeds.only {
+                None if !seeds.urls.is_empty() => Some(0),
+                Some(seed) if seed + 1 < seeds.urls.len() => Some(seed + 1),
+                _ => None,
+            };
+            notify.send(Notify(match seeds.only {
+                Some(seed) => format!("showing nodes reached from {}", seeds.urls[seed]),
+                None => format!("showing nodes from all {} seeds", seeds.urls.len()),
+            }));
+        } else if event.logical_key == Key::Character("b".into()) {
+            seeds.highlight_shared ^= true;
+        }
+    }
+}
+
+/// The seeds of a newly discovered node, those of the node it was discovered from or a new seed
+/// if it wasn't discovered from any
+fn resolve(
+    entity: Entity,
+    discovered: &HashMap<Entity, (Option<Entity>, &Url)>,
+    masks: &Query<&SeedMask>,
+    seeds: &mut Seeds,
+    resolved: &mut HashMap<Entity, SeedMask>,
+) -> SeedMask {
+    if let Some(&mask) = resolved.get(&entity) {
+        return mask;
+    }
+    let mask = match discovered.get(&entity) {
+        Some(&(None, url)) if seeds.urls.len() < 64 => {
+            seeds.urls.push(url.0.clone());
+            SeedMask(1 << (seeds.urls.len() - 1))
+        }
+        // Nodes discovered along with their parent get the parent's seeds once it has them
+        Some(&(Some(parent), _)) => masks
+            .get(parent)
+            .copied()
+            .unwrap_or_else(|_| resolve(parent, discovered, masks, seeds, resolved)),
+        _ => SeedMask::default(),
+    };
+    resolved.insert(entity, mask);
+    mask
+}
+
+fn label(
+    discovered: Query<(Entity, &DiscoveredVia, &Url), Added<DiscoveredVia>>,
+    masks: Query<&SeedMask>,
+    mut seeds: ResMut<Seeds>,
+    mut commands: Commands,
+) {
+    let parents = HashMap::from_iter(
+        discovered
+            .iter()
+            .map(|(entity, via, url)| (entity, (via.parent_entity, url))),
+    );
+    let mut resolved = HashMap::new();
+    for (entity, ..) in &discovered {
+        let mask = resolve(entity, &parents, &masks, &mut seeds, &mut resolved);
+        commands.entity(entity).insert(mask);
+    }
+}
+
+/// Relationships are found by scraping one of their nodes, so the other is reached from the same
+/// seeds as it, even if it was first discovered from another
+fn spread(
+    mut edges: EventReader<EdgeAdded>,
+    mut nodes: Query<(&mut SeedMask, &EntityType, Option<&Scrape>)>,
+) {
+    for edge in edges.read() {
+        let Ok([(mut from, from_ty, from_scrape), (mut to, to_ty, to_scrape)]) =
+            nodes.get_many_mut([edge.relationship.from, edge.relationship.to])
+        else {
+            continue;
+        };
+        // Tags are never scraped, they're only ever related by scraping the release
+        let scraped = |ty: &EntityType, scrape: Option<&Scrape>| {
+            *ty != EntityType::Tag && scrape.is_some_and(|scrape| *scrape >= Scrape::InProgress)
+        };
+        let (from_mask, to_mask) = (*from, *to);
+        if scraped(from_ty, from_scrape) {
+            to.set_if_neq(SeedMask(to_mask.0 | from_mask.0));
+        }
+        if scraped(to_ty, to_scrape) {
+            from.set_if_neq(SeedMask(from_mask.0 | to_mask.0));
+        }
+    }
+}
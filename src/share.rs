@@ -0,0 +1,449 @@
+use bevy::{
+    ecs::{
+        change_detection::{DetectChanges, Ref},
+        entity::Entity,
+        event::EventReader,
+        query::{Changed, With},
+        schedule::{common_conditions::resource_exists, Condition, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource, Single},
+    },
+    hierarchy::DespawnRecursiveExt,
+    time::common_conditions::on_timer,
+    transform::components::Transform,
+};
+
+use crossbeam::channel::{Receiver, Sender};
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{broadcast, oneshot},
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use std::time::Duration;
+
+use crate::{
+    camera::MainCamera,
+    data::{
+        events::{DetailsUpdated, NodeAdded, NodeRemoved},
+        ArtistDetails, ArtistId, EntityType, RelationKind, ReleaseId, Scrape, TagDetails, TagId,
+        Url, UserId,
+    },
+    graph::{bandcamp_id, export_type, BandcampId, ExportedNode, GraphQuery},
+    load::{self, Dehydrated, Loading},
+    sim::{Multiplicity, PredictedPosition, Relationship},
+    Args, KnownEntities, RelationshipParent, Runtime,
+};
+
+type Ids = (
+    &'static EntityType,
+    Option<&'static ArtistId>,
+    Option<&'static ReleaseId>,
+    Option<&'static UserId>,
+    Option<&'static TagId>,
+);
+
+/// An update from the sharing instance, as json-rpc notifications
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+enum Update {
+    /// The whole graph as an export, sent once when joining
+    Graph(serde_json::Value),
+    NodeAdded(ExportedNode),
+    NodeRemoved {
+        #[serde(rename = "type")]
+        ty: String,
+        id: BandcampId,
+    },
+    NodeScraped {
+        #[serde(rename = "type")]
+        ty: String,
+        id: BandcampId,
+    },
+    /// Scraped again, so its details may have changed
+    DetailsUpdated {
+        #[serde(rename = "type")]
+        ty: String,
+        id: BandcampId,
+    },
+    Relationship {
+        from: (String, BandcampId),
+        to: (String, BandcampId),
+        kinds: Vec<RelationKind>,
+    },
+    Focus {
+        x: f32,
+        y: f32,
+        scale: f32,
+    },
+}
+
+/// Bridge between the websocket server sharing the graph and the app
+#[derive(Debug, Resource)]
+pub struct Share {
+    /// Viewers that just connected, waiting for the graph to start from
+    joins: Receiver<oneshot::Sender<String>>,
+    updates: broadcast::Sender<String>,
+}
+
+impl Share {
+    fn update(&self, method: &str, params: serde_json::Value) {
+        // Errors only when there are no viewers
+        let _ = self.updates.send(notification(method, params));
+    }
+}
+
+/// Updates received from the instance being viewed
+#[derive(Debug, Resource)]
+pub struct View {
+    updates: Receiver<Update>,
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (
+                (welcome, send).run_if(resource_exists::<Share>),
+                focus.run_if(resource_exists::<Share>.and(on_timer(Duration::from_millis(100)))),
+                apply.run_if(resource_exists::<View>),
+            ),
+        );
+    }
+}
+
+fn setup(args: Res<Args>, runtime: Res<Runtime>, mut commands: Commands) {
+    if let Some(port) = args.share {
+        let (joins_tx, joins_rx) = crossbeam::channel::unbounded();
+        let (updates, _) = broadcast::channel(1024);
+        runtime.spawn_background(serve(port, joins_tx, updates.clone()));
+        commands.insert_resource(Share {
+            joins: joins_rx,
+            updates,
+        });
+    }
+
+    if let Some(url) = &args.view {
+        let (updates_tx, updates_rx) = crossbeam::channel::unbounded();
+        runtime.spawn_background(view(url.clone(), updates_tx));
+        commands.insert_resource(View {
+            updates: updates_rx,
+        });
+    }
+}
+
+fn notification(method: &str, params: serde_json::Value) -> String {
+    serde_json::json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string()
+}
+
+async fn serve(
+    port: u16,
+    joins: Sender<oneshot::Sender<String>>,
+    updates: broadcast::Sender<String>,
+) {
+    let listener = match TcpListener::bind(("0.0.0.0", port)).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            tracing::error!(?error, port, "failed to listen for viewers");
+            return;
+        }
+    };
+
+    tracing::info!(port, "sharing graph with viewers");
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                tracing::info!(%peer, "viewer connected");
+                tokio::spawn(connection(stream, joins.clone(), updates.subscribe()));
+            }
+            Err(error) => {
+                tracing::warn!(?error, "failed to accept viewer connection");
+            }
+        }
+    }
+}
+
+async fn connection(
+    stream: TcpStream,
+    joins: Sender<oneshot::Sender<String>>,
+    mut updates: broadcast::Receiver<String>,
+) {
+    let socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(error) => {
+            tracing::warn!(?error, "viewer websocket handshake failed");
+            return;
+        }
+    };
+
+    let (mut tx, mut rx) = socket.split();
+
+    // Subscribed before asking for the graph so nothing is missed in between, anything sent twice
+    // is already known by the viewer and ignored
+    let (graph_tx, graph_rx) = oneshot::channel();
+    if joins.send(graph_tx).is_err() {
+        return;
+    }
+    let Ok(graph) = graph_rx.await else { return };
+    if let Err(error) = tx.send(Message::text(graph)).await {
+        tracing::warn!(?error, "viewer connection failed");
+        return;
+    }
+
+    loop {
+        let outgoing = tokio::select! {
+            // Viewers are read-only, anything they send is dropped
+            message = rx.next() => match message {
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => {
+                    tracing::warn!(?error, "viewer connection failed");
+                    break;
+                }
+            },
+            update = updates.recv() => match update {
+                Ok(update) => update,
+                // Without the updates it missed the viewer's graph can't be kept in step, so it's
+                // dropped to join again from a fresh snapshot
+                Err(broadcast::error::RecvError::Lagged(count)) => {
+                    tracing::warn!(count, "viewer missed updates, disconnecting it");
+                    let _ = tx.send(Message::Close(None)).await;
+                    break;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+
+        if let Err(error) = tx.send(Message::text(outgoing)).await {
+            tracing::warn!(?error, "viewer connection failed");
+            break;
+        }
+    }
+
+    tracing::info!("viewer disconnected");
+}
+
+async fn view(url: String, updates: Sender<Update>) {
+    let socket = match tokio_tungstenite::connect_async(url.as_str()).await {
+        Ok((socket, _)) => socket,
+        Err(error) => {
+            tracing::error!(?error, url, "failed to connect to shared graph");
+            return;
+        }
+    };
+
+    tracing::info!(url, "viewing shared graph");
+
+    let (_, mut rx) = socket.split();
+    while let Some(message) = rx.next().await {
+        match message {
+            Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(update) => {
+                    if updates.send(update).is_err() {
+                        break;
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(?error, "failed parsing update from shared graph");
+                }
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(error) => {
+                tracing::warn!(?error, "shared graph connection failed");
+                break;
+            }
+        }
+    }
+
+    tracing::info!("shared graph disconnected");
+}
+
+fn welcome(share: Res<Share>, graph: GraphQuery) {
+    let mut json = None;
+    for join in share.joins.try_iter() {
+        let json = json.get_or_insert_with(|| notification("graph", graph.snapshot().to_json()));
+        let _ = join.send(json.clone());
+    }
+}
+
+fn key(
+    (&ty, artist, release, user, tag): (
+        &EntityType,
+        Option<&ArtistId>,
+        Option<&ReleaseId>,
+        Option<&UserId>,
+        Option<&TagId>,
+    ),
+) -> Option<(&'static str, BandcampId)> {
+    Some((export_type(ty), bandcamp_id(artist, release, user, tag)?))
+}
+
+fn send(
+    share: Res<Share>,
+    mut nodes: EventReader<NodeAdded>,
+    mut removed: EventReader<NodeRemoved>,
+    mut updated: EventReader<DetailsUpdated>,
+    details: Query<(
+        Ids,
+        &Url,
+        Option<&PredictedPosition>,
+        Option<&ArtistDetails>,
+        Option<&TagDetails>,
+    )>,
+    scrapes: Query<(Ids, Ref<Scrape>)>,
+    relationships: Query<(&Relationship, &Multiplicity), Changed<Multiplicity>>,
+    ids: Query<Ids>,
+) {
+    if share.updates.receiver_count() == 0 {
+        nodes.clear();
+        removed.clear();
+        updated.clear();
+        return;
+    }
+
+    for &NodeAdded { entity, .. } in nodes.read() {
+        let Ok((node, url, position, artist, tag)) = details.get(entity) else {
+            continue;
+        };
+        let Some((ty, id)) = key(node) else { continue };
+        share.update(
+            "node_added",
+            serde_json::json!({
+                "type": ty,
+                "id": id,
+                "url": url.0,
+                "name": artist.map(|artist| &artist.name).or(tag.map(|tag| &tag.name)),
+                "x": position.map(|position| position.0.x),
+                "y": position.map(|position| position.0.y),
+            }),
+        );
+    }
+
+    for NodeRemoved { ty, id, .. } in removed.read() {
+        let Some(id) = id else { continue };
+        share.update(
+            "node_removed",
+            serde_json::json!({ "type": export_type(*ty), "id": id }),
+        );
+    }
+
+    for (node, scrape) in &scrapes {
+        if scrape.is_changed() && *scrape >= Scrape::Shallow {
+            let Some((ty, id)) = key(node) else { continue };
+            share.update("node_scraped", serde_json::json!({ "type": ty, "id": id }));
+        }
+    }
+
+    for &DetailsUpdated { entity } in updated.read() {
+        let Ok((node, _)) = scrapes.get(entity) else {
+            continue;
+        };
+        let Some((ty, id)) = key(node) else { continue };
+        // Everything there is to know about a tag is sent when it's added
+        if ty == "tag" {
+            continue;
+        }
+        share.update(
+            "details_updated",
+            serde_json::json!({ "type": ty, "id": id }),
+        );
+    }
+
+    for (relationship, multiplicity) in &relationships {
+        let (Ok(from), Ok(to)) = (ids.get(relationship.from), ids.get(relationship.to)) else {
+            continue;
+        };
+        let (Some(from), Some(to)) = (key(from), key(to)) else {
+            continue;
+        };
+        share.update(
+            "relationship",
+            serde_json::json!({
+                "from": from,
+                "to": to,
+                "kinds": Vec::from_iter(multiplicity.kinds()),
+            }),
+        );
+    }
+}
+
+fn focus(share: Res<Share>, camera: Single<Ref<Transform>, With<MainCamera>>) {
+    if !camera.is_changed() || share.updates.receiver_count() == 0 {
+        return;
+    }
+    share.update(
+        "focus",
+        serde_json::json!({
+            "x": camera.translation.x,
+            "y": camera.translation.y,
+            "scale": camera.scale.x,
+        }),
+    );
+}
+
+fn apply(
+    view: Res<View>,
+    mut known: ResMut<KnownEntities>,
+    mut multiplicities: Query<&mut Multiplicity>,
+    relationship_parent: Single<Entity, With<RelationshipParent>>,
+    mut camera: Single<&mut Transform, With<MainCamera>>,
+    mut commands: Commands,
+) {
+    for update in view.updates.try_iter() {
+        match update {
+            Update::Graph(export) => match Loading::from_json(&export) {
+                Ok(loading) => commands.insert_resource(loading),
+                Err(error) => tracing::error!(?error, "failed reading shared graph"),
+            },
+            Update::NodeAdded(node) => {
+                load::spawn_node(&node, false, &mut known, &mut commands);
+            }
+            // The id component hooks unindex the node and despawn its relationships
+            Update::NodeRemoved { ty, id } => {
+                if let Some(entity) = load::find(&known, &ty, &id) {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+            // The details are filled in from this instance's own page cache, the same as a
+            // loaded graph
+            Update::NodeScraped { ty, id } => {
+                if let Some(entity) = load::find(&known, &ty, &id) {
+                    commands
+                        .entity(entity)
+                        .insert((Scrape::Shallow, Dehydrated));
+                }
+            }
+            Update::DetailsUpdated { ty, id } => {
+                if let Some(entity) = load::find(&known, &ty, &id) {
+                    commands.entity(entity).insert(Dehydrated);
+                }
+            }
+            Update::Relationship { from, to, kinds } => {
+                let (Some(from), Some(to)) = (
+                    load::find(&known, &from.0, &from.1),
+                    load::find(&known, &to.0, &to.1),
+                ) else {
+                    continue;
+                };
+                known.relate_kinds(
+                    Relationship { from, to },
+                    &kinds,
+                    *relationship_parent,
+                    &mut multiplicities,
+                    &mut commands,
+                );
+            }
+            Update::Focus { x, y, scale } => {
+                camera.translation.x = x;
+                camera.translation.y = y;
+                camera.scale.x = scale;
+                camera.scale.y = scale;
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::{
+    ecs::{
+        change_detection::DetectChangesMut,
+        component::Component,
+        entity::Entity,
+        schedule::IntoSystemConfigs,
+        system::{Query, Res, ResMut, Resource},
+    },
+    math::{Rect, Vec2},
+    render::camera::Camera,
+    time::common_conditions::on_timer,
+    transform::components::GlobalTransform,
+};
+
+use std::{collections::HashSet, time::Duration};
+
+use super::{Paused, Position, Relationship};
+
+/// How many fixed updates pass between each integration of a reduced node
+const REDUCED_INTERVAL: u32 = 4;
+
+/// How far past the edges of the view, as a multiple of its size, nodes are still considered
+/// visible, so they're already settled by the time they're panned to
+const VISIBLE_MARGIN: f32 = 1.5;
+
+/// How far past the edges of the view, as a multiple of its size, nodes keep being integrated at a
+/// reduced rate before freezing
+const REDUCED_MARGIN: f32 = 4.;
+
+/// How much simulation a node gets, depending on how near it is to what's being looked at
+#[derive(Debug, Default, Component, Copy, Clone, PartialEq, Eq)]
+pub enum Activity {
+    /// In or near the view, or connected to a node that is
+    #[default]
+    Active,
+    /// Off-screen, integrated every few fixed updates
+    Reduced,
+    /// Far off-screen, not integrated at all
+    Frozen,
+}
+
+impl Activity {
+    /// Whether the node is integrated on this fixed update
+    pub fn runs(self, tick: &Tick) -> bool {
+        match self {
+            Self::Active => true,
+            Self::Reduced => tick.0 % REDUCED_INTERVAL == 0,
+            Self::Frozen => false,
+        }
+    }
+}
+
+/// Count of the fixed updates that have run, for spreading out the integration of reduced nodes
+#[derive(Debug, Default, Resource)]
+pub struct Tick(u32);
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.insert_resource(Tick::default());
+        app.add_systems(bevy::app::FixedUpdate, tick.before(super::update_positions));
+        app.add_systems(
+            bevy::app::Update,
+            update.run_if(on_timer(Duration::from_millis(250))),
+        );
+    }
+}
+
+fn tick(paused: Res<Paused>, mut tick: ResMut<Tick>) {
+    if !paused.0 {
+        tick.0 = tick.0.wrapping_add(1);
+    }
+}
+
+fn update(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut nodes: Query<(Entity, &Position, &mut Activity)>,
+    relationships: Query<&Relationship>,
+) {
+    // While the view is split a node only needs to be near one of them
+    let views = Vec::from_iter(cameras.iter().filter_map(|(camera, transform)| {
+        let viewport = camera.logical_viewport_rect()?;
+        let min = camera.viewport_to_world_2d(transform, viewport.min).ok()?;
+        let max = camera.viewport_to_world_2d(transform, viewport.max).ok()?;
+        Some(Rect::from_corners(min, max))
+    }));
+    if views.is_empty() {
+        return;
+    }
+    let within = |position: Vec2, margin: f32| {
+        views.iter().any(|view| {
+            Rect::from_center_half_size(view.center(), view.half_size() * margin).contains(position)
+        })
+    };
+
+    let mut active = HashSet::<Entity>::from_iter(
+        nodes
+            .iter()
+            .filter(|(_, position, _)| within(position.0, VISIBLE_MARGIN))
+            .map(|(entity, ..)| entity),
+    );
+
+    // Keep the far ends of visible edges moving, otherwise they'd visibly hang in place
+    let connected = Vec::from_iter(relationships.iter().filter_map(|relationship| {
+        match (
+            active.contains(&relationship.from),
+            active.contains(&relationship.to),
+        ) {
+            (true, false) => Some(relationship.to),
+            (false, true) => Some(relationship.from),
+            _ => None,
+        }
+    }));
+    active.extend(connected);
+
+    nodes
+        .par_iter_mut()
+        .for_each(|(entity, position, mut activity)| {
+            activity.set_if_neq(if active.contains(&entity) {
+                Activity::Active
+            } else if within(position.0, REDUCED_MARGIN) {
+                Activity::Reduced
+            } else {
+                Activity::Frozen
+            });
+        });
+}
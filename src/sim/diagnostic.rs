@@ -1,9 +1,19 @@
 use bevy::{
     diagnostic::{Diagnostic, RegisterDiagnostic},
     ecs::{
+        entity::Entity,
         query::With,
+        schedule::IntoSystemConfigs,
         system::{Query, Res},
     },
+    time::common_conditions::on_timer,
+};
+
+use rand::seq::IndexedRandom;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
 };
 
 use crate::diagnostic::Diagnostics;
@@ -70,6 +80,54 @@ pub mod data {
     pub const RELATIONS: DiagnosticPath = DiagnosticPath::const_new("sim/data/relations");
 }
 
+/// How well the layout represents the graph, for comparing changes to the layout forces
+pub mod layout {
+    use bevy::diagnostic::DiagnosticPath;
+
+    pub mod edge_length {
+        use bevy::diagnostic::DiagnosticPath;
+
+        pub const MAX: DiagnosticPath = DiagnosticPath::const_new("sim/layout/edge-length/max");
+        pub const MEAN: DiagnosticPath = DiagnosticPath::const_new("sim/layout/edge-length/mean");
+        pub const MIN: DiagnosticPath = DiagnosticPath::const_new("sim/layout/edge-length/min");
+
+        /// Percentage of edges shorter than each length, the last bucket is everything longer
+        pub const HISTOGRAM: [(f32, DiagnosticPath); 6] = [
+            (
+                50.,
+                DiagnosticPath::const_new("sim/layout/edge-length/under-50"),
+            ),
+            (
+                100.,
+                DiagnosticPath::const_new("sim/layout/edge-length/under-100"),
+            ),
+            (
+                200.,
+                DiagnosticPath::const_new("sim/layout/edge-length/under-200"),
+            ),
+            (
+                400.,
+                DiagnosticPath::const_new("sim/layout/edge-length/under-400"),
+            ),
+            (
+                800.,
+                DiagnosticPath::const_new("sim/layout/edge-length/under-800"),
+            ),
+            (
+                f32::INFINITY,
+                DiagnosticPath::const_new("sim/layout/edge-length/over-800"),
+            ),
+        ];
+    }
+
+    /// Normalized stress between graph distances and layout distances of sampled pairs of nodes,
+    /// 0 when every pair is exactly as far apart as the number of hops between them
+    pub const STRESS: DiagnosticPath = DiagnosticPath::const_new("sim/layout/stress");
+}
+
+/// How many nodes to measure graph distances out from for the stress of each update
+const STRESS_SOURCES: usize = 16;
+
 pub struct Plugin;
 
 impl bevy::app::Plugin for Plugin {
@@ -115,7 +173,27 @@ impl bevy::app::Plugin for Plugin {
             app.register_diagnostic(Diagnostic::new(path));
         }
 
-        app.add_systems(bevy::app::Update, update);
+        for path in [
+            self::layout::edge_length::MAX,
+            self::layout::edge_length::MEAN,
+            self::layout::edge_length::MIN,
+            self::layout::STRESS,
+        ] {
+            app.register_diagnostic(Diagnostic::new(path).with_smoothing_factor(0.));
+        }
+
+        for (_, path) in self::layout::edge_length::HISTOGRAM {
+            app.register_diagnostic(
+                Diagnostic::new(path)
+                    .with_suffix("%")
+                    .with_smoothing_factor(0.),
+            );
+        }
+
+        app.add_systems(
+            bevy::app::Update,
+            (update, layout.run_if(on_timer(Duration::from_secs(1)))),
+        );
     }
 }
 
@@ -223,3 +301,81 @@ fn update(
         }
     }
 }
+
+fn layout(
+    mut diagnostics: Diagnostics,
+    nodes: Query<&super::Position>,
+    relations: Query<&super::Relationship>,
+) {
+    let mut lengths = Vec::new();
+    let mut neighbors = HashMap::<Entity, Vec<Entity>>::new();
+    for relationship in &relations {
+        let (Ok(from), Ok(to)) = (nodes.get(relationship.from), nodes.get(relationship.to)) else {
+            continue;
+        };
+        lengths.push(from.0.distance(to.0));
+        neighbors
+            .entry(relationship.from)
+            .or_default()
+            .push(relationship.to);
+        neighbors
+            .entry(relationship.to)
+            .or_default()
+            .push(relationship.from);
+    }
+    if lengths.is_empty() {
+        return;
+    }
+
+    let mean = lengths.iter().sum::<f32>() / lengths.len() as f32;
+    diagnostics.add_measurement(&self::layout::edge_length::MIN, || {
+        lengths.iter().copied().fold(f32::INFINITY, f32::min) as f64
+    });
+    diagnostics.add_measurement(&self::layout::edge_length::MEAN, || mean as f64);
+    diagnostics.add_measurement(&self::layout::edge_length::MAX, || {
+        lengths.iter().copied().fold(0., f32::max) as f64
+    });
+    let mut lower = 0.;
+    for (upper, path) in self::layout::edge_length::HISTOGRAM {
+        diagnostics.add_measurement(&path, || {
+            let count = lengths
+                .iter()
+                .filter(|&&length| length >= lower && length < upper)
+                .count();
+            count as f64 * 100. / lengths.len() as f64
+        });
+        lower = upper;
+    }
+
+    // The mean edge length is taken as the ideal length of a hop, so the stress only measures how
+    // the layout is shaped rather than how spread out it is
+    diagnostics.add_measurement(&self::layout::STRESS, || {
+        let sources = Vec::from_iter(neighbors.keys().copied());
+        let (mut stress, mut pairs) = (0., 0);
+        for &source in sources.choose_multiple(&mut rand::rng(), STRESS_SOURCES) {
+            let origin = nodes.get(source).unwrap().0;
+            let mut hops = HashMap::from([(source, 0)]);
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                let distance = hops[&node] + 1;
+                for &neighbor in &neighbors[&node] {
+                    if hops.contains_key(&neighbor) {
+                        continue;
+                    }
+                    hops.insert(neighbor, distance);
+                    queue.push_back(neighbor);
+
+                    let ideal = mean * distance as f32;
+                    let actual = origin.distance(nodes.get(neighbor).unwrap().0);
+                    stress += ((actual - ideal) / ideal).powi(2) as f64;
+                    pairs += 1;
+                }
+            }
+        }
+        if pairs == 0 {
+            0.
+        } else {
+            stress / pairs as f64
+        }
+    });
+}
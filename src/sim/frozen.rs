@@ -0,0 +1,123 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        event::EventReader,
+        query::Added,
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource},
+    },
+    input::keyboard::{Key, KeyboardInput},
+    math::Vec2,
+};
+
+use std::{collections::HashMap, path::Path};
+
+use super::{Pinned, Position, Velocity};
+use crate::{data::Url, ui::editor::Editor, ui::notify::Notify};
+
+/// Coordinates of nodes from an earlier export, nodes spawned at one of these urls are placed
+/// there and pinned until thawed with `f`
+#[derive(Debug, Resource)]
+pub struct FrozenLayout {
+    positions: HashMap<String, Vec2>,
+    /// Nodes placed from the layout that are still pinned
+    frozen: Vec<Entity>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Export {
+    nodes: Vec<ExportNode>,
+    layout: Option<ExportLayout>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ExportNode {
+    url: String,
+    x: Option<f32>,
+    y: Option<f32>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ExportLayout {
+    origin_force: super::OriginForceMode,
+}
+
+impl FrozenLayout {
+    /// Read the node coordinates and the origin force they settled under from an export
+    #[culpa::try_fn]
+    pub fn load(path: &Path) -> eyre::Result<(Self, Option<super::OriginForceMode>)> {
+        let export: Export = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let positions = HashMap::from_iter(
+            export
+                .nodes
+                .into_iter()
+                .filter_map(|node| Some((node.url, Vec2::new(node.x?, node.y?)))),
+        );
+        tracing::info!(count = positions.len(), "loaded frozen layout");
+        let layout = Self {
+            positions,
+            frozen: Vec::new(),
+        };
+        (layout, export.layout.map(|layout| layout.origin_force))
+    }
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(
+            bevy::app::PreUpdate,
+            (place
+                .before(super::init_predicted_position)
+                .before(super::lock_pinned))
+            .run_if(resource_exists::<FrozenLayout>),
+        );
+        app.add_systems(
+            bevy::app::Update,
+            thaw.run_if(resource_exists::<FrozenLayout>),
+        );
+    }
+}
+
+fn place(
+    mut layout: ResMut<FrozenLayout>,
+    mut nodes: Query<(Entity, &Url, &mut Position, &mut Velocity, &mut Pinned), Added<Url>>,
+) {
+    for (entity, url, mut position, mut velocity, mut pinned) in &mut nodes {
+        let Some(&frozen) = layout.positions.get(&url.0) else {
+            continue;
+        };
+        position.0 = frozen;
+        velocity.0 = Vec2::ZERO;
+        pinned.count += 1;
+        layout.frozen.push(entity);
+    }
+}
+
+/// Let the nodes placed so far settle from where they were frozen, later nodes are still placed
+fn thaw(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    mut layout: ResMut<FrozenLayout>,
+    mut pinned: Query<&mut Pinned>,
+    mut commands: Commands,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("f".into()) {
+            let frozen = std::mem::take(&mut layout.frozen);
+            let count = frozen.len();
+            for entity in frozen {
+                if let Ok(mut pinned) = pinned.get_mut(entity) {
+                    pinned.count = pinned.count.saturating_sub(1);
+                }
+            }
+            commands.send_event(Notify(format!("thawed {count} nodes")));
+        }
+    }
+}
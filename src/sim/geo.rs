@@ -0,0 +1,261 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        event::EventReader,
+        schedule::IntoSystemConfigs,
+        system::{Query, Res, ResMut, Resource},
+    },
+    input::keyboard::{Key, KeyboardInput},
+    math::Vec2,
+};
+
+use std::collections::HashSet;
+
+use super::{
+    activity::{Activity, Tick},
+    Acceleration, Paused, Pinned, Position, PredictedPosition, Velocity,
+};
+use crate::{
+    data::{ArtistDetails, UserDetails},
+    ui::editor::Editor,
+};
+
+/// World units per degree of longitude or latitude
+pub const SCALE: f32 = 20.;
+
+/// How strongly artists are pulled towards their country, per unit of distance from it, weak
+/// enough that their releases and fans still shape the graph
+const COUNTRY_STRENGTH: f32 = 0.002;
+
+/// Approximate longitude and latitude of places fans commonly give as their location, checked
+/// against each comma separated part of a location from most to least specific
+const PLACES: &[(&str, f32, f32)] = &[
+    ("amsterdam", 4.9, 52.4),
+    ("argentina", -64.0, -34.0),
+    ("athens", 23.7, 38.0),
+    ("atlanta", -84.4, 33.7),
+    ("austin", -97.7, 30.3),
+    ("australia", 134.0, -25.0),
+    ("austria", 14.6, 47.5),
+    ("barcelona", 2.2, 41.4),
+    ("belgium", 4.5, 50.5),
+    ("berlin", 13.4, 52.5),
+    ("boston", -71.1, 42.4),
+    ("brazil", -51.9, -14.2),
+    ("brighton", -0.1, 50.8),
+    ("bristol", -2.6, 51.5),
+    ("brooklyn", -73.9, 40.7),
+    ("brussels", 4.4, 50.8),
+    ("buenos aires", -58.4, -34.6),
+    ("california", -119.4, 36.8),
+    ("canada", -106.3, 56.1),
+    ("chicago", -87.6, 41.9),
+    ("chile", -71.5, -35.7),
+    ("china", 104.2, 35.9),
+    ("copenhagen", 12.6, 55.7),
+    ("czech republic", 15.5, 49.8),
+    ("denmark", 9.5, 56.3),
+    ("denver", -105.0, 39.7),
+    ("dublin", -6.3, 53.3),
+    ("england", -1.2, 52.4),
+    ("finland", 25.7, 61.9),
+    ("france", 2.2, 46.2),
+    ("germany", 10.5, 51.2),
+    ("glasgow", -4.3, 55.9),
+    ("greece", 21.8, 39.1),
+    ("hamburg", 10.0, 53.6),
+    ("helsinki", 24.9, 60.2),
+    ("hungary", 19.5, 47.2),
+    ("india", 78.9, 20.6),
+    ("indonesia", 113.9, -0.8),
+    ("ireland", -8.2, 53.4),
+    ("israel", 34.9, 31.0),
+    ("italy", 12.6, 41.9),
+    ("japan", 138.3, 36.2),
+    ("leeds", -1.5, 53.8),
+    ("lisbon", -9.1, 38.7),
+    ("london", -0.1, 51.5),
+    ("los angeles", -118.2, 34.1),
+    ("madrid", -3.7, 40.4),
+    ("manchester", -2.2, 53.5),
+    ("melbourne", 145.0, -37.8),
+    ("mexico", -102.6, 23.6),
+    ("mexico city", -99.1, 19.4),
+    ("milan", 9.2, 45.5),
+    ("minneapolis", -93.3, 45.0),
+    ("montreal", -73.6, 45.5),
+    ("moscow", 37.6, 55.8),
+    ("netherlands", 5.3, 52.1),
+    ("new york", -74.0, 40.7),
+    ("new zealand", 174.9, -40.9),
+    ("norway", 8.5, 60.5),
+    ("nyc", -74.0, 40.7),
+    ("oakland", -122.3, 37.8),
+    ("oslo", 10.8, 59.9),
+    ("paris", 2.4, 48.9),
+    ("philadelphia", -75.2, 40.0),
+    ("poland", 19.1, 51.9),
+    ("portland", -122.7, 45.5),
+    ("portugal", -8.2, 39.4),
+    ("rome", 12.5, 41.9),
+    ("russia", 105.3, 61.5),
+    ("san francisco", -122.4, 37.8),
+    ("sao paulo", -46.6, -23.6),
+    ("scotland", -4.2, 56.5),
+    ("seattle", -122.3, 47.6),
+    ("south africa", 22.9, -30.6),
+    ("south korea", 127.8, 35.9),
+    ("spain", -3.7, 40.5),
+    ("stockholm", 18.1, 59.3),
+    ("sweden", 18.6, 60.1),
+    ("switzerland", 8.2, 46.8),
+    ("sydney", 151.2, -33.9),
+    ("texas", -99.9, 31.0),
+    ("tokyo", 139.7, 35.7),
+    ("toronto", -79.4, 43.7),
+    ("uk", -3.4, 55.4),
+    ("ukraine", 31.2, 48.4),
+    ("united kingdom", -3.4, 55.4),
+    ("united states", -95.7, 37.1),
+    ("usa", -95.7, 37.1),
+    ("vancouver", -123.1, 49.3),
+    ("vienna", 16.4, 48.2),
+    ("wales", -3.8, 52.1),
+    ("warsaw", 21.0, 52.2),
+];
+
+/// Where a location is on the map, if any part of it is a known place
+pub fn locate(location: &str) -> Option<Vec2> {
+    location.split([',', '/']).find_map(|part| {
+        let part = part.trim().to_lowercase();
+        PLACES
+            .iter()
+            .find(|&&(name, ..)| name == part)
+            .map(|&(_, longitude, latitude)| Vec2::new(longitude, latitude) * SCALE)
+    })
+}
+
+/// Where the country of a location is on the map, checking its parts from least to most specific
+/// so places in the same country are grouped together
+pub fn locate_country(location: &str) -> Option<Vec2> {
+    location.rsplit([',', '/']).find_map(locate)
+}
+
+/// Pins fans whose location is known to their approximate position on a world map, toggled with
+/// `w`
+#[derive(Debug, Default, Resource)]
+pub struct MapLayout {
+    pub enabled: bool,
+    /// Fans pinned to the map, which need unpinning once it's turned off
+    placed: HashSet<Entity>,
+}
+
+/// Loosely pulls artists whose location is known towards their country on the same map as
+/// [`MapLayout`], toggled with `i`
+#[derive(Debug, Default, Resource)]
+pub struct CountryGroups(pub bool);
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<MapLayout>();
+        app.init_resource::<CountryGroups>();
+        app.add_systems(bevy::app::Update, (toggle, toggle_countries, place));
+        app.add_systems(
+            bevy::app::FixedUpdate,
+            group_countries
+                .after(super::attract)
+                .before(super::update_velocities),
+        );
+    }
+}
+
+fn toggle(mut events: EventReader<KeyboardInput>, editor: Res<Editor>, mut map: ResMut<MapLayout>) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("w".into()) {
+            map.enabled ^= true;
+        }
+    }
+}
+
+fn place(
+    mut map: ResMut<MapLayout>,
+    mut users: Query<(
+        Entity,
+        &UserDetails,
+        &mut Position,
+        &mut PredictedPosition,
+        &mut Velocity,
+        &mut Pinned,
+    )>,
+) {
+    if !map.enabled {
+        for entity in std::mem::take(&mut map.placed) {
+            if let Ok((.., mut pinned)) = users.get_mut(entity) {
+                pinned.count = pinned.count.saturating_sub(1);
+            }
+        }
+        return;
+    }
+
+    for (entity, details, mut position, mut predicted, mut velocity, mut pinned) in &mut users {
+        if map.placed.contains(&entity) {
+            continue;
+        }
+        let Some(location) = details.location.as_deref().and_then(locate) else {
+            continue;
+        };
+        // Pinning locks nodes at their predicted position
+        position.0 = location;
+        predicted.0 = location;
+        velocity.0 = Vec2::ZERO;
+        pinned.count += 1;
+        map.placed.insert(entity);
+    }
+}
+
+fn toggle_countries(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    mut countries: ResMut<CountryGroups>,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("i".into()) {
+            countries.0 ^= true;
+        }
+    }
+}
+
+fn group_countries(
+    paused: Res<Paused>,
+    countries: Res<CountryGroups>,
+    tick: Res<Tick>,
+    mut artists: Query<(&mut Acceleration, &Position, &ArtistDetails, &Activity)>,
+) {
+    if paused.0 || !countries.0 {
+        return;
+    }
+
+    artists
+        .par_iter_mut()
+        .for_each(|(mut acceleration, position, details, activity)| {
+            if !activity.runs(&tick) {
+                return;
+            }
+            let Some(country) = details.location.as_deref().and_then(locate_country) else {
+                return;
+            };
+            acceleration.0 += (country - position.0) * COUNTRY_STRENGTH;
+        });
+}
@@ -0,0 +1,190 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        event::{Event, EventReader},
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, Res, ResMut, Resource},
+    },
+    math::Vec2,
+};
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    f32::consts::TAU,
+};
+
+use super::{Paused, Pinned, Position, Relationship, Velocity};
+use crate::interact::Selected;
+
+/// How many fixed updates a re-layout relaxes for
+const TICKS: u32 = 60;
+
+/// Distance between the rings of each hop out from the re-layout root
+const RING_SPACING: f32 = 60.;
+
+/// Distance between nodes along a ring
+const NODE_SPACING: f32 = 20.;
+
+/// Largest random kick given to a relaxing node per update, cooling to nothing over the relaxation
+const TEMPERATURE: f32 = 20.;
+
+/// Sent to re-layout the cluster around a node, for untangling one that settled badly
+///
+/// The cluster is the connected selected nodes if the node is selected, otherwise just the node
+/// and its neighbors.
+#[derive(Debug, Event)]
+pub struct Relayout(pub Entity);
+
+/// A cluster relaxing while the rest of the graph is pinned in place
+#[derive(Debug, Resource)]
+struct Relaxing {
+    cluster: HashSet<Entity>,
+    /// Nodes pinned for the relaxation, which need unpinning once it's done
+    pinned: Vec<Entity>,
+    remaining: u32,
+}
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Relayout>();
+        app.add_systems(bevy::app::Update, start);
+        app.add_systems(
+            bevy::app::FixedUpdate,
+            relax.after(super::update_velocities),
+        );
+    }
+}
+
+fn unpin(entities: &[Entity], pinned: &mut Query<&mut Pinned>) {
+    for &entity in entities {
+        if let Ok(mut pinned) = pinned.get_mut(entity) {
+            pinned.count -= 1;
+        }
+    }
+}
+
+/// Nodes in the cluster around `root`, along with how many hops away from it they are
+fn cluster(
+    root: Entity,
+    relationships: &Query<&Relationship>,
+    selected: &Query<(), With<Selected>>,
+) -> Vec<(Entity, usize)> {
+    let mut neighbors = HashMap::<Entity, Vec<Entity>>::new();
+    for rel in relationships {
+        neighbors.entry(rel.from).or_default().push(rel.to);
+        neighbors.entry(rel.to).or_default().push(rel.from);
+    }
+
+    let within_selection = selected.contains(root);
+    let mut seen = HashSet::from([root]);
+    let mut queue = VecDeque::from([(root, 0)]);
+    let mut cluster = Vec::new();
+    while let Some((entity, hops)) = queue.pop_front() {
+        cluster.push((entity, hops));
+        if !within_selection && hops == 1 {
+            continue;
+        }
+        for &neighbor in neighbors.get(&entity).into_iter().flatten() {
+            if within_selection && !selected.contains(neighbor) {
+                continue;
+            }
+            if seen.insert(neighbor) {
+                queue.push_back((neighbor, hops + 1));
+            }
+        }
+    }
+    cluster
+}
+
+/// Re-seed the cluster with a radial layout around its root, and pin everything else
+fn start(
+    mut events: EventReader<Relayout>,
+    relaxing: Option<Res<Relaxing>>,
+    relationships: Query<&Relationship>,
+    selected: Query<(), With<Selected>>,
+    mut nodes: Query<(Entity, &mut Position, &mut Velocity)>,
+    mut pinned: Query<&mut Pinned>,
+    mut commands: Commands,
+) {
+    let Some(&Relayout(root)) = events.read().last() else {
+        return;
+    };
+    let Ok((_, center, _)) = nodes.get(root) else {
+        return;
+    };
+    let center = center.0;
+
+    if let Some(relaxing) = relaxing {
+        unpin(&relaxing.pinned, &mut pinned);
+    }
+
+    let cluster = cluster(root, &relationships, &selected);
+
+    let mut rings = HashMap::<usize, Vec<Entity>>::new();
+    for &(entity, hops) in &cluster {
+        rings.entry(hops).or_default().push(entity);
+    }
+    for (hops, ring) in rings {
+        let radius = (hops as f32 * RING_SPACING).max(ring.len() as f32 * NODE_SPACING / TAU);
+        let start = rand::random::<f32>() * TAU;
+        for (i, entity) in ring.iter().enumerate() {
+            let Ok((_, mut position, mut velocity)) = nodes.get_mut(*entity) else {
+                continue;
+            };
+            let angle = start + i as f32 * TAU / ring.len() as f32;
+            position.0 = center + Vec2::from_angle(angle) * radius;
+            velocity.0 = Vec2::ZERO;
+        }
+    }
+
+    let cluster = HashSet::<Entity>::from_iter(cluster.into_iter().map(|(entity, _)| entity));
+    let mut pinned_for_relaxing = Vec::new();
+    for (entity, ..) in &nodes {
+        if cluster.contains(&entity) {
+            continue;
+        }
+        if let Ok(mut pinned) = pinned.get_mut(entity) {
+            pinned.count += 1;
+            pinned_for_relaxing.push(entity);
+        }
+    }
+
+    tracing::info!(nodes = cluster.len(), "re-laying out cluster");
+
+    commands.insert_resource(Relaxing {
+        cluster,
+        pinned: pinned_for_relaxing,
+        remaining: TICKS,
+    });
+}
+
+/// Shake the cluster with a cooling random kick each update, letting it escape the tangle it was in
+fn relax(
+    paused: Res<Paused>,
+    relaxing: Option<ResMut<Relaxing>>,
+    mut velocities: Query<&mut Velocity>,
+    mut pinned: Query<&mut Pinned>,
+    mut commands: Commands,
+) {
+    let Some(mut relaxing) = relaxing else { return };
+    if paused.0 {
+        return;
+    }
+
+    let temperature = TEMPERATURE * relaxing.remaining as f32 / TICKS as f32;
+    for &entity in &relaxing.cluster {
+        if let Ok(mut velocity) = velocities.get_mut(entity) {
+            velocity.0 +=
+                Vec2::new(rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5) * temperature;
+        }
+    }
+
+    relaxing.remaining -= 1;
+    if relaxing.remaining == 0 {
+        unpin(&relaxing.pinned, &mut pinned);
+        commands.remove_resource::<Relaxing>();
+    }
+}
@@ -16,6 +16,7 @@ use bevy::{
 
 use std::{
     collections::{hash_map, HashMap, HashSet},
+    f32::consts::TAU,
     hash::BuildHasherDefault,
     sync::atomic::{AtomicU64, Ordering},
     time::Instant,
@@ -23,7 +24,15 @@ use std::{
 
 use rand::distr::{Distribution, Uniform};
 
-mod diagnostic;
+use self::activity::{Activity, Tick};
+use crate::data::RelationKind;
+
+pub mod activity;
+pub mod diagnostic;
+pub mod frozen;
+pub mod geo;
+pub mod layout;
+pub mod rings;
 
 #[derive(Debug, Default, Component, Copy, Clone)]
 pub struct Position(pub Vec2);
@@ -52,6 +61,7 @@ pub struct MotionBundle {
     pub acceleration: Acceleration,
     pinned: Pinned,
     relation_count: RelationCount,
+    activity: Activity,
 }
 
 impl MotionBundle {
@@ -69,34 +79,84 @@ impl MotionBundle {
             acceleration: Acceleration(Vec2::ZERO),
             pinned: Pinned::default(),
             relation_count: RelationCount::default(),
+            activity: Activity::default(),
+        }
+    }
+}
+
+/// Places a batch of new neighbors of a node evenly around a ring sized to fit them, shifted away
+/// from crowded partitions, so that hundreds of fans spawning at once don't explode outwards
+#[derive(Debug, Clone)]
+pub struct Ring {
+    center: Vec2,
+    radius: f32,
+    start: f32,
+    count: usize,
+    placed: usize,
+}
+
+impl Ring {
+    /// Distance between neighbors along the ring
+    const SPACING: f32 = 20.;
+    const MIN_RADIUS: f32 = 50.;
+
+    pub fn around(parent: Vec2, count: usize, partitions: &Partitions) -> Self {
+        let radius = (count as f32 * Self::SPACING / TAU).max(Self::MIN_RADIUS);
+
+        // Look one partition out in a few directions, and lean towards the emptiest if it's any
+        // emptier than where the parent is
+        let (direction, density) = (0..8)
+            .map(|i| Vec2::from_angle(i as f32 * TAU / 8.))
+            .map(|direction| {
+                let density = partitions.density(parent + direction * Partitions::SIZE);
+                (direction, density)
+            })
+            .min_by_key(|&(_, density)| density)
+            .unwrap();
+        let center = if density < partitions.density(parent) {
+            parent + direction * radius / 2.
+        } else {
+            parent
+        };
+
+        Self {
+            center,
+            radius,
+            start: rand::random::<f32>() * TAU,
+            count: count.max(1),
+            placed: 0,
         }
     }
 
-    pub fn random_near(position: Vec2) -> Self {
+    /// Motion for the next neighbor around the ring
+    pub fn place(&mut self) -> MotionBundle {
         let mut rng = rand::rng();
-        let positions = Uniform::new(-100.0, 100.0).unwrap();
         let velocities = Uniform::new(-10.0, 10.0).unwrap();
 
-        let position = position + Vec2::new(positions.sample(&mut rng), positions.sample(&mut rng));
+        let angle = self.start + self.placed as f32 * TAU / self.count as f32;
+        self.placed += 1;
+        let position = self.center + Vec2::from_angle(angle) * self.radius;
         let velocity = Vec2::new(velocities.sample(&mut rng), velocities.sample(&mut rng));
 
-        Self {
+        MotionBundle {
             position: Position(position),
             velocity: Velocity(velocity),
             acceleration: Acceleration(Vec2::ZERO),
             pinned: Pinned::default(),
             relation_count: RelationCount::default(),
+            activity: Activity::default(),
         }
     }
 }
 
+/// Number of unique neighbors, there is only ever one [`Relationship`] per pair of nodes
 #[derive(Debug, Default, Component)]
 pub struct RelationCount {
     pub count: u32,
 }
 
 #[derive(Debug, Component, Copy, Clone, Eq, PartialEq, Hash)]
-#[component(on_add = increment_relation_count)]
+#[component(on_add = increment_relation_count, on_remove = decrement_relation_count)]
 pub struct Relationship {
     pub from: Entity,
     pub to: Entity,
@@ -105,13 +165,93 @@ pub struct Relationship {
 #[derive(Debug, Component, Copy, Clone)]
 pub struct Weight(pub f32);
 
+/// Bitflags of the ways a relationship has been found, so that the same pair of nodes shares a
+/// single relationship entity however many times it is seen
+#[derive(Debug, Default, Component, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Multiplicity(u16);
+
+impl Multiplicity {
+    pub const FAN: Self = Self(1 << 0);
+    pub const COLLECTION: Self = Self(1 << 1);
+    pub const ARTIST: Self = Self(1 << 2);
+    pub const RANDOM: Self = Self(1 << 3);
+    pub const LABEL: Self = Self(1 << 4);
+    pub const TAG: Self = Self(1 << 5);
+    pub const WISHLIST: Self = Self(1 << 6);
+    pub const FOLLOW: Self = Self(1 << 7);
+    pub const ROSTER: Self = Self(1 << 8);
+    pub const TRACK: Self = Self(1 << 9);
+    pub const CREDIT: Self = Self(1 << 10);
+    pub const SUPPORT: Self = Self(1 << 11);
+
+    pub fn insert(&mut self, other: impl Into<Self>) {
+        let other = other.into();
+        self.0 |= other.0;
+    }
+
+    /// The kinds of relationship found, leaving out random ones
+    pub fn kinds(self) -> impl Iterator<Item = RelationKind> {
+        [
+            RelationKind::Fan,
+            RelationKind::Collection,
+            RelationKind::Artist,
+            RelationKind::Label,
+            RelationKind::Tag,
+            RelationKind::Wishlist,
+            RelationKind::Follow,
+            RelationKind::Roster,
+            RelationKind::Track,
+            RelationKind::Credit,
+            RelationKind::Support,
+        ]
+        .into_iter()
+        .filter(move |&kind| self.0 & Self::from(kind).0 != 0)
+    }
+}
+
+impl From<RelationKind> for Multiplicity {
+    fn from(kind: RelationKind) -> Self {
+        match kind {
+            RelationKind::Fan => Self::FAN,
+            RelationKind::Collection => Self::COLLECTION,
+            RelationKind::Artist => Self::ARTIST,
+            RelationKind::Label => Self::LABEL,
+            RelationKind::Tag => Self::TAG,
+            RelationKind::Wishlist => Self::WISHLIST,
+            RelationKind::Follow => Self::FOLLOW,
+            RelationKind::Roster => Self::ROSTER,
+            RelationKind::Track => Self::TRACK,
+            RelationKind::Credit => Self::CREDIT,
+            RelationKind::Support => Self::SUPPORT,
+        }
+    }
+}
+
 fn increment_relation_count(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
-    let Relationship { from, to, .. } = *world.get::<Relationship>(entity).unwrap();
-    world.get_mut::<RelationCount>(from).unwrap().count += 1;
-    world.get_mut::<RelationCount>(to).unwrap().count += 1;
+    let relationship = *world.get::<Relationship>(entity).unwrap();
+    world
+        .get_mut::<RelationCount>(relationship.from)
+        .unwrap()
+        .count += 1;
+    world
+        .get_mut::<RelationCount>(relationship.to)
+        .unwrap()
+        .count += 1;
+    crate::data::events::edge_added(&mut world, relationship);
+}
+
+fn decrement_relation_count(mut world: DeferredWorld, entity: Entity, _id: ComponentId) {
+    let relationship = *world.get::<Relationship>(entity).unwrap();
+    // The nodes may be despawning too
+    for node in [relationship.from, relationship.to] {
+        if let Some(mut relation_count) = world.get_mut::<RelationCount>(node) {
+            relation_count.count -= 1;
+        }
+    }
+    crate::known::unindex_relationship(&mut world, relationship, entity);
 }
 
-#[derive(Debug, Default, Resource, Copy, Clone)]
+#[derive(Debug, Default, Resource, Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub enum OriginForceMode {
     #[default]
     Unit,
@@ -152,6 +292,13 @@ impl Partitions {
         self.0.entry(to).or_default().insert(entity);
     }
 
+    /// How many nodes are in the same partition as a point
+    pub fn density(&self, point: Vec2) -> usize {
+        self.0
+            .get(&Self::key(point))
+            .map_or(0, |partition| partition.len())
+    }
+
     fn add(&mut self, key: I64Vec2, entity: Entity) {
         self.0.entry(key).or_default().insert(entity);
     }
@@ -217,8 +364,13 @@ impl bevy::app::Plugin for Plugin {
         );
         app.insert_resource(Paused(false));
         app.insert_resource(Partitions::default());
-        app.insert_resource(OriginForceMode::default());
+        app.init_resource::<OriginForceMode>();
+        app.add_plugins(self::activity::Plugin);
         app.add_plugins(self::diagnostic::Plugin);
+        app.add_plugins(self::frozen::Plugin);
+        app.add_plugins(self::geo::Plugin);
+        app.add_plugins(self::layout::Plugin);
+        app.add_plugins(self::rings::Plugin);
     }
 }
 
@@ -251,6 +403,7 @@ fn predict_positions(
         &Position,
         &Velocity,
         Option<&Pinned>,
+        &Activity,
     )>,
     time: Res<Time<Fixed>>,
 ) {
@@ -260,8 +413,8 @@ fn predict_positions(
 
     query
         .par_iter_mut()
-        .for_each(|(mut predicted, position, velocity, pinned)| {
-            if pinned.map_or(0, |p| p.count) == 0 {
+        .for_each(|(mut predicted, position, velocity, pinned, activity)| {
+            if pinned.map_or(0, |p| p.count) == 0 && *activity != Activity::Frozen {
                 predicted.0 = position.0 + velocity.0 * time.overstep_fraction();
             } else {
                 predicted.0 = position.0;
@@ -306,7 +459,8 @@ fn check_yeet(query: Query<(&Position, &Velocity, &Acceleration)>, mut diagnosti
 
 fn update_positions(
     paused: Res<Paused>,
-    mut query: Query<(&mut Position, &Velocity, Option<&Pinned>)>,
+    tick: Res<Tick>,
+    mut query: Query<(&mut Position, &Velocity, Option<&Pinned>, &Activity)>,
     mut diagnostics: Diagnostics,
 ) {
     if paused.0 {
@@ -317,8 +471,8 @@ fn update_positions(
 
     query
         .iter_mut()
-        .for_each(|(mut position, velocity, pinned)| {
-            if pinned.map_or(0, |p| p.count) == 0 {
+        .for_each(|(mut position, velocity, pinned, activity)| {
+            if pinned.map_or(0, |p| p.count) == 0 && activity.runs(&tick) {
                 position.0 = position.0 + velocity.0;
             }
         });
@@ -364,7 +518,8 @@ fn update_partitions(
 
 fn update_velocities(
     paused: Res<Paused>,
-    mut query: Query<(&mut Velocity, &Acceleration, Option<&Pinned>)>,
+    tick: Res<Tick>,
+    mut query: Query<(&mut Velocity, &Acceleration, Option<&Pinned>, &Activity)>,
     mut diagnostics: Diagnostics,
 ) {
     if paused.0 {
@@ -375,8 +530,8 @@ fn update_velocities(
 
     query
         .par_iter_mut()
-        .for_each(|(mut velocity, acceleration, pinned)| {
-            if pinned.map_or(0, |p| p.count) == 0 {
+        .for_each(|(mut velocity, acceleration, pinned, activity)| {
+            if pinned.map_or(0, |p| p.count) == 0 && activity.runs(&tick) {
                 velocity.0 = (velocity.0 * 0.7 + acceleration.0).clamp_length_max(50.0);
             }
         });
@@ -389,7 +544,8 @@ fn update_velocities(
 fn repel(
     paused: Res<Paused>,
     origin_force_mode: Res<OriginForceMode>,
-    mut nodes: Query<(&mut Acceleration, &Position)>,
+    tick: Res<Tick>,
+    mut nodes: Query<(&mut Acceleration, &Position, &Activity)>,
     partitions: Res<Partitions>,
     positions: Query<&Position>,
     mut diagnostics: Diagnostics,
@@ -427,7 +583,12 @@ fn repel(
 
     nodes
         .par_iter_mut()
-        .for_each(|(mut acceleration, position)| {
+        .for_each(|(mut acceleration, position, activity)| {
+            // Nodes that aren't integrated this update would have their acceleration ignored
+            if !activity.runs(&tick) {
+                return;
+            }
+
             acceleration.0 = match *origin_force_mode {
                 OriginForceMode::Unit => position.0 * -0.005,
                 OriginForceMode::Square => position.0 * position.0.length() * -0.00005,
@@ -480,8 +641,9 @@ fn repel(
 
 fn attract(
     paused: Res<Paused>,
+    tick: Res<Tick>,
     relationships: Query<(&Relationship, &Weight)>,
-    mut nodes: Query<(&mut Acceleration, &Position, &RelationCount)>,
+    mut nodes: Query<(&mut Acceleration, &Position, &RelationCount, &Activity)>,
     mut diagnostics: Diagnostics,
 ) {
     if paused.0 {
@@ -492,18 +654,21 @@ fn attract(
 
     relationships.iter().for_each(|(rel, weight)| {
         let attraction = {
-            let Ok((_, from, _)) = nodes.get(rel.from) else {
+            let Ok((_, from, _, from_activity)) = nodes.get(rel.from) else {
                 return;
             };
-            let Ok((_, to, _)) = nodes.get(rel.to) else {
+            let Ok((_, to, _, to_activity)) = nodes.get(rel.to) else {
                 return;
             };
+            if !from_activity.runs(&tick) && !to_activity.runs(&tick) {
+                return;
+            }
             (to.0 - from.0) * weight.0 / 10.
         };
-        if let Ok((mut from, _, relations)) = nodes.get_mut(rel.from) {
+        if let Ok((mut from, _, relations, _)) = nodes.get_mut(rel.from) {
             from.0 += attraction / (relations.count as f32);
         }
-        if let Ok((mut to, _, relations)) = nodes.get_mut(rel.to) {
+        if let Ok((mut to, _, relations, _)) = nodes.get_mut(rel.to) {
             to.0 -= attraction / (relations.count as f32);
         }
     });
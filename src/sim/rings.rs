@@ -0,0 +1,110 @@
+use bevy::{
+    ecs::{
+        event::EventReader,
+        schedule::IntoSystemConfigs,
+        system::{Query, Res, ResMut, Resource},
+    },
+    input::keyboard::{Key, KeyboardInput},
+};
+
+use super::{
+    activity::{Activity, Tick},
+    Acceleration, Paused, Position,
+};
+use crate::{
+    data::{EntityType, ReleaseDetails},
+    ui::editor::Editor,
+};
+
+/// Radius of the ring for the earliest year
+const INNER_RADIUS: f32 = 200.;
+
+/// Distance between the rings of consecutive years
+const YEAR_SPACING: f32 = 150.;
+
+/// How far outside the latest ring fans are kept
+const FAN_MARGIN: f32 = 300.;
+
+/// How strongly nodes are pulled onto their ring, per unit of distance from it
+const STRENGTH: f32 = 0.02;
+
+/// Constrains releases to rings by the year they were released, with fans kept outside all of
+/// them and artists left to float between their releases, toggled with `k`
+#[derive(Debug, Default, Resource)]
+pub struct YearRings(pub bool);
+
+pub(super) struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<YearRings>();
+        app.add_systems(bevy::app::Update, toggle);
+        app.add_systems(
+            bevy::app::FixedUpdate,
+            pull.after(super::attract).before(super::update_velocities),
+        );
+    }
+}
+
+fn toggle(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    mut rings: ResMut<YearRings>,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("k".into()) {
+            rings.0 ^= true;
+        }
+    }
+}
+
+fn pull(
+    paused: Res<Paused>,
+    rings: Res<YearRings>,
+    tick: Res<Tick>,
+    mut nodes: Query<(
+        &mut Acceleration,
+        &Position,
+        &EntityType,
+        Option<&ReleaseDetails>,
+        &Activity,
+    )>,
+) {
+    if paused.0 || !rings.0 {
+        return;
+    }
+
+    let years = nodes
+        .iter()
+        .filter_map(|(_, _, _, release, _)| release.map(|release| release.released.year()))
+        .fold(None, |range, year| match range {
+            None => Some((year, year)),
+            Some((first, last)) => Some((year.min(first), year.max(last))),
+        });
+    let Some((first, last)) = years else {
+        return;
+    };
+    let radius = |year: i16| INNER_RADIUS + f32::from(year - first) * YEAR_SPACING;
+    let outer = radius(last) + FAN_MARGIN;
+
+    nodes
+        .par_iter_mut()
+        .for_each(|(mut acceleration, position, ty, release, activity)| {
+            if !activity.runs(&tick) {
+                return;
+            }
+
+            let distance = position.0.length();
+            let target = match (ty, release) {
+                (EntityType::Release, Some(release)) => radius(release.released.year()),
+                (EntityType::User, _) if distance < outer => outer,
+                _ => return,
+            };
+            acceleration.0 += position.0.normalize_or_zero() * (target - distance) * STRENGTH;
+        });
+}
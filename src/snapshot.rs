@@ -0,0 +1,213 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        query::With,
+        system::{Commands, Query, Res, ResMut, Resource},
+    },
+    hierarchy::DespawnRecursiveExt,
+    input::keyboard::{Key, KeyboardInput},
+};
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    background::Scraper,
+    data::EntityType,
+    graph::GraphQuery,
+    load::Loading,
+    ui::{
+        editor::{Editor, Target},
+        notify::Notify,
+    },
+};
+
+/// Send to save the graph and its layout under a name, replacing any snapshot with the same name
+#[derive(Debug, Event)]
+pub struct TakeSnapshot(pub String);
+
+/// Send to replace the graph with a snapshot taken earlier, this only asks for confirmation and
+/// sending it again with the same name restores it
+#[derive(Debug, Event)]
+pub struct RestoreSnapshot(pub String);
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Entry {
+    name: String,
+    /// The export of the graph, relative to the snapshot directory
+    file: String,
+    taken: jiff::Timestamp,
+}
+
+/// Named exports of the graph to switch back to, so destructive changes like pruning can be tried
+/// out, persisted in the data directory
+#[derive(Debug, Resource)]
+pub struct Snapshots {
+    dir: PathBuf,
+    stored: Vec<Entry>,
+    /// The snapshot waiting on confirmation to be restored
+    unconfirmed: Option<String>,
+}
+
+impl Snapshots {
+    #[culpa::try_fn]
+    pub fn load(data_dir: &Path) -> eyre::Result<Self> {
+        let dir = data_dir.join("snapshots");
+        std::fs::create_dir_all(&dir)?;
+        let stored = match std::fs::read_to_string(dir.join("index.json")) {
+            Ok(data) => serde_json::from_str(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => Err(err)?,
+        };
+        Self {
+            dir,
+            stored,
+            unconfirmed: None,
+        }
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.try_save() {
+            tracing::error!(?error, "failed saving snapshot index");
+        }
+    }
+
+    #[culpa::try_fn]
+    fn try_save(&self) -> eyre::Result<()> {
+        std::fs::write(
+            self.dir.join("index.json"),
+            serde_json::to_string_pretty(&self.stored)?,
+        )?;
+    }
+
+    fn names(&self) -> String {
+        Vec::from_iter(self.stored.iter().map(|entry| entry.name.as_str())).join(", ")
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<TakeSnapshot>();
+        app.add_event::<RestoreSnapshot>();
+        app.add_systems(bevy::app::Update, (keys, take, restore));
+    }
+}
+
+fn keys(
+    mut events: EventReader<KeyboardInput>,
+    mut editor: ResMut<Editor>,
+    snapshots: Res<Snapshots>,
+    mut notify: EventWriter<Notify>,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        if event.logical_key == Key::Character("d".into()) {
+            editor.open(Target::TakeSnapshot, String::new());
+        } else if event.logical_key == Key::Character("j".into()) {
+            let Some(latest) = snapshots.stored.last() else {
+                notify.send(Notify("no snapshots taken yet".to_owned()));
+                continue;
+            };
+            notify.send(Notify(format!("snapshots: {}", snapshots.names())));
+            editor.open(Target::RestoreSnapshot, latest.name.clone());
+        }
+    }
+}
+
+fn take(
+    mut events: EventReader<TakeSnapshot>,
+    mut snapshots: ResMut<Snapshots>,
+    graph: GraphQuery,
+    mut notify: EventWriter<Notify>,
+) {
+    for TakeSnapshot(name) in events.read() {
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let taken = jiff::Timestamp::now();
+        let file = format!("{}.json", taken.as_millisecond());
+        if let Err(error) = graph.snapshot().export(&snapshots.dir.join(&file)) {
+            tracing::error!(?error, "failed writing snapshot");
+            notify.send(Notify(format!("failed taking snapshot {name}")));
+            continue;
+        }
+
+        // Moved to the end, so it's the one offered for restoring next
+        if let Some(index) = snapshots.stored.iter().position(|entry| entry.name == name) {
+            let old = snapshots.stored.remove(index);
+            if let Err(error) = std::fs::remove_file(snapshots.dir.join(&old.file)) {
+                tracing::warn!(?error, "failed removing replaced snapshot");
+            }
+        }
+        snapshots.stored.push(Entry {
+            name: name.to_owned(),
+            file,
+            taken,
+        });
+        snapshots.save();
+        notify.send(Notify(format!("took snapshot {name}")));
+    }
+}
+
+fn restore(
+    mut events: EventReader<RestoreSnapshot>,
+    mut snapshots: ResMut<Snapshots>,
+    scraper: Res<Scraper>,
+    nodes: Query<Entity, With<EntityType>>,
+    mut notify: EventWriter<Notify>,
+    mut commands: Commands,
+) {
+    for RestoreSnapshot(name) in events.read() {
+        let name = name.trim();
+        let Some((file, taken)) = snapshots
+            .stored
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| (entry.file.clone(), entry.taken))
+        else {
+            notify.send(Notify(format!(
+                "no snapshot named {name}, there are: {}",
+                snapshots.names()
+            )));
+            continue;
+        };
+        if snapshots.unconfirmed.as_deref() != Some(name) {
+            snapshots.unconfirmed = Some(name.to_owned());
+            notify.send(Notify(format!(
+                "restoring {name} replaces the whole graph, restore it again to confirm"
+            )));
+            continue;
+        }
+        snapshots.unconfirmed = None;
+        let loading = match Loading::read(&snapshots.dir.join(&file)) {
+            Ok(loading) => loading,
+            Err(error) => {
+                tracing::error!(?error, "failed reading snapshot");
+                notify.send(Notify(format!("failed restoring snapshot {name}")));
+                continue;
+            }
+        };
+
+        // The id component hooks unindex the nodes and despawn their relationships
+        for entity in &nodes {
+            commands.entity(entity).despawn_recursive();
+        }
+        // Pages scraped since the snapshot was taken need scraping again to fill in its nodes
+        scraper.forget();
+        commands.insert_resource(loading);
+        notify.send(Notify(format!(
+            "restored snapshot {name} from {}",
+            taken.strftime("%Y-%m-%d %H:%M")
+        )));
+    }
+}
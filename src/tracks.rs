@@ -0,0 +1,69 @@
+use bevy::ecs::{
+    entity::Entity,
+    event::{Event, EventReader},
+    query::With,
+    system::{Commands, Query, Res, ResMut, Single},
+};
+
+use crate::{
+    background::Request,
+    data::{DiscoveredVia, RelationKind, Release, ReleaseDetails, Scrape, Url},
+    sim::{Multiplicity, Partitions, PredictedPosition, Relationship, Ring},
+    KnownEntities, RelationshipParent,
+};
+
+/// Spawn the tracks listed on an album as their own nodes around it
+#[derive(Debug, Event)]
+pub struct SpawnTracks(pub Entity);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<SpawnTracks>();
+        app.add_systems(bevy::app::Update, spawn);
+    }
+}
+
+fn spawn(
+    mut events: EventReader<SpawnTracks>,
+    mut known: ResMut<KnownEntities>,
+    albums: Query<(&Url, &ReleaseDetails, &PredictedPosition)>,
+    mut multiplicities: Query<&mut Multiplicity>,
+    partitions: Res<Partitions>,
+    relationship_parent: Single<Entity, With<RelationshipParent>>,
+    mut commands: Commands,
+) {
+    for &SpawnTracks(album) in events.read() {
+        let Ok((url, details, position)) = albums.get(album) else {
+            continue;
+        };
+        let via = DiscoveredVia::new(&Request::Release { url: url.0.clone() }, Some(album));
+        let mut ring = Ring::around(position.0, details.track_list.len(), &partitions);
+        for track in &details.track_list {
+            let Some(url) = &track.url else {
+                continue;
+            };
+            // Tracks already found on their artist's page are keyed on the same id
+            let entity = *known.releases.entry(track.id).or_insert_with(|| {
+                let release = Release {
+                    id: track.id,
+                    url: url.clone(),
+                };
+                commands
+                    .spawn((release, ring.place(), Scrape::None, via.clone()))
+                    .id()
+            });
+            known.relate(
+                Relationship {
+                    from: album,
+                    to: entity,
+                },
+                RelationKind::Track,
+                *relationship_parent,
+                &mut multiplicities,
+                &mut commands,
+            );
+        }
+    }
+}
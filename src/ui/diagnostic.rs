@@ -4,8 +4,8 @@ use bevy::{
     ecs::{
         component::Component,
         entity::Entity,
-        query::With,
-        system::{Commands, Query, Res, Single},
+        query::{With, Without},
+        system::{Commands, Query, Res, Resource, Single},
     },
     hierarchy::{
         BuildChildren, ChildBuild, ChildBuilder, Children, DespawnRecursiveExt, HierarchyQueryExt,
@@ -13,10 +13,23 @@ use bevy::{
     picking::PickingBehavior,
     text::TextFont,
     ui::widget::{Label, Text},
-    ui::{BackgroundColor, Display, GridPlacement, Node, PositionType, RepeatedGridTrack, Val},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, GridPlacement, Node, PositionType,
+        RepeatedGridTrack, Val,
+    },
 };
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+
+/// How many of the most recent measurements are plotted
+const PLOT_LENGTH: usize = 60;
+
+/// Diagnostics to plot the recent history of, toggled by a key
+#[derive(Debug, Resource)]
+pub struct Plots {
+    paths: HashSet<String>,
+    pub visible: bool,
+}
 
 pub struct Plugin;
 
@@ -24,7 +37,7 @@ impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.add_systems(bevy::app::Startup, setup);
         app.add_systems(bevy::app::PreUpdate, pre_update);
-        app.add_systems(bevy::app::Update, update);
+        app.add_systems(bevy::app::Update, (update, update_plots));
     }
 }
 
@@ -36,7 +49,20 @@ struct DiagnosticLine {
     path: DiagnosticPath,
 }
 
-fn setup(mut commands: Commands) {
+#[derive(Component)]
+struct DiagnosticPlot {
+    path: DiagnosticPath,
+}
+
+#[derive(Default, Component)]
+struct PlotBar;
+
+fn setup(args: Res<crate::Args>, mut commands: Commands) {
+    commands.insert_resource(Plots {
+        paths: HashSet::from_iter(args.plots.iter().cloned()),
+        visible: false,
+    });
+
     commands.spawn((
         Node {
             display: Display::Grid,
@@ -55,6 +81,7 @@ fn setup(mut commands: Commands) {
 
 fn pre_update(
     diagnostics: Res<DiagnosticsStore>,
+    plots: Res<Plots>,
     parent: Single<Entity, With<DiagnosticLines>>,
     lines: Query<&DiagnosticLine>,
     children: Query<&Children>,
@@ -87,7 +114,7 @@ fn pre_update(
     }
 
     impl PathNode<'_> {
-        fn spawn_children(&self, parent: &mut ChildBuilder<'_>, depth: usize) {
+        fn spawn_children(&self, parent: &mut ChildBuilder<'_>, plots: &Plots, depth: usize) {
             for (component, tree) in &self.children {
                 let mut title = parent.spawn((
                     Text::new(format!("{:depth$}{component}", "")),
@@ -104,6 +131,36 @@ fn pre_update(
                         PickingBehavior::IGNORE,
                         DiagnosticLine { path: path.clone() },
                     ));
+
+                    if plots.paths.contains(path.as_str()) {
+                        parent
+                            .spawn((
+                                Node {
+                                    display: Display::None,
+                                    flex_direction: FlexDirection::Row,
+                                    align_items: AlignItems::End,
+                                    grid_column: GridPlacement::span(2),
+                                    height: Val::Px(24.),
+                                    ..Node::default()
+                                },
+                                PickingBehavior::IGNORE,
+                                DiagnosticPlot { path: path.clone() },
+                            ))
+                            .with_children(|plot| {
+                                for _ in 0..PLOT_LENGTH {
+                                    plot.spawn((
+                                        Node {
+                                            width: Val::Px(2.),
+                                            height: Val::Percent(0.),
+                                            ..Node::default()
+                                        },
+                                        BackgroundColor(Color::hsl(90., 0.95, 0.7)),
+                                        PickingBehavior::IGNORE,
+                                        PlotBar,
+                                    ));
+                                }
+                            });
+                    }
                 } else {
                     title.insert(Node {
                         grid_column: GridPlacement::span(2),
@@ -111,7 +168,7 @@ fn pre_update(
                     });
                 }
 
-                tree.spawn_children(parent, depth + 2);
+                tree.spawn_children(parent, plots, depth + 2);
             }
         }
     }
@@ -129,7 +186,7 @@ fn pre_update(
 
     commands
         .entity(*parent)
-        .with_children(|parent| tree.spawn_children(parent, 0));
+        .with_children(|parent| tree.spawn_children(parent, &plots, 0));
 }
 
 fn update(diagnostics: Res<DiagnosticsStore>, mut lines: Query<(&mut Text, &DiagnosticLine)>) {
@@ -157,3 +214,51 @@ fn update(diagnostics: Res<DiagnosticsStore>, mut lines: Query<(&mut Text, &Diag
         }
     });
 }
+
+fn update_plots(
+    diagnostics: Res<DiagnosticsStore>,
+    plots: Res<Plots>,
+    mut plot_nodes: Query<(&DiagnosticPlot, &Children, &mut Node), Without<PlotBar>>,
+    mut bars: Query<&mut Node, With<PlotBar>>,
+) {
+    for (plot, children, mut node) in &mut plot_nodes {
+        let display = if plots.visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+
+        if !plots.visible {
+            continue;
+        }
+
+        let Some(diagnostic) = diagnostics.get(&plot.path) else {
+            continue;
+        };
+
+        let values = Vec::from_iter(diagnostic.values().copied());
+        let values = &values[values.len().saturating_sub(PLOT_LENGTH)..];
+        let max = values.iter().copied().fold(0., f64::max);
+
+        // Right align so the newest measurement is always at the end
+        let padding = PLOT_LENGTH - values.len();
+        for (i, &child) in children.iter().enumerate() {
+            let Ok(mut bar) = bars.get_mut(child) else {
+                continue;
+            };
+            let value = i
+                .checked_sub(padding)
+                .and_then(|i| values.get(i))
+                .copied()
+                .unwrap_or(0.);
+            bar.height = if max > 0. {
+                Val::Percent((value / max * 100.) as f32)
+            } else {
+                Val::Percent(0.)
+            };
+        }
+    }
+}
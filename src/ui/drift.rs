@@ -0,0 +1,103 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        change_detection::DetectChangesMut,
+        component::Component,
+        event::EventWriter,
+        observer::Trigger,
+        query::With,
+        system::{Commands, Query, Res, Single},
+    },
+    picking::{
+        events::{Click, Pointer},
+        pointer::PointerButton,
+    },
+    render::view::Visibility,
+    text::{TextColor, TextFont},
+    ui::widget::{Button, Label, Text},
+    ui::{BackgroundColor, Node, PositionType, Val},
+};
+
+use crate::{background::Scraper, ui::notify::Notify};
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, update);
+        app.add_observer(button_click);
+    }
+}
+
+#[derive(Default, Component)]
+struct DriftWarning;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(40.),
+            top: Val::Px(0.),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        Text::default(),
+        TextFont::default(),
+        TextColor(Color::srgb(1.0, 0.6, 0.2)),
+        Label,
+        Button,
+        DriftWarning,
+        Visibility::Hidden,
+    ));
+}
+
+/// A single warning covering every page that failed to parse, the pages themselves are kept in
+/// the quarantine database in the cache directory along with the scrapes that failed on them,
+/// which are retried from the cache when the warning is clicked
+fn update(
+    scraper: Res<Scraper>,
+    warning: Single<(&mut Text, &mut Visibility), With<DriftWarning>>,
+) {
+    let (mut text, mut visibility) = warning.into_inner();
+    let failures = scraper.parse_failures();
+    let failed = scraper.failed();
+    if failures == 0 && failed == 0 {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+
+    let mut new = if failures == 0 {
+        format!("{failed} scrapes failed to parse before")
+    } else {
+        format!("Bandcamp layout may have changed — {failures} pages failed to parse")
+    };
+    if failed > 0 {
+        new += ", click to retry them from the cache";
+    }
+    if text.0 != new {
+        text.0 = new;
+    }
+    visibility.set_if_neq(Visibility::Visible);
+}
+
+fn button_click(
+    trigger: Trigger<Pointer<Click>>,
+    warnings: Query<(), With<DriftWarning>>,
+    scraper: Res<Scraper>,
+    mut notify: EventWriter<Notify>,
+) {
+    if trigger.event.button != PointerButton::Primary || !warnings.contains(trigger.entity()) {
+        return;
+    }
+
+    match scraper.retry_failed() {
+        Ok(0) => {}
+        Ok(count) => {
+            notify.send(Notify(format!(
+                "retrying {count} failed scrapes from the cache"
+            )));
+        }
+        Err(error) => tracing::error!(?error, "failed to retry failed scrapes"),
+    }
+}
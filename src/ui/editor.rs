@@ -0,0 +1,244 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{EventReader, EventWriter},
+        query::With,
+        system::{Commands, Query, ResMut, Resource, Single},
+    },
+    input::keyboard::{Key, KeyboardInput},
+    picking::PickingBehavior,
+    render::view::Visibility,
+    text::TextFont,
+    ui::widget::{Label, Text},
+    ui::{BackgroundColor, Node, PositionType, Val},
+};
+
+use crate::{
+    analysis::RunQuery,
+    annotation::Annotation,
+    clipboard::AddSeeds,
+    preset::SavePreset,
+    render::rules::ColorRules,
+    search::Search,
+    snapshot::{RestoreSnapshot, TakeSnapshot},
+    ui::notify::Notify,
+};
+
+/// What is being edited
+#[derive(Debug, Clone, Copy)]
+pub enum Target {
+    Note(Entity),
+    /// Edited as a comma separated list
+    Tags(Entity),
+    /// Edited as a semicolon separated list
+    ColorRules,
+    /// The name to save the current view settings as
+    SavePreset,
+    /// Urls found in the clipboard to add as seeds, separated by spaces
+    Seeds,
+    /// Nodes to select, see [`crate::analysis::query::Query`]
+    Query,
+    /// Text to search Bandcamp for
+    Search,
+    /// The name to save a snapshot of the graph as
+    TakeSnapshot,
+    /// The name of the snapshot to replace the graph with
+    RestoreSnapshot,
+}
+
+impl Target {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Note(_) => "note",
+            Self::Tags(_) => "tags",
+            Self::ColorRules => "color rules",
+            Self::SavePreset => "save preset as",
+            Self::Seeds => "add seeds",
+            Self::Query => "select where",
+            Self::Search => "search Bandcamp for",
+            Self::TakeSnapshot => "take snapshot as",
+            Self::RestoreSnapshot => "restore snapshot",
+        }
+    }
+}
+
+/// Single line text entry, while it's open key presses go to it rather than toggling things
+#[derive(Debug, Default, Resource)]
+pub struct Editor {
+    editing: Option<Target>,
+    text: String,
+    /// Set when opened, so the key press that opened it isn't typed into it
+    opened: bool,
+}
+
+impl Editor {
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Start editing, with what the target is currently set to
+    pub fn open(&mut self, target: Target, text: String) {
+        self.text = text;
+        self.editing = Some(target);
+        self.opened = true;
+    }
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<Editor>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, edit);
+    }
+}
+
+#[derive(Default, Component)]
+struct EditorUi;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(30.),
+            bottom: Val::Percent(10.),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        Text::new(""),
+        TextFont::default(),
+        Label,
+        PickingBehavior::IGNORE,
+        EditorUi,
+        Visibility::Hidden,
+    ));
+}
+
+fn edit(
+    mut events: EventReader<KeyboardInput>,
+    mut editor: ResMut<Editor>,
+    mut annotations: Query<&mut Annotation>,
+    mut rules: ResMut<ColorRules>,
+    mut notify: EventWriter<Notify>,
+    mut save_preset: EventWriter<SavePreset>,
+    mut add_seeds: EventWriter<AddSeeds>,
+    mut run_query: EventWriter<RunQuery>,
+    mut search: EventWriter<Search>,
+    mut take_snapshot: EventWriter<TakeSnapshot>,
+    mut restore_snapshot: EventWriter<RestoreSnapshot>,
+    ui: Single<(&mut Text, &mut Visibility), With<EditorUi>>,
+    mut commands: Commands,
+) {
+    let (mut text, mut visibility) = ui.into_inner();
+
+    let Some(target) = editor.editing else {
+        events.clear();
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    if std::mem::take(&mut editor.opened) {
+        events.clear();
+    }
+
+    for event in events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                let value = std::mem::take(&mut editor.text);
+                editor.editing = None;
+                match target {
+                    Target::Note(entity) => {
+                        annotate(entity, &mut annotations, &mut commands, |annotation| {
+                            annotation.note = value.trim().to_owned();
+                        });
+                    }
+                    Target::Tags(entity) => {
+                        annotate(entity, &mut annotations, &mut commands, |annotation| {
+                            annotation.tags = value
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|tag| !tag.is_empty())
+                                .map(str::to_owned)
+                                .collect();
+                        });
+                    }
+                    Target::ColorRules => {
+                        if let Err(error) = rules.set(value.split(';')) {
+                            notify.send(Notify(format!("invalid color rule: {error}")));
+                        }
+                    }
+                    Target::SavePreset => {
+                        if !value.trim().is_empty() {
+                            save_preset.send(SavePreset(value.trim().to_owned()));
+                        }
+                    }
+                    Target::Seeds => {
+                        add_seeds.send(AddSeeds(value));
+                    }
+                    Target::Query => {
+                        run_query.send(RunQuery(value));
+                    }
+                    Target::Search => {
+                        search.send(Search(value));
+                    }
+                    Target::TakeSnapshot => {
+                        take_snapshot.send(TakeSnapshot(value));
+                    }
+                    Target::RestoreSnapshot => {
+                        restore_snapshot.send(RestoreSnapshot(value));
+                    }
+                }
+                break;
+            }
+            Key::Escape => {
+                editor.editing = None;
+                editor.text.clear();
+                break;
+            }
+            Key::Backspace => {
+                editor.text.pop();
+            }
+            Key::Space => {
+                editor.text.push(' ');
+            }
+            Key::Character(character) => {
+                editor.text.push_str(character);
+            }
+            _ => {}
+        }
+    }
+
+    if editor.is_editing() {
+        text.0 = format!(
+            "{}: {}_\n(enter to save, escape to cancel)",
+            target.label(),
+            editor.text,
+        );
+        *visibility = Visibility::Visible;
+    } else {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn annotate(
+    entity: Entity,
+    annotations: &mut Query<&mut Annotation>,
+    commands: &mut Commands,
+    apply: impl FnOnce(&mut Annotation),
+) {
+    if let Ok(mut annotation) = annotations.get_mut(entity) {
+        apply(&mut annotation);
+    } else if let Some(mut node) = commands.get_entity(entity) {
+        let mut annotation = Annotation::default();
+        apply(&mut annotation);
+        node.insert(annotation);
+    }
+}
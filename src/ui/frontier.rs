@@ -0,0 +1,178 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        observer::Trigger,
+        query::With,
+        schedule::{common_conditions::resource_changed, Condition, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
+    input::keyboard::{Key, KeyboardInput},
+    picking::{
+        events::{Click, Pointer},
+        pointer::PointerButton,
+        PickingBehavior,
+    },
+    render::view::Visibility,
+    text::TextFont,
+    time::common_conditions::on_timer,
+    ui::widget::{Button, Label, Text},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, JustifyContent, Node, PositionType,
+        UiRect, Val,
+    },
+};
+
+use std::time::Duration;
+
+use super::{
+    editor::Editor,
+    nearest::{label, NodeDetails},
+};
+use crate::{background::Scraper, data::Scrape, graph::GraphQuery, sim::RelationCount};
+
+/// How many of the best connected unscraped nodes are listed
+const ROWS: usize = 20;
+
+/// A panel listing the unscraped nodes with the most neighbors, each with a button to scrape it,
+/// toggled with `u`
+#[derive(Debug, Default, Resource)]
+pub struct FrontierPanel {
+    pub visible: bool,
+}
+
+/// A button that scrapes the node it lists
+#[derive(Component)]
+struct FrontierRow(Entity);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<FrontierPanel>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (
+                toggle,
+                update
+                    .after(toggle)
+                    .run_if(resource_changed::<FrontierPanel>.or(on_timer(Duration::from_secs(1)))),
+            ),
+        );
+        app.add_observer(button_click);
+    }
+}
+
+#[derive(Default, Component)]
+struct FrontierUi;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Start,
+            align_items: AlignItems::Start,
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.),
+            bottom: Val::Px(0.),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        PickingBehavior::IGNORE,
+        FrontierUi,
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle(
+    mut events: EventReader<KeyboardInput>,
+    editor: Res<Editor>,
+    mut panel: ResMut<FrontierPanel>,
+) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("u".into()) {
+            panel.visible ^= true;
+        }
+    }
+}
+
+fn update(
+    panel: Res<FrontierPanel>,
+    nodes: Query<(Entity, NodeDetails, &Scrape, &RelationCount)>,
+    ui: Single<(Entity, &mut Visibility), With<FrontierUi>>,
+    mut commands: Commands,
+) {
+    let (ui, mut visibility) = ui.into_inner();
+
+    if !panel.visible {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Visible;
+
+    let mut frontier = Vec::from_iter(
+        nodes
+            .iter()
+            .filter(|(_, _, scrape, _)| **scrape == Scrape::None)
+            .map(|(entity, details, _, relations)| {
+                (entity, *details.ty, label(&details), relations.count)
+            }),
+    );
+    let total = frontier.len();
+    frontier.sort_by(|a, b| b.3.cmp(&a.3).then_with(|| a.2.cmp(&b.2)));
+    frontier.truncate(ROWS);
+
+    commands.entity(ui).despawn_descendants();
+    commands.entity(ui).with_children(|ui| {
+        ui.spawn((
+            Text::new(format!("{total} unscraped nodes, click to scrape")),
+            TextFont::default(),
+            Label,
+            PickingBehavior::IGNORE,
+        ));
+        for (entity, ty, name, degree) in frontier {
+            ui.spawn((
+                Node {
+                    padding: UiRect::all(Val::Px(2.)),
+                    ..Node::default()
+                },
+                Button,
+                BackgroundColor(Color::NONE),
+                FrontierRow(entity),
+            ))
+            .with_child((
+                Text::new(format!("{degree:>4} {ty:?}: {name}")),
+                TextFont::default(),
+                Label,
+                PickingBehavior::IGNORE,
+            ));
+        }
+    });
+}
+
+fn button_click(
+    trigger: Trigger<Pointer<Click>>,
+    rows: Query<&FrontierRow, With<Button>>,
+    mut graph: GraphQuery,
+    scraper: Res<Scraper>,
+) {
+    let Ok(&FrontierRow(entity)) = rows.get(trigger.entity()) else {
+        return;
+    };
+
+    if trigger.event.button != PointerButton::Primary {
+        return;
+    }
+
+    graph.scrape(entity, &scraper);
+}
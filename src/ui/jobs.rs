@@ -0,0 +1,118 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, Res, Single},
+    },
+    hierarchy::{BuildChildren, Children},
+    picking::PickingBehavior,
+    render::view::Visibility,
+    text::TextFont,
+    time::common_conditions::on_timer,
+    ui::widget::{Label, Text},
+    ui::{BackgroundColor, Node, PositionType, UiRect, Val},
+};
+
+use std::time::Duration;
+
+use crate::background::Scraper;
+
+/// How many of the latest jobs are listed
+const ROWS: usize = 5;
+
+/// How long a job stays listed after its last scrape finished
+const LINGER: Duration = Duration::from_secs(30);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            update.run_if(on_timer(Duration::from_millis(500))),
+        );
+    }
+}
+
+/// Lists the progress of running and recently finished jobs, just above the network button
+#[derive(Default, Component)]
+struct JobsUi;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(40.),
+                bottom: Val::Px(32.),
+                padding: UiRect::all(Val::Px(6.)),
+                ..Node::default()
+            },
+            BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+            PickingBehavior::IGNORE,
+            Visibility::Hidden,
+            JobsUi,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont::default(),
+            Label,
+            PickingBehavior::IGNORE,
+        ));
+}
+
+fn update(
+    scraper: Res<Scraper>,
+    ui: Single<(&mut Visibility, &Children), With<JobsUi>>,
+    mut texts: Query<&mut Text>,
+) {
+    let (mut visibility, children) = ui.into_inner();
+
+    let jobs = Vec::from_iter(
+        scraper
+            .jobs()
+            .into_iter()
+            .rev()
+            .filter(|(_, stats)| {
+                stats
+                    .finished
+                    .is_none_or(|finished| finished.elapsed() < LINGER)
+            })
+            .take(ROWS),
+    );
+
+    if jobs.is_empty() {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Visible;
+
+    let new = Vec::from_iter(jobs.iter().map(|(job, stats)| {
+        let mut line = format!(
+            "job {job} {}: {}/{} done, {:.0?}",
+            stats.label,
+            stats.completed + stats.failed,
+            stats.queued,
+            Duration::from_secs(stats.elapsed().as_secs()),
+        );
+        if stats.failed > 0 {
+            line += &format!(", {} failed", stats.failed);
+        }
+        if let Some(eta) = scraper.job_eta(stats) {
+            line += &format!(", ~{:.0?} left", Duration::from_secs(eta.as_secs()));
+        }
+        line
+    }))
+    .join("\n");
+
+    for &child in children {
+        if let Ok(mut text) = texts.get_mut(child) {
+            if text.0 != new {
+                text.0.clone_from(&new);
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        schedule::{common_conditions::resource_changed, Condition, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
+    input::{keyboard::KeyCode, ButtonInput},
+    picking::PickingBehavior,
+    render::view::Visibility,
+    text::TextFont,
+    time::common_conditions::on_timer,
+    ui::widget::{Label, Text},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, JustifyContent, Node, PositionType,
+        Val,
+    },
+};
+
+use std::{collections::HashSet, time::Duration};
+
+use super::nearest::{label, lines, NodeDetails};
+use crate::{data::currency::Rates, data::EntityType, sim::Relationship};
+
+/// How many nodes are listed at once, and how far page up and down move
+const PAGE: usize = 30;
+
+/// A textual list of the graph's nodes grouped by type, navigable with the keyboard for when the
+/// spatial view isn't usable
+///
+/// Up and down move between nodes, right or enter expands a node to show its details and
+/// neighbors, left collapses it again.
+#[derive(Debug, Default, Resource)]
+pub struct ListView {
+    pub visible: bool,
+    cursor: Option<Entity>,
+    expanded: HashSet<Entity>,
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<ListView>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (
+                navigate,
+                update
+                    .after(navigate)
+                    .run_if(resource_changed::<ListView>.or(on_timer(Duration::from_secs(1)))),
+            ),
+        );
+    }
+}
+
+#[derive(Default, Component)]
+struct ListUi;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Start,
+            align_items: AlignItems::Start,
+            position_type: PositionType::Absolute,
+            right: Val::Px(0.),
+            top: Val::Px(0.),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        PickingBehavior::IGNORE,
+        ListUi,
+        Visibility::Hidden,
+    ));
+}
+
+/// All nodes in the order they're listed, by type then name
+fn ordered(nodes: &Query<(Entity, NodeDetails)>) -> Vec<(Entity, EntityType, String)> {
+    let mut ordered = Vec::from_iter(
+        nodes
+            .iter()
+            .map(|(entity, details)| (entity, *details.ty, label(&details))),
+    );
+    ordered.sort_by_cached_key(|(_, ty, label)| (*ty, label.to_lowercase()));
+    ordered
+}
+
+fn navigate(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut list: ResMut<ListView>,
+    nodes: Query<(Entity, NodeDetails)>,
+) {
+    const KEYS: [KeyCode; 9] = [
+        KeyCode::ArrowUp,
+        KeyCode::ArrowDown,
+        KeyCode::ArrowLeft,
+        KeyCode::ArrowRight,
+        KeyCode::Enter,
+        KeyCode::PageUp,
+        KeyCode::PageDown,
+        KeyCode::Home,
+        KeyCode::End,
+    ];
+
+    if !list.visible || !keys.any_just_pressed(KEYS) {
+        return;
+    }
+
+    let ordered = ordered(&nodes);
+    let Some(last) = ordered.len().checked_sub(1) else {
+        return;
+    };
+
+    let index = list
+        .cursor
+        .and_then(|cursor| ordered.iter().position(|&(entity, ..)| entity == cursor))
+        .unwrap_or(0);
+    let index = if keys.just_pressed(KeyCode::ArrowUp) {
+        index.saturating_sub(1)
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        (index + 1).min(last)
+    } else if keys.just_pressed(KeyCode::PageUp) {
+        index.saturating_sub(PAGE)
+    } else if keys.just_pressed(KeyCode::PageDown) {
+        (index + PAGE).min(last)
+    } else if keys.just_pressed(KeyCode::Home) {
+        0
+    } else if keys.just_pressed(KeyCode::End) {
+        last
+    } else {
+        index
+    };
+
+    let entity = ordered[index].0;
+    list.cursor = Some(entity);
+    if keys.any_just_pressed([KeyCode::ArrowRight, KeyCode::Enter]) {
+        list.expanded.insert(entity);
+    } else if keys.just_pressed(KeyCode::ArrowLeft) {
+        list.expanded.remove(&entity);
+    }
+}
+
+fn update(
+    list: Res<ListView>,
+    nodes: Query<(Entity, NodeDetails)>,
+    relationships: Query<&Relationship>,
+    rates: Res<Rates>,
+    ui: Single<(Entity, &mut Visibility), With<ListUi>>,
+    mut commands: Commands,
+) {
+    let (ui, mut visibility) = ui.into_inner();
+
+    if !list.visible {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Visible;
+
+    let ordered = ordered(&nodes);
+    let index = list
+        .cursor
+        .and_then(|cursor| ordered.iter().position(|&(entity, ..)| entity == cursor))
+        .unwrap_or(0);
+    let start = index.saturating_sub(PAGE / 2);
+    let end = (start + PAGE).min(ordered.len());
+
+    let mut rows = Vec::new();
+    let mut group = None;
+    for (i, &(entity, ty, ref name)) in ordered.iter().enumerate().take(end).skip(start) {
+        if group != Some(ty) {
+            group = Some(ty);
+            let count = ordered.iter().filter(|&&(_, other, _)| other == ty).count();
+            rows.push(format!("{ty:?}s ({count})"));
+        }
+
+        let cursor = if i == index { ">" } else { " " };
+        let expanded = list.expanded.contains(&entity);
+        let marker = if expanded { "-" } else { "+" };
+        rows.push(format!("{cursor} {marker} {name}"));
+
+        if expanded {
+            if let Ok((_, details)) = nodes.get(entity) {
+                for line in lines(&details, &rates) {
+                    rows.push(format!("      {line}"));
+                }
+            }
+            let neighbors = Vec::from_iter(relationships.iter().filter_map(|relationship| {
+                if relationship.from == entity {
+                    Some(relationship.to)
+                } else if relationship.to == entity {
+                    Some(relationship.from)
+                } else {
+                    None
+                }
+            }));
+            rows.push(format!("      {} neighbors", neighbors.len()));
+            for neighbor in neighbors {
+                if let Ok((_, details)) = nodes.get(neighbor) {
+                    rows.push(format!("        {:?}: {}", details.ty, label(&details)));
+                }
+            }
+        }
+    }
+
+    commands.entity(ui).despawn_descendants();
+    commands.entity(ui).with_children(|ui| {
+        for row in rows {
+            ui.spawn((
+                Text::new(row),
+                TextFont::default(),
+                Label,
+                PickingBehavior::IGNORE,
+            ));
+        }
+    });
+}
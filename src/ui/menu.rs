@@ -4,9 +4,10 @@ use bevy::{
         change_detection::{DetectChanges, Ref},
         component::Component,
         entity::Entity,
+        event::EventWriter,
         observer::Trigger,
-        query::{QueryData, With},
-        system::{Commands, Query, Res, Single},
+        query::{Has, QueryData, With},
+        system::{Commands, Query, Res, ResMut, Single},
     },
     hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
     input::{mouse::MouseButton, ButtonInput},
@@ -25,11 +26,23 @@ use bevy::{
 };
 
 use crate::{
-    background::Request,
+    annotation::Annotation,
+    archive::{Archived, Restore},
     camera::Cursor,
     data::{ArtistDetails, EntityType, ReleaseDetails, Scrape, Url, UserDetails},
-    interact::Nearest,
-    sim::Relationship,
+    graph::GraphQuery,
+    interact::{Nearest, Selected},
+    open::{open_url, OpenSelected, Opening},
+    policy::ScrapePolicy,
+    sample::{ExpandFans, UnspawnedFans},
+    sim::layout::Relayout,
+    tracks::SpawnTracks,
+    ui::{
+        editor::{Editor, Target},
+        preview::Preview,
+        raw::ViewRaw,
+    },
+    watch::Watched,
 };
 
 pub struct Plugin;
@@ -85,14 +98,31 @@ struct NodeDetails {
     release: Option<Ref<'static, ReleaseDetails>>,
     user: Option<Ref<'static, UserDetails>>,
     scrape: Ref<'static, Scrape>,
+    watched: Has<Watched>,
+    selected: Has<Selected>,
+    unspawned: Has<UnspawnedFans>,
+    archived: Has<Archived>,
 }
 
 #[derive(Component)]
 enum Action {
     Open,
+    OpenSelected,
+    Select,
+    Deselect,
+    Preview,
+    ViewRaw,
     Scrape,
     ScrapeDeep,
     ScrapeExtraDeep,
+    ExpandFans,
+    SpawnTracks,
+    RestoreArchived,
+    Relayout,
+    EditNote,
+    EditTags,
+    Watch,
+    Unwatch,
 }
 
 fn show_hide(
@@ -100,6 +130,8 @@ fn show_hide(
     cursor: Option<Res<Cursor>>,
     nearest: Option<Res<Nearest>>,
     details: Query<NodeDetails>,
+    selected: Query<(), With<Selected>>,
+    opening: Res<Opening>,
     mut menu: Single<Menu>,
     mut commands: Commands,
 ) {
@@ -125,7 +157,7 @@ fn show_hide(
             commands.despawn_descendants();
 
             commands.with_children(|menu| {
-                let mut button = |text: &'static str, action: Action| {
+                let mut button = |text: &str, action: Action| {
                     menu.spawn((
                         Node {
                             padding: UiRect::all(Val::Px(6.)),
@@ -144,6 +176,29 @@ fn show_hide(
 
                 button("open url", Action::Open);
 
+                let count = selected.iter().count();
+                if opening.unconfirmed() == Some(count) {
+                    button(
+                        &format!("confirm opening {count} urls"),
+                        Action::OpenSelected,
+                    );
+                } else if count > 0 {
+                    button(&format!("open {count} selected urls"), Action::OpenSelected);
+                }
+
+                if details.selected {
+                    button("deselect", Action::Deselect);
+                } else {
+                    button("select", Action::Select);
+                }
+
+                // Tags are scraped from the discover api rather than their page, so there's nothing cached
+                // to preview
+                if *details.scrape >= Scrape::Shallow && *details.ty != EntityType::Tag {
+                    button("preview page", Action::Preview);
+                    button("view raw data", Action::ViewRaw);
+                }
+
                 match *details.scrape {
                     Scrape::None => button("scrape", Action::Scrape),
                     Scrape::InProgress => {}
@@ -151,6 +206,39 @@ fn show_hide(
                     Scrape::Deep => button("scrape (extra deep)", Action::ScrapeExtraDeep),
                     Scrape::ExtraDeep => {}
                 }
+
+                if details.unspawned {
+                    button("expand all fans", Action::ExpandFans);
+                }
+
+                if let Some(release) = details.release.as_deref() {
+                    if !release.track_list.is_empty() {
+                        button(
+                            &format!("spawn {} tracks", release.track_list.len()),
+                            Action::SpawnTracks,
+                        );
+                    }
+                }
+
+                if details.archived {
+                    button("restore archived", Action::RestoreArchived);
+                }
+
+                button("re-layout cluster", Action::Relayout);
+                button("edit note", Action::EditNote);
+                button("edit tags", Action::EditTags);
+
+                let watchable = matches!(
+                    *details.ty,
+                    EntityType::Artist | EntityType::Label | EntityType::Release
+                );
+                if watchable && *details.scrape >= Scrape::Shallow {
+                    if details.watched {
+                        button("unwatch", Action::Unwatch);
+                    } else {
+                        button("watch", Action::Watch);
+                    }
+                }
             });
         }
     }
@@ -183,10 +271,18 @@ fn button_click(
     scraper: Res<crate::background::Scraper>,
     query: Query<&Action, With<Button>>,
     nearest: Option<Res<Nearest>>,
-    mut data: Query<(&Url, &EntityType, &mut Scrape)>,
-    relationships: Query<&Relationship>,
+    urls: Query<&Url>,
+    mut graph: GraphQuery,
+    policy: Res<ScrapePolicy>,
+    mut expand: EventWriter<ExpandFans>,
+    mut relayout: EventWriter<Relayout>,
+    mut restore: EventWriter<Restore>,
+    mut preview: EventWriter<Preview>,
+    annotations: Query<&Annotation>,
+    mut editor: ResMut<Editor>,
     mut menu: Single<Menu>,
     runtime: Res<crate::Runtime>,
+    mut commands: Commands,
 ) {
     let Ok(action) = query.get(trigger.entity()) else {
         return;
@@ -194,76 +290,91 @@ fn button_click(
     let Some(nearest) = nearest else { return };
 
     if trigger.event.button == PointerButton::Primary {
-        let request = |data: &mut Query<(&Url, &EntityType, &mut Scrape)>, entity| match data
-            .get_mut(entity)
-        {
-            Ok((Url(url), EntityType::Release, mut scrape)) => {
-                scrape.clamp_to(Scrape::InProgress..);
-                scraper.send(Request::Release { url: url.clone() }).unwrap();
-            }
-            Ok((Url(url), EntityType::Artist, mut scrape)) => {
-                scrape.clamp_to(Scrape::InProgress..);
-                scraper.send(Request::Artist { url: url.clone() }).unwrap();
-            }
-            Ok((Url(url), EntityType::User, mut scrape)) => {
-                scrape.clamp_to(Scrape::InProgress..);
-                scraper.send(Request::User { url: url.clone() }).unwrap();
-            }
-            Err(_) => {}
-        };
-
-        let next_level = |entity| {
-            relationships.iter().filter_map(move |rel| {
-                (rel.from == entity)
-                    .then_some(rel.to)
-                    .or((rel.to == entity).then_some(rel.from))
-            })
-        };
-
         match action {
             Action::Open => {
-                let Ok((url, _, _)) = data.get(nearest.entity) else {
+                let Ok(url) = urls.get(nearest.entity) else {
                     return;
                 };
-                let url = url::Url::parse(&url.0).unwrap();
-                runtime.spawn_background(async move {
-                    use ashpd::desktop::open_uri::OpenFileRequest;
-                    match OpenFileRequest::default()
-                        .send_uri(&url)
-                        .await
-                        .and_then(|req| req.response())
-                    {
-                        Ok(()) => tracing::info!("opened {url}"),
-                        Err(err) => {
-                            tracing::error!("failed to open {url}: {:?}", eyre::Report::from(err));
-                        }
-                    }
-                });
+                open_url(&runtime, &url.0);
+            }
+            Action::OpenSelected => {
+                commands.send_event(OpenSelected);
+            }
+            Action::Select => {
+                commands.entity(nearest.entity).insert(Selected);
+            }
+            Action::Deselect => {
+                commands.entity(nearest.entity).remove::<Selected>();
+            }
+            Action::Preview => {
+                preview.send(Preview(nearest.entity));
+            }
+            Action::ViewRaw => {
+                commands.send_event(ViewRaw(nearest.entity));
             }
             Action::Scrape => {
-                request(&mut data, nearest.entity);
+                let job = scraper.start_job(job_label("scrape", nearest.entity, &urls));
+                policy.propagate(nearest.entity, 0, job, &mut graph, &scraper);
             }
             Action::ScrapeDeep => {
-                if let Ok((_, _, mut scrape)) = data.get_mut(nearest.entity) {
-                    scrape.clamp_to(Scrape::Deep..);
-                }
-                next_level(nearest.entity).for_each(|entity| request(&mut data, entity));
+                let job = scraper.start_job(job_label("deep scrape", nearest.entity, &urls));
+                policy.propagate(nearest.entity, 1, job, &mut graph, &scraper);
             }
             Action::ScrapeExtraDeep => {
-                if let Ok((_, _, mut scrape)) = data.get_mut(nearest.entity) {
-                    scrape.clamp_to(Scrape::ExtraDeep..);
-                }
-                for entity in next_level(nearest.entity) {
-                    if let Ok((_, _, mut scrape)) = data.get_mut(entity) {
-                        scrape.clamp_to(Scrape::Deep..);
-                    }
-                    for entity in next_level(entity) {
-                        request(&mut data, entity);
-                    }
-                }
+                let job = scraper.start_job(job_label("extra deep scrape", nearest.entity, &urls));
+                policy.propagate(nearest.entity, 2, job, &mut graph, &scraper);
+            }
+            Action::ExpandFans => {
+                expand.send(ExpandFans {
+                    release: nearest.entity,
+                    scrape: false,
+                });
+            }
+            Action::SpawnTracks => {
+                commands.send_event(SpawnTracks(nearest.entity));
+            }
+            Action::RestoreArchived => {
+                restore.send(Restore(nearest.entity));
+            }
+            Action::Relayout => {
+                relayout.send(Relayout(nearest.entity));
+            }
+            Action::EditNote => {
+                let annotation = annotations.get(nearest.entity).ok();
+                editor.open(
+                    Target::Note(nearest.entity),
+                    annotation
+                        .map(|annotation| annotation.note.clone())
+                        .unwrap_or_default(),
+                );
+            }
+            Action::EditTags => {
+                let annotation = annotations.get(nearest.entity).ok();
+                editor.open(
+                    Target::Tags(nearest.entity),
+                    annotation
+                        .map(|annotation| {
+                            Vec::from_iter(annotation.tags.iter().map(String::as_str)).join(", ")
+                        })
+                        .unwrap_or_default(),
+                );
+            }
+            Action::Watch => {
+                commands.entity(nearest.entity).insert(Watched);
+            }
+            Action::Unwatch => {
+                commands.entity(nearest.entity).remove::<Watched>();
             }
         }
     }
 
     menu.visibility.toggle_visible_hidden();
 }
+
+/// Names a scrape started from the menu after the page it started from
+fn job_label(action: &str, entity: Entity, urls: &Query<&Url>) -> String {
+    match urls.get(entity) {
+        Ok(url) => format!("{action} {}", url.0),
+        Err(_) => action.to_owned(),
+    }
+}
@@ -1,6 +1,18 @@
-mod diagnostic;
+pub mod diagnostic;
+mod drift;
+pub mod editor;
+mod frontier;
+mod jobs;
+pub mod list;
 pub mod menu;
 mod nearest;
+mod network;
+pub mod notify;
+pub mod preview;
+pub mod prune;
+pub mod raw;
+mod review;
+mod tags;
 mod time;
 
 pub struct Plugin;
@@ -8,8 +20,20 @@ pub struct Plugin;
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.add_plugins(self::diagnostic::Plugin);
+        app.add_plugins(self::editor::Plugin);
+        app.add_plugins(self::drift::Plugin);
+        app.add_plugins(self::frontier::Plugin);
+        app.add_plugins(self::jobs::Plugin);
+        app.add_plugins(self::list::Plugin);
         app.add_plugins(self::menu::Plugin);
         app.add_plugins(self::nearest::Plugin);
+        app.add_plugins(self::network::Plugin);
+        app.add_plugins(self::notify::Plugin);
+        app.add_plugins(self::preview::Plugin);
+        app.add_plugins(self::prune::Plugin);
+        app.add_plugins(self::raw::Plugin);
+        app.add_plugins(self::review::Plugin);
+        app.add_plugins(self::tags::Plugin);
         app.add_plugins(self::time::Plugin);
     }
 }
@@ -18,8 +18,20 @@ use bevy::{
 };
 
 use crate::{
-    data::{ArtistDetails, EntityType, ReleaseDetails, Url, UserDetails},
+    alias::Aliases,
+    annotation::Annotation,
+    archive::Archived,
+    data::{
+        currency::Rates,
+        stats::{ArtistStats, CollectionValue},
+        Acquired, ArtistDetails, CollectionProgress, DiscoveredVia, EntityType, LabelDetails,
+        ReleaseDetails, ScrapeHistory, TagDetails, Url, UserDetails,
+    },
     interact::Nearest,
+    sample::UnspawnedFans,
+    seed::SeedMask,
+    sim::Relationship,
+    watch::FanCounts,
 };
 
 pub struct Plugin;
@@ -53,20 +65,45 @@ fn setup(mut commands: Commands) {
 }
 
 #[derive(QueryData)]
-struct NodeDetails {
-    ty: &'static EntityType,
+pub(super) struct NodeDetails {
+    pub(super) ty: &'static EntityType,
     url: &'static Url,
     artist: Option<Ref<'static, ArtistDetails>>,
+    label: Option<Ref<'static, LabelDetails>>,
     release: Option<Ref<'static, ReleaseDetails>>,
     user: Option<Ref<'static, UserDetails>>,
+    tag: Option<Ref<'static, TagDetails>>,
+    progress: Option<Ref<'static, CollectionProgress>>,
+    via: Option<&'static DiscoveredVia>,
+    aliases: Option<Ref<'static, Aliases>>,
+    history: Option<Ref<'static, ScrapeHistory>>,
+    unspawned: Option<Ref<'static, UnspawnedFans>>,
+    stats: Option<Ref<'static, ArtistStats>>,
+    value: Option<Ref<'static, CollectionValue>>,
+    archived: Option<Ref<'static, Archived>>,
+    annotation: Option<Ref<'static, Annotation>>,
+    seeds: Option<Ref<'static, SeedMask>>,
+    fan_counts: Option<Ref<'static, FanCounts>>,
 }
 
 impl NodeDetailsItem<'_> {
     fn is_changed(&self) -> bool {
         [
             self.artist.as_ref().map(|x| x.is_changed()),
+            self.label.as_ref().map(|x| x.is_changed()),
             self.release.as_ref().map(|x| x.is_changed()),
             self.user.as_ref().map(|x| x.is_changed()),
+            self.tag.as_ref().map(|x| x.is_changed()),
+            self.progress.as_ref().map(|x| x.is_changed()),
+            self.aliases.as_ref().map(|x| x.is_changed()),
+            self.history.as_ref().map(|x| x.is_changed()),
+            self.unspawned.as_ref().map(|x| x.is_changed()),
+            self.stats.as_ref().map(|x| x.is_changed()),
+            self.value.as_ref().map(|x| x.is_changed()),
+            self.archived.as_ref().map(|x| x.is_changed()),
+            self.annotation.as_ref().map(|x| x.is_changed()),
+            self.seeds.as_ref().map(|x| x.is_changed()),
+            self.fan_counts.as_ref().map(|x| x.is_changed()),
         ]
         .into_iter()
         .flatten()
@@ -74,9 +111,57 @@ impl NodeDetailsItem<'_> {
     }
 }
 
+/// A single line chart of some values, scaled between their smallest and largest
+fn sparkline(values: impl IntoIterator<Item = usize> + Clone) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.clone().into_iter().min().unwrap_or(0);
+    let max = values.clone().into_iter().max().unwrap_or(0);
+    String::from_iter(values.into_iter().map(|value| {
+        let scaled = (value - min) * (BARS.len() - 1) / (max - min).max(1);
+        BARS[scaled]
+    }))
+}
+
+/// Format a count with separators, e.g. 12,340
+fn thousands(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// When a fan's collection grew over, or when a release's fans collected it
+fn acquired_line(
+    ty: EntityType,
+    dates: impl IntoIterator<Item = jiff::Timestamp>,
+) -> Option<String> {
+    let mut dates = dates.into_iter();
+    let first = dates.next()?;
+    let (first, last) = dates.fold((first, first), |(min, max), date| {
+        (min.min(date), max.max(date))
+    });
+    let day = |date: jiff::Timestamp| date.strftime("%Y-%m-%d").to_string();
+    Some(match ty {
+        _ if first == last => format!("collected on {}", day(first)),
+        EntityType::User => format!("collected from {} to {}", day(first), day(last)),
+        _ => format!(
+            "first collected on {}, most recently on {}",
+            day(first),
+            day(last)
+        ),
+    })
+}
+
 fn update(
     nearest: Option<Res<Nearest>>,
     details: Query<NodeDetails>,
+    acquired: Query<(&Relationship, &Acquired)>,
+    rates: Res<Rates>,
     ui: Single<Entity, With<NodeUi>>,
     mut commands: Commands,
 ) {
@@ -90,71 +175,251 @@ fn update(
     if nearest.is_changed() || details.is_changed() {
         commands.entity(*ui).despawn_descendants();
 
-        commands.entity(*ui).with_children(|ui| {
-            if let Some(release) = details.release.as_deref() {
-                let ReleaseDetails {
-                    title,
-                    artist,
-                    tracks,
-                    length,
-                    released,
-                    ty,
-                } = release;
+        let dates = acquired
+            .iter()
+            .filter(|(relationship, _)| {
+                relationship.from == nearest.entity || relationship.to == nearest.entity
+            })
+            .map(|(_, acquired)| acquired.0);
+        let acquired = acquired_line(*details.ty, dates);
 
+        commands.entity(*ui).with_children(|ui| {
+            for line in lines(&details, &rates).into_iter().chain(acquired) {
                 ui.spawn((
-                    Text::new(format!("{ty:?}: {title}")),
+                    Text::new(line),
                     TextFont::default(),
                     Label,
                     PickingBehavior::IGNORE,
                 ));
+            }
+        });
+    }
+}
 
-                ui.spawn((
-                    Text::new(format!("by {artist} in {}", released.year())),
-                    TextFont::default(),
-                    Label,
-                    PickingBehavior::IGNORE,
-                ));
+/// A short name for a node, for listing it alongside others
+pub(super) fn label(details: &NodeDetailsItem) -> String {
+    if let Some(release) = details.release.as_deref() {
+        format!("{} by {}", release.title, release.artist)
+    } else if let Some(artist) = details.artist.as_deref() {
+        artist.name.clone()
+    } else if let Some(user) = details.user.as_deref() {
+        user.name.clone()
+    } else if let Some(tag) = details.tag.as_deref() {
+        tag.name.clone()
+    } else {
+        details.url.0.clone()
+    }
+}
 
-                ui.spawn((
-                    Text::new(if let Some(tracks) = tracks {
-                        format!("{tracks} tracks | {length:?}")
-                    } else {
-                        format!("{length:?}")
-                    }),
-                    TextFont::default(),
-                    Label,
-                    PickingBehavior::IGNORE,
-                ));
-            } else if let Some(artist) = details.artist.as_deref() {
-                let ArtistDetails { name } = artist;
-                ui.spawn((
-                    Text::new(format!("Artist: {name}")),
-                    TextFont::default(),
-                    Label,
-                    PickingBehavior::IGNORE,
-                ));
-            } else if let Some(user) = details.user.as_deref() {
-                let UserDetails { name, username } = user;
-                ui.spawn((
-                    Text::new(format!("User: {name} ({username})")),
-                    TextFont::default(),
-                    Label,
-                    PickingBehavior::IGNORE,
-                ));
-            } else {
-                ui.spawn((
-                    Text::new(format!("Unscraped {:?}", details.ty)),
-                    TextFont::default(),
-                    Label,
-                    PickingBehavior::IGNORE,
-                ));
-                ui.spawn((
-                    Text::new(&details.url.0),
-                    TextFont::default(),
-                    Label,
-                    PickingBehavior::IGNORE,
+/// Everything known about a node, one line of text each
+pub(super) fn lines(details: &NodeDetailsItem, rates: &Rates) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    if let Some(release) = details.release.as_deref() {
+        let ReleaseDetails {
+            title,
+            artist,
+            tracks,
+            length,
+            released,
+            ty,
+            fan_count,
+            price,
+            name_your_price,
+            free_download,
+            sold_out,
+            tags,
+            track_list,
+            ..
+        } = release;
+
+        lines.push(format!("{ty:?}: {title}"));
+        lines.push(format!("by {artist} in {}", released.year()));
+        lines.push(if let Some(tracks) = tracks {
+            format!("{tracks} tracks | {length:?}")
+        } else {
+            format!("{length:?}")
+        });
+
+        if let Some(price) = price {
+            let converted = rates
+                .convert(price)
+                .filter(|_| price.currency != rates.target)
+                .map(|converted| format!(" ({converted:.2} {})", rates.target))
+                .unwrap_or_default();
+            let more = if *name_your_price { " or more" } else { "" };
+            lines.push(format!(
+                "{:.2} {}{more}{converted}",
+                price.amount, price.currency
+            ));
+        }
+
+        let availability = Vec::from_iter(
+            [
+                (*name_your_price, "name your price"),
+                (*free_download, "free download"),
+                (*sold_out, "physical sold out"),
+            ]
+            .into_iter()
+            .filter_map(|(flag, label)| flag.then_some(label)),
+        );
+        if !availability.is_empty() {
+            lines.push(availability.join(" | "));
+        }
+
+        if !tags.is_empty() {
+            lines.push(format!("tagged {}", tags.join(", ")));
+        }
+
+        for (i, track) in track_list.iter().enumerate() {
+            lines.push(format!("{}. {} ({:?})", i + 1, track.title, track.length));
+        }
+
+        lines.push(if let Some(unspawned) = details.unspawned.as_deref() {
+            let shown = fan_count.saturating_sub(unspawned.users.len());
+            format!(
+                "showing {} of {} fans",
+                thousands(shown),
+                thousands(*fan_count),
+            )
+        } else {
+            format!("{} fans", thousands(*fan_count))
+        });
+
+        if let Some(counts) = details.fan_counts.as_deref() {
+            if let [(since, first), .., (_, last)] = counts.0.as_slice() {
+                lines.push(format!(
+                    "{} {} -> {} fans since {}",
+                    sparkline(counts.0.iter().map(|&(_, count)| count)),
+                    thousands(*first),
+                    thousands(*last),
+                    since.strftime("%Y-%m-%d"),
                 ));
             }
-        });
+        }
+    } else if let Some(artist) = details.artist.as_deref() {
+        let ArtistDetails { name, location } = artist;
+        if let Some(label) = details.label.as_deref() {
+            let LabelDetails { roster } = label;
+            lines.push(format!("Label: {name}"));
+            lines.push(format!("{roster} artists on roster"));
+        } else {
+            lines.push(format!("Artist: {name}"));
+        }
+        if let Some(location) = location {
+            lines.push(format!("in {location}"));
+        }
+
+        if let Some(stats) = details.stats.as_deref() {
+            let ArtistStats {
+                releases,
+                tracks,
+                runtime,
+                ..
+            } = stats;
+            lines.push(format!(
+                "{releases} releases | {tracks} tracks | {runtime:?}"
+            ));
+
+            if let Some(price) = stats.average_album_price() {
+                lines.push(format!("average album price {price:.2} {}", rates.target));
+            }
+
+            if let Some(average) = stats.average_album_length() {
+                lines.push(format!("average album {average:?}"));
+            }
+        }
+    } else if let Some(user) = details.user.as_deref() {
+        let UserDetails {
+            name,
+            username,
+            location,
+        } = user;
+        lines.push(format!("User: {name} ({username})"));
+        if let Some(location) = location {
+            lines.push(format!("in {location}"));
+        }
+
+        if let Some(value) = details.value.as_deref().filter(|value| value.priced > 0) {
+            let CollectionValue {
+                releases,
+                priced,
+                total,
+            } = value;
+            lines.push(format!(
+                "collection worth ~{total:.2} {} (estimate from {priced} of {releases} releases)",
+                rates.target,
+            ));
+        }
+    } else if let Some(tag) = details.tag.as_deref() {
+        let TagDetails { name } = tag;
+        lines.push(format!("Tag: {name}"));
+        lines.push(details.url.0.clone());
+    } else {
+        lines.push(format!("Unscraped {:?}", details.ty));
+        lines.push(details.url.0.clone());
+    }
+
+    // Shown even before the user's details arrive, as those come after their whole collection
+    if let Some(progress) = details.progress.as_deref() {
+        let CollectionProgress { scraped, total } = progress;
+        lines.push(format!(
+            "{} / {} items",
+            thousands(*scraped),
+            thousands(*total)
+        ));
+    }
+
+    if let Some(archived) = details.archived.as_deref() {
+        let Archived {
+            artists,
+            releases,
+            users,
+        } = archived;
+        for (count, kind) in [
+            (artists, "artists"),
+            (releases, "releases"),
+            (users, "fans"),
+        ] {
+            if *count > 0 {
+                lines.push(format!("+{} archived {kind}", thousands(*count)));
+            }
+        }
+    }
+
+    if let Some(annotation) = details.annotation.as_deref() {
+        if !annotation.tags.is_empty() {
+            let tags = Vec::from_iter(annotation.tags.iter().map(String::as_str));
+            lines.push(format!("tags: {}", tags.join(", ")));
+        }
+        if !annotation.note.is_empty() {
+            lines.push(format!("note: {}", annotation.note));
+        }
     }
+
+    if let Some(seeds) = details.seeds.as_deref().filter(|seeds| seeds.is_shared()) {
+        lines.push(format!("shared by {} seeds", seeds.0.count_ones()));
+    }
+
+    for alias in details.aliases.iter().flat_map(|aliases| &aliases.0) {
+        lines.push(format!("also known as {alias}"));
+    }
+
+    for record in details.history.iter().flat_map(|history| &history.0) {
+        lines.push(format!(
+            "scraped {:?} at {}",
+            record.level,
+            record.timestamp.strftime("%Y-%m-%d %H:%M:%S"),
+        ));
+    }
+
+    if let Some(via) = details.via {
+        lines.push(format!(
+            "found scraping {} at {}",
+            via.request.url(),
+            via.timestamp.strftime("%Y-%m-%d %H:%M:%S"),
+        ));
+    }
+
+    lines
 }
@@ -0,0 +1,99 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        event::EventReader,
+        observer::Trigger,
+        query::With,
+        system::{Commands, Query, Res, Single},
+    },
+    hierarchy::BuildChildren,
+    input::keyboard::{Key, KeyboardInput},
+    picking::{
+        events::{Click, Pointer},
+        pointer::PointerButton,
+        PickingBehavior,
+    },
+    text::TextFont,
+    ui::widget::{Button, Label, Text},
+    ui::{BackgroundColor, Node, PositionType, UiRect, Val},
+};
+
+use crate::{background::Scraper, ui::editor::Editor};
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, (toggle, update));
+        app.add_observer(button_click);
+    }
+}
+
+/// Shows whether the scraper is retrieving pages, and pauses or resumes it when clicked
+#[derive(Default, Component)]
+struct NetworkButton;
+
+#[derive(Default, Component)]
+struct NetworkLabel;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(40.),
+                bottom: Val::Px(0.),
+                padding: UiRect::all(Val::Px(6.)),
+                ..Node::default()
+            },
+            Button,
+            BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+            NetworkButton,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont::default(),
+            Label,
+            PickingBehavior::IGNORE,
+            NetworkLabel,
+        ));
+}
+
+/// Every letter is already taken, so this gets the key actually meant for pausing
+fn toggle(mut events: EventReader<KeyboardInput>, editor: Res<Editor>, scraper: Res<Scraper>) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Pause {
+            scraper.pause_network(!scraper.network_paused());
+        }
+    }
+}
+
+fn update(scraper: Res<Scraper>, mut text: Single<&mut Text, With<NetworkLabel>>) {
+    let new = if scraper.network_paused() {
+        format!("network paused, {} queued", scraper.queued())
+    } else {
+        "network running".to_owned()
+    };
+    if text.0 != new {
+        text.0 = new;
+    }
+}
+
+fn button_click(
+    trigger: Trigger<Pointer<Click>>,
+    buttons: Query<(), With<NetworkButton>>,
+    scraper: Res<Scraper>,
+) {
+    if trigger.event.button != PointerButton::Primary || !buttons.contains(trigger.entity()) {
+        return;
+    }
+
+    scraper.pause_network(!scraper.network_paused());
+}
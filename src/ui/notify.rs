@@ -0,0 +1,91 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader},
+        query::With,
+        system::{Commands, Query, Res, Single},
+    },
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    picking::PickingBehavior,
+    text::TextFont,
+    time::{Real, Time, Timer, TimerMode},
+    ui::widget::{Label, Text},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, JustifyContent, Node, PositionType,
+        Val,
+    },
+};
+
+use std::time::Duration;
+
+/// How long a notification stays on screen
+const DURATION: Duration = Duration::from_secs(10);
+
+/// Send to show a short-lived message in the corner of the screen
+#[derive(Debug, Event)]
+pub struct Notify(pub String);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Notify>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, (show, expire));
+    }
+}
+
+#[derive(Default, Component)]
+struct Notifications;
+
+#[derive(Component)]
+struct Expiry(Timer);
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::End,
+            align_items: AlignItems::Start,
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.),
+            bottom: Val::Px(0.),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        PickingBehavior::IGNORE,
+        Notifications,
+    ));
+}
+
+fn show(
+    mut events: EventReader<Notify>,
+    parent: Single<Entity, With<Notifications>>,
+    mut commands: Commands,
+) {
+    for Notify(message) in events.read() {
+        tracing::info!(message, "notification");
+        commands.entity(*parent).with_child((
+            Text::new(message),
+            TextFont::default(),
+            Label,
+            PickingBehavior::IGNORE,
+            Expiry(Timer::new(DURATION, TimerMode::Once)),
+        ));
+    }
+}
+
+fn expire(
+    time: Res<Time<Real>>,
+    mut notifications: Query<(Entity, &mut Expiry)>,
+    mut commands: Commands,
+) {
+    for (entity, mut expiry) in &mut notifications {
+        if expiry.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
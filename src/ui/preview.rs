@@ -0,0 +1,166 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        query::With,
+        system::{Commands, Query, Res, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
+    input::{keyboard::KeyCode, ButtonInput},
+    picking::PickingBehavior,
+    render::view::Visibility,
+    text::TextFont,
+    ui::widget::{Label, Text},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, JustifyContent, Node, PositionType,
+        Val,
+    },
+};
+
+use crate::{background::Scraper, data::Url, ui::notify::Notify};
+
+/// How many lines of a page's text are shown
+const LINES: usize = 40;
+
+/// Send to preview the cached snapshot of a node's page
+#[derive(Debug, Event)]
+pub struct Preview(pub Entity);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<Preview>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, (open, close));
+    }
+}
+
+#[derive(Default, Component)]
+struct PreviewUi;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Start,
+            align_items: AlignItems::Start,
+            position_type: PositionType::Absolute,
+            right: Val::Px(0.),
+            top: Val::Px(0.),
+            max_width: Val::Percent(40.),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        PickingBehavior::IGNORE,
+        PreviewUi,
+        Visibility::Hidden,
+    ));
+}
+
+/// The readable text of a page, one line per text node, leaving out scripts and styles
+fn text_lines(page: &str) -> Vec<String> {
+    let html = scraper::Html::parse_document(page);
+    let mut lines = Vec::new();
+    for node in html.root_element().descendants() {
+        let Some(text) = node.value().as_text() else {
+            continue;
+        };
+        let hidden = node
+            .parent()
+            .and_then(|parent| parent.value().as_element())
+            .is_some_and(|parent| matches!(parent.name(), "script" | "style" | "noscript"));
+        if hidden {
+            continue;
+        }
+        let line = Vec::from_iter(text.split_whitespace()).join(" ");
+        if !line.is_empty() && lines.last() != Some(&line) {
+            lines.push(line);
+        }
+        if lines.len() == LINES {
+            break;
+        }
+    }
+    lines
+}
+
+/// Hand the snapshot to a helper program to render, with a base url so its links and images
+/// still resolve
+#[culpa::try_fn]
+fn open_with_helper(helper: &str, url: &str, page: &str) -> eyre::Result<()> {
+    let path = std::env::temp_dir().join("bc-scraper3-preview.html");
+    let base = format!("<base href=\"{url}\">");
+    let page = match page.find("<head>") {
+        Some(head) => format!("{}{base}{}", &page[..head + 6], &page[head + 6..]),
+        None => format!("{base}{page}"),
+    };
+    std::fs::write(&path, page)?;
+    std::process::Command::new(helper).arg(&path).spawn()?;
+}
+
+fn open(
+    mut events: EventReader<Preview>,
+    urls: Query<&Url>,
+    scraper: Res<Scraper>,
+    args: Res<crate::Args>,
+    mut notify: EventWriter<Notify>,
+    ui: Single<(Entity, &mut Visibility), With<PreviewUi>>,
+    mut commands: Commands,
+) {
+    let (ui, mut visibility) = ui.into_inner();
+
+    for &Preview(entity) in events.read() {
+        let Ok(url) = urls.get(entity) else {
+            continue;
+        };
+
+        let page = match scraper.cached_page(&url.0) {
+            Ok(Some(page)) => page,
+            Ok(None) => {
+                notify.send(Notify("page hasn't been scraped yet".to_owned()));
+                continue;
+            }
+            Err(error) => {
+                tracing::error!(?error, url = url.0, "failed reading cached page");
+                notify.send(Notify("couldn't read the cached page".to_owned()));
+                continue;
+            }
+        };
+
+        if let Some(helper) = &args.preview_helper {
+            if let Err(error) = open_with_helper(helper, &url.0, &page) {
+                tracing::error!(?error, helper, "failed running preview helper");
+                notify.send(Notify(format!("couldn't run {helper}")));
+            }
+            continue;
+        }
+
+        commands.entity(ui).despawn_descendants();
+        commands.entity(ui).with_children(|ui| {
+            for line in [url.0.clone()].into_iter().chain(text_lines(&page)) {
+                ui.spawn((
+                    Text::new(line),
+                    TextFont::default(),
+                    Label,
+                    PickingBehavior::IGNORE,
+                ));
+            }
+            ui.spawn((
+                Text::new("(escape to close)"),
+                TextFont::default(),
+                Label,
+                PickingBehavior::IGNORE,
+            ));
+        });
+        *visibility = Visibility::Visible;
+    }
+}
+
+fn close(keys: Res<ButtonInput<KeyCode>>, mut ui: Single<&mut Visibility, With<PreviewUi>>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        **ui = Visibility::Hidden;
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventWriter,
+        observer::Trigger,
+        query::With,
+        system::{Commands, Query, Res, Resource, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
+    picking::{
+        events::{Click, Pointer},
+        pointer::PointerButton,
+        PickingBehavior,
+    },
+    render::view::Visibility,
+    text::TextFont,
+    ui::widget::{Button, Label, Text},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, JustifyContent, Node, PositionType,
+        UiRect, Val,
+    },
+};
+
+use crate::{
+    data::{EntityType, Scrape},
+    sim::RelationCount,
+    ui::notify::Notify,
+};
+
+/// Whether the prune dialog is shown
+#[derive(Debug, Default, Resource)]
+pub struct PruneDialog {
+    pub visible: bool,
+}
+
+/// Ways of removing nodes to keep huge crawls manageable
+#[derive(Debug, Clone, Copy, Component)]
+enum Prune {
+    LeafUsers,
+    Unscraped,
+}
+
+impl Prune {
+    const ALL: [Self; 2] = [Self::LeafUsers, Self::Unscraped];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::LeafUsers => "remove users with exactly 1 edge",
+            Self::Unscraped => "remove unscraped nodes",
+        }
+    }
+
+    fn matches(self, ty: EntityType, scrape: Option<&Scrape>, relations: &RelationCount) -> bool {
+        match self {
+            Self::LeafUsers => ty == EntityType::User && relations.count == 1,
+            Self::Unscraped => scrape.is_none_or(|scrape| *scrape == Scrape::None),
+        }
+    }
+}
+
+/// The text of a prune button, previewing how many nodes it would remove
+#[derive(Component)]
+struct PruneLabel(Prune);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<PruneDialog>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, update);
+        app.add_observer(button_click);
+    }
+}
+
+#[derive(Default, Component)]
+struct PruneDialogMarker;
+
+type Nodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static EntityType,
+        Option<&'static Scrape>,
+        &'static RelationCount,
+    ),
+>;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
+                align_items: AlignItems::Start,
+                position_type: PositionType::Absolute,
+                left: Val::Percent(40.),
+                top: Val::Percent(40.),
+                ..Node::default()
+            },
+            BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+            PickingBehavior::IGNORE,
+            PruneDialogMarker,
+            Visibility::Hidden,
+        ))
+        .with_children(|dialog| {
+            for prune in Prune::ALL {
+                dialog
+                    .spawn((
+                        Node {
+                            padding: UiRect::all(Val::Px(6.)),
+                            ..Node::default()
+                        },
+                        Button,
+                        BackgroundColor(Color::NONE),
+                        prune,
+                    ))
+                    .with_child((
+                        Text::new(prune.label()),
+                        TextFont::default(),
+                        Label,
+                        PickingBehavior::IGNORE,
+                        PruneLabel(prune),
+                    ));
+            }
+        });
+}
+
+fn update(
+    state: Res<PruneDialog>,
+    mut dialog: Single<&mut Visibility, With<PruneDialogMarker>>,
+    mut labels: Query<(&mut Text, &PruneLabel)>,
+    nodes: Nodes,
+) {
+    let visibility = if state.visible {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    if **dialog != visibility {
+        **dialog = visibility;
+    }
+
+    if !state.visible {
+        return;
+    }
+
+    for (mut text, &PruneLabel(prune)) in &mut labels {
+        let count = nodes
+            .iter()
+            .filter(|(_, ty, scrape, relations)| prune.matches(**ty, *scrape, relations))
+            .count();
+        let new = format!("{} ({count})", prune.label());
+        if text.0 != new {
+            text.0 = new;
+        }
+    }
+}
+
+fn button_click(
+    trigger: Trigger<Pointer<Click>>,
+    buttons: Query<&Prune, With<Button>>,
+    nodes: Nodes,
+    mut notify: EventWriter<Notify>,
+    mut commands: Commands,
+) {
+    let Ok(&prune) = buttons.get(trigger.entity()) else {
+        return;
+    };
+
+    if trigger.event.button != PointerButton::Primary {
+        return;
+    }
+
+    // The id component hooks unindex each node and despawn its relationships, which keeps the
+    // relation counts of the remaining nodes correct
+    let mut count = 0;
+    for (entity, ty, scrape, relations) in &nodes {
+        if prune.matches(*ty, scrape, relations) {
+            commands.entity(entity).despawn_recursive();
+            count += 1;
+        }
+    }
+
+    notify.send(Notify(format!("pruned {count} nodes")));
+}
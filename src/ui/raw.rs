@@ -0,0 +1,197 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        query::With,
+        system::{Commands, Query, Res, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
+    input::{keyboard::KeyCode, ButtonInput},
+    picking::PickingBehavior,
+    render::view::Visibility,
+    text::TextFont,
+    ui::widget::{Label, Text},
+    ui::{
+        BackgroundColor, FlexDirection, Node, Overflow, PositionType, ScrollPosition, UiRect, Val,
+    },
+};
+
+use crate::{background::Scraper, data::Url, ui::notify::Notify};
+
+/// How far one press of the arrow keys scrolls, page up and down go ten times as far
+const STEP: f32 = 40.;
+
+/// Longer blobs are cut off, the text gets too slow to lay out past this
+const MAX_LINES: usize = 4000;
+
+/// Send to view the json data embedded in the cached copy of a node's page
+#[derive(Debug, Event)]
+pub struct ViewRaw(pub Entity);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_event::<ViewRaw>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, (open, scroll, close));
+    }
+}
+
+#[derive(Default, Component)]
+struct RawUi;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            flex_direction: FlexDirection::Column,
+            position_type: PositionType::Absolute,
+            right: Val::Px(0.),
+            top: Val::Px(0.),
+            max_width: Val::Percent(50.),
+            max_height: Val::Percent(90.),
+            padding: UiRect::all(Val::Px(6.)),
+            overflow: Overflow::scroll_y(),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        ScrollPosition::default(),
+        PickingBehavior::IGNORE,
+        RawUi,
+        Visibility::Hidden,
+    ));
+}
+
+/// Every json blob on a page, labelled with where it was found: the data attributes (like
+/// `data-tralbum`, and `data-blob` on `#pagedata` and `#collectors-data`), json in meta tags, and
+/// the ld+json script
+fn blobs(page: &str) -> Vec<(String, serde_json::Value)> {
+    let html = scraper::Html::parse_document(page);
+    let mut blobs = Vec::new();
+    for element in html
+        .root_element()
+        .descendants()
+        .filter_map(scraper::ElementRef::wrap)
+    {
+        let name = element.value().name();
+        let label = match element.value().id() {
+            Some(id) => format!("{name}#{id}"),
+            None => name.to_owned(),
+        };
+        for (attr, value) in element.value().attrs() {
+            if !(attr.starts_with("data-") || attr == "content") {
+                continue;
+            }
+            // Plenty of attributes are plain strings or numbers that happen to parse as json
+            if let Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) =
+                serde_json::from_str(value)
+            {
+                blobs.push((format!("{label}[{attr}]"), value));
+            }
+        }
+        if name == "script" && element.value().attr("type") == Some("application/ld+json") {
+            if let Ok(value) = serde_json::from_str(&element.text().collect::<String>()) {
+                blobs.push((format!("{label}[type=application/ld+json]"), value));
+            }
+        }
+    }
+    blobs
+}
+
+fn open(
+    mut events: EventReader<ViewRaw>,
+    urls: Query<&Url>,
+    scraper: Res<Scraper>,
+    mut notify: EventWriter<Notify>,
+    ui: Single<(Entity, &mut Visibility, &mut ScrollPosition), With<RawUi>>,
+    mut commands: Commands,
+) {
+    let (ui, mut visibility, mut scroll) = ui.into_inner();
+
+    for &ViewRaw(entity) in events.read() {
+        let Ok(url) = urls.get(entity) else {
+            continue;
+        };
+
+        let page = match scraper.cached_page(&url.0) {
+            Ok(Some(page)) => page,
+            Ok(None) => {
+                notify.send(Notify("page hasn't been scraped yet".to_owned()));
+                continue;
+            }
+            Err(error) => {
+                tracing::error!(?error, url = url.0, "failed reading cached page");
+                notify.send(Notify("couldn't read the cached page".to_owned()));
+                continue;
+            }
+        };
+
+        let blobs = blobs(&page);
+        if blobs.is_empty() {
+            notify.send(Notify("no json data on the cached page".to_owned()));
+            continue;
+        }
+
+        let mut lines = Vec::new();
+        for (label, value) in blobs {
+            lines.push(format!("— {label}"));
+            let pretty = serde_json::to_string_pretty(&value).unwrap_or_default();
+            lines.extend(pretty.lines().map(String::from));
+        }
+        if lines.len() > MAX_LINES {
+            let more = lines.len() - MAX_LINES;
+            lines.truncate(MAX_LINES);
+            lines.push(format!("... {more} more lines"));
+        }
+
+        commands.entity(ui).despawn_descendants();
+        commands.entity(ui).with_children(|ui| {
+            for text in [
+                url.0.clone(),
+                "(arrows and page up/down to scroll, escape to close)".to_owned(),
+                lines.join("\n"),
+            ] {
+                ui.spawn((
+                    Text::new(text),
+                    TextFont::default(),
+                    Label,
+                    PickingBehavior::IGNORE,
+                ));
+            }
+        });
+        scroll.offset_y = 0.;
+        *visibility = Visibility::Visible;
+    }
+}
+
+fn scroll(
+    keys: Res<ButtonInput<KeyCode>>,
+    ui: Single<(&Visibility, &mut ScrollPosition), With<RawUi>>,
+) {
+    let (visibility, mut scroll) = ui.into_inner();
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    let delta = if keys.just_pressed(KeyCode::ArrowUp) {
+        -STEP
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        STEP
+    } else if keys.just_pressed(KeyCode::PageUp) {
+        -STEP * 10.
+    } else if keys.just_pressed(KeyCode::PageDown) {
+        STEP * 10.
+    } else {
+        return;
+    };
+    // Layout clamps scrolling past the end
+    scroll.offset_y = (scroll.offset_y + delta).max(0.);
+}
+
+fn close(keys: Res<ButtonInput<KeyCode>>, mut ui: Single<&mut Visibility, With<RawUi>>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        **ui = Visibility::Hidden;
+    }
+}
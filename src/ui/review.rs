@@ -0,0 +1,114 @@
+use bevy::{
+    color::Color,
+    ecs::{
+        change_detection::DetectChangesMut,
+        component::Component,
+        query::With,
+        system::{Commands, Query, Res, Single},
+    },
+    math::Vec2,
+    picking::PickingBehavior,
+    render::view::Visibility,
+    text::TextFont,
+    transform::components::Transform,
+    ui::widget::{Label, Text},
+    ui::{BackgroundColor, Node, PositionType, Val},
+};
+
+use crate::{
+    camera::{ActiveCamera, Cursor},
+    data::{ReleaseDetails, Review, Url, UserDetails},
+    sim::{PredictedPosition, Relationship},
+};
+
+/// How close the cursor has to be to a reviewed link to show its review, in screen pixels
+const HOVER_DISTANCE: f32 = 6.0;
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(bevy::app::Update, update);
+    }
+}
+
+#[derive(Default, Component)]
+struct ReviewUi;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            max_width: Val::Px(400.),
+            ..Node::default()
+        },
+        BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+        Text::default(),
+        TextFont::default(),
+        Label,
+        ReviewUi,
+        PickingBehavior::IGNORE,
+        Visibility::Hidden,
+    ));
+}
+
+/// How far a point is from the line segment between two others
+fn distance_to_segment(point: Vec2, from: Vec2, to: Vec2) -> f32 {
+    let delta = to - from;
+    let along =
+        ((point - from).dot(delta) / delta.length_squared().max(f32::EPSILON)).clamp(0., 1.);
+    point.distance(from + delta * along)
+}
+
+/// Show the review on the link from a fan to a release under the cursor, links are too thin to
+/// be picked so the closest one within a few pixels is used
+fn update(
+    cursor: Option<Res<Cursor>>,
+    camera: Single<&Transform, With<ActiveCamera>>,
+    reviews: Query<(&Relationship, &Review)>,
+    positions: Query<&PredictedPosition>,
+    users: Query<(&Url, Option<&UserDetails>)>,
+    releases: Query<(&Url, Option<&ReleaseDetails>)>,
+    ui: Single<(&mut Node, &mut Text, &mut Visibility), With<ReviewUi>>,
+) {
+    let (mut node, mut text, mut visibility) = ui.into_inner();
+
+    let hovered = cursor.as_ref().and_then(|cursor| {
+        reviews
+            .iter()
+            .filter_map(|(relationship, review)| {
+                let from = positions.get(relationship.from).ok()?.0;
+                let to = positions.get(relationship.to).ok()?.0;
+                let distance = distance_to_segment(cursor.world_position, from, to);
+                Some((distance, relationship, review))
+            })
+            .filter(|&(distance, ..)| distance <= HOVER_DISTANCE * camera.scale.x)
+            .min_by(|(a, ..), (b, ..)| a.total_cmp(b))
+    });
+
+    let (Some(cursor), Some((_, relationship, review))) = (cursor, hovered) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    let fan = users.get(relationship.from).map_or_else(
+        |_| "a fan".to_owned(),
+        |(url, details)| details.map_or_else(|| url.0.clone(), |details| details.name.clone()),
+    );
+    let release = releases.get(relationship.to).map_or_else(
+        |_| "a release".to_owned(),
+        |(url, details)| details.map_or_else(|| url.0.clone(), |details| details.title.clone()),
+    );
+    let mut new = format!("{fan} on {release}:\n{}", review.text);
+    if let Some(track) = &review.favorite_track {
+        new.push_str(&format!("\nfavorite track: {track}"));
+    }
+
+    if text.0 != new {
+        text.0 = new;
+    }
+    node.left = Val::Px(cursor.screen_position.x + 16.);
+    node.top = Val::Px(cursor.screen_position.y + 16.);
+    visibility.set_if_neq(Visibility::Visible);
+}
@@ -0,0 +1,361 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        observer::Trigger,
+        query::With,
+        schedule::{common_conditions::resource_changed, Condition, IntoSystemConfigs},
+        system::{Commands, Query, Res, ResMut, Resource, Single},
+    },
+    hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
+    input::keyboard::{Key, KeyboardInput},
+    math::primitives::Rectangle,
+    picking::{
+        events::{Down, Drag, Pointer},
+        PickingBehavior,
+    },
+    render::{
+        mesh::{Mesh, Mesh2d},
+        view::Visibility,
+    },
+    sprite::{ColorMaterial, MeshMaterial2d},
+    text::TextFont,
+    time::common_conditions::on_timer,
+    transform::components::Transform,
+    ui::widget::{Label, Text},
+    ui::{
+        AlignItems, BackgroundColor, Display, FlexDirection, JustifyContent, Node, PositionType,
+        RelativeCursorPosition, UiRect, Val,
+    },
+};
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    time::Duration,
+};
+
+use super::editor::Editor;
+use crate::{
+    data::{TagDetails, TagId},
+    sim::PredictedPosition,
+    KnownEntities,
+};
+
+static TAG_LINK_MESH_HANDLE: Handle<Mesh> =
+    Handle::weak_from_u128(0x5e0c2a7b94d14f6e8a3b1c9d7e2f4a60);
+static TAG_LINK_COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
+    Handle::weak_from_u128(0x1f8d3e6a20c74b59a6e4d8b2c0f19e37);
+
+/// How many of the most frequent tag pairs are listed
+const ROWS: usize = 40;
+
+/// Which tag nodes appear together on the scraped releases, for mapping out how genres border each
+/// other within the crawl, toggled with `y`. Tags appearing together often enough are linked
+/// directly, and the most frequent pairs listed
+#[derive(Debug, Resource)]
+pub struct TagView {
+    pub visible: bool,
+    /// Pairs of tags appearing together on fewer releases than this aren't linked or listed
+    threshold: usize,
+    /// The most releases any pair of tags appears together on, the top of the slider
+    most: usize,
+}
+
+impl Default for TagView {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            threshold: 2,
+            most: 2,
+        }
+    }
+}
+
+/// The track of the slider picking the threshold
+#[derive(Debug, Default, Component)]
+struct ThresholdSlider;
+
+/// The handle of the threshold slider, positioned along its track
+#[derive(Debug, Default, Component)]
+struct ThresholdHandle;
+
+/// A link drawn between two tags that appear together on `count` releases, separate from the
+/// relationships so it doesn't pull on the simulation
+#[derive(Debug, Component)]
+struct TagLink {
+    from: Entity,
+    to: Entity,
+    count: usize,
+}
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.init_resource::<TagView>();
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (
+                toggle,
+                update
+                    .after(toggle)
+                    .run_if(resource_changed::<TagView>.or(on_timer(Duration::from_secs(2)))),
+                place_links.after(update),
+            ),
+        );
+        app.add_observer(slider_down);
+        app.add_observer(slider_drag);
+    }
+}
+
+#[derive(Default, Component)]
+struct TagUi;
+
+/// The part of the panel rebuilt as the counts change
+#[derive(Default, Component)]
+struct TagRows;
+
+fn setup(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    meshes.insert(&TAG_LINK_MESH_HANDLE, Rectangle::new(1.0, 1.0).into());
+    materials.insert(
+        &TAG_LINK_COLOR_MATERIAL_HANDLE,
+        Color::srgba(0.95, 0.75, 0.3, 0.6).into(),
+    );
+
+    commands
+        .spawn((
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Start,
+                align_items: AlignItems::Start,
+                position_type: PositionType::Absolute,
+                left: Val::Percent(30.),
+                top: Val::Px(0.),
+                ..Node::default()
+            },
+            BackgroundColor(Color::srgba(0.10, 0.10, 0.10, 0.98)),
+            PickingBehavior::IGNORE,
+            TagUi,
+            Visibility::Hidden,
+        ))
+        .with_children(|panel| {
+            panel
+                .spawn((
+                    Node {
+                        width: Val::Px(200.),
+                        height: Val::Px(12.),
+                        margin: UiRect::all(Val::Px(4.)),
+                        ..Node::default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                    RelativeCursorPosition::default(),
+                    ThresholdSlider,
+                ))
+                .with_child((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(6.),
+                        height: Val::Percent(100.),
+                        ..Node::default()
+                    },
+                    BackgroundColor(Color::srgb(0.9, 0.9, 0.9)),
+                    PickingBehavior::IGNORE,
+                    ThresholdHandle,
+                ));
+            panel.spawn((
+                Node {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    ..Node::default()
+                },
+                PickingBehavior::IGNORE,
+                TagRows,
+            ));
+        });
+}
+
+fn toggle(mut events: EventReader<KeyboardInput>, editor: Res<Editor>, mut view: ResMut<TagView>) {
+    if editor.is_editing() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state.is_pressed() && event.logical_key == Key::Character("y".into()) {
+            view.visible ^= true;
+        }
+    }
+}
+
+fn update(
+    mut view: ResMut<TagView>,
+    known: Res<KnownEntities>,
+    tags: Query<(Entity, &TagDetails), With<TagId>>,
+    links: Query<Entity, With<TagLink>>,
+    positions: Query<&PredictedPosition>,
+    ui: Single<&mut Visibility, With<TagUi>>,
+    rows: Single<Entity, With<TagRows>>,
+    mut handle: Single<&mut Node, With<ThresholdHandle>>,
+    mut commands: Commands,
+) {
+    let mut visibility = ui.into_inner();
+
+    for link in &links {
+        commands.entity(link).despawn();
+    }
+
+    if !view.visible {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Visible;
+
+    // Tags are only related to releases, so whatever is on the other end of a tag's relationships
+    // is a release it's on
+    let mut releases = HashMap::<Entity, BTreeSet<Entity>>::new();
+    let mut counts = HashMap::<Entity, usize>::new();
+    for (tag, _) in &tags {
+        for relationship in known.edges_of(tag) {
+            let release = if relationship.from == tag {
+                relationship.to
+            } else {
+                relationship.from
+            };
+            if releases.entry(release).or_default().insert(tag) {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+    }
+
+    let mut pairs = BTreeMap::<(Entity, Entity), usize>::new();
+    for tags in releases.values() {
+        for (i, &a) in tags.iter().enumerate() {
+            for &b in tags.iter().skip(i + 1) {
+                *pairs.entry((a, b)).or_default() += 1;
+            }
+        }
+    }
+
+    // Only write back when it moves, so this doesn't trigger itself every frame
+    let most = pairs.values().copied().max().unwrap_or(1).max(2);
+    if view.most != most {
+        view.most = most;
+    }
+    if view.threshold > most {
+        view.threshold = most;
+    }
+    handle.left = Val::Percent((view.threshold - 1) as f32 / (most - 1) as f32 * 100.);
+
+    let mut pairs = Vec::from_iter(
+        pairs
+            .into_iter()
+            .filter(|&(_, count)| count >= view.threshold),
+    );
+    pairs.sort_by(|a, b| b.1.cmp(&a.1));
+
+    for &((from, to), count) in &pairs {
+        let link = TagLink { from, to, count };
+        let Some(transform) = link.transform(&positions) else {
+            continue;
+        };
+        commands.spawn((
+            link,
+            Mesh2d(TAG_LINK_MESH_HANDLE.clone()),
+            MeshMaterial2d(TAG_LINK_COLOR_MATERIAL_HANDLE.clone()),
+            transform,
+            Visibility::Visible,
+            PickingBehavior::IGNORE,
+        ));
+    }
+
+    let name = |tag| {
+        tags.get(tag)
+            .map_or("?", |(_, details)| details.name.as_str())
+    };
+    let mut lines = vec![format!(
+        "{} tags, {} pairs on at least {} releases",
+        counts.len(),
+        pairs.len(),
+        view.threshold,
+    )];
+    for &((a, b), count) in pairs.iter().take(ROWS) {
+        lines.push(format!(
+            "{count:>5} {} ({}) - {} ({})",
+            name(a),
+            counts[&a],
+            name(b),
+            counts[&b],
+        ));
+    }
+
+    commands.entity(*rows).despawn_descendants();
+    commands.entity(*rows).with_children(|rows| {
+        for line in lines {
+            rows.spawn((
+                Text::new(line),
+                TextFont::default(),
+                Label,
+                PickingBehavior::IGNORE,
+            ));
+        }
+    });
+}
+
+impl TagLink {
+    /// Stretched between the tags it joins, thicker the more releases they share
+    fn transform(&self, positions: &Query<&PredictedPosition>) -> Option<Transform> {
+        let from = positions.get(self.from).ok()?;
+        let to = positions.get(self.to).ok()?;
+        let mut transform = crate::render::relationship_transform(from, to);
+        transform.scale.y = (self.count as f32).log2() + 1.;
+        Some(transform)
+    }
+}
+
+fn place_links(mut links: Query<(&TagLink, &mut Transform)>, positions: Query<&PredictedPosition>) {
+    for (link, mut transform) in &mut links {
+        if let Some(placed) = link.transform(&positions) {
+            *transform = placed;
+        }
+    }
+}
+
+/// Set the threshold from where along the slider the pointer is
+fn slide(slider: &RelativeCursorPosition, view: &mut TagView) {
+    let Some(position) = slider.normalized else {
+        return;
+    };
+    let threshold = 1 + (position.x.clamp(0., 1.) * (view.most - 1) as f32).round() as usize;
+    if view.threshold != threshold {
+        view.threshold = threshold;
+    }
+}
+
+fn slider_down(
+    trigger: Trigger<Pointer<Down>>,
+    sliders: Query<&RelativeCursorPosition, With<ThresholdSlider>>,
+    mut view: ResMut<TagView>,
+) {
+    if let Ok(slider) = sliders.get(trigger.entity()) {
+        slide(slider, &mut view);
+    }
+}
+
+fn slider_drag(
+    trigger: Trigger<Pointer<Drag>>,
+    sliders: Query<&RelativeCursorPosition, With<ThresholdSlider>>,
+    mut view: ResMut<TagView>,
+) {
+    if let Ok(slider) = sliders.get(trigger.entity()) {
+        slide(slider, &mut view);
+    }
+}
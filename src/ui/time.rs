@@ -12,6 +12,14 @@ use bevy::{
     ui::{BackgroundColor, Node, PositionType, Val},
 };
 
+use std::time::Duration;
+
+use crate::{
+    background::Scraper,
+    explore::AutoExplore,
+    quality::{Level, Quality},
+};
+
 pub struct Plugin;
 
 impl bevy::app::Plugin for Plugin {
@@ -41,9 +49,41 @@ fn setup(mut commands: Commands) {
     ));
 }
 
-fn update(time: Res<Time<Virtual>>, mut text: Single<&mut Text, With<TimeText>>) {
+fn update(
+    time: Res<Time<Virtual>>,
+    quality: Res<Quality>,
+    explore: Res<AutoExplore>,
+    scraper: Res<Scraper>,
+    mut text: Single<&mut Text, With<TimeText>>,
+) {
     use std::fmt::Write;
 
     text.clear();
     write!(&mut text, "speed: {}", time.relative_speed()).unwrap();
+    if quality.level != Level::Full {
+        write!(&mut text, " | quality: {:?}", quality.level).unwrap();
+    }
+    if explore.enabled {
+        write!(
+            &mut text,
+            " | auto-explore: {}/{} per minute, {} unscraped, {} queued",
+            explore.used(),
+            explore.budget,
+            explore.frontier(),
+            scraper.queued(),
+        )
+        .unwrap();
+    }
+    let queued = scraper.queued();
+    if queued > 0 {
+        write!(&mut text, " | scraping: {queued} queued").unwrap();
+        if let Some(eta) = scraper.eta() {
+            write!(
+                &mut text,
+                ", ~{:.0?} left",
+                Duration::from_secs(eta.as_secs())
+            )
+            .unwrap();
+        }
+    }
 }
@@ -0,0 +1,425 @@
+use bevy::ecs::{
+    component::Component,
+    entity::Entity,
+    event::EventWriter,
+    query::{Added, Changed, Or, With},
+    removal_detection::RemovedComponents,
+    schedule::IntoSystemConfigs,
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+use bevy::time::{Real, Time, Timer, TimerMode};
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    background::Request,
+    data::{
+        ArtistDetails, ArtistId, RelationKind, Release, ReleaseDetails, ReleaseId, ScrapeHistory,
+        Url, User, UserId,
+    },
+    sim::{Multiplicity, Relationship},
+    ui::notify::Notify,
+    Args, KnownEntities,
+};
+
+/// How many new fans a watched release must gain before it is worth alerting about
+const FAN_ALERT_THRESHOLD: usize = 5;
+
+/// Marks an artist or release that is on the watchlist
+#[derive(Debug, Default, Component)]
+pub struct Watched;
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Entry {
+    url: String,
+    /// Ids of the releases (for an artist) or fans (for a release) already seen
+    seen: BTreeSet<u64>,
+    /// For a release, its fan count each time it was scraped while watched
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fan_counts: Vec<(jiff::Timestamp, usize)>,
+}
+
+/// A watched release's fan count each time it was scraped, oldest first, to show how it grew
+#[derive(Debug, Clone, Default, Component)]
+pub struct FanCounts(pub Vec<(jiff::Timestamp, usize)>);
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Stored {
+    artists: BTreeMap<u64, Entry>,
+    releases: BTreeMap<u64, Entry>,
+}
+
+#[derive(Debug)]
+enum Alert {
+    NewRelease { artist: ArtistId, release: Url },
+    NewFans { release: ReleaseId, count: usize },
+}
+
+/// Artists and releases to periodically re-scrape, persisted across runs
+#[derive(Debug, Resource)]
+pub struct Watchlist {
+    path: PathBuf,
+    stored: Stored,
+    unreported_fans: HashMap<ReleaseId, usize>,
+    alerts: Vec<Alert>,
+}
+
+impl Watchlist {
+    #[culpa::try_fn]
+    pub fn load(data_dir: &Path) -> eyre::Result<Self> {
+        let path = data_dir.join("watchlist.json");
+        let stored = match std::fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Stored::default(),
+            Err(err) => Err(err)?,
+        };
+        Self {
+            path,
+            stored,
+            unreported_fans: HashMap::new(),
+            alerts: Vec::new(),
+        }
+    }
+
+    fn requests(&self) -> impl Iterator<Item = Request> + use<'_> {
+        let artists = self.stored.artists.values().map(|entry| Request::Artist {
+            url: entry.url.clone(),
+        });
+        let releases = self.stored.releases.values().map(|entry| Request::Release {
+            url: entry.url.clone(),
+        });
+        artists.chain(releases)
+    }
+
+    /// Record releases scraped for an artist, alerting on any new ones if it is watched
+    pub fn saw_releases(&mut self, artist: ArtistId, releases: &[Release]) {
+        let Some(entry) = self.stored.artists.get_mut(&artist.0) else {
+            return;
+        };
+
+        let mut changed = false;
+        for release in releases {
+            if entry.seen.insert(release.id.0) {
+                self.alerts.push(Alert::NewRelease {
+                    artist,
+                    release: release.url.clone(),
+                });
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.save();
+        }
+    }
+
+    /// Record fans scraped for a release, alerting once enough new ones have accumulated if it is
+    /// watched
+    pub fn saw_fans(&mut self, release: ReleaseId, fans: &[User]) {
+        let Some(entry) = self.stored.releases.get_mut(&release.0) else {
+            return;
+        };
+
+        let count = fans
+            .iter()
+            .filter(|fan| entry.seen.insert(fan.id.0))
+            .count();
+
+        if count > 0 {
+            let unreported = self.unreported_fans.entry(release).or_default();
+            *unreported += count;
+            if *unreported >= FAN_ALERT_THRESHOLD {
+                self.alerts.push(Alert::NewFans {
+                    release,
+                    count: std::mem::take(unreported),
+                });
+            }
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.try_save() {
+            tracing::error!(?error, "failed saving watchlist");
+        }
+    }
+
+    #[culpa::try_fn]
+    fn try_save(&self) -> eyre::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.stored)?)?;
+    }
+}
+
+#[derive(Resource)]
+struct Schedule(Timer);
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_systems(bevy::app::Startup, setup);
+        app.add_systems(
+            bevy::app::Update,
+            (
+                mark_watched,
+                track,
+                untrack,
+                refresh,
+                report,
+                sample.after(track),
+            ),
+        );
+    }
+}
+
+fn setup(
+    args: Res<Args>,
+    watchlist: Res<Watchlist>,
+    scraper: Res<crate::background::Scraper>,
+    mut commands: Commands,
+) {
+    commands.insert_resource(Schedule(Timer::new(
+        Duration::from_secs(args.watch_interval * 60),
+        TimerMode::Repeating,
+    )));
+
+    for request in watchlist.requests() {
+        scraper.send(request).unwrap();
+    }
+}
+
+fn mark_watched(
+    watchlist: Res<Watchlist>,
+    artists: Query<(Entity, &ArtistId), Added<ArtistId>>,
+    releases: Query<(Entity, &ReleaseId), Added<ReleaseId>>,
+    mut commands: Commands,
+) {
+    for (entity, id) in &artists {
+        if watchlist.stored.artists.contains_key(&id.0) {
+            commands.entity(entity).insert(Watched);
+        }
+    }
+
+    for (entity, id) in &releases {
+        if let Some(entry) = watchlist.stored.releases.get(&id.0) {
+            commands
+                .entity(entity)
+                .insert((Watched, FanCounts(entry.fan_counts.clone())));
+        }
+    }
+}
+
+fn track(
+    mut watchlist: ResMut<Watchlist>,
+    watched: Query<(Entity, &Url, Option<&ArtistId>, Option<&ReleaseId>), Added<Watched>>,
+    relationships: Query<&Relationship>,
+    ids: Query<(Option<&ReleaseId>, Option<&UserId>)>,
+) {
+    for (entity, url, artist, release) in &watched {
+        let (entries, id) = match (artist, release) {
+            (Some(artist), _) => (&mut watchlist.stored.artists, artist.0),
+            (_, Some(release)) => (&mut watchlist.stored.releases, release.0),
+            _ => continue,
+        };
+
+        if entries.contains_key(&id) {
+            continue;
+        }
+
+        // Treat everything already in the graph as seen, so only later changes are alerted on
+        let seen = relationships
+            .iter()
+            .filter_map(|rel| {
+                (rel.from == entity)
+                    .then_some(rel.to)
+                    .or((rel.to == entity).then_some(rel.from))
+            })
+            .filter_map(|other| ids.get(other).ok())
+            .filter_map(|(release_id, user_id)| {
+                if artist.is_some() {
+                    release_id.map(|id| id.0)
+                } else {
+                    user_id.map(|id| id.0)
+                }
+            })
+            .collect();
+
+        entries.insert(
+            id,
+            Entry {
+                url: url.0.clone(),
+                seen,
+                fan_counts: Vec::new(),
+            },
+        );
+        watchlist.save();
+    }
+}
+
+fn untrack(
+    mut watchlist: ResMut<Watchlist>,
+    mut removed: RemovedComponents<Watched>,
+    ids: Query<(Option<&ArtistId>, Option<&ReleaseId>)>,
+    mut commands: Commands,
+) {
+    for entity in removed.read() {
+        // Despawned entities stay watched, only an explicit unwatch removes them
+        let Ok((artist, release)) = ids.get(entity) else {
+            continue;
+        };
+
+        if let Some(artist) = artist {
+            watchlist.stored.artists.remove(&artist.0);
+        }
+        if let Some(release) = release {
+            watchlist.stored.releases.remove(&release.0);
+            watchlist.unreported_fans.remove(release);
+            commands.entity(entity).remove::<FanCounts>();
+        }
+        watchlist.save();
+    }
+}
+
+fn refresh(
+    time: Res<Time<Real>>,
+    mut schedule: ResMut<Schedule>,
+    watchlist: Res<Watchlist>,
+    scraper: Res<crate::background::Scraper>,
+    known: Res<KnownEntities>,
+    watched: Query<(Entity, &Url, &ScrapeHistory), With<Watched>>,
+    multiplicities: Query<&Multiplicity>,
+    users: Query<&UserId>,
+) {
+    if schedule.0.tick(time.delta()).just_finished() {
+        // Anything scraped in the last half interval (e.g. by hand) is fresh enough to wait until
+        // next time round
+        let fresh = jiff::Timestamp::now()
+            - jiff::SignedDuration::try_from(schedule.0.duration() / 2).unwrap();
+        let recent = HashSet::<&str>::from_iter(
+            watched
+                .iter()
+                .filter(|(_, _, history)| history.last_scraped().is_some_and(|last| last > fresh))
+                .map(|(_, url, _)| url.0.as_str()),
+        );
+        let entities = HashMap::<&str, Entity>::from_iter(
+            watched
+                .iter()
+                .map(|(entity, url, _)| (url.0.as_str(), entity)),
+        );
+
+        for request in watchlist.requests() {
+            if recent.contains(request.url()) {
+                tracing::debug!(?request, "skipping recently scraped watched entity");
+                continue;
+            }
+            tracing::info!(?request, "refreshing watched entity");
+            // A release in the graph only needs the fans that changed since those it already has
+            let request = match (&request, entities.get(request.url())) {
+                (Request::Release { url }, Some(&entity)) => Request::RefreshFans {
+                    url: url.clone(),
+                    known: known_fans(entity, &known, &multiplicities, &users),
+                },
+                _ => Request::Refresh(Box::new(request)),
+            };
+            scraper.send(request).unwrap();
+        }
+    }
+}
+
+/// The ids of the fans related to a release in the graph
+fn known_fans(
+    release: Entity,
+    known: &KnownEntities,
+    multiplicities: &Query<&Multiplicity>,
+    users: &Query<&UserId>,
+) -> Vec<u64> {
+    let mut fans = Vec::from_iter(
+        known
+            .edges_of(release)
+            .filter(|relationship| relationship.to == release)
+            .filter(|relationship| {
+                known
+                    .relationships
+                    .get(relationship)
+                    .and_then(|&entity| multiplicities.get(entity).ok())
+                    .is_some_and(|multiplicity| {
+                        multiplicity.kinds().any(|kind| kind == RelationKind::Fan)
+                    })
+            })
+            .filter_map(|relationship| users.get(relationship.from).ok())
+            .map(|id| id.0),
+    );
+    fans.sort_unstable();
+    fans
+}
+
+/// Record the fan count of watched releases whenever they're scraped, starting from when they're
+/// first watched
+fn sample(
+    mut watchlist: ResMut<Watchlist>,
+    releases: Query<
+        (Entity, &ReleaseId, &ReleaseDetails),
+        (With<Watched>, Or<(Changed<ReleaseDetails>, Added<Watched>)>),
+    >,
+    mut commands: Commands,
+) {
+    let mut changed = false;
+    for (entity, id, details) in &releases {
+        let Some(entry) = watchlist.stored.releases.get_mut(&id.0) else {
+            continue;
+        };
+        // Pages are often scraped again from the cache, only keep the count when it changes
+        if entry
+            .fan_counts
+            .last()
+            .is_some_and(|&(_, count)| count == details.fan_count)
+        {
+            continue;
+        }
+        entry
+            .fan_counts
+            .push((jiff::Timestamp::now(), details.fan_count));
+        commands
+            .entity(entity)
+            .insert(FanCounts(entry.fan_counts.clone()));
+        changed = true;
+    }
+
+    if changed {
+        watchlist.save();
+    }
+}
+
+fn report(
+    mut watchlist: ResMut<Watchlist>,
+    known: Res<KnownEntities>,
+    artists: Query<&ArtistDetails>,
+    releases: Query<&ReleaseDetails>,
+    mut notifications: EventWriter<Notify>,
+) {
+    for alert in watchlist.alerts.drain(..) {
+        let message = match alert {
+            Alert::NewRelease { artist, release } => {
+                let name = known
+                    .artists
+                    .get(&artist)
+                    .and_then(|&entity| artists.get(entity).ok())
+                    .map_or("watched artist", |details| details.name.as_str());
+                format!("new release by {name}: {}", release.0)
+            }
+            Alert::NewFans { release, count } => {
+                let title = known
+                    .releases
+                    .get(&release)
+                    .and_then(|&entity| releases.get(entity).ok())
+                    .map_or("watched release", |details| details.title.as_str());
+                format!("{count} new fans of {title}")
+            }
+        };
+        notifications.send(Notify(message));
+    }
+}